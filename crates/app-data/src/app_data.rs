@@ -23,7 +23,9 @@ pub struct ProtocolAppData {
     pub hooks: Hooks,
     pub signer: Option<H160>,
     pub replaced_order: Option<ReplacedOrder>,
-    pub partner_fee: Option<PartnerFee>,
+    /// Either a single partner fee or a list of partner fees.
+    #[serde(default, deserialize_with = "deserialize_partner_fees")]
+    pub partner_fee: Vec<PartnerFee>,
 }
 
 #[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq)]
@@ -31,12 +33,103 @@ pub struct ReplacedOrder {
     pub uid: OrderUid,
 }
 
-#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq)]
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq)]
+#[serde(try_from = "RawPartnerFee")]
 pub struct PartnerFee {
-    pub bps: u64,
+    pub policy: PartnerFeePolicy,
     pub recipient: H160,
 }
 
+/// How a partner fee is computed. All values are in basis points.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum PartnerFeePolicy {
+    /// A share of the order's volume.
+    Volume { bps: u64 },
+    /// A share of the surplus the order received over its limit price, capped
+    /// at a share of the order's volume.
+    Surplus { bps: u64, max_volume_bps: u64 },
+    /// A share of the surplus the order received over the quote, capped at a
+    /// share of the order's volume.
+    PriceImprovement { bps: u64, max_volume_bps: u64 },
+}
+
+/// A partner fee as specified in the app data. Unknown fields are ignored
+/// like they were before partner fees supported multiple policies.
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RawPartnerFee {
+    /// The legacy volume fee.
+    bps: Option<u64>,
+    volume_bps: Option<u64>,
+    surplus_bps: Option<u64>,
+    price_improvement_bps: Option<u64>,
+    max_volume_bps: Option<u64>,
+    recipient: H160,
+}
+
+impl TryFrom<RawPartnerFee> for PartnerFee {
+    type Error = String;
+
+    fn try_from(raw: RawPartnerFee) -> Result<Self, Self::Error> {
+        let recipient = raw.recipient;
+        // Fees with the legacy field predate the other policies, so their
+        // fields are ignored.
+        if let (Some(bps), None) = (raw.bps, raw.volume_bps) {
+            return Ok(Self {
+                policy: PartnerFeePolicy::Volume { bps },
+                recipient,
+            });
+        }
+        let policy = match (
+            raw.volume_bps,
+            raw.surplus_bps,
+            raw.price_improvement_bps,
+            raw.max_volume_bps,
+        ) {
+            (Some(bps), None, None, None) => PartnerFeePolicy::Volume { bps },
+            (None, Some(bps), None, Some(max_volume_bps)) => PartnerFeePolicy::Surplus {
+                bps,
+                max_volume_bps,
+            },
+            (None, None, Some(bps), Some(max_volume_bps)) => PartnerFeePolicy::PriceImprovement {
+                bps,
+                max_volume_bps,
+            },
+            _ => {
+                return Err(
+                    "partner fee must specify exactly one of volumeBps, or surplusBps or \
+                     priceImprovementBps together with maxVolumeBps"
+                        .to_owned(),
+                )
+            }
+        };
+        Ok(Self { policy, recipient })
+    }
+}
+
+/// Invalid partner fees are ignored instead of invalidating the whole app
+/// data, which would also drop all the valid partner fees.
+fn deserialize_partner_fees<'de, D>(deserializer: D) -> Result<Vec<PartnerFee>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let fees = match Option::<serde_json::Value>::deserialize(deserializer)? {
+        None => Vec::new(),
+        Some(serde_json::Value::Array(fees)) => fees,
+        Some(fee) => vec![fee],
+    };
+    Ok(fees
+        .into_iter()
+        .filter_map(|fee| match PartnerFee::deserialize(&fee) {
+            Ok(fee) => Some(fee),
+            Err(err) => {
+                tracing::debug!(%fee, ?err, "ignoring invalid partner fee");
+                None
+            }
+        })
+        .collect())
+}
+
 #[derive(Clone)]
 pub struct Validator {
     size_limit: usize,
@@ -216,7 +309,7 @@ impl From<BackendAppData> for ProtocolAppData {
             hooks: value.hooks,
             signer: None,
             replaced_order: None,
-            partner_fee: None,
+            partner_fee: Vec::new(),
         }
     }
 }
@@ -426,6 +519,114 @@ mod tests {
         );
     }
 
+    #[test]
+    fn partner_fees() {
+        let recipient = H160([1; 20]);
+        assert_app_data!(
+            r#"{"metadata":{"partnerFee":{"bps":50,"recipient":"0x0101010101010101010101010101010101010101"}}}"#,
+            ProtocolAppData {
+                partner_fee: vec![PartnerFee {
+                    policy: PartnerFeePolicy::Volume { bps: 50 },
+                    recipient,
+                }],
+                ..Default::default()
+            },
+        );
+        assert_app_data!(
+            r#"
+                {
+                    "metadata": {
+                        "partnerFee": [
+                            {
+                                "volumeBps": 10,
+                                "recipient": "0x0101010101010101010101010101010101010101"
+                            },
+                            {
+                                "surplusBps": 5000,
+                                "maxVolumeBps": 100,
+                                "recipient": "0x0202020202020202020202020202020202020202"
+                            },
+                            {
+                                "priceImprovementBps": 2000,
+                                "maxVolumeBps": 50,
+                                "recipient": "0x0303030303030303030303030303030303030303"
+                            }
+                        ]
+                    }
+                }
+            "#,
+            ProtocolAppData {
+                partner_fee: vec![
+                    PartnerFee {
+                        policy: PartnerFeePolicy::Volume { bps: 10 },
+                        recipient,
+                    },
+                    PartnerFee {
+                        policy: PartnerFeePolicy::Surplus {
+                            bps: 5000,
+                            max_volume_bps: 100,
+                        },
+                        recipient: H160([2; 20]),
+                    },
+                    PartnerFee {
+                        policy: PartnerFeePolicy::PriceImprovement {
+                            bps: 2000,
+                            max_volume_bps: 50,
+                        },
+                        recipient: H160([3; 20]),
+                    },
+                ],
+                ..Default::default()
+            },
+        );
+
+        // Invalid partner fees get ignored while unknown fields and the other
+        // partner fees are kept.
+        assert_app_data!(
+            r#"
+                {
+                    "metadata": {
+                        "partnerFee": [
+                            {
+                                "surplusBps": 50,
+                                "recipient": "0x0101010101010101010101010101010101010101"
+                            },
+                            {
+                                "volumeBps": 50,
+                                "surplusBps": 50,
+                                "maxVolumeBps": 50,
+                                "recipient": "0x0101010101010101010101010101010101010101"
+                            },
+                            {
+                                "bps": 20000,
+                                "surplusBps": 50,
+                                "unknown": true,
+                                "recipient": "0x0101010101010101010101010101010101010101"
+                            },
+                            {
+                                "volumeBps": 10,
+                                "recipient": "0x0202020202020202020202020202020202020202"
+                            }
+                        ]
+                    }
+                }
+            "#,
+            ProtocolAppData {
+                partner_fee: vec![
+                    PartnerFee {
+                        policy: PartnerFeePolicy::Volume { bps: 20_000 },
+                        recipient,
+                    },
+                    PartnerFee {
+                        policy: PartnerFeePolicy::Volume { bps: 10 },
+                        recipient: H160([2; 20]),
+                    },
+                ],
+                ..Default::default()
+            },
+        );
+    }
+
     #[test]
     fn misc() {
         let mut validator = Validator::default();
@@ -41,6 +41,23 @@ pub struct Arguments {
     #[clap(long, env)]
     pub ethflow_indexing_start: Option<u64>,
 
+    /// Address of the ComposableCoW contract. If specified, conditional orders
+    /// (e.g. TWAP orders) created via this contract get indexed and their
+    /// discrete orders get placed in the orderbook once they become tradeable.
+    #[clap(long, env)]
+    pub composable_cow_contract: Option<H160>,
+
+    /// Block at which we should start indexing ComposableCoW contract events.
+    /// If there are already events in the database for a block later than
+    /// this, then this block is ignored and can be omitted.
+    #[clap(long, env)]
+    pub composable_cow_indexing_start: Option<u64>,
+
+    /// The orderbook API the discrete orders of conditional orders get placed
+    /// with.
+    #[clap(long, env, default_value = "http://localhost:8080")]
+    pub orderbook_url: Url,
+
     /// A tracing Ethereum node URL to connect to, allowing a separate node URL
     /// to be used exclusively for tracing calls.
     #[clap(long, env)]
@@ -247,6 +264,9 @@ impl std::fmt::Display for Arguments {
             tracing_node_url,
             ethflow_contract,
             ethflow_indexing_start,
+            composable_cow_contract,
+            composable_cow_indexing_start,
+            orderbook_url,
             metrics_address,
             skip_event_sync,
             allowed_tokens,
@@ -289,6 +309,13 @@ impl std::fmt::Display for Arguments {
         display_option(f, "tracing_node_url", tracing_node_url)?;
         writeln!(f, "ethflow_contract: {:?}", ethflow_contract)?;
         writeln!(f, "ethflow_indexing_start: {:?}", ethflow_indexing_start)?;
+        writeln!(f, "composable_cow_contract: {:?}", composable_cow_contract)?;
+        writeln!(
+            f,
+            "composable_cow_indexing_start: {:?}",
+            composable_cow_indexing_start
+        )?;
+        writeln!(f, "orderbook_url: {}", orderbook_url)?;
         writeln!(f, "metrics_address: {}", metrics_address)?;
         let _intentionally_ignored = db_url;
         writeln!(f, "db_url: SECRET")?;
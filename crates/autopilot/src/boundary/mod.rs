@@ -1,7 +1,7 @@
 pub use {
     crate::database::{
         competition::Competition,
-        order_events::{store_order_events, OrderEventLabel},
+        order_events::{store_order_events, OrderEventLabel, OrderEventReason},
     },
     database,
     model::{
@@ -19,9 +19,8 @@ use {
 #[derive(Clone, Default, Debug)]
 pub struct Competition {
     pub auction_id: AuctionId,
-    pub winner: H160,
-    pub winning_score: U256,
-    pub reference_score: U256,
+    /// All solvers that won the auction together with their scores.
+    pub winners: Vec<Winner>,
     /// Addresses to which the CIP20 participation rewards will be payed out.
     /// Usually the same as the solver addresses.
     pub participants: HashSet<H160>,
@@ -34,6 +33,16 @@ pub struct Competition {
     pub competition_table: SolverCompetitionDB,
 }
 
+#[derive(Clone, Default, Debug)]
+pub struct Winner {
+    pub solver: H160,
+    /// Sum of the scores of all winning solutions of the solver.
+    pub score: U256,
+    /// Sum of the scores of all winning solutions had the solver not
+    /// participated in the auction.
+    pub reference_score: U256,
+}
+
 impl super::Postgres {
     pub async fn save_competition(&self, competition: &Competition) -> anyhow::Result<()> {
         let _timer = super::Metrics::get()
@@ -53,25 +62,27 @@ impl super::Postgres {
         .await
         .context("solver_competition::save_solver_competition")?;
 
-        database::settlement_scores::insert(
-            &mut ex,
-            Score {
-                auction_id: competition.auction_id,
-                winner: ByteArray(competition.winner.0),
-                winning_score: u256_to_big_decimal(&competition.winning_score),
-                reference_score: u256_to_big_decimal(&competition.reference_score),
-                block_deadline: competition
-                    .block_deadline
-                    .try_into()
-                    .context("convert block deadline")?,
-                simulation_block: competition
-                    .competition_simulation_block
-                    .try_into()
-                    .context("convert simulation block")?,
-            },
-        )
-        .await
-        .context("settlement_scores::insert")?;
+        for winner in &competition.winners {
+            database::settlement_scores::insert(
+                &mut ex,
+                Score {
+                    auction_id: competition.auction_id,
+                    winner: ByteArray(winner.solver.0),
+                    winning_score: u256_to_big_decimal(&winner.score),
+                    reference_score: u256_to_big_decimal(&winner.reference_score),
+                    block_deadline: competition
+                        .block_deadline
+                        .try_into()
+                        .context("convert block deadline")?,
+                    simulation_block: competition
+                        .competition_simulation_block
+                        .try_into()
+                        .context("convert simulation block")?,
+                },
+            )
+            .await
+            .context("settlement_scores::insert")?;
+        }
 
         database::auction_participants::insert(
             &mut ex,
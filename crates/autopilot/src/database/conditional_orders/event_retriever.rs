@@ -0,0 +1,36 @@
+//! A component that listens exclusively for `ConditionalOrderCreated` events
+//! of the ComposableCoW contract.
+use {
+    ethcontract::{contract::AllEventsBuilder, transport::DynTransport, H160},
+    shared::{ethrpc::Web3, event_handling::EventRetrieving},
+};
+
+pub struct ComposableCoWRetriever {
+    web3: Web3,
+    address: H160,
+}
+
+impl ComposableCoWRetriever {
+    pub fn new(web3: Web3, address: H160) -> Self {
+        Self { web3, address }
+    }
+}
+
+impl EventRetrieving for ComposableCoWRetriever {
+    type Event = contracts::composable_cow::Event;
+
+    fn get_events(&self) -> AllEventsBuilder<DynTransport, Self::Event> {
+        let topic = contracts::ComposableCoW::raw_contract()
+            .interface
+            .abi
+            .event("ConditionalOrderCreated")
+            .expect("ComposableCoW emits ConditionalOrderCreated events")
+            .signature();
+        let mut events = AllEventsBuilder::new(self.web3.clone(), self.address, None);
+        // Merkle roots of conditional orders can't be indexed since the proofs
+        // are not necessarily published onchain, so only single orders are
+        // picked up.
+        events.filter = events.filter.topic0(vec![topic].into());
+        events
+    }
+}
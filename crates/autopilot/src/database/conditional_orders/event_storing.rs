@@ -0,0 +1,100 @@
+//! Implements the logic for indexing `ConditionalOrderCreated` events of the
+//! ComposableCoW contract.
+use {
+    crate::database::Postgres,
+    anyhow::{Context, Result},
+    contracts::composable_cow::{event_data::ConditionalOrderCreated, Event},
+    database::{byte_array::ByteArray, conditional_orders::ConditionalOrder},
+    ethrpc::block_stream::RangeInclusive,
+    shared::event_handling::EventStoring,
+    web3::ethabi::Token,
+};
+
+/// `ComposableCoW.hash(params)` which identifies a conditional order of an
+/// owner.
+pub fn params_hash(handler: ethcontract::H160, salt: [u8; 32], static_input: &[u8]) -> [u8; 32] {
+    web3::signing::keccak256(&web3::ethabi::encode(&[Token::Tuple(vec![
+        Token::Address(handler),
+        Token::FixedBytes(salt.to_vec()),
+        Token::Bytes(static_input.to_vec()),
+    ])]))
+}
+
+fn get_conditional_orders(events: Vec<ethcontract::Event<Event>>) -> Result<Vec<ConditionalOrder>> {
+    events
+        .into_iter()
+        .filter_map(|event| {
+            let Event::ConditionalOrderCreated(ConditionalOrderCreated { owner, params }) =
+                event.data
+            else {
+                return None;
+            };
+            let order = event.meta.context("event without metadata").map(|meta| {
+                let (handler, salt, static_input) = params;
+                ConditionalOrder {
+                    owner: ByteArray(owner.0),
+                    params_hash: ByteArray(params_hash(handler, salt.0, &static_input.0)),
+                    handler: ByteArray(handler.0),
+                    salt: ByteArray(salt.0),
+                    static_input: static_input.0,
+                    block_number: i64::try_from(meta.block_number).unwrap_or(i64::MAX),
+                    log_index: i64::try_from(meta.log_index).unwrap_or(i64::MAX),
+                    next_poll_block: 0,
+                    next_poll_timestamp: 0,
+                }
+            });
+            Some(order)
+        })
+        .collect()
+}
+
+/// This name is used to store the latest indexed block in the db.
+const INDEX_NAME: &str = "conditional_orders";
+
+#[async_trait::async_trait]
+impl EventStoring<Event> for Postgres {
+    async fn last_event_block(&self) -> Result<u64> {
+        crate::boundary::events::read_last_block_from_db(&self.pool, INDEX_NAME).await
+    }
+
+    async fn persist_last_indexed_block(&mut self, last_block: u64) -> Result<()> {
+        crate::boundary::events::write_last_block_to_db(&self.pool, last_block, INDEX_NAME).await
+    }
+
+    async fn append_events(&mut self, events: Vec<ethcontract::Event<Event>>) -> Result<()> {
+        let orders = match get_conditional_orders(events)? {
+            orders if !orders.is_empty() => orders,
+            _ => return Ok(()),
+        };
+        let _timer = crate::database::Metrics::get()
+            .database_queries
+            .with_label_values(&["append_conditional_order_events"])
+            .start_timer();
+        let mut ex = self.pool.begin().await?;
+        database::conditional_orders::insert(&mut ex, &orders).await?;
+        ex.commit().await?;
+        Ok(())
+    }
+
+    async fn replace_events(
+        &mut self,
+        events: Vec<ethcontract::Event<Event>>,
+        range: RangeInclusive<u64>,
+    ) -> Result<()> {
+        let orders = get_conditional_orders(events)?;
+        let _timer = crate::database::Metrics::get()
+            .database_queries
+            .with_label_values(&["replace_conditional_order_events"])
+            .start_timer();
+        let mut ex = self.pool.begin().await?;
+        database::conditional_orders::delete_in_block_range(
+            &mut ex,
+            i64::try_from(*range.start()).unwrap_or(i64::MAX),
+            i64::try_from(*range.end()).unwrap_or(i64::MAX),
+        )
+        .await?;
+        database::conditional_orders::insert(&mut ex, &orders).await?;
+        ex.commit().await?;
+        Ok(())
+    }
+}
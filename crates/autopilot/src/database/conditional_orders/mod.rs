@@ -0,0 +1,4 @@
+//! Contains all the components to index conditional orders created via the
+//! ComposableCoW contract.
+pub mod event_retriever;
+pub mod event_storing;
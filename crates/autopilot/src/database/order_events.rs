@@ -1,4 +1,4 @@
-pub use database::order_events::OrderEventLabel;
+pub use database::order_events::{OrderEventLabel, OrderEventReason};
 use {
     crate::domain,
     anyhow::Result,
@@ -23,21 +23,51 @@ pub async fn store_order_events(
     order_uids: Vec<domain::OrderUid>,
     label: OrderEventLabel,
     timestamp: DateTime<Utc>,
+) {
+    let events = order_uids.into_iter().map(|uid| (uid, None)).collect();
+    store(ex, events, label, timestamp).await
+}
+
+/// Stores the events together with the reason why they happened (e.g. why an
+/// order got filtered from the auction).
+pub async fn store_order_events_with_reasons(
+    ex: &mut PgConnection,
+    events: Vec<(domain::OrderUid, OrderEventReason)>,
+    label: OrderEventLabel,
+    timestamp: DateTime<Utc>,
+) {
+    let events = events
+        .into_iter()
+        .map(|(uid, reason)| (uid, Some(reason)))
+        .collect();
+    store(ex, events, label, timestamp).await
+}
+
+async fn store(
+    ex: &mut PgConnection,
+    events: Vec<(domain::OrderUid, Option<OrderEventReason>)>,
+    label: OrderEventLabel,
+    timestamp: DateTime<Utc>,
 ) {
     let start = Instant::now();
-    let count = order_uids.len();
+    let count = events.len();
 
     let insert = async move {
         let mut ex = ex.begin().await?;
 
-        for uid in order_uids {
+        for (uid, reason) in events {
             let event = OrderEvent {
                 order_uid: ByteArray(uid.0),
                 timestamp,
                 label,
             };
 
-            order_events::insert_order_event(&mut ex, &event).await?;
+            match reason {
+                Some(reason) => {
+                    order_events::insert_order_event_with_reason(&mut ex, &event, &reason).await?
+                }
+                None => order_events::insert_order_event(&mut ex, &event).await?,
+            }
         }
 
         ex.commit().await
@@ -12,7 +12,7 @@ use {
         boundary::{self},
         domain::{self, eth},
     },
-    app_data::Validator,
+    app_data::{PartnerFee, PartnerFeePolicy, Validator},
     derive_more::Into,
     primitive_types::{H160, U256},
     prometheus::core::Number,
@@ -81,29 +81,25 @@ impl ProtocolFees {
         quote: Option<domain::Quote>,
         surplus_capturing_jit_order_owners: &[eth::Address],
     ) -> domain::Order {
-        let partner_fee = order
+        let partner_fees = order
             .metadata
             .full_app_data
             .as_ref()
             .and_then(|full_app_data| {
                 Validator::new(usize::MAX)
                     .validate(full_app_data.as_bytes())
-                    .ok()?
-                    .protocol
-                    .partner_fee
-                    .map(|partner_fee| Policy::Volume {
-                        factor: FeeFactor::try_from_capped(
-                            partner_fee.bps.into_f64() / 10_000.0,
-                            self.max_partner_fee.into(),
-                        )
-                        .unwrap(),
-                    })
+                    .ok()
             })
-            .into_iter()
-            .collect::<Vec<_>>();
+            .map(|app_data| app_data.protocol.partner_fee)
+            .unwrap_or_default();
+
+        // Partner fees based on the price improvement need the actual quote,
+        // so they are built before a quote gets made up for orders without one.
+        let partner_fees =
+            self.partner_fees_into_policies(&order.metadata.uid, &partner_fees, quote.as_ref());
 
         if surplus_capturing_jit_order_owners.contains(&order.metadata.owner.into()) {
-            return boundary::order::to_domain(order, partner_fee, quote);
+            return boundary::order::to_domain(order, partner_fees, quote);
         }
 
         let order_ = boundary::Amounts {
@@ -128,7 +124,90 @@ impl ProtocolFees {
             fee: quote.fee.into(),
         };
 
-        self.apply_policies(order, quote, order_, quote_, partner_fee)
+        self.apply_policies(order, quote, order_, quote_, partner_fees)
+    }
+
+    /// Converts the partner fees from the app data into fee policies. The
+    /// volume based parts of all partner fees together are capped at the
+    /// maximum partner fee, with earlier partner fees taking precedence.
+    fn partner_fees_into_policies(
+        &self,
+        order: &boundary::OrderUid,
+        partner_fees: &[PartnerFee],
+        quote: Option<&domain::Quote>,
+    ) -> Vec<Policy> {
+        let mut remaining: f64 = self.max_partner_fee.into();
+        let mut policies = Vec::new();
+        for partner_fee in partner_fees {
+            let policy = match Self::partner_fee_into_policy(partner_fee, quote, remaining) {
+                Ok(policy) => policy,
+                Err(reason) => {
+                    tracing::debug!(
+                        %order,
+                        ?partner_fee,
+                        reason,
+                        "ignoring partner fee"
+                    );
+                    Metrics::get()
+                        .ignored_partner_fees
+                        .with_label_values(&[reason])
+                        .inc();
+                    continue;
+                }
+            };
+            let volume_factor = match policy {
+                Policy::Volume { factor, .. } => factor,
+                Policy::Surplus {
+                    max_volume_factor, ..
+                }
+                | Policy::PriceImprovement {
+                    max_volume_factor, ..
+                } => max_volume_factor,
+            };
+            remaining = (remaining - f64::from(volume_factor)).max(0.0);
+            policies.push(policy);
+        }
+        policies
+    }
+
+    /// Converts a partner fee from the app data into a fee policy whose volume
+    /// based part is capped at `cap`. Returns the reason if the partner fee
+    /// can't be applied.
+    fn partner_fee_into_policy(
+        partner_fee: &PartnerFee,
+        quote: Option<&domain::Quote>,
+        cap: f64,
+    ) -> Result<Policy, &'static str> {
+        let capped = |bps: u64| {
+            FeeFactor::try_from_capped(bps.into_f64() / 10_000.0, cap).map_err(|_| "invalid_factor")
+        };
+        let factor =
+            |bps: u64| FeeFactor::try_from(bps.into_f64() / 10_000.0).map_err(|_| "invalid_factor");
+        let recipient = Some(partner_fee.recipient.into());
+        let policy = match partner_fee.policy {
+            PartnerFeePolicy::Volume { bps } => Policy::Volume {
+                factor: capped(bps)?,
+                recipient,
+            },
+            PartnerFeePolicy::Surplus {
+                bps,
+                max_volume_bps,
+            } => Policy::Surplus {
+                factor: factor(bps)?,
+                max_volume_factor: capped(max_volume_bps)?,
+                recipient,
+            },
+            PartnerFeePolicy::PriceImprovement {
+                bps,
+                max_volume_bps,
+            } => Policy::PriceImprovement {
+                factor: factor(bps)?,
+                max_volume_factor: capped(max_volume_bps)?,
+                quote: Quote::from_domain(quote.ok_or("missing_quote")?),
+                recipient,
+            },
+        };
+        Ok(policy)
     }
 
     fn apply_policies(
@@ -196,6 +275,9 @@ pub enum Policy {
         factor: FeeFactor,
         /// Cap protocol fee with a percentage of the order's volume.
         max_volume_factor: FeeFactor,
+        /// The partner the fee is charged for. [`None`] for fees charged by
+        /// the protocol itself.
+        recipient: Option<eth::Address>,
     },
     /// A price improvement corresponds to a situation where the order is
     /// executed at a better price than the top quote. The protocol fee in such
@@ -204,6 +286,9 @@ pub enum Policy {
         factor: FeeFactor,
         max_volume_factor: FeeFactor,
         quote: Quote,
+        /// The partner the fee is charged for. [`None`] for fees charged by
+        /// the protocol itself.
+        recipient: Option<eth::Address>,
     },
     /// How much of the order's volume should be taken as a protocol fee.
     /// The fee is taken in `sell` token for `sell` orders and in `buy`
@@ -212,6 +297,9 @@ pub enum Policy {
         /// Percentage of the order's volume should be taken as a protocol
         /// fee.
         factor: FeeFactor,
+        /// The partner the fee is charged for. [`None`] for fees charged by
+        /// the protocol itself.
+        recipient: Option<eth::Address>,
     },
 }
 
@@ -267,3 +355,71 @@ impl Quote {
         }
     }
 }
+
+#[derive(prometheus_metric_storage::MetricStorage)]
+struct Metrics {
+    /// Partner fees which could not be applied to orders, by reason.
+    #[metric(labels("reason"))]
+    ignored_partner_fees: prometheus::IntCounterVec,
+}
+
+impl Metrics {
+    fn get() -> &'static Self {
+        Metrics::instance(observe::metrics::get_storage_registry()).unwrap()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn caps_partner_fees_in_total() {
+        let fees = ProtocolFees::new(&[], FeeFactor::try_from(0.01).unwrap());
+        let partner_fee = |policy, byte| PartnerFee {
+            policy,
+            recipient: H160([byte; 20]),
+        };
+        let volume_factors = |partner_fees: &[PartnerFee]| {
+            fees.partner_fees_into_policies(&Default::default(), partner_fees, None)
+                .into_iter()
+                .map(|policy| match policy {
+                    Policy::Volume { factor, .. } => f64::from(factor),
+                    Policy::Surplus {
+                        max_volume_factor, ..
+                    }
+                    | Policy::PriceImprovement {
+                        max_volume_factor, ..
+                    } => max_volume_factor.into(),
+                })
+                .collect::<Vec<_>>()
+        };
+
+        let partner_fees = [
+            partner_fee(PartnerFeePolicy::Volume { bps: 60 }, 1),
+            partner_fee(
+                PartnerFeePolicy::Surplus {
+                    bps: 5_000,
+                    max_volume_bps: 60,
+                },
+                2,
+            ),
+            partner_fee(PartnerFeePolicy::Volume { bps: 60 }, 3),
+        ];
+        assert_eq!(volume_factors(&partner_fees), [0.006, 0.004, 0.]);
+
+        // Price improvement fees without a quote are ignored and don't use up
+        // the cap.
+        let partner_fees = [
+            partner_fee(
+                PartnerFeePolicy::PriceImprovement {
+                    bps: 5_000,
+                    max_volume_bps: 60,
+                },
+                1,
+            ),
+            partner_fee(PartnerFeePolicy::Volume { bps: 200 }, 2),
+        ];
+        assert_eq!(volume_factors(&partner_fees), [0.01]);
+    }
+}
@@ -58,6 +58,7 @@ impl Surplus {
                 let policy = domain::fee::Policy::Surplus {
                     factor: self.factor,
                     max_volume_factor: self.max_volume_factor,
+                    recipient: None,
                 };
                 Some(policy)
             }
@@ -78,6 +79,7 @@ impl PriceImprovement {
                 factor: self.factor,
                 max_volume_factor: self.max_volume_factor,
                 quote: Quote::from_domain(quote),
+                recipient: None,
             }),
         }
     }
@@ -90,6 +92,7 @@ impl Volume {
             boundary::OrderClass::Liquidity => None,
             boundary::OrderClass::Limit => Some(domain::fee::Policy::Volume {
                 factor: self.factor,
+                recipient: None,
             }),
         }
     }
@@ -478,6 +478,7 @@ mod tests {
                 vec![domain::fee::Policy::Surplus {
                     factor: 0.5f64.try_into().unwrap(),
                     max_volume_factor: 0.01.try_into().unwrap(),
+                    recipient: None,
                 }],
             )]),
         };
@@ -821,6 +822,7 @@ mod tests {
                 vec![domain::fee::Policy::Surplus {
                     factor: 0.5f64.try_into().unwrap(),
                     max_volume_factor: 0.01.try_into().unwrap(),
+                    recipient: None,
                 }],
             )]),
         };
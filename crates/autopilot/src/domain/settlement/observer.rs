@@ -75,9 +75,13 @@ impl Observer {
         };
 
         // Build the <auction_id, settlement> association
-        let (auction_id, settlement) = match transaction {
+        let (auction_id, solver, settlement) = match transaction {
             Ok(transaction) => {
                 let auction_id = transaction.auction_id;
+                // The settlement event reports the caller of the settlement contract
+                // which is the flash loan router for settlements with flash loans.
+                // Attribute the settlement to the account submitting it instead.
+                let solver = transaction.solver;
                 let settlement = match settlement::Settlement::new(
                     transaction,
                     &self.persistence,
@@ -92,12 +96,12 @@ impl Observer {
                         None
                     }
                 };
-                (auction_id, settlement)
+                (auction_id, Some(solver), settlement)
             }
             Err(err) => {
                 tracing::warn!(hash = ?event.transaction, ?err, "invalid settlement transaction");
                 // default values so we don't get stuck on invalid settlement transactions
-                (0.into(), None)
+                (0.into(), None, None)
             }
         };
 
@@ -105,7 +109,7 @@ impl Observer {
 
         if let Err(err) = self
             .persistence
-            .save_settlement(event, auction_id, settlement.as_ref())
+            .save_settlement(event, auction_id, solver, settlement.as_ref())
             .await
         {
             return Err(anyhow!(
@@ -264,6 +264,7 @@ impl Trade {
             fee::Policy::Surplus {
                 factor,
                 max_volume_factor,
+                ..
             } => {
                 let surplus = self.surplus_over_limit_price()?;
                 std::cmp::min(
@@ -275,6 +276,7 @@ impl Trade {
                 factor,
                 max_volume_factor,
                 quote,
+                ..
             } => {
                 let price_improvement = self.price_improvement(quote)?;
                 std::cmp::min(
@@ -283,7 +285,7 @@ impl Trade {
                     self.volume_fee((*max_volume_factor).into())?.amount,
                 )
             }
-            fee::Policy::Volume { factor } => self.volume_fee((*factor).into())?.amount,
+            fee::Policy::Volume { factor, .. } => self.volume_fee((*factor).into())?.amount,
         };
         Ok(eth::Asset {
             token: self.surplus_token(),
@@ -35,10 +35,10 @@ impl Transaction {
         /// auction id.
         const META_DATA_LEN: usize = 8;
 
-        let (data, metadata) = transaction
-            .input
+        let input = tokenized::unwrap_flashloan_router(&transaction.input)?;
+        let (data, metadata) = input
             .0
-            .split_at(transaction.input.0.len() - META_DATA_LEN);
+            .split_at(input.0.len().saturating_sub(META_DATA_LEN));
         let metadata: Option<[u8; META_DATA_LEN]> = metadata.try_into().ok();
         let auction_id = metadata
             .map(crate::domain::auction::Id::from_be_bytes)
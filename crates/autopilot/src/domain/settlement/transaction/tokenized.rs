@@ -41,6 +41,41 @@ impl Tokenized {
     }
 }
 
+/// Settlements requiring flash loans are executed by calling
+/// `flashLoanAndSettle(loans, settlement)` on the flash loan router which
+/// takes out the loans and then calls the settlement contract with the
+/// wrapped calldata. Returns the calldata of the `settle` call (including the
+/// appended auction id) for both kinds of transactions.
+///
+/// cf. https://github.com/cowprotocol/flash-loan-router/blob/main/src/FlashLoanRouter.sol
+pub fn unwrap_flashloan_router(calldata: &eth::Calldata) -> Result<eth::Calldata, error::Decoding> {
+    let params = flashloan_and_settle_params();
+    let selector = web3::ethabi::short_signature("flashLoanAndSettle", &params);
+    let Some(data) = calldata.0.strip_prefix(&selector) else {
+        return Ok(calldata.clone());
+    };
+    let mut tokens = web3::ethabi::decode(&params, data).map_err(error::Decoding::Ethabi)?;
+    match tokens.pop() {
+        Some(web3::ethabi::Token::Bytes(settlement)) => Ok(crate::util::Bytes(settlement)),
+        _ => Err(error::Decoding::InvalidSelector),
+    }
+}
+
+/// Parameters of `flashLoanAndSettle(Loan.Data[] loans, bytes settlement)`
+/// where a `Loan.Data` is `(amount, borrower, lender, token)`.
+fn flashloan_and_settle_params() -> [web3::ethabi::ParamType; 2] {
+    use web3::ethabi::ParamType;
+    [
+        ParamType::Array(Box::new(ParamType::Tuple(vec![
+            ParamType::Uint(256),
+            ParamType::Address,
+            ParamType::Address,
+            ParamType::Address,
+        ]))),
+        ParamType::Bytes,
+    ]
+}
+
 type Token = Address;
 type Trade = (
     U256,            // sellTokenIndex
@@ -169,3 +204,31 @@ pub mod error {
         Tokenizing(ethcontract::tokens::Error),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use {super::*, hex_literal::hex, web3::ethabi::Token};
+
+    #[test]
+    fn unwraps_flashloan_router_calldata() {
+        // `settle` calldata followed by the auction id.
+        let settlement = hex!("13d79a0b01020304050000000000000007").to_vec();
+        let mut router = hex!("e7c438c9").to_vec();
+        router.extend(web3::ethabi::encode(&[
+            Token::Array(vec![Token::Tuple(vec![
+                Token::Uint(U256::exp10(18)),
+                Token::Address(Address::repeat_byte(1)),
+                Token::Address(Address::repeat_byte(2)),
+                Token::Address(Address::repeat_byte(3)),
+            ])]),
+            Token::Bytes(settlement.clone()),
+        ]));
+
+        let unwrapped = unwrap_flashloan_router(&crate::util::Bytes(router)).unwrap();
+        assert_eq!(unwrapped.0, settlement);
+
+        // Regular settlements are returned as is.
+        let unwrapped = unwrap_flashloan_router(&crate::util::Bytes(settlement.clone())).unwrap();
+        assert_eq!(unwrapped.0, settlement);
+    }
+}
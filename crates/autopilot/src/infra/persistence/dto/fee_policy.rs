@@ -1,5 +1,8 @@
 use {
-    crate::{boundary, domain},
+    crate::{
+        boundary,
+        domain::{self, eth},
+    },
     anyhow::Context,
     database::fee_policies::{FeePolicy, FeePolicyKind},
 };
@@ -13,6 +16,7 @@ pub fn from_domain(
         domain::fee::Policy::Surplus {
             factor,
             max_volume_factor,
+            recipient,
         } => FeePolicy {
             auction_id,
             order_uid: boundary::database::byte_array::ByteArray(order_uid.0),
@@ -22,8 +26,10 @@ pub fn from_domain(
             volume_factor: None,
             price_improvement_factor: None,
             price_improvement_max_volume_factor: None,
+            partner_fee_recipient: recipient
+                .map(|recipient| boundary::database::byte_array::ByteArray(recipient.0 .0)),
         },
-        domain::fee::Policy::Volume { factor } => FeePolicy {
+        domain::fee::Policy::Volume { factor, recipient } => FeePolicy {
             auction_id,
             order_uid: boundary::database::byte_array::ByteArray(order_uid.0),
             kind: FeePolicyKind::Volume,
@@ -32,11 +38,14 @@ pub fn from_domain(
             volume_factor: Some(factor.into()),
             price_improvement_factor: None,
             price_improvement_max_volume_factor: None,
+            partner_fee_recipient: recipient
+                .map(|recipient| boundary::database::byte_array::ByteArray(recipient.0 .0)),
         },
         domain::fee::Policy::PriceImprovement {
             factor,
             max_volume_factor,
             quote: _,
+            recipient,
         } => FeePolicy {
             auction_id,
             order_uid: boundary::database::byte_array::ByteArray(order_uid.0),
@@ -46,6 +55,8 @@ pub fn from_domain(
             volume_factor: None,
             price_improvement_factor: Some(factor.into()),
             price_improvement_max_volume_factor: Some(max_volume_factor.into()),
+            partner_fee_recipient: recipient
+                .map(|recipient| boundary::database::byte_array::ByteArray(recipient.0 .0)),
         },
     }
 }
@@ -54,6 +65,9 @@ pub fn try_into_domain(
     policy: FeePolicy,
     quote: Option<&domain::quote::Quote>,
 ) -> Result<domain::fee::Policy, Error> {
+    let recipient = policy
+        .partner_fee_recipient
+        .map(|recipient| eth::H160(recipient.0).into());
     let policy = match policy.kind {
         FeePolicyKind::Surplus => domain::fee::Policy::Surplus {
             factor: policy
@@ -64,12 +78,14 @@ pub fn try_into_domain(
                 .surplus_max_volume_factor
                 .context("missing surplus_max_volume_factor")?
                 .try_into()?,
+            recipient,
         },
         FeePolicyKind::Volume => domain::fee::Policy::Volume {
             factor: policy
                 .volume_factor
                 .context("missing volume_factor")?
                 .try_into()?,
+            recipient,
         },
         FeePolicyKind::PriceImprovement => domain::fee::Policy::PriceImprovement {
             factor: policy
@@ -89,6 +105,7 @@ pub fn try_into_domain(
                     solver: quote.solver.into(),
                 }
             },
+            recipient,
         },
     };
     Ok(policy)
@@ -259,15 +259,26 @@ impl From<boundary::EcdsaSignature> for domain::auction::order::EcdsaSignature {
 #[serde(rename_all = "camelCase")]
 pub enum FeePolicy {
     #[serde(rename_all = "camelCase")]
-    Surplus { factor: f64, max_volume_factor: f64 },
+    Surplus {
+        factor: f64,
+        max_volume_factor: f64,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        recipient: Option<H160>,
+    },
     #[serde(rename_all = "camelCase")]
     PriceImprovement {
         factor: f64,
         max_volume_factor: f64,
         quote: Quote,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        recipient: Option<H160>,
     },
     #[serde(rename_all = "camelCase")]
-    Volume { factor: f64 },
+    Volume {
+        factor: f64,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        recipient: Option<H160>,
+    },
 }
 
 impl FeePolicy {
@@ -276,14 +287,17 @@ impl FeePolicy {
             domain::fee::Policy::Surplus {
                 factor,
                 max_volume_factor,
+                recipient,
             } => Self::Surplus {
                 factor: factor.into(),
                 max_volume_factor: max_volume_factor.into(),
+                recipient: recipient.map(Into::into),
             },
             domain::fee::Policy::PriceImprovement {
                 factor,
                 max_volume_factor,
                 quote,
+                recipient,
             } => Self::PriceImprovement {
                 factor: factor.into(),
                 max_volume_factor: max_volume_factor.into(),
@@ -293,9 +307,11 @@ impl FeePolicy {
                     fee: quote.fee,
                     solver: quote.solver,
                 },
+                recipient: recipient.map(Into::into),
             },
-            domain::fee::Policy::Volume { factor } => Self::Volume {
+            domain::fee::Policy::Volume { factor, recipient } => Self::Volume {
                 factor: factor.into(),
+                recipient: recipient.map(Into::into),
             },
         }
     }
@@ -305,14 +321,17 @@ impl FeePolicy {
             Self::Surplus {
                 factor,
                 max_volume_factor,
+                recipient,
             } => domain::fee::Policy::Surplus {
                 factor: FeeFactor::try_from(factor).unwrap(),
                 max_volume_factor: FeeFactor::try_from(max_volume_factor).unwrap(),
+                recipient: recipient.map(Into::into),
             },
             Self::PriceImprovement {
                 factor,
                 max_volume_factor,
                 quote,
+                recipient,
             } => domain::fee::Policy::PriceImprovement {
                 factor: FeeFactor::try_from(factor).unwrap(),
                 max_volume_factor: FeeFactor::try_from(max_volume_factor).unwrap(),
@@ -322,9 +341,11 @@ impl FeePolicy {
                     fee: quote.fee,
                     solver: quote.solver,
                 },
+                recipient: recipient.map(Into::into),
             },
-            Self::Volume { factor } => domain::fee::Policy::Volume {
+            Self::Volume { factor, recipient } => domain::fee::Policy::Volume {
                 factor: FeeFactor::try_from(factor).unwrap(),
+                recipient: recipient.map(Into::into),
             },
         }
     }
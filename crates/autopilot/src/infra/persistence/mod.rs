@@ -1,7 +1,10 @@
 use {
     crate::{
         boundary,
-        database::{order_events::store_order_events, Postgres},
+        database::{
+            order_events::{store_order_events, store_order_events_with_reasons},
+            Postgres,
+        },
         domain::{self, eth},
         infra::persistence::dto::AuctionId,
     },
@@ -231,6 +234,25 @@ impl Persistence {
         );
     }
 
+    /// Inserts an order event with the reason why it happened for each of the
+    /// given orders. Just like [`Self::store_order_events`] errors only get
+    /// printed.
+    pub fn store_order_events_with_reasons(
+        &self,
+        events: impl IntoIterator<Item = (domain::OrderUid, boundary::OrderEventReason)>,
+        label: boundary::OrderEventLabel,
+    ) {
+        let db = self.postgres.clone();
+        let events = events.into_iter().collect();
+        tokio::spawn(
+            async move {
+                let mut tx = db.pool.acquire().await.expect("failed to acquire tx");
+                store_order_events_with_reasons(&mut tx, events, label, Utc::now()).await;
+            }
+            .instrument(tracing::Span::current()),
+        );
+    }
+
     /// Saves the given fee policies to the DB as a single batch.
     pub async fn store_fee_policies(
         &self,
@@ -639,6 +661,7 @@ impl Persistence {
         &self,
         event: domain::eth::SettlementEvent,
         auction_id: domain::auction::Id,
+        solver: Option<domain::eth::Address>,
         settlement: Option<&domain::settlement::Settlement>,
     ) -> Result<(), DatabaseError> {
         let _timer = Metrics::get()
@@ -659,6 +682,16 @@ impl Persistence {
         )
         .await?;
 
+        if let Some(solver) = solver {
+            database::settlements::update_settlement_solver(
+                &mut ex,
+                block_number,
+                log_index,
+                ByteArray(solver.0 .0),
+            )
+            .await?;
+        }
+
         if let Some(settlement) = settlement {
             let gas = settlement.gas();
             let gas_price = settlement.gas_price();
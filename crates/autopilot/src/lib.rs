@@ -11,5 +11,6 @@ pub mod run_loop;
 pub mod shadow;
 pub mod solvable_orders;
 pub mod util;
+pub mod watch_tower;
 
 pub use self::run::{run, start};
@@ -3,6 +3,7 @@ use {
         arguments::Arguments,
         boundary,
         database::{
+            conditional_orders::event_retriever::ComposableCoWRetriever,
             ethflow_events::event_retriever::EthFlowRefundRetriever,
             onchain_order_events::{
                 ethflow_events::{
@@ -22,6 +23,7 @@ use {
         run_loop::{self, RunLoop},
         shadow,
         solvable_orders::SolvableOrdersCache,
+        watch_tower::WatchTower,
     },
     chain::Chain,
     clap::Parser,
@@ -330,6 +332,7 @@ pub async fn run(args: Arguments) {
             bad_token_detector: bad_token_detector.clone(),
             tokens: token_info_fetcher.clone(),
             code_fetcher: code_fetcher.clone(),
+            balance_override_storage: Arc::new(db.pool.clone()),
         },
     )
     .await
@@ -476,6 +479,40 @@ pub async fn run(args: Arguments) {
     let mut maintenance = Maintenance::new(settlement_event_indexer, db.clone());
     maintenance.with_cow_amms(&cow_amm_registry);
 
+    if let Some(composable_cow_contract) = args.composable_cow_contract {
+        let start_block = determine_ethflow_indexing_start(
+            &skip_event_sync_start,
+            args.composable_cow_indexing_start,
+            &web3,
+            chain_id,
+        )
+        .await;
+        let conditional_order_indexer = EventUpdater::new_skip_blocks_before(
+            ComposableCoWRetriever::new(web3.clone(), composable_cow_contract),
+            db.clone(),
+            block_retriever.clone(),
+            start_block,
+        )
+        .await
+        .expect("Should be able to initialize event updater. Database read issues?");
+        // conditional orders only need to be indexed before they get polled, so
+        // this happens in a background task independent of the auctions
+        let service_maintainer = ServiceMaintenance::new(vec![Arc::new(conditional_order_indexer)]);
+        tokio::task::spawn(
+            service_maintainer.run_maintenance_on_new_block(eth.current_block().clone()),
+        );
+
+        WatchTower::new(
+            db.clone(),
+            web3.clone(),
+            contracts::ComposableCoW::at(&web3, composable_cow_contract),
+            http_factory.create(),
+            args.orderbook_url.clone(),
+            DomainSeparator::new(chain_id, eth.contracts().settlement().address()),
+        )
+        .spawn(eth.current_block().clone());
+    }
+
     if let Some(ethflow_contract) = args.ethflow_contract {
         let ethflow_refund_start_block = determine_ethflow_refund_indexing_start(
             &skip_event_sync_start,
@@ -520,7 +557,7 @@ pub async fn run(args: Arguments) {
             // interface called CoWSwapOnchainOrders.
             CoWSwapOnchainOrdersContract::new(web3.clone(), ethflow_contract),
             onchain_order_event_parser,
-            block_retriever,
+            block_retriever.clone(),
             ethflow_start_block,
         )
         .await
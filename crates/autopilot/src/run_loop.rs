@@ -1,6 +1,6 @@
 use {
     crate::{
-        database::competition::Competition,
+        database::competition::{Competition, Winner},
         domain::{
             self,
             auction::Id,
@@ -352,22 +352,34 @@ impl RunLoop {
         block_deadline: u64,
     ) -> Result<()> {
         let start = Instant::now();
-        // TODO: Support multiple winners
-        // https://github.com/cowprotocol/services/issues/3021
-        let Some(winning_solution) = solutions
+        let reference_scores = self.compute_reference_scores(solutions);
+        let mut winners = Vec::<Winner>::new();
+        for solution in solutions
             .iter()
-            .find(|participant| participant.is_winner())
+            .filter(|participant| participant.is_winner())
             .map(|participant| participant.solution())
-        else {
+        {
+            let score = solution.score().get().0;
+            match winners
+                .iter_mut()
+                .find(|winner| winner.solver == solution.solver().0)
+            {
+                // A solver can win with multiple solutions, in which case
+                // its scores are accumulated.
+                Some(winner) => winner.score = winner.score.saturating_add(score),
+                None => winners.push(Winner {
+                    solver: solution.solver().0,
+                    score,
+                    reference_score: reference_scores
+                        .get(&solution.solver())
+                        .map(|score| score.0)
+                        .unwrap_or_default(),
+                }),
+            }
+        }
+        if winners.is_empty() {
             return Err(anyhow::anyhow!("no winners found"));
-        };
-        let winner = winning_solution.solver().into();
-        let winning_score = winning_solution.score().get().0;
-        let reference_score = solutions
-            // todo multiple winners per auction
-            .get(1)
-            .map(|participant| participant.solution().score().get().0)
-            .unwrap_or_default();
+        }
         let participants = solutions
             .iter()
             .map(|participant| participant.solution().solver().into())
@@ -439,9 +451,7 @@ impl RunLoop {
         };
         let competition = Competition {
             auction_id: auction.id,
-            winner,
-            winning_score,
-            reference_score,
+            winners,
             participants,
             prices: auction
                 .prices
@@ -539,18 +549,38 @@ impl RunLoop {
                 }
             });
 
-        // Winners are selected one by one, starting from the best solution,
-        // until `max_winners_per_auction` are selected. The solution is a winner
-        // if it swaps tokens that are not yet swapped by any previously processed
-        // solution.
-        let wrapped_native_token = self.eth.contracts().wrapped_native_token();
+        let solutions = solutions.cloned().collect::<Vec<_>>();
+        let winners = Self::select_winners(
+            solutions.iter().map(|participant| participant.solution()),
+            self.eth.contracts().wrapped_native_token(),
+            self.config.max_winners_per_auction,
+        );
+        solutions
+            .into_iter()
+            .zip(winners)
+            .map(|(participant, is_winner)| participant.rank(is_winner))
+            .collect()
+    }
+
+    /// Selects the winners among the solutions, which are expected to be
+    /// sorted by score (best to worst). Winners are selected one by one,
+    /// starting from the best solution, until `max_winners` are selected. A
+    /// solution is a winner if it neither settles an order nor swaps a token
+    /// that is already part of any previously processed solution.
+    ///
+    /// Returns whether the solution at the respective position is a winner.
+    fn select_winners<'a>(
+        solutions: impl IntoIterator<Item = &'a Solution>,
+        wrapped_native_token: eth::WrappedNativeToken,
+        max_winners: usize,
+    ) -> Vec<bool> {
         let mut already_swapped_tokens = HashSet::new();
+        let mut already_settled_orders = HashSet::new();
         let mut winners = 0;
-        let solutions = solutions
-            .cloned()
-            .map(|participant| {
-                let swapped_tokens = participant
-                    .solution()
+        solutions
+            .into_iter()
+            .map(|solution| {
+                let swapped_tokens = solution
                     .orders()
                     .iter()
                     .flat_map(|(_, order)| {
@@ -560,18 +590,53 @@ impl RunLoop {
                         ]
                     })
                     .collect::<HashSet<_>>();
+                let settled_orders = solution.order_ids().copied().collect::<HashSet<_>>();
 
                 let is_winner = swapped_tokens.is_disjoint(&already_swapped_tokens)
-                    && winners < self.config.max_winners_per_auction;
+                    && settled_orders.is_disjoint(&already_settled_orders)
+                    && winners < max_winners;
 
                 already_swapped_tokens.extend(swapped_tokens);
+                already_settled_orders.extend(settled_orders);
                 winners += usize::from(is_winner);
 
-                participant.rank(is_winner)
+                is_winner
             })
-            .collect();
+            .collect()
+    }
 
+    /// Computes the reference score of every winning solver. The reference
+    /// score is the total score of all winning solutions the auction would
+    /// have had if the solver had not participated in the competition.
+    fn compute_reference_scores(
+        &self,
+        solutions: &[competition::Participant],
+    ) -> HashMap<eth::Address, eth::Ether> {
         solutions
+            .iter()
+            .filter(|participant| participant.is_winner())
+            .map(|participant| participant.solution().solver())
+            .unique()
+            .map(|solver| {
+                let others = solutions
+                    .iter()
+                    .map(|participant| participant.solution())
+                    .filter(|solution| solution.solver() != solver)
+                    .collect::<Vec<_>>();
+                let winners = Self::select_winners(
+                    others.iter().copied(),
+                    self.eth.contracts().wrapped_native_token(),
+                    self.config.max_winners_per_auction,
+                );
+                let reference_score = others
+                    .iter()
+                    .zip(winners)
+                    .filter(|(_, is_winner)| *is_winner)
+                    .map(|(solution, _)| *solution.score().get())
+                    .sum();
+                (solver, reference_score)
+            })
+            .collect()
     }
 
     /// Returns true if solution is fair to other solutions
@@ -1108,3 +1173,63 @@ pub mod observe {
         super::Metrics::matched_unsettled(winner.driver(), non_winning_orders);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::domain::{auction::order::Side, competition::Score},
+        primitive_types::H160,
+    };
+
+    fn solution(id: u64, score: u64, orders: &[(u8, u8, u8)]) -> Solution {
+        let token = |t: u8| eth::TokenAddress(H160([t; 20]));
+        Solution::new(
+            id,
+            eth::Address(H160([id as u8; 20])),
+            Score::new(eth::Ether(score.into())).unwrap(),
+            orders
+                .iter()
+                .map(|&(uid, sell, buy)| {
+                    (
+                        OrderUid([uid; 56]),
+                        TradedOrder {
+                            side: Side::Sell,
+                            sell: eth::Asset {
+                                amount: eth::TokenAmount(1.into()),
+                                token: token(sell),
+                            },
+                            buy: eth::Asset {
+                                amount: eth::TokenAmount(1.into()),
+                                token: token(buy),
+                            },
+                            executed_sell: eth::TokenAmount(1.into()),
+                            executed_buy: eth::TokenAmount(1.into()),
+                        },
+                    )
+                })
+                .collect(),
+            Default::default(),
+        )
+    }
+
+    #[test]
+    fn selects_non_overlapping_winners() {
+        let weth = eth::WrappedNativeToken::from(eth::TokenAddress(H160([0xff; 20])));
+        let solutions = [
+            solution(1, 100, &[(1, 1, 2)]),
+            // conflicts on token with the first solution
+            solution(2, 90, &[(2, 2, 3)]),
+            solution(3, 80, &[(3, 4, 5)]),
+            // conflicts on order with the third solution
+            solution(4, 70, &[(3, 6, 7)]),
+            solution(5, 60, &[(5, 8, 9)]),
+        ];
+
+        let winners = RunLoop::select_winners(&solutions, weth, usize::MAX);
+        assert_eq!(winners, [true, false, true, false, true]);
+
+        let winners = RunLoop::select_winners(&solutions, weth, 2);
+        assert_eq!(winners, [true, false, true, false, false]);
+    }
+}
@@ -6,7 +6,7 @@ use {
     },
     anyhow::{Context, Result},
     bigdecimal::BigDecimal,
-    database::order_events::OrderEventLabel,
+    database::order_events::{OrderEventLabel, OrderEventReason},
     futures::{future::join_all, FutureExt},
     indexmap::IndexSet,
     itertools::{Either, Itertools},
@@ -169,25 +169,28 @@ impl SolvableOrdersCache {
             .collect::<Vec<_>>();
 
         let mut counter = OrderFilterCounter::new(self.metrics, &orders);
-        let mut invalid_order_uids = HashSet::new();
-        let mut filtered_order_events = Vec::new();
+        let mut invalid_orders = HashMap::new();
+        let mut filtered_orders = Vec::new();
 
         let (balances, orders, cow_amms) = {
             let queries = orders.iter().map(Query::from_order).collect::<Vec<_>>();
             tokio::join!(
                 self.fetch_balances(queries),
-                self.filter_invalid_orders(orders, &mut counter, &mut invalid_order_uids,),
+                self.filter_invalid_orders(orders, &mut counter, &mut invalid_orders),
                 self.timed_future("cow_amm_registry", self.cow_amm_registry.amms()),
             )
         };
 
         let orders = orders_with_balance(orders, &balances);
-        let removed = counter.checkpoint("insufficient_balance", &orders);
-        invalid_order_uids.extend(removed);
+        let removed = counter.checkpoint_with_details("insufficient_balance", &orders, |uid| {
+            let order = db_solvable_orders.orders.get(&domain::OrderUid(uid.0))?;
+            Some(insufficient_balance_details(order, &balances))
+        });
+        invalid_orders.extend(removed);
 
         let orders = filter_dust_orders(orders, &balances);
         let removed = counter.checkpoint("dust_order", &orders);
-        filtered_order_events.extend(removed);
+        filtered_orders.extend(removed);
 
         let cow_amm_tokens = cow_amms
             .iter()
@@ -223,26 +226,31 @@ impl SolvableOrdersCache {
             entry.insert(weth_price);
         }
 
-        let removed = counter.checkpoint("missing_price", &orders);
-        filtered_order_events.extend(removed);
+        let removed = counter.checkpoint_with_details("missing_price", &orders, |uid| {
+            let order = db_solvable_orders.orders.get(&domain::OrderUid(uid.0))?;
+            Some(missing_price_details(order, &prices))
+        });
+        filtered_orders.extend(removed);
 
         let orders = filter_mispriced_limit_orders(orders, &prices, &self.limit_order_price_factor);
         let removed = counter.checkpoint("out_of_market", &orders);
-        filtered_order_events.extend(removed);
+        filtered_orders.extend(removed);
 
         let removed = counter.record(&orders);
-        filtered_order_events.extend(removed);
+        filtered_orders.extend(removed);
 
         // spawning a background task since `order_events` table insert operation takes
         // a while and the result is ignored.
-        self.persistence.store_order_events(
-            invalid_order_uids.iter().map(|id| domain::OrderUid(id.0)),
+        self.persistence.store_order_events_with_reasons(
+            invalid_orders
+                .into_iter()
+                .map(|(uid, reason)| (domain::OrderUid(uid.0), reason)),
             OrderEventLabel::Invalid,
         );
-        self.persistence.store_order_events(
-            filtered_order_events
-                .iter()
-                .map(|id| domain::OrderUid(id.0)),
+        self.persistence.store_order_events_with_reasons(
+            filtered_orders
+                .into_iter()
+                .map(|(uid, reason)| (domain::OrderUid(uid.0), reason)),
             OrderEventLabel::Filtered,
         );
 
@@ -365,7 +373,7 @@ impl SolvableOrdersCache {
         &self,
         mut orders: Vec<Order>,
         counter: &mut OrderFilterCounter,
-        invalid_order_uids: &mut HashSet<OrderUid>,
+        invalid_orders: &mut HashMap<OrderUid, OrderEventReason>,
     ) -> Vec<Order> {
         let (banned_user_orders, invalid_signature_orders, unsupported_token_orders) = tokio::join!(
             self.timed_future(
@@ -382,14 +390,16 @@ impl SolvableOrdersCache {
             ),
         );
 
-        counter.checkpoint_by_invalid_orders("banned_user", &banned_user_orders);
-        counter.checkpoint_by_invalid_orders("invalid_signature", &invalid_signature_orders);
-        counter.checkpoint_by_invalid_orders("unsupported_token", &unsupported_token_orders);
-        invalid_order_uids.extend(banned_user_orders);
-        invalid_order_uids.extend(invalid_signature_orders);
-        invalid_order_uids.extend(unsupported_token_orders);
+        invalid_orders
+            .extend(counter.checkpoint_by_invalid_orders("banned_user", &banned_user_orders));
+        invalid_orders.extend(
+            counter.checkpoint_by_invalid_orders("invalid_signature", &invalid_signature_orders),
+        );
+        invalid_orders.extend(
+            counter.checkpoint_by_invalid_orders("unsupported_token", &unsupported_token_orders),
+        );
 
-        orders.retain(|order| !invalid_order_uids.contains(&order.metadata.uid));
+        orders.retain(|order| !invalid_orders.contains_key(&order.metadata.uid));
         orders
     }
 
@@ -731,6 +741,39 @@ fn filter_mispriced_limit_orders(
     orders
 }
 
+/// Builds the reason that gets stored alongside the event of an order that got
+/// removed from the auction.
+fn event_reason(reason: Reason, details: Option<serde_json::Value>) -> OrderEventReason {
+    OrderEventReason {
+        reason: reason.to_string(),
+        details,
+    }
+}
+
+/// Context for orders whose owner does not have enough sell token balance.
+fn insufficient_balance_details(order: &Order, balances: &Balances) -> serde_json::Value {
+    // Partially fillable orders only need some balance to be tradable.
+    let required = match order.data.partially_fillable {
+        true => U256::one(),
+        false => order.data.sell_amount.saturating_add(order.data.fee_amount),
+    };
+    serde_json::json!({
+        "token": order.data.sell_token,
+        "balance": balances.get(&Query::from_order(order)).map(U256::to_string),
+        "required": required.to_string(),
+    })
+}
+
+/// Context for orders that are missing a native price for one of their tokens.
+fn missing_price_details(order: &Order, prices: &BTreeMap<H160, U256>) -> serde_json::Value {
+    let tokens = [order.data.sell_token, order.data.buy_token]
+        .into_iter()
+        .filter(|token| !prices.contains_key(token))
+        .unique()
+        .collect::<Vec<_>>();
+    serde_json::json!({ "tokens": tokens })
+}
+
 /// Order filtering state for recording filtered orders over the course of
 /// building an auction.
 struct OrderFilterCounter {
@@ -771,7 +814,22 @@ impl OrderFilterCounter {
     }
 
     /// Creates a new checkpoint from the current remaining orders.
-    fn checkpoint(&mut self, reason: Reason, orders: &[Order]) -> Vec<OrderUid> {
+    fn checkpoint(
+        &mut self,
+        reason: Reason,
+        orders: &[Order],
+    ) -> Vec<(OrderUid, OrderEventReason)> {
+        self.checkpoint_with_details(reason, orders, |_| None)
+    }
+
+    /// Creates a new checkpoint from the current remaining orders and attaches
+    /// additional context to the reason of every filtered order.
+    fn checkpoint_with_details(
+        &mut self,
+        reason: Reason,
+        orders: &[Order],
+        details: impl Fn(&OrderUid) -> Option<serde_json::Value>,
+    ) -> Vec<(OrderUid, OrderEventReason)> {
         let filtered_orders = orders
             .iter()
             .fold(self.orders.clone(), |mut order_uids, order| {
@@ -790,13 +848,23 @@ impl OrderFilterCounter {
                 orders = ?filtered_orders, "filtered orders"
             );
         }
-        filtered_orders.into_keys().collect()
+        filtered_orders
+            .into_keys()
+            .map(|uid| {
+                let details = details(&uid);
+                (uid, event_reason(reason, details))
+            })
+            .collect()
     }
 
     /// Creates a new checkpoint based on the found invalid orders.
-    fn checkpoint_by_invalid_orders(&mut self, reason: Reason, invalid_orders: &[OrderUid]) {
+    fn checkpoint_by_invalid_orders(
+        &mut self,
+        reason: Reason,
+        invalid_orders: &[OrderUid],
+    ) -> Vec<(OrderUid, OrderEventReason)> {
         if invalid_orders.is_empty() {
-            return;
+            return Vec::new();
         }
 
         let mut counter = 0;
@@ -813,13 +881,17 @@ impl OrderFilterCounter {
                 orders = ?invalid_orders, "filtered orders"
             );
         }
+        invalid_orders
+            .iter()
+            .map(|uid| (*uid, event_reason(reason, None)))
+            .collect()
     }
 
     /// Records the filter counter to metrics.
     /// If there are orders that have been filtered out since the last
     /// checkpoint these orders will get recorded with the readon "other".
     /// Returns these catch-all orders.
-    fn record(mut self, orders: &[Order]) -> Vec<OrderUid> {
+    fn record(mut self, orders: &[Order]) -> Vec<(OrderUid, OrderEventReason)> {
         let removed = self.checkpoint("other", orders);
 
         self.metrics.auction_creations.inc();
@@ -1370,4 +1442,41 @@ mod tests {
             token(5), // coming from limit order (part of 1 orders)
         ]));
     }
+
+    #[test]
+    fn filter_reason_details() {
+        let token = H160::from_low_u64_be;
+        let order = OrderBuilder::default()
+            .with_sell_token(token(1))
+            .with_buy_token(token(2))
+            .with_sell_amount(10.into())
+            .with_fee_amount(1.into())
+            .build();
+
+        let balances = [(Query::from_order(&order), U256::from(5))]
+            .into_iter()
+            .collect();
+        assert_eq!(
+            insufficient_balance_details(&order, &balances),
+            serde_json::json!({
+                "token": token(1),
+                "balance": "5",
+                "required": "11",
+            })
+        );
+        assert_eq!(
+            insufficient_balance_details(&order, &Default::default()),
+            serde_json::json!({
+                "token": token(1),
+                "balance": null,
+                "required": "11",
+            })
+        );
+
+        let prices = btreemap! { token(1) => U256::one() };
+        assert_eq!(
+            missing_price_details(&order, &prices),
+            serde_json::json!({ "tokens": [token(2)] })
+        );
+    }
 }
@@ -0,0 +1,511 @@
+//! Places the discrete orders of conditional orders created via the
+//! ComposableCoW contract (e.g. the parts of a TWAP order) in the orderbook as
+//! soon as they become tradeable.
+//!
+//! Every conditional order gets polled by calling
+//! `ComposableCoW.getTradeableOrderWithSignature` on the current block. The
+//! handler of the conditional order either returns a discrete order with its
+//! EIP-1271 signature or reverts with a hint when to poll again.
+
+use {
+    crate::database::{conditional_orders::event_storing::params_hash, Postgres},
+    anyhow::{anyhow, Context, Result},
+    app_data::AppDataHash,
+    database::{byte_array::ByteArray, conditional_orders::ConditionalOrder},
+    ethcontract::{dyns::DynWeb3, Bytes, H160, U256},
+    ethrpc::block_stream::{into_stream, BlockInfo, CurrentBlockWatcher},
+    futures::StreamExt,
+    model::{
+        order::{
+            BuyTokenDestination,
+            OrderCreation,
+            OrderCreationAppData,
+            OrderKind,
+            OrderUid,
+            SellTokenSource,
+        },
+        signature::Signature,
+        DomainSeparator,
+    },
+    prometheus::IntCounterVec,
+    serde::Deserialize,
+    std::{collections::HashMap, sync::Mutex},
+    tracing::Instrument,
+    url::Url,
+    web3::{
+        ethabi::Token,
+        types::{BlockId, CallRequest},
+    },
+};
+
+/// How many conditional orders get polled concurrently.
+const MAX_CONCURRENT_POLLS: usize = 10;
+
+pub struct WatchTower {
+    db: Postgres,
+    web3: DynWeb3,
+    composable_cow: contracts::ComposableCoW,
+    client: reqwest::Client,
+    orderbook: Url,
+    domain_separator: DomainSeparator,
+    /// The discrete order which was placed last for every conditional order
+    /// together with its `valid_to`. Handlers keep returning the same
+    /// discrete order until it expires or gets filled, so it only gets placed
+    /// once. Entries get dropped once the order expired.
+    placed: Mutex<HashMap<(H160, [u8; 32]), (OrderUid, u32)>>,
+}
+
+/// The error body returned by the orderbook API.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ApiError {
+    error_type: String,
+    description: String,
+}
+
+/// What polling a conditional order resulted in.
+#[derive(Debug)]
+enum Poll {
+    /// The discrete order can be placed now.
+    Order(Box<OrderCreation>),
+    /// There is no order to place yet, poll again on the next block.
+    TryNextBlock(String),
+    /// Poll again once the given block got mined.
+    TryAtBlock(u64, String),
+    /// Poll again once a block with at least the given timestamp got mined.
+    TryAtEpoch(u64, String),
+    /// The conditional order will never produce another discrete order, e.g.
+    /// because the owner cancelled it.
+    Never(String),
+}
+
+impl WatchTower {
+    pub fn new(
+        db: Postgres,
+        web3: DynWeb3,
+        composable_cow: contracts::ComposableCoW,
+        client: reqwest::Client,
+        orderbook: Url,
+        domain_separator: DomainSeparator,
+    ) -> Self {
+        Self {
+            db,
+            web3,
+            composable_cow,
+            client,
+            orderbook,
+            domain_separator,
+            placed: Default::default(),
+        }
+    }
+
+    /// Spawns a background task polling all due conditional orders on every
+    /// new block.
+    pub fn spawn(self, current_block: CurrentBlockWatcher) {
+        tokio::task::spawn(
+            async move {
+                let mut stream = into_stream(current_block);
+                while let Some(block) = stream.next().await {
+                    if let Err(err) = self.poll_all(&block).await {
+                        tracing::warn!(
+                            ?err,
+                            block = block.number,
+                            "failed to poll conditional orders"
+                        );
+                    }
+                }
+                panic!("block stream terminated unexpectedly");
+            }
+            .instrument(tracing::info_span!("watch_tower")),
+        );
+    }
+
+    async fn poll_all(&self, block: &BlockInfo) -> Result<()> {
+        self.placed
+            .lock()
+            .unwrap()
+            .retain(|_, (_, valid_to)| u64::from(*valid_to) >= block.timestamp);
+        let orders = {
+            let mut ex = self.db.pool.acquire().await?;
+            database::conditional_orders::due(
+                &mut ex,
+                i64::try_from(block.number).unwrap_or(i64::MAX),
+                i64::try_from(block.timestamp).unwrap_or(i64::MAX),
+            )
+            .await?
+        };
+        futures::stream::iter(orders)
+            .for_each_concurrent(MAX_CONCURRENT_POLLS, |order| async move {
+                if let Err(err) = self.poll_and_handle(&order, block).await {
+                    tracing::warn!(
+                        ?err,
+                        owner = ?H160(order.owner.0),
+                        "failed to poll conditional order"
+                    );
+                }
+            })
+            .await;
+        Ok(())
+    }
+
+    async fn poll_and_handle(&self, order: &ConditionalOrder, block: &BlockInfo) -> Result<()> {
+        let key = (H160(order.owner.0), order.params_hash.0);
+        let poll = self.poll(order, block).await?;
+        Metrics::get()
+            .polls
+            .with_label_values(&[match &poll {
+                Poll::Order(_) => "order",
+                Poll::TryNextBlock(_) => "try_next_block",
+                Poll::TryAtBlock(..) => "try_at_block",
+                Poll::TryAtEpoch(..) => "try_at_epoch",
+                Poll::Never(_) => "never",
+            }])
+            .inc();
+
+        let mut ex = self.db.pool.acquire().await?;
+        match poll {
+            Poll::Order(creation) => {
+                let uid = creation
+                    .data()
+                    .uid(&self.domain_separator, &H160(order.owner.0));
+                if self
+                    .placed
+                    .lock()
+                    .unwrap()
+                    .get(&key)
+                    .is_some_and(|(placed, _)| *placed == uid)
+                {
+                    return Ok(());
+                }
+                // The order might have been placed before a restart.
+                let exists = database::orders::read_order(&mut ex, &ByteArray(uid.0))
+                    .await?
+                    .is_some();
+                if !exists {
+                    self.place(&creation).await?;
+                    tracing::info!(?uid, "placed discrete order of conditional order");
+                }
+                self.placed
+                    .lock()
+                    .unwrap()
+                    .insert(key, (uid, creation.data().valid_to));
+            }
+            Poll::TryNextBlock(reason) => {
+                tracing::trace!(owner = ?key.0, reason, "no tradeable order yet");
+            }
+            Poll::TryAtBlock(block, reason) => {
+                tracing::debug!(owner = ?key.0, block, reason, "postponing conditional order");
+                database::conditional_orders::schedule(
+                    &mut ex,
+                    &order.owner,
+                    &order.params_hash,
+                    i64::try_from(block).unwrap_or(i64::MAX),
+                    0,
+                )
+                .await?;
+            }
+            Poll::TryAtEpoch(timestamp, reason) => {
+                tracing::debug!(owner = ?key.0, timestamp, reason, "postponing conditional order");
+                database::conditional_orders::schedule(
+                    &mut ex,
+                    &order.owner,
+                    &order.params_hash,
+                    0,
+                    i64::try_from(timestamp).unwrap_or(i64::MAX),
+                )
+                .await?;
+            }
+            Poll::Never(reason) => {
+                tracing::info!(owner = ?key.0, reason, "conditional order will never trade again");
+                database::conditional_orders::delete(&mut ex, &order.owner, &order.params_hash)
+                    .await?;
+                self.placed.lock().unwrap().remove(&key);
+            }
+        }
+        Ok(())
+    }
+
+    /// Asks the handler of the conditional order for a tradeable order.
+    async fn poll(&self, order: &ConditionalOrder, block: &BlockInfo) -> Result<Poll> {
+        let owner = H160(order.owner.0);
+        let handler = H160(order.handler.0);
+        debug_assert_eq!(
+            params_hash(handler, order.salt.0, &order.static_input),
+            order.params_hash.0
+        );
+        let call = self.composable_cow.get_tradeable_order_with_signature(
+            owner,
+            (
+                handler,
+                Bytes(order.salt.0),
+                Bytes(order.static_input.clone()),
+            ),
+            Bytes(Default::default()),
+            Default::default(),
+        );
+        let request = CallRequest {
+            to: Some(self.composable_cow.address()),
+            data: call.m.tx.data,
+            ..Default::default()
+        };
+        // The contract call is made manually since the generated bindings
+        // drop the data of custom errors which contains the polling hints.
+        let result = self
+            .web3
+            .eth()
+            .call(request, Some(BlockId::Number(block.number.into())))
+            .await;
+        match result {
+            Ok(output) => {
+                let (order, signature) = decode_tradeable_order(&output.0)?;
+                Ok(Poll::Order(Box::new(order_creation(
+                    owner, order, signature,
+                )?)))
+            }
+            Err(web3::Error::Rpc(err)) => {
+                let data = err
+                    .data
+                    .as_ref()
+                    .and_then(|data| data.as_str())
+                    .and_then(|data| hex::decode(data.trim_start_matches("0x")).ok())
+                    .ok_or_else(|| anyhow!("call failed without revert data: {err:?}"))?;
+                Ok(decode_revert(&data))
+            }
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    /// Places the order in the orderbook. Orders which were already placed
+    /// (e.g. before a restart) are accepted as well.
+    async fn place(&self, order: &OrderCreation) -> Result<()> {
+        let url = shared::url::join(&self.orderbook, "api/v1/orders");
+        let response = self.client.post(url).json(order).send().await?;
+        let status = response.status();
+        let result = if status.is_success() {
+            Ok(())
+        } else {
+            let body = response.text().await.unwrap_or_default();
+            match serde_json::from_str::<ApiError>(&body) {
+                Ok(err) if err.error_type == "DuplicatedOrder" => Ok(()),
+                Ok(err) => Err(anyhow!(
+                    "orderbook rejected order with {status}: {}: {}",
+                    err.error_type,
+                    err.description
+                )),
+                Err(_) => Err(anyhow!("orderbook rejected order with {status}: {body}")),
+            }
+        };
+        Metrics::get()
+            .placed_orders
+            .with_label_values(&[match &result {
+                Ok(()) if status.is_success() => "success",
+                Ok(()) => "duplicate",
+                Err(_) => "error",
+            }])
+            .inc();
+        result
+    }
+}
+
+/// Decodes the return data of `getTradeableOrderWithSignature`.
+fn decode_tradeable_order(output: &[u8]) -> Result<(Vec<Token>, Vec<u8>)> {
+    let function = contracts::ComposableCoW::raw_contract()
+        .interface
+        .abi
+        .function("getTradeableOrderWithSignature")
+        .context("missing getTradeableOrderWithSignature function")?;
+    let mut tokens = function.decode_output(output)?.into_iter();
+    let order = tokens
+        .next()
+        .and_then(Token::into_tuple)
+        .context("missing order")?;
+    let signature = tokens
+        .next()
+        .and_then(Token::into_bytes)
+        .context("missing signature")?;
+    Ok((order, signature))
+}
+
+/// Converts a `GPv2Order.Data` into an order which can be placed in the
+/// orderbook on behalf of the owner.
+fn order_creation(owner: H160, order: Vec<Token>, signature: Vec<u8>) -> Result<OrderCreation> {
+    let mut fields = order.into_iter();
+    let mut next = |name: &str| fields.next().with_context(|| format!("missing {name}"));
+    let address = |token: Token| token.into_address().context("invalid address");
+    let uint = |token: Token| token.into_uint().context("invalid uint");
+    let bytes32 = |token: Token| -> Result<[u8; 32]> {
+        token
+            .into_fixed_bytes()
+            .and_then(|bytes| bytes.try_into().ok())
+            .context("invalid bytes32")
+    };
+
+    let sell_token = address(next("sell token")?)?;
+    let buy_token = address(next("buy token")?)?;
+    let receiver = address(next("receiver")?)?;
+    let sell_amount = uint(next("sell amount")?)?;
+    let buy_amount = uint(next("buy amount")?)?;
+    let valid_to = uint(next("valid to")?)?;
+    let app_data = bytes32(next("app data")?)?;
+    let fee_amount = uint(next("fee amount")?)?;
+    let kind = bytes32(next("kind")?)?;
+    let partially_fillable = next("partially fillable")?
+        .into_bool()
+        .context("invalid bool")?;
+    let sell_token_balance = bytes32(next("sell token balance")?)?;
+    let buy_token_balance = bytes32(next("buy token balance")?)?;
+
+    Ok(OrderCreation {
+        sell_token,
+        buy_token,
+        receiver: Some(receiver).filter(|receiver| !receiver.is_zero()),
+        sell_amount,
+        buy_amount,
+        valid_to: (valid_to <= U256::from(u32::MAX))
+            .then(|| valid_to.as_u32())
+            .context("valid to out of range")?,
+        fee_amount,
+        kind: OrderKind::from_contract_bytes(kind)?,
+        partially_fillable,
+        sell_token_balance: SellTokenSource::from_contract_bytes(sell_token_balance)?,
+        buy_token_balance: BuyTokenDestination::from_contract_bytes(buy_token_balance)?,
+        from: Some(owner),
+        signature: Signature::Eip1271(signature),
+        quote_id: None,
+        app_data: OrderCreationAppData::Hash {
+            hash: AppDataHash(app_data),
+        },
+    })
+}
+
+/// Interprets the custom error a conditional order reverted with.
+fn decode_revert(data: &[u8]) -> Poll {
+    let abi = &contracts::ComposableCoW::raw_contract().interface.abi;
+    let decoded = data.get(..4).and_then(|selector| {
+        abi.errors().find_map(|error| {
+            let types = error
+                .inputs
+                .iter()
+                .map(|input| input.kind.clone())
+                .collect::<Vec<_>>();
+            if web3::ethabi::short_signature(&error.name, &types) != selector {
+                return None;
+            }
+            let params = web3::ethabi::decode(&types, &data[4..]).ok()?;
+            Some((error.name.as_str(), params))
+        })
+    });
+    let Some((name, params)) = decoded else {
+        return Poll::TryNextBlock(format!("unknown revert 0x{}", hex::encode(data)));
+    };
+
+    let reason = params
+        .iter()
+        .rev()
+        .find_map(|param| param.clone().into_string())
+        .unwrap_or_else(|| name.to_owned());
+    let number = params
+        .first()
+        .and_then(|param| param.clone().into_uint())
+        .map(|number| number.try_into().unwrap_or(u64::MAX));
+    match (name, number) {
+        ("PollTryAtBlock", Some(block)) => Poll::TryAtBlock(block, reason),
+        ("PollTryAtEpoch", Some(timestamp)) => Poll::TryAtEpoch(timestamp, reason),
+        // The owner removed the conditional order, or it was never valid.
+        (
+            "PollNever"
+            | "ProofNotAuthed"
+            | "SingleOrderNotAuthed"
+            | "InvalidHandler"
+            | "InvalidFallbackHandler"
+            | "InterfaceNotSupported",
+            _,
+        ) => Poll::Never(reason),
+        // `OrderNotValid`, `PollTryNextBlock` and anything unexpected.
+        _ => Poll::TryNextBlock(reason),
+    }
+}
+
+#[derive(prometheus_metric_storage::MetricStorage)]
+#[metric(subsystem = "watch_tower")]
+struct Metrics {
+    /// Outcomes of polling conditional orders.
+    #[metric(labels("result"))]
+    polls: IntCounterVec,
+
+    /// Results of placing discrete orders in the orderbook.
+    #[metric(labels("result"))]
+    placed_orders: IntCounterVec,
+}
+
+impl Metrics {
+    fn get() -> &'static Self {
+        Self::instance(observe::metrics::get_storage_registry()).unwrap()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, web3::ethabi::ParamType};
+
+    fn revert(name: &str, params: &[Token]) -> Vec<u8> {
+        let types = params
+            .iter()
+            .map(|param| match param {
+                Token::Uint(_) => ParamType::Uint(256),
+                _ => ParamType::String,
+            })
+            .collect::<Vec<_>>();
+        [
+            web3::ethabi::short_signature(name, &types).to_vec(),
+            web3::ethabi::encode(params),
+        ]
+        .concat()
+    }
+
+    #[test]
+    fn decodes_polling_hints() {
+        let reason = || Token::String("not yet".to_owned());
+        assert!(matches!(
+            decode_revert(&revert("PollTryNextBlock", &[reason()])),
+            Poll::TryNextBlock(reason) if reason == "not yet"
+        ));
+        assert!(matches!(
+            decode_revert(&revert(
+                "PollTryAtBlock",
+                &[Token::Uint(42.into()), reason()]
+            )),
+            Poll::TryAtBlock(42, _)
+        ));
+        assert!(matches!(
+            decode_revert(&revert(
+                "PollTryAtEpoch",
+                &[Token::Uint(1_700_000_000.into()), reason()]
+            )),
+            Poll::TryAtEpoch(1_700_000_000, _)
+        ));
+        assert!(matches!(
+            decode_revert(&revert("PollNever", &[reason()])),
+            Poll::Never(_)
+        ));
+        assert!(matches!(
+            decode_revert(&revert("SingleOrderNotAuthed", &[])),
+            Poll::Never(reason) if reason == "SingleOrderNotAuthed"
+        ));
+        assert!(matches!(
+            decode_revert(&revert("OrderNotValid", &[reason()])),
+            Poll::TryNextBlock(_)
+        ));
+        assert!(matches!(decode_revert(&[1, 2, 3]), Poll::TryNextBlock(_)));
+    }
+
+    #[test]
+    fn deserializes_orderbook_errors() {
+        let err: ApiError = serde_json::from_str(
+            r#"{"errorType": "DuplicatedOrder", "description": "order already exists"}"#,
+        )
+        .unwrap();
+        assert_eq!(err.error_type, "DuplicatedOrder");
+        assert_eq!(err.description, "order already exists");
+    }
+}
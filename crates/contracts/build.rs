@@ -80,6 +80,15 @@ fn main() {
                 },
             )
     });
+    generate_contract_with_config("ComposableCoW", |builder| {
+        // <https://github.com/cowprotocol/composable-cow/blob/main/networks.json>
+        builder
+            .contract_mod_override("composable_cow")
+            .add_network_str(MAINNET, "0xfdaFc9d1902f4e0b84f65F49f244b32b31013b74")
+            .add_network_str(GNOSIS, "0xfdaFc9d1902f4e0b84f65F49f244b32b31013b74")
+            .add_network_str(SEPOLIA, "0xfdaFc9d1902f4e0b84f65F49f244b32b31013b74")
+            .add_network_str(ARBITRUM_ONE, "0xfdaFc9d1902f4e0b84f65F49f244b32b31013b74")
+    });
     generate_contract_with_config("CoWSwapOnchainOrders", |builder| {
         builder.contract_mod_override("cowswap_onchain_orders")
     });
@@ -70,6 +70,7 @@ include_contracts! {
     BalancerV2WeightedPoolFactoryV3;
     BalancerV2WeightedPoolFactoryV4;
     BaoswapRouter;
+    ComposableCoW;
     CowAmm;
     CowAmmConstantProductFactory;
     CowAmmLegacyHelper;
@@ -29,6 +29,21 @@ pub async fn fetch(
     Ok(row)
 }
 
+/// Fetches the ids of all auctions the given order was part of, in ascending
+/// order.
+pub async fn fetch_auctions_of_order(
+    ex: &mut PgConnection,
+    order: &OrderUid,
+) -> Result<Vec<AuctionId>, sqlx::Error> {
+    const QUERY: &str = r#"
+        SELECT auction_id
+        FROM auction_orders
+        WHERE order_uids @> ARRAY[$1]
+        ORDER BY auction_id
+    "#;
+    sqlx::query_scalar(QUERY).bind(order).fetch_all(ex).await
+}
+
 #[cfg(test)]
 mod tests {
     use {super::*, crate::byte_array::ByteArray, sqlx::Connection};
@@ -51,4 +66,26 @@ mod tests {
         let output = fetch(&mut db, 2).await.unwrap();
         assert!(output.is_none());
     }
+
+    #[tokio::test]
+    #[ignore]
+    async fn postgres_auctions_of_order() {
+        let mut db = PgConnection::connect("postgresql://").await.unwrap();
+        let mut db = db.begin().await.unwrap();
+        crate::clear_DANGER_(&mut db).await.unwrap();
+
+        let (a, b) = (ByteArray([1; 56]), ByteArray([2; 56]));
+        insert(&mut db, 1, &[a, b]).await.unwrap();
+        insert(&mut db, 2, &[b]).await.unwrap();
+        insert(&mut db, 3, &[a]).await.unwrap();
+
+        let output = fetch_auctions_of_order(&mut db, &a).await.unwrap();
+        assert_eq!(output, vec![1, 3]);
+        let output = fetch_auctions_of_order(&mut db, &b).await.unwrap();
+        assert_eq!(output, vec![1, 2]);
+        let output = fetch_auctions_of_order(&mut db, &ByteArray([3; 56]))
+            .await
+            .unwrap();
+        assert!(output.is_empty());
+    }
 }
@@ -55,6 +55,65 @@ SELECT * FROM auction_prices WHERE auction_id = (
     sqlx::query_as(QUERY).fetch_all(ex).await
 }
 
+/// Native price of a token in an auction created on top of `block`.
+#[derive(Debug, Clone, PartialEq, sqlx::FromRow)]
+pub struct BlockPrice {
+    pub block: i64,
+    pub price: BigDecimal,
+}
+
+/// Fetches the price of the token from the latest auction created at or
+/// before `block` and from the earliest auction created after `block`.
+///
+/// Auctions get created on increasing blocks, so the auctions surrounding the
+/// block are resolved first and the prices are then searched by auction id,
+/// which the `(token, auction_id)` index serves directly.
+pub async fn fetch_surrounding_block(
+    ex: &mut PgConnection,
+    token: &Address,
+    block: i64,
+) -> Result<(Option<BlockPrice>, Option<BlockPrice>), sqlx::Error> {
+    const BEFORE: &str = r#"
+SELECT ca.block, ap.price
+FROM auction_prices ap
+JOIN competition_auctions ca ON ca.id = ap.auction_id
+WHERE ap.token = $1 AND ap.auction_id <= (
+    SELECT id
+    FROM competition_auctions
+    WHERE block <= $2
+    ORDER BY block DESC, id DESC
+    LIMIT 1
+)
+ORDER BY ap.auction_id DESC
+LIMIT 1
+    "#;
+    const AFTER: &str = r#"
+SELECT ca.block, ap.price
+FROM auction_prices ap
+JOIN competition_auctions ca ON ca.id = ap.auction_id
+WHERE ap.token = $1 AND ap.auction_id >= (
+    SELECT id
+    FROM competition_auctions
+    WHERE block > $2
+    ORDER BY block ASC, id ASC
+    LIMIT 1
+)
+ORDER BY ap.auction_id ASC
+LIMIT 1
+    "#;
+    let before = sqlx::query_as(BEFORE)
+        .bind(token)
+        .bind(block)
+        .fetch_optional(&mut *ex)
+        .await?;
+    let after = sqlx::query_as(AFTER)
+        .bind(token)
+        .bind(block)
+        .fetch_optional(ex)
+        .await?;
+    Ok((before, after))
+}
+
 #[cfg(test)]
 mod tests {
     use {super::*, crate::byte_array::ByteArray, sqlx::Connection};
@@ -104,4 +163,62 @@ mod tests {
         let output = fetch(&mut db, 4).await.unwrap();
         assert!(output.is_empty());
     }
+
+    #[tokio::test]
+    #[ignore]
+    async fn postgres_fetch_surrounding_block() {
+        let mut db = PgConnection::connect("postgresql://").await.unwrap();
+        let mut db = db.begin().await.unwrap();
+        crate::clear_DANGER_(&mut db).await.unwrap();
+
+        let token = ByteArray([1; 20]);
+        for (id, block) in [(1, 10), (2, 20), (3, 30)] {
+            crate::auction::save(
+                &mut db,
+                crate::auction::Auction {
+                    id,
+                    block,
+                    deadline: block + 5,
+                    order_uids: Default::default(),
+                    price_tokens: Default::default(),
+                    price_values: Default::default(),
+                    surplus_capturing_jit_order_owners: Default::default(),
+                },
+            )
+            .await
+            .unwrap();
+        }
+        let prices = [(1, token, 4), (2, ByteArray([2; 20]), 5), (3, token, 6)].map(
+            |(auction_id, token, price)| AuctionPrice {
+                auction_id,
+                token,
+                price: price.into(),
+            },
+        );
+        insert(&mut db, &prices).await.unwrap();
+
+        let price = |block: i64, price: i32| BlockPrice {
+            block,
+            price: price.into(),
+        };
+        assert_eq!(
+            fetch_surrounding_block(&mut db, &token, 5).await.unwrap(),
+            (None, Some(price(10, 4)))
+        );
+        // Auction 2 doesn't have a price for the token.
+        assert_eq!(
+            fetch_surrounding_block(&mut db, &token, 20).await.unwrap(),
+            (Some(price(10, 4)), Some(price(30, 6)))
+        );
+        assert_eq!(
+            fetch_surrounding_block(&mut db, &token, 30).await.unwrap(),
+            (Some(price(30, 6)), None)
+        );
+        assert_eq!(
+            fetch_surrounding_block(&mut db, &ByteArray([3; 20]), 20)
+                .await
+                .unwrap(),
+            (None, None)
+        );
+    }
 }
@@ -0,0 +1,134 @@
+//! Tokens which drivers detected to be unsupported.
+
+use {
+    crate::Address,
+    chrono::{DateTime, Utc},
+    sqlx::PgConnection,
+};
+
+#[derive(Clone, Debug, Eq, PartialEq, sqlx::FromRow)]
+pub struct BadToken {
+    pub token: Address,
+    pub reason: String,
+    pub detected_at: DateTime<Utc>,
+}
+
+/// Inserts the token or updates the reason and detection time if the token
+/// was already stored.
+pub async fn upsert(ex: &mut PgConnection, token: &BadToken) -> Result<(), sqlx::Error> {
+    const QUERY: &str = r#"
+INSERT INTO bad_tokens (token, reason, detected_at)
+VALUES ($1, $2, $3)
+ON CONFLICT (token) DO UPDATE
+SET reason = EXCLUDED.reason, detected_at = EXCLUDED.detected_at
+;"#;
+    sqlx::query(QUERY)
+        .bind(token.token)
+        .bind(&token.reason)
+        .bind(token.detected_at)
+        .execute(ex)
+        .await?;
+    Ok(())
+}
+
+/// Fetches all tokens detected at or after the given time.
+pub async fn fetch_since(
+    ex: &mut PgConnection,
+    since: DateTime<Utc>,
+) -> Result<Vec<BadToken>, sqlx::Error> {
+    const QUERY: &str = r#"
+SELECT token, reason, detected_at
+FROM bad_tokens
+WHERE detected_at >= $1
+;"#;
+    sqlx::query_as(QUERY).bind(since).fetch_all(ex).await
+}
+
+/// Deletes the token. Returns whether the token was stored.
+pub async fn delete(ex: &mut PgConnection, token: &Address) -> Result<bool, sqlx::Error> {
+    const QUERY: &str = "DELETE FROM bad_tokens WHERE token = $1;";
+    let result = sqlx::query(QUERY).bind(token).execute(ex).await?;
+    Ok(result.rows_affected() > 0)
+}
+
+/// Deletes all tokens detected before the given time.
+pub async fn delete_before(
+    ex: &mut PgConnection,
+    before: DateTime<Utc>,
+) -> Result<u64, sqlx::Error> {
+    const QUERY: &str = "DELETE FROM bad_tokens WHERE detected_at < $1;";
+    let result = sqlx::query(QUERY).bind(before).execute(ex).await?;
+    Ok(result.rows_affected())
+}
+
+/// Deletes all tokens.
+pub async fn delete_all(ex: &mut PgConnection) -> Result<(), sqlx::Error> {
+    const QUERY: &str = "DELETE FROM bad_tokens;";
+    sqlx::query(QUERY).execute(ex).await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::byte_array::ByteArray,
+        chrono::{Duration, TimeZone},
+        sqlx::Connection,
+    };
+
+    #[tokio::test]
+    #[ignore]
+    async fn postgres_bad_tokens() {
+        let mut db = PgConnection::connect("postgresql://").await.unwrap();
+        let mut db = db.begin().await.unwrap();
+        crate::clear_DANGER_(&mut db).await.unwrap();
+
+        let now = Utc.timestamp_opt(1_700_000_000, 0).unwrap();
+        let old = BadToken {
+            token: ByteArray([1; 20]),
+            reason: "fee on transfer".to_owned(),
+            detected_at: now - Duration::days(2),
+        };
+        let new = BadToken {
+            token: ByteArray([2; 20]),
+            reason: "transfer reverts".to_owned(),
+            detected_at: now,
+        };
+        upsert(&mut db, &old).await.unwrap();
+        upsert(&mut db, &new).await.unwrap();
+
+        let since = now - Duration::days(1);
+        assert_eq!(
+            fetch_since(&mut db, since).await.unwrap(),
+            vec![new.clone()]
+        );
+
+        // Detecting the token again refreshes the entry.
+        let refreshed = BadToken {
+            detected_at: now,
+            ..old.clone()
+        };
+        upsert(&mut db, &refreshed).await.unwrap();
+        assert_eq!(fetch_since(&mut db, since).await.unwrap().len(), 2);
+
+        assert!(delete(&mut db, &new.token).await.unwrap());
+        assert!(!delete(&mut db, &new.token).await.unwrap());
+        assert_eq!(
+            fetch_since(&mut db, since).await.unwrap(),
+            vec![refreshed.clone()]
+        );
+
+        assert_eq!(
+            delete_before(&mut db, now + Duration::seconds(1))
+                .await
+                .unwrap(),
+            1
+        );
+        assert!(fetch_since(&mut db, since).await.unwrap().is_empty());
+
+        upsert(&mut db, &new).await.unwrap();
+        delete_all(&mut db).await.unwrap();
+        assert!(fetch_since(&mut db, since).await.unwrap().is_empty());
+    }
+}
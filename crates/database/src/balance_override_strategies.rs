@@ -0,0 +1,93 @@
+//! Balance override strategies detected for tokens during quote verification.
+
+use {
+    crate::{byte_array::ByteArray, Address},
+    chrono::{DateTime, Utc},
+    sqlx::PgConnection,
+};
+
+#[derive(Clone, Debug, Eq, PartialEq, sqlx::FromRow)]
+pub struct BalanceOverrideStrategy {
+    pub token: Address,
+    pub code_hash: ByteArray<32>,
+    /// [`None`] if no strategy could be detected for the token.
+    pub strategy: Option<String>,
+    pub detected_at: DateTime<Utc>,
+}
+
+/// Inserts the detection result or replaces the previous result of the token.
+pub async fn upsert(
+    ex: &mut PgConnection,
+    strategy: &BalanceOverrideStrategy,
+) -> Result<(), sqlx::Error> {
+    const QUERY: &str = r#"
+INSERT INTO balance_override_strategies (token, code_hash, strategy, detected_at)
+VALUES ($1, $2, $3, $4)
+ON CONFLICT (token) DO UPDATE
+SET code_hash = EXCLUDED.code_hash, strategy = EXCLUDED.strategy, detected_at = EXCLUDED.detected_at
+;"#;
+    sqlx::query(QUERY)
+        .bind(strategy.token)
+        .bind(strategy.code_hash)
+        .bind(&strategy.strategy)
+        .bind(strategy.detected_at)
+        .execute(ex)
+        .await?;
+    Ok(())
+}
+
+pub async fn fetch(
+    ex: &mut PgConnection,
+    token: &Address,
+) -> Result<Option<BalanceOverrideStrategy>, sqlx::Error> {
+    const QUERY: &str = r#"
+SELECT token, code_hash, strategy, detected_at
+FROM balance_override_strategies
+WHERE token = $1
+;"#;
+    sqlx::query_as(QUERY).bind(token).fetch_optional(ex).await
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        chrono::{Duration, TimeZone},
+        sqlx::Connection,
+    };
+
+    #[tokio::test]
+    #[ignore]
+    async fn postgres_balance_override_strategies() {
+        let mut db = PgConnection::connect("postgresql://").await.unwrap();
+        let mut db = db.begin().await.unwrap();
+        crate::clear_DANGER_(&mut db).await.unwrap();
+
+        let now = Utc.timestamp_opt(1_700_000_000, 0).unwrap();
+        let token = ByteArray([1; 20]);
+        assert_eq!(fetch(&mut db, &token).await.unwrap(), None);
+
+        let not_found = BalanceOverrideStrategy {
+            token,
+            code_hash: ByteArray([2; 32]),
+            strategy: None,
+            detected_at: now - Duration::days(1),
+        };
+        upsert(&mut db, &not_found).await.unwrap();
+        assert_eq!(
+            fetch(&mut db, &token).await.unwrap(),
+            Some(not_found.clone())
+        );
+
+        // Detecting the strategy again replaces the previous result.
+        let detected = BalanceOverrideStrategy {
+            code_hash: ByteArray([3; 32]),
+            strategy: Some("0x3".to_owned()),
+            detected_at: now,
+            ..not_found
+        };
+        upsert(&mut db, &detected).await.unwrap();
+        assert_eq!(fetch(&mut db, &token).await.unwrap(), Some(detected));
+        assert_eq!(fetch(&mut db, &ByteArray([2; 20])).await.unwrap(), None);
+    }
+}
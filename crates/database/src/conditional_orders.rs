@@ -0,0 +1,169 @@
+//! Conditional orders created via the ComposableCoW contract.
+
+use {
+    crate::{Address, PgTransaction},
+    sqlx::PgConnection,
+};
+
+type Hash = crate::byte_array::ByteArray<32>;
+
+#[derive(Clone, Debug, Default, Eq, PartialEq, sqlx::FromRow)]
+pub struct ConditionalOrder {
+    pub owner: Address,
+    pub params_hash: Hash,
+    pub handler: Address,
+    pub salt: Hash,
+    pub static_input: Vec<u8>,
+    pub block_number: i64,
+    pub log_index: i64,
+    pub next_poll_block: i64,
+    pub next_poll_timestamp: i64,
+}
+
+/// Inserts the conditional orders. Orders which are already stored keep their
+/// polling schedule.
+pub async fn insert(
+    ex: &mut PgTransaction<'_>,
+    orders: &[ConditionalOrder],
+) -> Result<(), sqlx::Error> {
+    const QUERY: &str = r#"
+INSERT INTO conditional_orders (owner, params_hash, handler, salt, static_input, block_number, log_index, next_poll_block, next_poll_timestamp)
+VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+ON CONFLICT (owner, params_hash) DO NOTHING
+;"#;
+    for order in orders {
+        sqlx::query(QUERY)
+            .bind(order.owner)
+            .bind(order.params_hash)
+            .bind(order.handler)
+            .bind(order.salt)
+            .bind(&order.static_input)
+            .bind(order.block_number)
+            .bind(order.log_index)
+            .bind(order.next_poll_block)
+            .bind(order.next_poll_timestamp)
+            .execute(&mut **ex)
+            .await?;
+    }
+    Ok(())
+}
+
+/// Deletes all conditional orders created in the given (inclusive) block range.
+pub async fn delete_in_block_range(
+    ex: &mut PgConnection,
+    start: i64,
+    end: i64,
+) -> Result<(), sqlx::Error> {
+    const QUERY: &str =
+        "DELETE FROM conditional_orders WHERE block_number >= $1 AND block_number <= $2;";
+    sqlx::query(QUERY).bind(start).bind(end).execute(ex).await?;
+    Ok(())
+}
+
+/// Fetches the conditional orders which are due to be polled at the given
+/// block.
+pub async fn due(
+    ex: &mut PgConnection,
+    block: i64,
+    timestamp: i64,
+) -> Result<Vec<ConditionalOrder>, sqlx::Error> {
+    const QUERY: &str = r#"
+SELECT * FROM conditional_orders
+WHERE next_poll_block <= $1 AND next_poll_timestamp <= $2
+ORDER BY block_number, log_index
+;"#;
+    sqlx::query_as(QUERY)
+        .bind(block)
+        .bind(timestamp)
+        .fetch_all(ex)
+        .await
+}
+
+/// Postpones polling the conditional order until the given block and
+/// timestamp.
+pub async fn schedule(
+    ex: &mut PgConnection,
+    owner: &Address,
+    params_hash: &Hash,
+    next_poll_block: i64,
+    next_poll_timestamp: i64,
+) -> Result<(), sqlx::Error> {
+    const QUERY: &str = r#"
+UPDATE conditional_orders
+SET next_poll_block = $3, next_poll_timestamp = $4
+WHERE owner = $1 AND params_hash = $2
+;"#;
+    sqlx::query(QUERY)
+        .bind(owner)
+        .bind(params_hash)
+        .bind(next_poll_block)
+        .bind(next_poll_timestamp)
+        .execute(ex)
+        .await?;
+    Ok(())
+}
+
+/// Deletes a conditional order which will never produce any more orders.
+pub async fn delete(
+    ex: &mut PgConnection,
+    owner: &Address,
+    params_hash: &Hash,
+) -> Result<(), sqlx::Error> {
+    const QUERY: &str = "DELETE FROM conditional_orders WHERE owner = $1 AND params_hash = $2;";
+    sqlx::query(QUERY)
+        .bind(owner)
+        .bind(params_hash)
+        .execute(ex)
+        .await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, crate::byte_array::ByteArray, sqlx::Connection};
+
+    #[tokio::test]
+    #[ignore]
+    async fn postgres_conditional_orders() {
+        let mut db = PgConnection::connect("postgresql://").await.unwrap();
+        let mut db = db.begin().await.unwrap();
+        crate::clear_DANGER_(&mut db).await.unwrap();
+
+        let order = |byte: u8, block_number: i64| ConditionalOrder {
+            owner: ByteArray([byte; 20]),
+            params_hash: ByteArray([byte; 32]),
+            handler: ByteArray([0xff; 20]),
+            salt: ByteArray([byte; 32]),
+            static_input: vec![byte],
+            block_number,
+            ..Default::default()
+        };
+        let (first, second) = (order(1, 1), order(2, 2));
+        insert(&mut db, &[first.clone(), second.clone()])
+            .await
+            .unwrap();
+        assert_eq!(
+            due(&mut db, 0, 0).await.unwrap(),
+            vec![first.clone(), second.clone()]
+        );
+
+        schedule(&mut db, &first.owner, &first.params_hash, 10, 0)
+            .await
+            .unwrap();
+        assert_eq!(due(&mut db, 9, 0).await.unwrap(), vec![second.clone()]);
+        schedule(&mut db, &second.owner, &second.params_hash, 0, 100)
+            .await
+            .unwrap();
+        assert!(due(&mut db, 9, 99).await.unwrap().is_empty());
+
+        // Re-indexing the same event doesn't reset the schedule.
+        insert(&mut db, &[first.clone()]).await.unwrap();
+        assert!(due(&mut db, 9, 99).await.unwrap().is_empty());
+
+        delete(&mut db, &first.owner, &first.params_hash)
+            .await
+            .unwrap();
+        delete_in_block_range(&mut db, 2, 2).await.unwrap();
+        assert!(due(&mut db, 100, 100).await.unwrap().is_empty());
+    }
+}
@@ -1,5 +1,5 @@
 use {
-    crate::{auction::AuctionId, OrderUid},
+    crate::{auction::AuctionId, Address, OrderUid},
     sqlx::{PgConnection, QueryBuilder},
     std::collections::HashMap,
 };
@@ -16,6 +16,7 @@ pub struct FeePolicy {
     pub volume_factor: Option<f64>,
     pub price_improvement_factor: Option<f64>,
     pub price_improvement_max_volume_factor: Option<f64>,
+    pub partner_fee_recipient: Option<Address>,
 }
 
 #[derive(Debug, Clone, PartialEq, sqlx::Type)]
@@ -38,7 +39,7 @@ pub async fn insert_batch(
     let mut query_builder = QueryBuilder::new(
         "INSERT INTO fee_policies (auction_id, order_uid, kind, surplus_factor, \
          surplus_max_volume_factor, volume_factor, price_improvement_factor, \
-         price_improvement_max_volume_factor)",
+         price_improvement_max_volume_factor, partner_fee_recipient)",
     );
 
     query_builder.push_values(fee_policies, |mut b, fee_policy| {
@@ -49,7 +50,8 @@ pub async fn insert_batch(
             .push_bind(fee_policy.surplus_max_volume_factor)
             .push_bind(fee_policy.volume_factor)
             .push_bind(fee_policy.price_improvement_factor)
-            .push_bind(fee_policy.price_improvement_max_volume_factor);
+            .push_bind(fee_policy.price_improvement_max_volume_factor)
+            .push_bind(fee_policy.partner_fee_recipient);
     });
 
     query_builder.build().execute(ex).await.map(|_| ())
@@ -124,6 +126,7 @@ mod tests {
             volume_factor: None,
             price_improvement_factor: None,
             price_improvement_max_volume_factor: None,
+            partner_fee_recipient: None,
         };
         // surplus fee policy with caps
         let fee_policy_2 = FeePolicy {
@@ -135,8 +138,9 @@ mod tests {
             volume_factor: None,
             price_improvement_factor: None,
             price_improvement_max_volume_factor: None,
+            partner_fee_recipient: None,
         };
-        // volume based fee policy
+        // volume based partner fee policy
         let fee_policy_3 = FeePolicy {
             auction_id: auction_id_b,
             order_uid: order_uid_b,
@@ -146,6 +150,7 @@ mod tests {
             volume_factor: Some(0.06),
             price_improvement_factor: None,
             price_improvement_max_volume_factor: None,
+            partner_fee_recipient: Some(ByteArray([3; 20])),
         };
         // price improvement fee policy
         let fee_policy_4 = FeePolicy {
@@ -157,6 +162,7 @@ mod tests {
             volume_factor: None,
             price_improvement_factor: Some(0.1),
             price_improvement_max_volume_factor: Some(0.99999),
+            partner_fee_recipient: None,
         };
 
         let fee_policies = vec![
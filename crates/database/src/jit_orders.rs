@@ -16,6 +16,8 @@ use {
     },
 };
 
+// The NULL columns are typed so that the rows can be combined with rows of
+// `orders::SELECT` even when selected through a subquery.
 pub const SELECT: &str = r#"
 o.uid, o.owner, o.creation_timestamp, o.sell_token, o.buy_token, o.sell_amount, o.buy_amount,
 o.valid_to, o.app_data, o.fee_amount, o.fee_amount AS full_fee_amount, o.kind, o.partially_fillable, o.signature,
@@ -28,12 +30,12 @@ FALSE AS invalidated,
 FALSE AS presignature_pending,
 ARRAY[]::record[] AS pre_interactions,
 ARRAY[]::record[] AS post_interactions,
-NULL AS ethflow_data,
-NULL AS onchain_user,
-NULL AS onchain_placement_error,
+NULL::record AS ethflow_data,
+NULL::bytea AS onchain_user,
+NULL::OnchainOrderPlacementError AS onchain_placement_error,
 COALESCE((SELECT SUM(executed_fee) FROM order_execution oe WHERE oe.order_uid = o.uid), 0) as executed_fee,
 COALESCE((SELECT executed_fee_token FROM order_execution oe WHERE oe.order_uid = o.uid LIMIT 1), o.sell_token) as executed_fee_token, -- TODO surplus token
-NULL AS full_app_data
+NULL::bytea AS full_app_data
 "#;
 
 pub const FROM: &str = "jit_orders o";
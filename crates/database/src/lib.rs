@@ -3,7 +3,10 @@ pub mod auction;
 pub mod auction_orders;
 pub mod auction_participants;
 pub mod auction_prices;
+pub mod bad_tokens;
+pub mod balance_override_strategies;
 pub mod byte_array;
+pub mod conditional_orders;
 pub mod ethflow_orders;
 pub mod events;
 pub mod fee_policies;
@@ -72,6 +75,9 @@ pub const TABLES: &[&str] = &[
     "auction_participants",
     "app_data",
     "jit_orders",
+    "bad_tokens",
+    "conditional_orders",
+    "balance_override_strategies",
 ];
 
 /// The names of potentially big volume tables we use in the db.
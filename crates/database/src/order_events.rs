@@ -4,11 +4,15 @@
 use {
     crate::{byte_array::ByteArray, OrderUid},
     chrono::Utc,
-    sqlx::{types::chrono::DateTime, PgConnection, PgPool},
+    sqlx::{
+        types::{chrono::DateTime, JsonValue},
+        PgConnection,
+        PgPool,
+    },
 };
 
 /// Describes what kind of event was registered for an order.
-#[derive(Clone, Copy, Debug, Eq, PartialEq, sqlx::Type)]
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, sqlx::Type)]
 #[sqlx(type_name = "OrderEventLabel")]
 #[sqlx(rename_all = "lowercase")]
 pub enum OrderEventLabel {
@@ -34,7 +38,7 @@ pub enum OrderEventLabel {
 
 /// Contains a single event of the life cycle of an order and when it was
 /// registered.
-#[derive(Clone, Copy, Debug, Eq, PartialEq, sqlx::Type, sqlx::FromRow)]
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, sqlx::Type, sqlx::FromRow)]
 pub struct OrderEvent {
     /// Which order this event belongs to
     pub order_uid: OrderUid,
@@ -45,32 +49,63 @@ pub struct OrderEvent {
     pub label: OrderEventLabel,
 }
 
+/// Explains why an order was filtered from the auction or deemed invalid.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct OrderEventReason {
+    /// Machine readable reason (e.g. `insufficient_balance`).
+    pub reason: String,
+    /// Additional context of the reason (e.g. the token missing a native
+    /// price).
+    pub details: Option<JsonValue>,
+}
+
 /// Inserts a row into the `order_events` table only if the latest event for the
 /// corresponding order UID has a different label than the provided event..
 pub async fn insert_order_event(
     ex: &mut PgConnection,
     event: &OrderEvent,
+) -> Result<(), sqlx::Error> {
+    insert(ex, event, None).await
+}
+
+/// Inserts a row with the reason for the event into the `order_events` table
+/// only if the latest event for the corresponding order UID has a different
+/// label or reason than the provided event.
+pub async fn insert_order_event_with_reason(
+    ex: &mut PgConnection,
+    event: &OrderEvent,
+    reason: &OrderEventReason,
+) -> Result<(), sqlx::Error> {
+    insert(ex, event, Some(reason)).await
+}
+
+async fn insert(
+    ex: &mut PgConnection,
+    event: &OrderEvent,
+    reason: Option<&OrderEventReason>,
 ) -> Result<(), sqlx::Error> {
     const QUERY: &str = r#"
         WITH cte AS (
-            SELECT label
+            SELECT label, reason
             FROM order_events
             WHERE order_uid = $1
             ORDER BY timestamp DESC
             LIMIT 1
         )
-        INSERT INTO order_events (order_uid, timestamp, label)
-        SELECT $1, $2, $3
+        INSERT INTO order_events (order_uid, timestamp, label, reason, details)
+        SELECT $1, $2, $3, $4, $5
         WHERE NOT EXISTS (
             SELECT 1
             FROM cte
-            WHERE label = $3
+            WHERE label = $3 AND reason IS NOT DISTINCT FROM $4
         )
     "#;
     sqlx::query(QUERY)
         .bind(event.order_uid)
         .bind(event.timestamp)
         .bind(event.label)
+        .bind(reason.map(|reason| reason.reason.as_str()))
+        .bind(reason.and_then(|reason| reason.details.as_ref()))
         .execute(ex)
         .await
         .map(|_| ())
@@ -104,6 +139,47 @@ pub async fn get_latest(
         .await
 }
 
+/// Fetches all events registered for the given order together with their
+/// reasons, oldest first.
+pub async fn get_all(
+    ex: &mut PgConnection,
+    order: &OrderUid,
+) -> Result<Vec<(OrderEvent, Option<OrderEventReason>)>, sqlx::Error> {
+    #[derive(sqlx::FromRow)]
+    struct Row {
+        #[sqlx(flatten)]
+        event: OrderEvent,
+        reason: Option<String>,
+        details: Option<JsonValue>,
+    }
+
+    const QUERY: &str = r#"SELECT * FROM order_events WHERE order_uid = $1 ORDER BY timestamp"#;
+    let rows: Vec<Row> = sqlx::query_as(QUERY)
+        .bind(ByteArray(order.0))
+        .fetch_all(ex)
+        .await?;
+    Ok(rows
+        .into_iter()
+        .map(|row| {
+            let reason = row.reason.map(|reason| OrderEventReason {
+                reason,
+                details: row.details,
+            });
+            (row.event, reason)
+        })
+        .collect())
+}
+
+/// Fetches all events registered strictly after the given timestamp, oldest
+/// first.
+pub async fn get_after(
+    ex: &mut PgConnection,
+    after: DateTime<Utc>,
+) -> Result<Vec<OrderEvent>, sqlx::Error> {
+    const QUERY: &str = r#"SELECT * FROM order_events WHERE timestamp > $1 ORDER BY timestamp"#;
+    sqlx::query_as(QUERY).bind(after).fetch_all(ex).await
+}
+
 #[cfg(test)]
 mod tests {
     use {
@@ -170,6 +246,63 @@ mod tests {
             latest.timestamp.timestamp_micros(),
             event_b.timestamp.timestamp_micros()
         );
+
+        let after = get_after(&mut db, event_a.timestamp).await.unwrap();
+        assert_eq!(after.len(), 2);
+        assert_eq!(after[0].order_uid, uid_a);
+        assert_eq!(after[0].label, OrderEventLabel::Invalid);
+        assert_eq!(after[1].order_uid, uid_b);
+        assert_eq!(after[1].label, OrderEventLabel::Invalid);
+
+        let all = get_all(&mut db, &uid_a).await.unwrap();
+        assert_eq!(all.len(), 2);
+        assert_eq!(all[0].0.label, OrderEventLabel::Created);
+        assert_eq!(all[1].0.label, OrderEventLabel::Invalid);
+    }
+
+    #[tokio::test]
+    #[ignore]
+    async fn postgres_order_event_reasons() {
+        let mut db = PgConnection::connect("postgresql://").await.unwrap();
+        let mut db = db.begin().await.unwrap();
+        crate::clear_DANGER_(&mut db).await.unwrap();
+
+        let now = Utc::now();
+        let uid = ByteArray([1; 56]);
+        let event = |millis: i64| OrderEvent {
+            order_uid: uid,
+            timestamp: now + chrono::Duration::milliseconds(millis),
+            label: OrderEventLabel::Filtered,
+        };
+        let missing_price = OrderEventReason {
+            reason: "missing_price".to_string(),
+            details: Some(serde_json::json!({ "token": "0x0101" })),
+        };
+        let dust_order = OrderEventReason {
+            reason: "dust_order".to_string(),
+            details: None,
+        };
+
+        insert_order_event_with_reason(&mut db, &event(0), &missing_price)
+            .await
+            .unwrap();
+        // Same label and reason as the latest event get skipped.
+        insert_order_event_with_reason(&mut db, &event(1), &missing_price)
+            .await
+            .unwrap();
+        // A different reason gets recorded even if the label is the same.
+        insert_order_event_with_reason(&mut db, &event(2), &dust_order)
+            .await
+            .unwrap();
+        insert_order_event(&mut db, &event(3)).await.unwrap();
+
+        let all = get_all(&mut db, &uid).await.unwrap();
+        assert_eq!(
+            all.into_iter()
+                .map(|(_, reason)| reason)
+                .collect::<Vec<_>>(),
+            vec![Some(missing_price), Some(dust_order), None],
+        );
     }
 
     async fn all_order_events(ex: &mut PgConnection) -> Vec<OrderEvent> {
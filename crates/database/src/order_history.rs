@@ -1,5 +1,11 @@
 use {
-    crate::{jit_orders, orders, Address},
+    crate::{
+        jit_orders,
+        orders::{self, OrderClass},
+        Address,
+        OrderUid,
+    },
+    chrono::{DateTime, Utc},
     futures::stream::BoxStream,
     sqlx::PgConnection,
 };
@@ -47,6 +53,139 @@ pub fn user_orders<'a>(
         .fetch(ex)
 }
 
+/// Filters applied when paginating through the orders of a user. Any default
+/// value means that this field is unfiltered.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct UserOrderFilter {
+    pub class: Option<OrderClass>,
+    pub status: Option<OrderStatus>,
+    pub sell_token: Option<Address>,
+    pub buy_token: Option<Address>,
+    /// Only orders created at or after this time.
+    pub created_after: Option<DateTime<Utc>>,
+    /// Only orders created strictly before this time.
+    pub created_before: Option<DateTime<Utc>>,
+}
+
+/// Status of an order as reported by the API. It is derived from the order's
+/// trades, invalidations and presignature events, so it can't be stored in
+/// the `orders` table.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum OrderStatus {
+    PresignaturePending,
+    Open,
+    Fulfilled,
+    Cancelled,
+    Expired,
+}
+
+impl OrderStatus {
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::PresignaturePending => "presignaturePending",
+            Self::Open => "open",
+            Self::Fulfilled => "fulfilled",
+            Self::Cancelled => "cancelled",
+            Self::Expired => "expired",
+        }
+    }
+}
+
+/// Identifies the last order of the previous page. Orders are sorted by their
+/// creation timestamp and uid descending, so the next page starts with the
+/// order that directly follows this one.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct UserOrderCursor {
+    pub creation_timestamp: DateTime<Utc>,
+    pub uid: OrderUid,
+}
+
+/// Computes the status of a row selected with [`orders::SELECT`] (or
+/// [`jit_orders::SELECT`]) and an additional `effective_valid_to` column the
+/// same way the orderbook does. Must be kept in sync with the orderbook's
+/// `calculate_status`.
+#[rustfmt::skip]
+const STATUS: &str = const_format::concatcp!(
+"CASE",
+" WHEN (o.kind = 'buy' AND o.sum_buy <> 0 AND o.sum_buy = o.buy_amount)",
+"  OR (o.kind = 'sell' AND o.sum_sell <> 0 AND o.sum_sell - o.sum_fee = o.sell_amount)",
+"  THEN 'fulfilled'",
+" WHEN o.invalidated THEN 'cancelled'",
+" WHEN o.effective_valid_to < EXTRACT(EPOCH FROM now()) THEN 'expired'",
+" WHEN o.presignature_pending THEN 'presignaturePending'",
+" ELSE 'open' ",
+"END",
+);
+
+/// Returns at most `limit` orders of the user that come after the `cursor`
+/// (or the newest orders if there is no cursor). Unlike [`user_orders`] the
+/// pages are stable when new orders get created in the mean time and the
+/// database can jump to the start of the page directly through the index.
+pub fn user_orders_page<'a>(
+    ex: &'a mut PgConnection,
+    owner: &'a Address,
+    filter: &'a UserOrderFilter,
+    cursor: Option<UserOrderCursor>,
+    limit: i64,
+) -> BoxStream<'a, Result<orders::FullOrder, sqlx::Error>> {
+    #[rustfmt::skip]
+    const FILTER: &str = const_format::concatcp!(
+" AND ($2::timestamptz IS NULL OR (o.creation_timestamp, o.uid) < ($2, $3)) ",
+" AND ($4::bytea IS NULL OR o.sell_token = $4) ",
+" AND ($5::bytea IS NULL OR o.buy_token = $5) ",
+" AND ($6::timestamptz IS NULL OR o.creation_timestamp >= $6) ",
+" AND ($7::timestamptz IS NULL OR o.creation_timestamp < $7) ",
+    );
+    // The status is computed from the selected columns so every branch first
+    // selects the matching orders and then filters them by their status. This
+    // way the database walks the orders in page order and stops as soon as
+    // the page is full.
+    #[rustfmt::skip]
+    const STATUS_FILTER: &str = const_format::concatcp!(
+" WHERE ($10::text IS NULL OR ", STATUS, " = $10) ",
+" ORDER BY creation_timestamp DESC, uid DESC LIMIT $9 ) ",
+    );
+    #[rustfmt::skip]
+    const QUERY: &str = const_format::concatcp!(
+"(SELECT * FROM (SELECT ", orders::SELECT,
+", COALESCE((SELECT eth_o.valid_to FROM ethflow_orders eth_o WHERE eth_o.uid = o.uid), o.valid_to) AS effective_valid_to",
+" FROM ", orders::FROM,
+" LEFT OUTER JOIN onchain_placed_orders onchain_o on onchain_o.uid = o.uid",
+" WHERE o.owner = $1", FILTER,
+" AND ($8::OrderClass IS NULL OR o.class = $8) ",
+") o", STATUS_FILTER,
+" UNION ",
+" (SELECT * FROM (SELECT ", orders::SELECT,
+", COALESCE((SELECT eth_o.valid_to FROM ethflow_orders eth_o WHERE eth_o.uid = o.uid), o.valid_to) AS effective_valid_to",
+" FROM ", orders::FROM,
+" LEFT OUTER JOIN onchain_placed_orders onchain_o on onchain_o.uid = o.uid",
+" WHERE onchain_o.sender = $1 ", FILTER,
+" AND ($8::OrderClass IS NULL OR o.class = $8) ",
+") o", STATUS_FILTER,
+" UNION ",
+" (SELECT * FROM (SELECT ", jit_orders::SELECT,
+", o.valid_to AS effective_valid_to",
+" FROM ", jit_orders::FROM,
+" WHERE o.owner = $1 AND NOT EXISTS (SELECT 1 FROM orders ord WHERE o.uid = ord.uid)", FILTER,
+" AND ($8::OrderClass IS NULL OR $8 = 'liquidity') ",
+") o", STATUS_FILTER,
+" ORDER BY creation_timestamp DESC, uid DESC ",
+" LIMIT $9 ",
+    );
+    sqlx::query_as(QUERY)
+        .bind(owner)
+        .bind(cursor.map(|cursor| cursor.creation_timestamp))
+        .bind(cursor.map(|cursor| cursor.uid))
+        .bind(filter.sell_token)
+        .bind(filter.buy_token)
+        .bind(filter.created_after)
+        .bind(filter.created_before)
+        .bind(filter.class)
+        .bind(limit)
+        .bind(filter.status.map(OrderStatus::as_str))
+        .fetch(ex)
+}
+
 #[cfg(test)]
 mod tests {
     use {
@@ -174,4 +313,106 @@ mod tests {
         );
         assert!(elapsed / number_of_query_executions < std::time::Duration::from_secs(1));
     }
+
+    #[tokio::test]
+    #[ignore]
+    async fn postgres_user_orders_page() {
+        let mut db = PgConnection::connect("postgresql://").await.unwrap();
+        let mut db = db.begin().await.unwrap();
+        crate::clear_DANGER_(&mut db).await.unwrap();
+
+        let owner = ByteArray([1; 20]);
+        // Postgres only stores microseconds so use a round timestamp to make
+        // the range filters below exact.
+        let now = DateTime::from_timestamp(Utc::now().timestamp(), 0).unwrap();
+        // Two orders share the same creation timestamp to verify that the uid
+        // is used as a tie breaker.
+        let orders = [
+            (ByteArray([1; 56]), now, OrderClass::Market),
+            (ByteArray([2; 56]), now, OrderClass::Limit),
+            (
+                ByteArray([3; 56]),
+                now - chrono::Duration::seconds(1),
+                OrderClass::Market,
+            ),
+            (
+                ByteArray([4; 56]),
+                now - chrono::Duration::seconds(2),
+                OrderClass::Limit,
+            ),
+        ];
+        for (uid, creation_timestamp, class) in orders {
+            let order = orders::Order {
+                owner,
+                uid,
+                creation_timestamp,
+                class,
+                ..Default::default()
+            };
+            orders::insert_order(&mut db, &order).await.unwrap();
+        }
+
+        async fn page(
+            ex: &mut PgConnection,
+            owner: &Address,
+            filter: &UserOrderFilter,
+            cursor: Option<UserOrderCursor>,
+            limit: i64,
+        ) -> Vec<orders::FullOrder> {
+            super::user_orders_page(ex, owner, filter, cursor, limit)
+                .map(Result::unwrap)
+                .collect()
+                .await
+        }
+        let uids = |orders: &[orders::FullOrder]| orders.iter().map(|o| o.uid).collect::<Vec<_>>();
+
+        let filter = UserOrderFilter::default();
+        let first = page(&mut db, &owner, &filter, None, 3).await;
+        assert_eq!(uids(&first), [orders[1].0, orders[0].0, orders[2].0]);
+
+        let last = first.last().unwrap();
+        let cursor = UserOrderCursor {
+            creation_timestamp: last.creation_timestamp,
+            uid: last.uid,
+        };
+        let second = page(&mut db, &owner, &filter, Some(cursor), 3).await;
+        assert_eq!(uids(&second), [orders[3].0]);
+
+        let filter = UserOrderFilter {
+            class: Some(OrderClass::Limit),
+            ..Default::default()
+        };
+        let limit_orders = page(&mut db, &owner, &filter, None, 10).await;
+        assert_eq!(uids(&limit_orders), [orders[1].0, orders[3].0]);
+
+        let filter = UserOrderFilter {
+            created_after: Some(now - chrono::Duration::seconds(1)),
+            created_before: Some(now),
+            ..Default::default()
+        };
+        let in_range = page(&mut db, &owner, &filter, None, 10).await;
+        assert_eq!(uids(&in_range), [orders[2].0]);
+
+        // All orders are expired (`valid_to` is 0) unless they got cancelled.
+        orders::cancel_order(&mut db, &orders[2].0, now)
+            .await
+            .unwrap();
+        let filter = UserOrderFilter {
+            status: Some(OrderStatus::Cancelled),
+            ..Default::default()
+        };
+        let cancelled = page(&mut db, &owner, &filter, None, 1).await;
+        assert_eq!(uids(&cancelled), [orders[2].0]);
+        let filter = UserOrderFilter {
+            status: Some(OrderStatus::Expired),
+            ..Default::default()
+        };
+        let expired = page(&mut db, &owner, &filter, None, 10).await;
+        assert_eq!(uids(&expired), [orders[1].0, orders[0].0, orders[3].0]);
+        let filter = UserOrderFilter {
+            status: Some(OrderStatus::Open),
+            ..Default::default()
+        };
+        assert!(page(&mut db, &owner, &filter, None, 10).await.is_empty());
+    }
 }
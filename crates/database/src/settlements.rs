@@ -61,6 +61,29 @@ WHERE block_number = $2 AND log_index = $3
         .map(|_| ())
 }
 
+/// Overwrites the solver of the settlement event with the account that
+/// submitted the transaction. They differ if the settlement contract wasn't
+/// called directly (e.g. by the flash loan router).
+pub async fn update_settlement_solver(
+    ex: &mut PgConnection,
+    block_number: i64,
+    log_index: i64,
+    solver: Address,
+) -> Result<(), sqlx::Error> {
+    const QUERY: &str = r#"
+UPDATE settlements
+SET solver = $1
+WHERE block_number = $2 AND log_index = $3
+    ;"#;
+    sqlx::query(QUERY)
+        .bind(solver)
+        .bind(block_number)
+        .bind(log_index)
+        .execute(ex)
+        .await
+        .map(|_| ())
+}
+
 /// Deletes all database data that referenced the deleted settlement events.
 pub async fn delete(
     ex: &mut PgTransaction<'_>,
@@ -186,5 +209,20 @@ mod tests {
         let settlement = get_settlement_without_auction(&mut db).await.unwrap();
 
         assert!(settlement.is_none());
+
+        let solver = ByteArray([1u8; 20]);
+        assert!(find_settlement_transaction(&mut db, 1, solver)
+            .await
+            .unwrap()
+            .is_none());
+        update_settlement_solver(&mut db, event.block_number, event.log_index, solver)
+            .await
+            .unwrap();
+        assert_eq!(
+            find_settlement_transaction(&mut db, 1, solver)
+                .await
+                .unwrap(),
+            Some(Default::default())
+        );
     }
 }
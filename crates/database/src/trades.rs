@@ -20,12 +20,7 @@ pub struct TradesQueryRow {
     pub auction_id: Option<AuctionId>,
 }
 
-pub fn trades<'a>(
-    ex: &'a mut PgConnection,
-    owner_filter: Option<&'a Address>,
-    order_uid_filter: Option<&'a OrderUid>,
-) -> BoxStream<'a, Result<TradesQueryRow, sqlx::Error>> {
-    const COMMON_QUERY: &str = r#"
+const COMMON_QUERY: &str = r#"
 SELECT
     t.block_number,
     t.log_index,
@@ -47,6 +42,11 @@ LEFT OUTER JOIN LATERAL (
     LIMIT 1
 ) AS settlement ON true"#;
 
+pub fn trades<'a>(
+    ex: &'a mut PgConnection,
+    owner_filter: Option<&'a Address>,
+    order_uid_filter: Option<&'a OrderUid>,
+) -> BoxStream<'a, Result<TradesQueryRow, sqlx::Error>> {
     const QUERY: &str = const_format::concatcp!(
         COMMON_QUERY,
         " JOIN orders o ON o.uid = t.order_uid",
@@ -72,6 +72,64 @@ LEFT OUTER JOIN LATERAL (
         .fetch(ex)
 }
 
+/// Filters applied when paginating through trades. Any default value means
+/// that this field is unfiltered.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct TradeFilter {
+    pub owner: Option<Address>,
+    pub order_uid: Option<OrderUid>,
+    /// Only trades in this block or later.
+    pub from_block: Option<i64>,
+    /// Only trades in this block or earlier.
+    pub to_block: Option<i64>,
+}
+
+/// Returns at most `limit` trades matching the `filter` ordered from newest to
+/// oldest. Only trades strictly older than the `cursor` (which is the event
+/// index of the last trade of the previous page) get returned.
+pub fn trades_page<'a>(
+    ex: &'a mut PgConnection,
+    filter: &'a TradeFilter,
+    cursor: Option<EventIndex>,
+    limit: i64,
+) -> BoxStream<'a, Result<TradesQueryRow, sqlx::Error>> {
+    #[rustfmt::skip]
+    const FILTER: &str = const_format::concatcp!(
+" AND ($2::bytea IS NULL OR o.uid = $2)",
+" AND ($3::bigint IS NULL OR t.block_number >= $3)",
+" AND ($4::bigint IS NULL OR t.block_number <= $4)",
+" AND ($5::bigint IS NULL OR (t.block_number, t.log_index) < ($5, $6))",
+" ORDER BY block_number DESC, log_index DESC",
+" LIMIT $7",
+    );
+    #[rustfmt::skip]
+    const QUERY: &str = const_format::concatcp!(
+"(", COMMON_QUERY,
+" JOIN orders o ON o.uid = t.order_uid",
+" WHERE ($1::bytea IS NULL OR o.owner = $1)", FILTER,
+") UNION (", COMMON_QUERY,
+" JOIN orders o ON o.uid = t.order_uid",
+" LEFT OUTER JOIN onchain_placed_orders onchain_o",
+" ON onchain_o.uid = t.order_uid",
+" WHERE onchain_o.sender = $1", FILTER,
+") UNION (", COMMON_QUERY,
+" JOIN jit_orders o ON o.uid = t.order_uid",
+" WHERE ($1::bytea IS NULL OR o.owner = $1)", FILTER,
+") ORDER BY block_number DESC, log_index DESC",
+" LIMIT $7",
+    );
+
+    sqlx::query_as(QUERY)
+        .bind(filter.owner)
+        .bind(filter.order_uid)
+        .bind(filter.from_block)
+        .bind(filter.to_block)
+        .bind(cursor.map(|cursor| cursor.block_number))
+        .bind(cursor.map(|cursor| cursor.log_index))
+        .bind(limit)
+        .fetch(ex)
+}
+
 #[derive(Clone, Debug, Default, Eq, PartialEq, sqlx::FromRow)]
 pub struct TradeEvent {
     pub block_number: i64,
@@ -579,4 +637,74 @@ mod tests {
             }]
         );
     }
+
+    #[tokio::test]
+    #[ignore]
+    async fn postgres_trades_page() {
+        let mut db = PgConnection::connect("postgresql://").await.unwrap();
+        let mut db = db.begin().await.unwrap();
+        crate::clear_DANGER_(&mut db).await.unwrap();
+
+        let (owners, order_ids) = generate_owners_and_order_ids(2, 4).await;
+        let mut trades = Vec::new();
+        for (i, (block_number, log_index)) in
+            [(1, 0), (1, 1), (2, 0), (3, 0)].into_iter().enumerate()
+        {
+            let event_index = EventIndex {
+                block_number,
+                log_index,
+            };
+            trades.push(
+                add_order_and_trade(
+                    &mut db,
+                    owners[i % 2],
+                    order_ids[i],
+                    event_index,
+                    None,
+                    None,
+                )
+                .await,
+            );
+        }
+
+        async fn page(
+            ex: &mut PgConnection,
+            filter: &TradeFilter,
+            cursor: Option<EventIndex>,
+            limit: i64,
+        ) -> Vec<TradesQueryRow> {
+            trades_page(ex, filter, cursor, limit)
+                .try_collect()
+                .await
+                .unwrap()
+        }
+
+        let filter = TradeFilter::default();
+        let first = page(&mut db, &filter, None, 3).await;
+        assert_eq!(
+            first,
+            [trades[3].clone(), trades[2].clone(), trades[1].clone()]
+        );
+        let cursor = EventIndex {
+            block_number: 1,
+            log_index: 1,
+        };
+        let second = page(&mut db, &filter, Some(cursor), 3).await;
+        assert_eq!(second, [trades[0].clone()]);
+
+        let filter = TradeFilter {
+            owner: Some(owners[0]),
+            ..Default::default()
+        };
+        let by_owner = page(&mut db, &filter, None, 10).await;
+        assert_eq!(by_owner, [trades[2].clone(), trades[0].clone()]);
+
+        let filter = TradeFilter {
+            from_block: Some(2),
+            to_block: Some(2),
+            ..Default::default()
+        };
+        let by_block = page(&mut db, &filter, None, 10).await;
+        assert_eq!(by_block, [trades[2].clone()]);
+    }
 }
@@ -17,31 +17,46 @@ pub fn to_domain(id: liquidity::Id, pool: StablePoolOrder) -> Result<liquidity::
     Ok(liquidity::Liquidity {
         id,
         gas: GAS_PER_SWAP.into(),
-        kind: liquidity::Kind::BalancerV2Stable(balancer::v2::stable::Pool {
-            vault: vault(&pool),
-            id: pool_id(&pool),
-            reserves: balancer::v2::stable::Reserves::new(
-                pool.reserves
-                    .into_iter()
-                    .map(|(token, reserve)| {
-                        Ok(balancer::v2::stable::Reserve {
-                            asset: eth::Asset {
-                                token: token.into(),
-                                amount: reserve.balance.into(),
-                            },
-                            scale: balancer::v2::ScalingFactor::from_raw(
-                                reserve.scaling_factor.as_uint256(),
-                            )?,
-                        })
+        kind: liquidity::Kind::BalancerV2Stable(to_pool(pool)?),
+    })
+}
+
+pub fn to_composable_domain(
+    id: liquidity::Id,
+    pool: StablePoolOrder,
+) -> Result<liquidity::Liquidity> {
+    Ok(liquidity::Liquidity {
+        id,
+        gas: GAS_PER_SWAP.into(),
+        kind: liquidity::Kind::BalancerV2ComposableStable(to_pool(pool)?),
+    })
+}
+
+fn to_pool(pool: StablePoolOrder) -> Result<balancer::v2::stable::Pool> {
+    Ok(balancer::v2::stable::Pool {
+        vault: vault(&pool),
+        id: pool_id(&pool),
+        reserves: balancer::v2::stable::Reserves::new(
+            pool.reserves
+                .into_iter()
+                .map(|(token, reserve)| {
+                    Ok(balancer::v2::stable::Reserve {
+                        asset: eth::Asset {
+                            token: token.into(),
+                            amount: reserve.balance.into(),
+                        },
+                        scale: balancer::v2::ScalingFactor::from_raw(
+                            reserve.scaling_factor.as_uint256(),
+                        )?,
                     })
-                    .collect::<Result<_>>()?,
-            )?,
-            amplification_parameter: balancer::v2::stable::AmplificationParameter::new(
-                pool.amplification_parameter.factor(),
-                pool.amplification_parameter.precision(),
-            )?,
-            fee: balancer::v2::Fee::from_raw(pool.fee.as_uint256()),
-        }),
+                })
+                .collect::<Result<_>>()?,
+        )?,
+        amplification_parameter: balancer::v2::stable::AmplificationParameter::new(
+            pool.amplification_parameter.factor(),
+            pool.amplification_parameter.precision(),
+        )?,
+        fee: balancer::v2::Fee::from_raw(pool.fee.as_uint256()),
     })
 }
 
@@ -18,34 +18,49 @@ pub fn to_domain(id: liquidity::Id, pool: WeightedProductOrder) -> Result<liquid
     Ok(liquidity::Liquidity {
         id,
         gas: GAS_PER_SWAP.into(),
-        kind: liquidity::Kind::BalancerV2Weighted(balancer::v2::weighted::Pool {
-            vault: vault(&pool),
-            id: pool_id(&pool),
-            reserves: balancer::v2::weighted::Reserves::new(
-                pool.reserves
-                    .into_iter()
-                    .map(|(token, reserve)| {
-                        Ok(balancer::v2::weighted::Reserve {
-                            asset: eth::Asset {
-                                token: token.into(),
-                                amount: reserve.common.balance.into(),
-                            },
-                            weight: balancer::v2::weighted::Weight::from_raw(
-                                reserve.weight.as_uint256(),
-                            ),
-                            scale: balancer::v2::ScalingFactor::from_raw(
-                                reserve.common.scaling_factor.as_uint256(),
-                            )?,
-                        })
+        kind: liquidity::Kind::BalancerV2Weighted(to_pool(pool)?),
+    })
+}
+
+pub fn to_liquidity_bootstrapping_domain(
+    id: liquidity::Id,
+    pool: WeightedProductOrder,
+) -> Result<liquidity::Liquidity> {
+    Ok(liquidity::Liquidity {
+        id,
+        gas: GAS_PER_SWAP.into(),
+        kind: liquidity::Kind::BalancerV2LiquidityBootstrapping(to_pool(pool)?),
+    })
+}
+
+fn to_pool(pool: WeightedProductOrder) -> Result<balancer::v2::weighted::Pool> {
+    Ok(balancer::v2::weighted::Pool {
+        vault: vault(&pool),
+        id: pool_id(&pool),
+        reserves: balancer::v2::weighted::Reserves::new(
+            pool.reserves
+                .into_iter()
+                .map(|(token, reserve)| {
+                    Ok(balancer::v2::weighted::Reserve {
+                        asset: eth::Asset {
+                            token: token.into(),
+                            amount: reserve.common.balance.into(),
+                        },
+                        weight: balancer::v2::weighted::Weight::from_raw(
+                            reserve.weight.as_uint256(),
+                        ),
+                        scale: balancer::v2::ScalingFactor::from_raw(
+                            reserve.common.scaling_factor.as_uint256(),
+                        )?,
                     })
-                    .collect::<Result<_>>()?,
-            )?,
-            fee: balancer::v2::Fee::from_raw(pool.fee.as_uint256()),
-            version: match pool.version {
-                WeightedPoolVersion::V0 => balancer::v2::weighted::Version::V0,
-                WeightedPoolVersion::V3Plus => balancer::v2::weighted::Version::V3Plus,
-            },
-        }),
+                })
+                .collect::<Result<_>>()?,
+        )?,
+        fee: balancer::v2::Fee::from_raw(pool.fee.as_uint256()),
+        version: match pool.version {
+            WeightedPoolVersion::V0 => balancer::v2::weighted::Version::V0,
+            WeightedPoolVersion::V3Plus => balancer::v2::weighted::Version::V3Plus,
+        },
     })
 }
 
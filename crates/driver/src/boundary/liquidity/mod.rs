@@ -169,6 +169,12 @@ impl Fetcher {
                     }
                     Liquidity::BalancerWeighted(pool) => balancer::v2::weighted::to_domain(id, pool),
                     Liquidity::BalancerStable(pool) => balancer::v2::stable::to_domain(id, pool),
+                    Liquidity::BalancerComposableStable(pool) => {
+                        balancer::v2::stable::to_composable_domain(id, pool)
+                    }
+                    Liquidity::BalancerLiquidityBootstrapping(pool) => {
+                        balancer::v2::weighted::to_liquidity_bootstrapping_domain(id, pool)
+                    }
                     Liquidity::LimitOrder(pool) => zeroex::to_domain(id, pool),
                     Liquidity::Concentrated(pool) => uniswap::v3::to_domain(id, pool),
                 }
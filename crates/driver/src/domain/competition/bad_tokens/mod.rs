@@ -7,6 +7,7 @@ use {
 
 pub mod cache;
 pub mod metrics;
+pub mod persisted;
 pub mod simulation;
 
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
@@ -31,6 +32,9 @@ pub struct Detector {
     /// tokens that get detected incorrectly by the automatic detectors get
     /// listed here and therefore have a higher precedence.
     hardcoded: HashMap<eth::TokenAddress, Quality>,
+    /// Unsupported tokens detected by this or other drivers which survive
+    /// restarts.
+    persisted: Option<persisted::List>,
     simulation_detector: Option<simulation::Detector>,
     metrics: Option<metrics::Detector>,
 }
@@ -45,6 +49,13 @@ impl Detector {
         }
     }
 
+    /// Enables looking up tokens which were persistently detected as
+    /// unsupported.
+    pub fn with_persisted_tokens(&mut self, list: persisted::List) -> &mut Self {
+        self.persisted = Some(list);
+        self
+    }
+
     /// Enables detection of unsupported tokens via simulation based detection
     /// methods.
     pub fn with_simulation_detector(&mut self, detector: simulation::Detector) -> &mut Self {
@@ -115,8 +126,19 @@ impl Detector {
 
     /// Updates the tokens quality metric for failures.
     pub fn encoding_failed(&self, token_pairs: &[(eth::TokenAddress, eth::TokenAddress)]) {
-        if let Some(metrics) = &self.metrics {
-            metrics.update_tokens(token_pairs, true);
+        let Some(metrics) = &self.metrics else {
+            return;
+        };
+        metrics.update_tokens(token_pairs, true);
+
+        if let Some(persisted) = &self.persisted {
+            token_pairs
+                .iter()
+                .flat_map(|(token_a, token_b)| [token_a, token_b])
+                .filter(|token| metrics.get_quality(token) == Some(Quality::Unsupported))
+                .for_each(|token| {
+                    persisted.record(*token, "too many failed settlement encodings".to_owned())
+                });
         }
     }
 
@@ -125,6 +147,12 @@ impl Detector {
             return Some(*quality);
         }
 
+        if let Some(persisted) = &self.persisted {
+            if let Some(quality) = persisted.get_quality(&token) {
+                return Some(quality);
+            }
+        }
+
         if let Some(detector) = &self.simulation_detector {
             if let Some(quality) = detector.get_quality(&token, now) {
                 return Some(quality);
@@ -0,0 +1,151 @@
+use {
+    super::Quality,
+    crate::{
+        domain::eth,
+        infra::{
+            self,
+            persistence::bad_tokens::{Config, Entry, Error, Storage},
+        },
+    },
+    dashmap::DashMap,
+    itertools::Itertools,
+    std::{sync::Arc, time::Duration},
+    tracing::Instrument,
+};
+
+/// How often entries added by other drivers sharing the same storage get
+/// picked up.
+const REFRESH_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Unsupported tokens which get persisted so that they are still known after
+/// the driver restarted. Internally reference counted for cheap clones and
+/// easy sharing.
+///
+/// Entries expire after a configurable period of time so tokens which got
+/// fixed (e.g. by an upgrade of the token contract) eventually get supported
+/// again.
+#[derive(Clone)]
+pub struct List(Arc<Inner>);
+
+struct Inner {
+    tokens: DashMap<eth::TokenAddress, Entry>,
+    storage: Storage,
+    max_age: chrono::Duration,
+}
+
+impl List {
+    /// Loads the persisted entries and keeps picking up entries added by
+    /// other drivers in the background.
+    pub async fn load(config: Config) -> Result<Self, Error> {
+        let list = Self(Arc::new(Inner {
+            tokens: Default::default(),
+            storage: Storage::new(config.backend)?,
+            max_age: config.max_age,
+        }));
+        list.refresh().await?;
+
+        let weak = Arc::downgrade(&list.0);
+        tokio::spawn(
+            async move {
+                loop {
+                    tokio::time::sleep(REFRESH_INTERVAL).await;
+                    let Some(inner) = weak.upgrade() else {
+                        break;
+                    };
+                    if let Err(err) = Self(inner).refresh().await {
+                        tracing::warn!(?err, "failed to refresh persisted bad tokens");
+                    }
+                }
+            }
+            .instrument(tracing::info_span!("persisted_bad_tokens")),
+        );
+
+        Ok(list)
+    }
+
+    /// Returns [`Quality::Unsupported`] if the token was detected as
+    /// unsupported and the entry has not expired yet.
+    pub fn get_quality(&self, token: &eth::TokenAddress) -> Option<Quality> {
+        let entry = self.0.tokens.get(token)?;
+        (!self.is_expired(&entry)).then_some(Quality::Unsupported)
+    }
+
+    /// All entries which have not expired yet, oldest first.
+    pub fn entries(&self) -> Vec<Entry> {
+        self.0
+            .tokens
+            .iter()
+            .filter(|entry| !self.is_expired(entry))
+            .map(|entry| entry.value().clone())
+            .sorted_by_key(|entry| entry.detected_at)
+            .collect()
+    }
+
+    /// Records the token as unsupported and persists it in the background.
+    pub fn record(&self, token: eth::TokenAddress, reason: String) {
+        if self.get_quality(&token).is_some() {
+            return;
+        }
+        let list = self.clone();
+        tokio::spawn(
+            async move {
+                if let Err(err) = list.insert(token, reason).await {
+                    tracing::warn!(?err, ?token, "failed to persist bad token");
+                }
+            }
+            .instrument(tracing::Span::current()),
+        );
+    }
+
+    /// Records the token as unsupported and waits until it got persisted.
+    pub async fn insert(&self, token: eth::TokenAddress, reason: String) -> Result<Entry, Error> {
+        let entry = Entry {
+            token,
+            reason,
+            detected_at: infra::time::now(),
+        };
+        tracing::debug!(?token, reason = entry.reason, "persisting bad token");
+        self.0.tokens.insert(token, entry.clone());
+        self.0.storage.store(&entry).await?;
+        Ok(entry)
+    }
+
+    /// Removes the token. Returns whether the token was listed.
+    pub async fn remove(&self, token: eth::TokenAddress) -> Result<bool, Error> {
+        let cached = self.0.tokens.remove(&token).is_some();
+        let stored = self.0.storage.remove(token).await?;
+        Ok(cached || stored)
+    }
+
+    /// Removes all tokens.
+    pub async fn clear(&self) -> Result<(), Error> {
+        self.0.tokens.clear();
+        self.0.storage.clear().await
+    }
+
+    /// Evicts expired entries and adds the entries stored by other drivers.
+    /// Entries removed by other drivers are only dropped once they expire
+    /// or this driver restarts.
+    async fn refresh(&self) -> Result<(), Error> {
+        let oldest = infra::time::now() - self.0.max_age;
+        self.0.storage.evict(oldest).await?;
+        let entries = self.0.storage.load(oldest).await?;
+        self.0.tokens.retain(|_, entry| !self.is_expired(entry));
+        for entry in entries {
+            self.0
+                .tokens
+                .entry(entry.token)
+                .and_modify(|existing| {
+                    if existing.detected_at < entry.detected_at {
+                        *existing = entry.clone();
+                    }
+                })
+                .or_insert(entry);
+        }
+        Ok(())
+    }
+
+    fn is_expired(&self, entry: &Entry) -> bool {
+        infra::time::now() - entry.detected_at > self.0.max_age
+    }
+}
@@ -2,7 +2,7 @@ use {
     crate::{
         domain::{
             competition::{
-                bad_tokens::{cache::Cache, Quality},
+                bad_tokens::{cache::Cache, persisted, Quality},
                 order,
                 Order,
             },
@@ -33,16 +33,23 @@ struct Inner {
     cache: Cache,
     detector: TraceCallDetectorRaw,
     sharing: BoxRequestSharing<order::Uid, Option<Quality>>,
+    /// Unsupported tokens additionally get persisted here if configured.
+    persisted: Option<persisted::List>,
 }
 
 impl Detector {
-    pub fn new(max_age: Duration, eth: &infra::Ethereum) -> Self {
+    pub fn new(
+        max_age: Duration,
+        eth: &infra::Ethereum,
+        persisted: Option<persisted::List>,
+    ) -> Self {
         let detector =
             TraceCallDetectorRaw::new(eth.web3().clone(), eth.contracts().settlement().address());
         Self(Arc::new(Inner {
             cache: Cache::new(max_age),
             detector,
             sharing: BoxRequestSharing::labelled("bad_tokens".into()),
+            persisted,
         }))
     }
 
@@ -106,6 +113,9 @@ impl Detector {
                             inner
                                 .cache
                                 .update_quality(sell_token, Quality::Unsupported, now);
+                            if let Some(persisted) = &inner.persisted {
+                                persisted.record(sell_token, reason);
+                            }
                             Some(Quality::Unsupported)
                         }
                     }
@@ -22,7 +22,7 @@ use {
     std::{
         cmp::Reverse,
         collections::{HashMap, HashSet, VecDeque},
-        sync::{Arc, Mutex},
+        sync::{Arc, Mutex, Weak},
     },
     tap::TapFallible,
     tokio::sync::{mpsc, oneshot},
@@ -43,6 +43,11 @@ pub use {
 
 use crate::domain::BlockNo;
 
+/// Cached solutions with the most recent solutions at the front. They are
+/// shared by all competitions of a solver across config reloads so solutions
+/// of requests which were in flight during a reload can still be settled.
+pub type Settlements = Arc<Mutex<VecDeque<Settlement>>>;
+
 /// An ongoing competition. There is one competition going on per solver at any
 /// time. The competition stores settlements to solutions generated by the
 /// driver, and allows them to be executed onchain when requested later. The
@@ -55,8 +60,7 @@ pub struct Competition {
     pub liquidity: infra::liquidity::Fetcher,
     pub simulator: Simulator,
     pub mempools: Mempools,
-    /// Cached solutions with the most recent solutions at the front.
-    pub settlements: Mutex<VecDeque<Settlement>>,
+    pub settlements: Settlements,
     pub bad_tokens: Arc<bad_tokens::Detector>,
     settle_queue: mpsc::Sender<SettleRequest>,
 }
@@ -69,6 +73,7 @@ impl Competition {
         simulator: Simulator,
         mempools: Mempools,
         bad_tokens: Arc<bad_tokens::Detector>,
+        settlements: Settlements,
     ) -> Arc<Self> {
         let (settle_sender, settle_receiver) = mpsc::channel(solver.settle_queue_size());
 
@@ -78,17 +83,15 @@ impl Competition {
             liquidity,
             simulator,
             mempools,
-            settlements: Default::default(),
+            settlements,
             settle_queue: settle_sender,
             bad_tokens,
         });
 
-        let competition_clone = Arc::clone(&competition);
-        tokio::spawn(async move {
-            competition_clone
-                .process_settle_requests(settle_receiver)
-                .await;
-        });
+        tokio::spawn(Self::process_settle_requests(
+            Arc::downgrade(&competition),
+            settle_receiver,
+        ));
 
         competition
     }
@@ -259,6 +262,7 @@ impl Competition {
                         trades: settlement.orders(),
                         prices: settlement.prices(),
                         gas: Some(settlement.gas.estimate),
+                        submitter: settlement.submitter(),
                     },
                     settlement,
                 )
@@ -380,20 +384,58 @@ impl Competition {
         }
     }
 
+    /// Only holds a weak reference to the competition, so the queue gets
+    /// closed and this task ends once the competition is dropped, e.g. after
+    /// it got replaced by a config reload.
     async fn process_settle_requests(
-        self: Arc<Self>,
+        competition: Weak<Self>,
         mut settle_receiver: mpsc::Receiver<SettleRequest>,
     ) {
         while let Some(request) = settle_receiver.recv().await {
-            let SettleRequest {
-                auction_id,
-                solution_id,
-                submission_deadline,
-                response_sender,
-            } = request;
-            let solver = self.solver.name().as_str();
-            async {
-                if self.eth.current_block().borrow().number >= submission_deadline {
+            let Some(competition) = competition.upgrade() else {
+                break;
+            };
+            // Settlements get processed in parallel, each one blocking the
+            // submission account it was simulated with until it is done.
+            let submitter = competition
+                .settlements
+                .lock()
+                .unwrap()
+                .iter()
+                .find(|s| s.is_for(request.auction_id, request.solution_id))
+                .map(|s| s.submitter());
+            let Some(submitter) = submitter else {
+                if let Err(err) = request
+                    .response_sender
+                    .send(Err(Error::SolutionNotAvailable))
+                {
+                    tracing::error!(?err, "Failed to send /settle response");
+                }
+                continue;
+            };
+            tokio::spawn(competition.handle_settle_request(request, submitter));
+        }
+    }
+
+    async fn handle_settle_request(
+        self: Arc<Self>,
+        request: SettleRequest,
+        submitter: eth::Address,
+    ) {
+        let SettleRequest {
+            auction_id,
+            solution_id,
+            submission_deadline,
+            response_sender,
+        } = request;
+        let solver = self.solver.name().as_str();
+        async {
+            // Wait for the submission account to become free, but not past
+            // the submission deadline.
+            let mut blocks = self.eth.current_block().clone();
+            let account = tokio::select! {
+                biased;
+                _ = blocks.wait_for(|block| block.number >= submission_deadline) => {
                     if let Err(err) = response_sender.send(Err(DeadlineExceeded.into())) {
                         tracing::error!(
                             ?err,
@@ -402,22 +444,32 @@ impl Competition {
                     }
                     return;
                 }
-
-                observe::settling();
-                let result = self
-                    .process_settle_request(auction_id, solution_id, submission_deadline)
-                    .await;
-                observe::settled(self.solver.name(), &result);
-
-                if let Err(err) = response_sender.send(result) {
+                account = self.solver.accounts().acquire(submitter) => account,
+            };
+            let Some(account) = account else {
+                if let Err(err) = response_sender.send(Err(Error::SolutionNotAvailable)) {
                     tracing::error!(?err, "Failed to send /settle response");
                 }
+                return;
+            };
+
+            observe::settling();
+            let result = self
+                .process_settle_request(auction_id, solution_id, submission_deadline, account)
+                .await;
+            observe::settled(self.solver.name(), &result);
+
+            if let Err(err) = response_sender.send(result) {
+                tracing::error!(?err, "Failed to send /settle response");
             }
-            .instrument(
-                tracing::info_span!("/settle", solver, auction_id = ?auction_id.map(|id| id.0)),
-            )
-            .await
         }
+        .instrument(tracing::info_span!(
+            "/settle",
+            solver,
+            account = ?submitter.0,
+            auction_id = ?auction_id.map(|id| id.0)
+        ))
+        .await
     }
 
     async fn process_settle_request(
@@ -425,15 +477,13 @@ impl Competition {
         auction_id: Option<auction::Id>,
         solution_id: u64,
         submission_deadline: BlockNo,
+        mut account: solver::Lease,
     ) -> Result<Settled, Error> {
         let settlement = {
             let mut lock = self.settlements.lock().unwrap();
             let index = lock
                 .iter()
-                .position(|s| {
-                    s.solution().get() == solution_id
-                        && auction_id.is_none_or(|id| s.auction_id == id)
-                })
+                .position(|s| s.is_for(auction_id, solution_id))
                 .ok_or(Error::SolutionNotAvailable)?;
             // remove settlement to ensure we can't settle it twice by accident
             lock.swap_remove_front(index)
@@ -442,7 +492,7 @@ impl Competition {
 
         let executed = self
             .mempools
-            .execute(&self.solver, &settlement, submission_deadline)
+            .execute(&mut account, &settlement, submission_deadline)
             .await;
         notify::executed(
             &self.solver,
@@ -552,6 +602,8 @@ pub struct Solved {
     pub trades: HashMap<order::Uid, Amounts>,
     pub prices: HashMap<eth::TokenAddress, eth::TokenAmount>,
     pub gas: Option<eth::Gas>,
+    /// The account the settlement will be submitted from.
+    pub submitter: eth::Address,
 }
 
 #[derive(Debug)]
@@ -236,7 +236,7 @@ impl PartialEq<[u8; UID_LEN]> for Uid {
 }
 
 // TODO These doc comments are incorrect for limit orders
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum Side {
     /// Buy an exact amount. The sell amount can vary due to e.g. partial fills
     /// or slippage.
@@ -5,11 +5,12 @@ use {
             competition::{
                 self,
                 order::{self, Partial},
+                solution,
             },
             eth::{self, allowance, Ether},
             liquidity,
         },
-        infra::{self, solver::ManageNativeToken},
+        infra::{self, blockchain::contracts::FlashloanRouter, solver::ManageNativeToken},
         util::Bytes,
     },
     allowance::Allowance,
@@ -24,6 +25,10 @@ pub enum Error {
     MissingAuctionId,
     #[error("invalid clearing price: {0:?}")]
     InvalidClearingPrice(eth::TokenAddress),
+    #[error("solution requires flash loans but no flash loan router is configured")]
+    FlashloanRouterNotConfigured,
+    #[error("flash loan lender is not supported: {0:?}")]
+    UnsupportedFlashloanLender(eth::ContractAddress),
     #[error(transparent)]
     Math(#[from] Math),
 }
@@ -35,6 +40,7 @@ pub fn tx(
     approvals: impl Iterator<Item = eth::allowance::Approval>,
     internalization: settlement::Internalization,
     solver_native_token: ManageNativeToken,
+    submitter: eth::Address,
 ) -> Result<eth::Tx, Error> {
     let mut tokens = Vec::with_capacity(solution.prices.len() + (solution.trades().len() * 2));
     let mut clearing_prices =
@@ -215,9 +221,24 @@ pub fn tx(
     let mut calldata = tx.data.unwrap().0;
     calldata.extend(auction.id().ok_or(Error::MissingAuctionId)?.to_be_bytes());
 
+    // Solutions requiring flash loans get settled through the router which takes
+    // out the loans and then calls the settlement contract with our calldata.
+    let (to, calldata) = match solution.flashloans() {
+        [] => (contracts.settlement().address().into(), calldata),
+        flashloans => {
+            let router = contracts
+                .flashloan_router()
+                .ok_or(Error::FlashloanRouterNotConfigured)?;
+            (
+                router.address,
+                flashloan_and_settle(router, flashloans, calldata)?,
+            )
+        }
+    };
+
     Ok(eth::Tx {
-        from: solution.solver().address(),
-        to: contracts.settlement().address().into(),
+        from: submitter,
+        to: to.into(),
         input: calldata.into(),
         value: Ether(0.into()),
         access_list: Default::default(),
@@ -247,6 +268,12 @@ pub fn liquidity_interaction(
         liquidity::Kind::BalancerV2Weighted(pool) => pool
             .swap(&input, &output, &settlement.address().into())
             .ok(),
+        liquidity::Kind::BalancerV2ComposableStable(pool) => pool
+            .swap(&input, &output, &settlement.address().into())
+            .ok(),
+        liquidity::Kind::BalancerV2LiquidityBootstrapping(pool) => pool
+            .swap(&input, &output, &settlement.address().into())
+            .ok(),
         liquidity::Kind::Swapr(pool) => pool
             .swap(&input, &output, &settlement.address().into())
             .ok(),
@@ -274,6 +301,51 @@ pub fn approve(allowance: &Allowance) -> eth::Interaction {
     }
 }
 
+/// Wraps the settlement calldata into a call to the flash loan router.
+///
+/// cf. https://github.com/cowprotocol/flash-loan-router/blob/main/src/FlashLoanRouter.sol
+fn flashloan_and_settle(
+    router: &FlashloanRouter,
+    flashloans: &[solution::Flashloan],
+    settlement: Vec<u8>,
+) -> Result<Vec<u8>, Error> {
+    use ethabi::{ParamType, Token};
+
+    let loans = flashloans
+        .iter()
+        .map(|loan| {
+            let borrower = router
+                .borrowers
+                .get(&loan.lender)
+                .ok_or(Error::UnsupportedFlashloanLender(loan.lender))?;
+            // cf. `Loan.Data` (amount, borrower, lender, token)
+            Ok(Token::Tuple(vec![
+                Token::Uint(loan.amount.0),
+                Token::Address(borrower.0),
+                Token::Address(loan.lender.0),
+                Token::Address(loan.token.0 .0),
+            ]))
+        })
+        .collect::<Result<Vec<_>, Error>>()?;
+    let selector = ethabi::short_signature(
+        "flashLoanAndSettle",
+        &[
+            ParamType::Array(Box::new(ParamType::Tuple(vec![
+                ParamType::Uint(256),
+                ParamType::Address,
+                ParamType::Address,
+                ParamType::Address,
+            ]))),
+            ParamType::Bytes,
+        ],
+    );
+    Ok([
+        selector.as_slice(),
+        &ethabi::encode(&[Token::Array(loans), Token::Bytes(settlement)]),
+    ]
+    .concat())
+}
+
 fn unwrap(amount: eth::TokenAmount, weth: &contracts::WETH9) -> eth::Interaction {
     let tx = weth.withdraw(amount.into()).into_inner();
     eth::Interaction {
@@ -426,4 +498,58 @@ mod test {
         );
         assert_eq!(interaction.call_data.0.as_slice(), hex!("095ea7b3000000000000000000000000000000000022d473030f116ddee9f6b43ac78ba3ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff"));
     }
+
+    #[test]
+    fn test_flashloan_and_settle() {
+        let lender = eth::ContractAddress(eth::H160([1; 20]));
+        let borrower = eth::ContractAddress(eth::H160([2; 20]));
+        let router = FlashloanRouter {
+            address: eth::ContractAddress(eth::H160([3; 20])),
+            borrowers: [(lender, borrower)].into_iter().collect(),
+        };
+        let loan = solution::Flashloan {
+            lender,
+            token: eth::H160([4; 20]).into(),
+            amount: eth::U256::exp10(18).into(),
+        };
+        let settlement = hex!("13d79a0b0102030405").to_vec();
+
+        let calldata = flashloan_and_settle(&router, &[loan], settlement.clone()).unwrap();
+        // keccak256("flashLoanAndSettle((uint256,address,address,address)[],bytes)")
+        assert_eq!(calldata[..4], hex!("e7c438c9"));
+        let decoded = ethabi::decode(
+            &[
+                ethabi::ParamType::Array(Box::new(ethabi::ParamType::Tuple(vec![
+                    ethabi::ParamType::Uint(256),
+                    ethabi::ParamType::Address,
+                    ethabi::ParamType::Address,
+                    ethabi::ParamType::Address,
+                ]))),
+                ethabi::ParamType::Bytes,
+            ],
+            &calldata[4..],
+        )
+        .unwrap();
+        assert_eq!(
+            decoded,
+            vec![
+                ethabi::Token::Array(vec![ethabi::Token::Tuple(vec![
+                    ethabi::Token::Uint(eth::U256::exp10(18)),
+                    ethabi::Token::Address(borrower.0),
+                    ethabi::Token::Address(lender.0),
+                    ethabi::Token::Address(eth::H160([4; 20])),
+                ])]),
+                ethabi::Token::Bytes(settlement),
+            ]
+        );
+
+        let unknown = solution::Flashloan {
+            lender: eth::ContractAddress(eth::H160([5; 20])),
+            ..loan
+        };
+        assert!(matches!(
+            flashloan_and_settle(&router, &[unknown], vec![]),
+            Err(Error::UnsupportedFlashloanLender(_))
+        ));
+    }
 }
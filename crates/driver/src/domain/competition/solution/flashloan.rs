@@ -0,0 +1,30 @@
+use crate::domain::eth;
+
+/// A flash loan a solution requires to be executed. The borrowed funds are
+/// available during the whole settlement and have to be repaid (including any
+/// fees charged by the lender) by the end of it.
+///
+/// Solutions with flash loans get settled by calling the flash loan router
+/// which takes out all loans before calling into the settlement contract.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Flashloan {
+    /// The contract lending the funds.
+    pub lender: eth::ContractAddress,
+    /// The borrowed token and amount.
+    pub token: eth::TokenAddress,
+    pub amount: eth::TokenAmount,
+}
+
+/// Estimated gas the flash loan router needs on top of the wrapped
+/// settlement: calling into the router and then, for every loan, calling the
+/// borrower which takes out the loan and transfers the funds back to the
+/// lender. Solvers only estimate the gas of the `settle` call itself.
+pub fn router_gas(flashloans: &[Flashloan]) -> eth::Gas {
+    const ROUTER_GAS: u64 = 30_000;
+    const GAS_PER_LOAN: u64 = 120_000;
+
+    match flashloans.len() {
+        0 => 0.into(),
+        loans => (ROUTER_GAS + GAS_PER_LOAN * loans as u64).into(),
+    }
+}
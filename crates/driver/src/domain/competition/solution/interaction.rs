@@ -41,6 +41,8 @@ impl Interaction {
                     liquidity::Kind::UniswapV3(pool) => pool.router.into(),
                     liquidity::Kind::BalancerV2Stable(pool) => pool.vault.into(),
                     liquidity::Kind::BalancerV2Weighted(pool) => pool.vault.into(),
+                    liquidity::Kind::BalancerV2ComposableStable(pool) => pool.vault.into(),
+                    liquidity::Kind::BalancerV2LiquidityBootstrapping(pool) => pool.vault.into(),
                     liquidity::Kind::Swapr(pool) => pool.base.router.into(),
                     liquidity::Kind::ZeroEx(pool) => pool.zeroex.address().into(),
                 };
@@ -28,13 +28,20 @@ use {
 
 pub mod encoding;
 pub mod fee;
+pub mod flashloan;
 pub mod interaction;
 pub mod scoring;
 pub mod settlement;
 pub mod slippage;
 pub mod trade;
 
-pub use {error::Error, interaction::Interaction, settlement::Settlement, trade::Trade};
+pub use {
+    error::Error,
+    flashloan::Flashloan,
+    interaction::Interaction,
+    settlement::Settlement,
+    trade::Trade,
+};
 
 type Prices = HashMap<eth::TokenAddress, eth::U256>;
 
@@ -51,6 +58,7 @@ pub struct Solution {
     pre_interactions: Vec<eth::Interaction>,
     interactions: Vec<Interaction>,
     post_interactions: Vec<eth::Interaction>,
+    flashloans: Vec<Flashloan>,
     solver: Solver,
     weth: eth::WethAddress,
     gas: Option<eth::Gas>,
@@ -65,6 +73,7 @@ impl Solution {
         pre_interactions: Vec<eth::Interaction>,
         interactions: Vec<Interaction>,
         post_interactions: Vec<eth::Interaction>,
+        flashloans: Vec<Flashloan>,
         solver: Solver,
         weth: eth::WethAddress,
         gas: Option<eth::Gas>,
@@ -122,6 +131,7 @@ impl Solution {
             pre_interactions,
             interactions,
             post_interactions,
+            flashloans,
             solver,
             weth,
             gas,
@@ -195,13 +205,21 @@ impl Solution {
         &self.pre_interactions
     }
 
+    /// Flash loans that need to be taken out before executing the settlement.
+    pub fn flashloans(&self) -> &[Flashloan] {
+        &self.flashloans
+    }
+
     /// The solver which generated this solution.
     pub fn solver(&self) -> &Solver {
         &self.solver
     }
 
+    /// The gas the solver estimated for settling this solution including the
+    /// overhead of the flash loan router.
     pub fn gas(&self) -> Option<eth::Gas> {
         self.gas
+            .map(|gas| gas + flashloan::router_gas(&self.flashloans))
     }
 
     fn trade_count_for_scorable(
@@ -347,6 +365,7 @@ impl Solution {
                 other.post_interactions.clone(),
             ]
             .concat(),
+            flashloans: [self.flashloans.clone(), other.flashloans.clone()].concat(),
             solver: self.solver.clone(),
             weth: self.weth,
             // Same solver are guaranteed to have the same fee handler
@@ -488,6 +507,7 @@ impl std::fmt::Debug for Solution {
             .field("pre_interactions", &self.pre_interactions)
             .field("interactions", &self.interactions)
             .field("post_interactions", &self.post_interactions)
+            .field("flashloans", &self.flashloans)
             .field("solver", &self.solver.name())
             .finish()
     }
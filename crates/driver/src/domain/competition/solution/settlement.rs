@@ -92,7 +92,9 @@ impl Settlement {
             return Err(Error::NonBufferableTokensUsed(untrusted_tokens));
         }
 
-        // Encode the solution into a settlement.
+        // Encode the solution into a settlement which gets sent by the account
+        // of the solver's pool it is assigned to.
+        let submitter = solution.solver().accounts().assign();
         let tx = SettlementTx {
             internalized: encoding::tx(
                 auction,
@@ -101,6 +103,7 @@ impl Settlement {
                 solution.approvals(eth, Internalization::Enable).await?,
                 Internalization::Enable,
                 solver_native_token,
+                submitter,
             )?,
             uninternalized: encoding::tx(
                 auction,
@@ -109,6 +112,7 @@ impl Settlement {
                 solution.approvals(eth, Internalization::Disable).await?,
                 Internalization::Disable,
                 solver_native_token,
+                submitter,
             )?,
             may_revert: solution.revertable(),
         };
@@ -139,7 +143,7 @@ impl Settlement {
                 return Ok(Default::default());
             }
             let tx = eth::Tx {
-                from: solution.solver().address(),
+                from: transaction.internalized.from,
                 to: trade.order().receiver(),
                 value: 1.into(),
                 input: Default::default(),
@@ -163,8 +167,9 @@ impl Settlement {
         let price = eth.gas_price().await?;
         let gas = Gas::new(gas, eth.block_gas_limit(), price)?;
 
-        // Ensure that the solver has sufficient balance for the settlement to be mined.
-        if eth.balance(solution.solver().address()).await? < gas.required_balance() {
+        // Ensure that the submitting account has sufficient balance for the
+        // settlement to be mined.
+        if eth.balance(transaction.internalized.from).await? < gas.required_balance() {
             return Err(Error::SolverAccountInsufficientBalance(
                 gas.required_balance(),
             ));
@@ -211,16 +216,24 @@ impl Settlement {
         // Add the partial access list to the settlement tx.
         let tx = tx.set_access_list(partial_access_list.to_owned());
 
-        // Simulate the full access list, passing the partial access
-        // list into the simulation.
-        let access_list = simulator.access_list(&tx).await?;
-        let tx = tx.set_access_list(access_list.clone());
+        // Simulate the full access list and the gas used, passing the partial
+        // access list into the simulation.
+        let simulation = simulator.simulate(&tx).await;
 
-        // Simulate the settlement using the full access list and get the gas used.
-        let gas = simulator.gas(&tx).await;
+        observe::simulated(eth, &tx, &simulation);
+        let simulation = simulation?;
+        Ok((simulation.access_list, simulation.gas))
+    }
+
+    /// Whether this is the settlement of the given solution.
+    pub fn is_for(&self, auction_id: Option<auction::Id>, solution_id: u64) -> bool {
+        self.solution().get() == solution_id && auction_id.is_none_or(|id| self.auction_id == id)
+    }
 
-        observe::simulated(eth, &tx, &gas);
-        Ok((access_list, gas?))
+    /// The account this settlement was simulated from and has to be
+    /// submitted from.
+    pub fn submitter(&self) -> eth::Address {
+        self.transaction.internalized.from
     }
 
     /// The calldata for this settlement.
@@ -49,6 +49,8 @@ pub enum Kind {
     UniswapV3(uniswap::v3::Pool),
     BalancerV2Stable(balancer::v2::stable::Pool),
     BalancerV2Weighted(balancer::v2::weighted::Pool),
+    BalancerV2ComposableStable(balancer::v2::stable::Pool),
+    BalancerV2LiquidityBootstrapping(balancer::v2::weighted::Pool),
     Swapr(swapr::Pool),
     ZeroEx(zeroex::LimitOrder),
 }
@@ -60,6 +62,8 @@ impl From<&Kind> for &'static str {
             Kind::UniswapV3(_) => "UniswapV3",
             Kind::BalancerV2Stable(_) => "BalancerV2Stable",
             Kind::BalancerV2Weighted(_) => "BalancerV2Weighted",
+            Kind::BalancerV2ComposableStable(_) => "BalancerV2ComposableStable",
+            Kind::BalancerV2LiquidityBootstrapping(_) => "BalancerV2LiquidityBootstrapping",
             Kind::Swapr(_) => "Swapr",
             Kind::ZeroEx(_) => "ZeroExLimitOrder",
         }
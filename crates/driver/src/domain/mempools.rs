@@ -9,7 +9,7 @@ use {
             eth::{TxId, TxStatus},
             BlockNo,
         },
-        infra::{self, observe, solver::Solver, Ethereum},
+        infra::{self, observe, solver, Ethereum},
     },
     anyhow::Context,
     ethrpc::block_stream::into_stream,
@@ -41,18 +41,25 @@ impl Mempools {
         }
     }
 
-    /// Publish a settlement to the mempools.
+    /// Publish a settlement to the mempools from the leased account.
     pub async fn execute(
         &self,
-        solver: &Solver,
+        account: &mut solver::Lease,
         settlement: &Settlement,
         submission_deadline: BlockNo,
     ) -> Result<eth::TxId, Error> {
-        let (tx_hash, _remaining_futures) =
+        // All mempools submit the settlement at the same nonce, so that at most
+        // one of them can get included and a cancellation replaces all of them.
+        let nonce = account
+            .nonce(&self.ethereum)
+            .await
+            .context("failed to fetch account nonce")?;
+        let result = {
+            let account = &*account;
             select_ok(self.mempools.iter().cloned().map(|mempool| {
                 async move {
                     let result = self
-                        .submit(&mempool, solver, settlement, submission_deadline)
+                        .submit(&mempool, account, nonce, settlement, submission_deadline)
                         .instrument(tracing::info_span!("mempool", kind = mempool.to_string()))
                         .await;
                     observe::mempool_executed(&mempool, settlement, &result);
@@ -60,9 +67,14 @@ impl Mempools {
                 }
                 .boxed()
             }))
-            .await?;
+            .await
+            .map(|(tx_hash, _remaining_futures)| tx_hash)
+        };
 
-        Ok(tx_hash)
+        if let Ok(_) | Err(Error::Revert(_)) = result {
+            account.mined(nonce);
+        }
+        result
     }
 
     /// Defines if the mempools are configured in a way that guarantees that
@@ -86,7 +98,8 @@ impl Mempools {
     async fn submit(
         &self,
         mempool: &infra::mempool::Mempool,
-        solver: &Solver,
+        account: &solver::Lease,
+        nonce: eth::U256,
         settlement: &Settlement,
         submission_deadline: BlockNo,
     ) -> Result<eth::TxId, Error> {
@@ -125,7 +138,9 @@ impl Mempools {
             }
         }
 
-        let hash = mempool.submit(tx.clone(), settlement.gas, solver).await?;
+        let hash = mempool
+            .submit(tx.clone(), settlement.gas, account.account(), nonce)
+            .await?;
         tracing::debug!(?hash, "submitted tx to the mempool");
 
         // Wait for the transaction to be mined, expired or failing.
@@ -146,8 +161,17 @@ impl Mempools {
                     TxStatus::Pending => {
                         // Check if the current block reached the submission deadline block number
                         if block.number >= submission_deadline {
+                            if !mempool.requires_cancellation() {
+                                tracing::info!(
+                                    settle_tx_hash = ?hash,
+                                    deadline = submission_deadline,
+                                    current_block = block.number,
+                                    "tx not included in time, dropping",
+                                );
+                                return Err(Error::Expired);
+                            }
                             let cancellation_tx_hash = self
-                                .cancel(mempool, settlement.gas.price, solver)
+                                .cancel(mempool, settlement.gas.price, account, nonce)
                                 .await
                                 .context("cancellation tx due to deadline failed")?;
                             tracing::info!(
@@ -162,8 +186,16 @@ impl Mempools {
                         // Check if transaction still simulates
                         if let Err(err) = self.ethereum.estimate_gas(tx).await {
                             if err.is_revert() {
+                                if !mempool.requires_cancellation() {
+                                    tracing::info!(
+                                        settle_tx_hash = ?hash,
+                                        ?err,
+                                        "tx started failing, dropping"
+                                    );
+                                    return Err(Error::SimulationRevert);
+                                }
                                 let cancellation_tx_hash = self
-                                    .cancel(mempool, settlement.gas.price, solver)
+                                    .cancel(mempool, settlement.gas.price, account, nonce)
                                     .await
                                     .context("cancellation tx due to revert failed")?;
                                 tracing::info!(
@@ -177,6 +209,11 @@ impl Mempools {
                                 tracing::warn!(?hash, ?err, "couldn't re-simulate tx");
                             }
                         }
+                        // Target the next block in case the tx was submitted
+                        // as a bundle.
+                        if let Err(err) = mempool.resubmit(&hash, block.number + 1).await {
+                            tracing::warn!(?hash, ?err, "failed to resubmit tx");
+                        }
                     }
                 }
             }
@@ -185,6 +222,7 @@ impl Mempools {
             )))
         }
         .await;
+        mempool.forget(&hash);
 
         if result.is_err() {
             // Do one last attempt to see if the transaction was confirmed (in case of race
@@ -197,17 +235,18 @@ impl Mempools {
         result
     }
 
-    /// Cancel a pending settlement by sending a transaction to self with a
-    /// slightly higher gas price than the existing one.
+    /// Cancel a pending settlement by sending a transaction to self at the
+    /// same nonce with a slightly higher gas price than the existing one.
     async fn cancel(
         &self,
         mempool: &infra::mempool::Mempool,
         pending: eth::GasPrice,
-        solver: &Solver,
+        account: &solver::Lease,
+        nonce: eth::U256,
     ) -> Result<TxId, Error> {
         let cancellation = eth::Tx {
-            from: solver.address(),
-            to: solver.address(),
+            from: account.address(),
+            to: account.address(),
             value: 0.into(),
             input: Default::default(),
             access_list: Default::default(),
@@ -217,7 +256,9 @@ impl Mempools {
             limit: CANCELLATION_GAS_AMOUNT.into(),
             price: pending * GAS_PRICE_BUMP,
         };
-        mempool.submit(cancellation, gas, solver).await
+        mempool
+            .submit(cancellation, gas, account.account(), nonce)
+            .await
     }
 }
 
@@ -22,8 +22,12 @@ use {
     },
 };
 
+mod cache;
+
+pub use cache::{Cache, Lookup, Outcome};
+
 /// A quote describing the expected outcome of an order.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct Quote {
     pub clearing_prices: HashMap<eth::H160, eth::U256>,
     pub pre_interactions: Vec<eth::Interaction>,
@@ -68,7 +72,7 @@ impl Quote {
 }
 
 /// An order which needs to be quoted.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct Order {
     pub tokens: Tokens,
     pub amount: order::TargetAmount,
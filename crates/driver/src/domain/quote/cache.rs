@@ -0,0 +1,268 @@
+use {
+    super::{Error, Order, Quote, Tokens},
+    crate::domain::{competition::order, eth, BlockNo},
+    futures::{
+        future::{BoxFuture, Shared},
+        Future,
+        FutureExt,
+    },
+    std::{
+        collections::HashMap,
+        sync::{Arc, Mutex},
+    },
+};
+
+/// The outcome of a quote which can be shared between multiple requests.
+pub type Outcome = Result<Quote, Arc<Error>>;
+
+/// Shares quotes between identical quote requests of the same block, so the
+/// solver only gets asked once. Requests arriving while an identical quote is
+/// still being computed wait for that quote instead of starting another one.
+///
+/// Quotes are only valid for the block they were computed on, so the cache
+/// gets cleared as soon as a request for a newer block arrives. Failed quotes
+/// don't get cached.
+#[derive(Debug, Clone)]
+pub struct Cache(Arc<Inner>);
+
+#[derive(Debug)]
+struct Inner {
+    /// Amounts which are equal when rounded down to this many significant
+    /// digits share one quote. [`None`] to only share quotes of identical
+    /// amounts.
+    significant_digits: Option<u32>,
+    quotes: Mutex<Quotes>,
+}
+
+#[derive(Debug, Default)]
+struct Quotes {
+    block: BlockNo,
+    entries: HashMap<Key, Shared<BoxFuture<'static, Outcome>>>,
+}
+
+/// Identifies quote requests which can be answered with the same quote. The
+/// deadline is not part of the key since callers derive it from the time of
+/// their request, so it differs for every request.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+struct Key {
+    tokens: Tokens,
+    side: order::Side,
+    amount: eth::U256,
+}
+
+/// How a quote request was answered.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Lookup {
+    /// The quote was already computed for the current block.
+    Hit,
+    /// An identical quote was in flight and got awaited.
+    Coalesced,
+    /// The quote had to be computed.
+    Miss,
+}
+
+impl Cache {
+    pub fn new(significant_digits: Option<u32>) -> Self {
+        Self(Arc::new(Inner {
+            significant_digits,
+            quotes: Default::default(),
+        }))
+    }
+
+    /// Returns the quote for the order on the given block. The quote gets
+    /// computed with `quote` unless an identical request was already answered
+    /// or is in flight.
+    pub async fn get_or_quote<F>(
+        &self,
+        order: &Order,
+        block: BlockNo,
+        quote: F,
+    ) -> (Outcome, Lookup)
+    where
+        F: Future<Output = Result<Quote, Error>> + Send + 'static,
+    {
+        let key = Key {
+            tokens: order.tokens,
+            side: order.side,
+            amount: bucket(order.amount.0, self.0.significant_digits),
+        };
+        let (shared, lookup) = {
+            let mut quotes = self.0.quotes.lock().unwrap();
+            if block > quotes.block {
+                *quotes = Quotes {
+                    block,
+                    entries: Default::default(),
+                };
+            }
+            match quotes.entries.get(&key) {
+                Some(shared) if shared.peek().is_some() => (shared.clone(), Lookup::Hit),
+                Some(shared) => (shared.clone(), Lookup::Coalesced),
+                None => {
+                    let shared = async move { quote.await.map_err(Arc::new) }
+                        .boxed()
+                        .shared();
+                    quotes.entries.insert(key, shared.clone());
+                    (shared, Lookup::Miss)
+                }
+            }
+        };
+
+        let outcome = shared.clone().await;
+        if outcome.is_err() {
+            // Let the next request retry instead of serving the error for
+            // the rest of the block.
+            let mut quotes = self.0.quotes.lock().unwrap();
+            if quotes
+                .entries
+                .get(&key)
+                .is_some_and(|cached| cached.ptr_eq(&shared))
+            {
+                quotes.entries.remove(&key);
+            }
+        }
+        (outcome, lookup)
+    }
+}
+
+/// Rounds the amount down to the given number of significant digits.
+fn bucket(amount: eth::U256, significant_digits: Option<u32>) -> eth::U256 {
+    let Some(digits) = significant_digits else {
+        return amount;
+    };
+    let limit = eth::U256::exp10(digits as usize);
+    let mut scale = eth::U256::one();
+    while amount / scale >= limit {
+        scale *= 10;
+    }
+    amount / scale * scale
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::{
+            domain::{quote::QuotingFailed, time},
+            infra::solver::Timeouts,
+        },
+        std::sync::atomic::{AtomicUsize, Ordering},
+    };
+
+    fn sell_order(amount: u64) -> Order {
+        sell_order_with_deadline(amount, time::Deadline::default())
+    }
+
+    fn sell_order_with_deadline(amount: u64, deadline: time::Deadline) -> Order {
+        Order {
+            tokens: Tokens::new(
+                eth::H160::repeat_byte(1).into(),
+                eth::H160::repeat_byte(2).into(),
+            )
+            .unwrap(),
+            amount: eth::U256::from(amount).into(),
+            side: order::Side::Sell,
+            deadline,
+        }
+    }
+
+    fn quote(calls: &Arc<AtomicUsize>) -> impl Future<Output = Result<Quote, Error>> {
+        let calls = calls.clone();
+        async move {
+            calls.fetch_add(1, Ordering::SeqCst);
+            tokio::task::yield_now().await;
+            Ok(Quote {
+                clearing_prices: Default::default(),
+                pre_interactions: Default::default(),
+                interactions: Default::default(),
+                solver: Default::default(),
+                gas: None,
+                tx_origin: None,
+                jit_orders: Default::default(),
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn shares_quotes_of_the_same_block() {
+        let cache = Cache::new(Some(3));
+        let calls = Arc::new(AtomicUsize::default());
+
+        let (first, second) = futures::join!(
+            cache.get_or_quote(&sell_order(123_400), 1, quote(&calls)),
+            cache.get_or_quote(&sell_order(123_499), 1, quote(&calls)),
+        );
+        assert_eq!((first.1, second.1), (Lookup::Miss, Lookup::Coalesced));
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+
+        let (_, lookup) = cache
+            .get_or_quote(&sell_order(123_000), 1, quote(&calls))
+            .await;
+        assert_eq!(lookup, Lookup::Hit);
+        let (_, lookup) = cache
+            .get_or_quote(&sell_order(124_000), 1, quote(&calls))
+            .await;
+        assert_eq!(lookup, Lookup::Miss);
+
+        // A new block invalidates all quotes.
+        let (_, lookup) = cache
+            .get_or_quote(&sell_order(123_000), 2, quote(&calls))
+            .await;
+        assert_eq!(lookup, Lookup::Miss);
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn shares_quotes_of_requests_with_different_deadlines() {
+        let cache = Cache::new(Some(3));
+        let calls = Arc::new(AtomicUsize::default());
+        // Like the orderbook, which sets the deadline relative to the time
+        // the request is sent.
+        let deadline = |requested_at: i64| {
+            time::Deadline::new(
+                chrono::DateTime::from_timestamp(requested_at + 5, 0).unwrap(),
+                Timeouts {
+                    http_delay: chrono::Duration::zero(),
+                    solving_share_of_deadline: 1.0_f64.try_into().unwrap(),
+                },
+            )
+        };
+
+        let (_, lookup) = cache
+            .get_or_quote(
+                &sell_order_with_deadline(123_400, deadline(1_700_000_000)),
+                1,
+                quote(&calls),
+            )
+            .await;
+        assert_eq!(lookup, Lookup::Miss);
+        let (_, lookup) = cache
+            .get_or_quote(
+                &sell_order_with_deadline(123_456, deadline(1_700_000_003)),
+                1,
+                quote(&calls),
+            )
+            .await;
+        assert_eq!(lookup, Lookup::Hit);
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn does_not_cache_errors() {
+        let cache = Cache::new(None);
+        let failing = async { Err::<Quote, _>(Error::QuotingFailed(QuotingFailed::NoSolutions)) };
+        let (outcome, _) = cache.get_or_quote(&sell_order(1), 1, failing).await;
+        assert!(outcome.is_err());
+
+        let calls = Arc::new(AtomicUsize::default());
+        let (outcome, lookup) = cache.get_or_quote(&sell_order(1), 1, quote(&calls)).await;
+        assert!(outcome.is_ok());
+        assert_eq!(lookup, Lookup::Miss);
+    }
+
+    #[test]
+    fn buckets_amounts() {
+        assert_eq!(bucket(123_456.into(), None), 123_456.into());
+        assert_eq!(bucket(123_456.into(), Some(3)), 123_000.into());
+        assert_eq!(bucket(99.into(), Some(3)), 99.into());
+    }
+}
@@ -4,6 +4,7 @@ use {
         infra::api,
     },
     serde::Serialize,
+    std::sync::Arc,
 };
 
 #[derive(Debug, Clone, Copy, Serialize)]
@@ -64,9 +65,9 @@ impl From<Kind> for (hyper::StatusCode, axum::Json<Error>) {
     }
 }
 
-impl From<quote::Error> for (hyper::StatusCode, axum::Json<Error>) {
-    fn from(value: quote::Error) -> Self {
-        let error = match value {
+impl From<Arc<quote::Error>> for (hyper::StatusCode, axum::Json<Error>) {
+    fn from(value: Arc<quote::Error>) -> Self {
+        let error = match value.as_ref() {
             quote::Error::QuotingFailed(_) => Kind::QuotingFailed,
             quote::Error::DeadlineExceeded(_) => Kind::DeadlineExceeded,
             quote::Error::Solver(_) => Kind::SolverFailed,
@@ -11,10 +11,16 @@ use {
             Simulator,
         },
     },
+    axum::response::IntoResponse,
     error::Error,
     futures::Future,
-    std::{net::SocketAddr, sync::Arc},
-    tokio::sync::oneshot,
+    std::{
+        collections::HashMap,
+        net::SocketAddr,
+        sync::{Arc, RwLock},
+    },
+    tokio::sync::{mpsc, oneshot},
+    tower::ServiceExt,
 };
 
 mod error;
@@ -29,10 +35,28 @@ pub struct Api {
     pub eth: Ethereum,
     pub mempools: Mempools,
     pub addr: SocketAddr,
+    /// The admin routes are served on this address if it is set.
+    pub admin_addr: Option<SocketAddr>,
     pub bad_token_detector: bad_tokens::simulation::Detector,
+    /// Tokens persistently detected as unsupported. If configured they can
+    /// be managed via the `/bad-tokens` admin routes.
+    pub persisted_bad_tokens: Option<bad_tokens::persisted::List>,
+    /// Quotes for amounts which are equal when rounded down to this many
+    /// significant digits get shared.
+    pub quote_cache_significant_digits: Option<u32>,
     /// If this channel is specified, the bound address will be sent to it. This
     /// allows the driver to bind to 0.0.0.0:0 during testing.
     pub addr_sender: Option<oneshot::Sender<SocketAddr>>,
+    /// Receives the components to use from now on whenever the driver config
+    /// got reloaded.
+    pub reloads: mpsc::UnboundedReceiver<Reload>,
+}
+
+/// The components that get replaced when the driver config gets reloaded.
+pub struct Reload {
+    pub solvers: Vec<Solver>,
+    pub mempools: Mempools,
+    pub eth: Ethereum,
 }
 
 impl Api {
@@ -50,68 +74,67 @@ impl Api {
                 .layer(tower_http::trace::TraceLayer::new_for_http()),
         );
 
-        let tokens = tokens::Fetcher::new(&self.eth);
-        let pre_processor =
-            domain::competition::AuctionProcessor::new(&self.eth, order_priority_strategies);
-
         // Add the metrics and healthz endpoints.
         app = routes::metrics(app);
         app = routes::healthz(app);
+        // The admin routes allow changing the driver's behaviour so they are
+        // served on a separate address which doesn't have to be exposed.
+        if let (Some(list), Some(addr)) = (&self.persisted_bad_tokens, self.admin_addr) {
+            let admin = routes::bad_tokens(axum::Router::new(), list.clone());
+            let server = axum::Server::bind(&addr).serve(admin.into_make_service());
+            tracing::info!(
+                port = server.local_addr().port(),
+                "serving driver admin routes"
+            );
+            tokio::spawn(async move {
+                if let Err(err) = server.await {
+                    tracing::error!(?err, "admin server failed");
+                }
+            });
+        }
 
-        let metrics_bad_token_detector_builder = bad_tokens::metrics::DetectorBuilder::default();
-
-        // Multiplex each solver as part of the API. Multiple solvers are multiplexed
-        // on the same driver so only one liquidity collector collects the liquidity
-        // for all of them. This is important because liquidity collection is
-        // computationally expensive for the Ethereum node.
-        for solver in self.solvers {
-            let name = solver.name().clone();
-            let router = axum::Router::new();
-            let router = routes::info(router);
-            let router = routes::quote(router);
-            let router = routes::solve(router);
-            let router = routes::reveal(router);
-            let router = routes::settle(router);
-
-            let mut bad_tokens =
-                bad_tokens::Detector::new(solver.bad_token_detection().tokens_supported.clone());
-            if solver.bad_token_detection().enable_simulation_strategy {
-                bad_tokens.with_simulation_detector(self.bad_token_detector.clone());
-            }
+        let mounter = Mounter {
+            liquidity: self.liquidity,
+            simulator: self.simulator,
+            tokens: tokens::Fetcher::new(&self.eth),
+            pre_processor: domain::competition::AuctionProcessor::new(
+                &self.eth,
+                order_priority_strategies,
+            ),
+            bad_token_detector: self.bad_token_detector,
+            persisted_bad_tokens: self.persisted_bad_tokens,
+            quote_cache_significant_digits: self.quote_cache_significant_digits,
+            metrics_bad_token_detector_builder: Default::default(),
+        };
+        let solvers = Solvers::default();
+        solvers.replace(mounter.mount(self.solvers, &self.eth, &self.mempools, &solvers));
 
-            if solver.bad_token_detection().enable_metrics_strategy {
-                bad_tokens.with_metrics_detector(
-                    metrics_bad_token_detector_builder.clone().build(
-                        solver.bad_token_detection().metrics_strategy_failure_ratio,
-                        solver
-                            .bad_token_detection()
-                            .metrics_strategy_required_measurements,
-                    ),
+        // Swap the solvers once the config got reloaded. Requests that are
+        // already in flight keep using the solvers they were routed to.
+        let mut reloads = self.reloads;
+        let reloaded_solvers = solvers.clone();
+        tokio::spawn(async move {
+            while let Some(reload) = reloads.recv().await {
+                let mounted = mounter.mount(
+                    reload.solvers,
+                    &reload.eth,
+                    &reload.mempools,
+                    &reloaded_solvers,
                 );
+                reloaded_solvers.replace(mounted);
             }
+        });
 
-            let router = router.with_state(State(Arc::new(Inner {
-                eth: self.eth.clone(),
-                solver: solver.clone(),
-                competition: domain::Competition::new(
-                    solver,
-                    self.eth.clone(),
-                    self.liquidity.clone(),
-                    self.simulator.clone(),
-                    self.mempools.clone(),
-                    Arc::new(bad_tokens),
-                ),
-                liquidity: self.liquidity.clone(),
-                tokens: tokens.clone(),
-                pre_processor: pre_processor.clone(),
-            })));
-            let path = format!("/{name}");
-            infra::observe::mounting_solver(&name, &path);
-            app = app
-                .nest(&path, router)
-                // axum's default body limit needs to be disabled to not have the default limit on top of our custom limit
-                .layer(axum::extract::DefaultBodyLimit::disable());
-        }
+        // Multiple solvers are multiplexed on the same driver so only one
+        // liquidity collector collects the liquidity for all of them. This is
+        // important because liquidity collection is computationally expensive
+        // for the Ethereum node.
+        app = app
+            .fallback(move |req: axum::http::Request<axum::body::Body>| {
+                route_to_solver(solvers.clone(), req)
+            })
+            // axum's default body limit needs to be disabled to not have the default limit on top of our custom limit
+            .layer(axum::extract::DefaultBodyLimit::disable());
 
         let make_svc = observe::make_service_with_task_local_storage!(app);
 
@@ -125,6 +148,156 @@ impl Api {
     }
 }
 
+/// Builds the API routes of every solver.
+struct Mounter {
+    liquidity: liquidity::Fetcher,
+    simulator: Simulator,
+    tokens: tokens::Fetcher,
+    pre_processor: domain::competition::AuctionProcessor,
+    bad_token_detector: bad_tokens::simulation::Detector,
+    persisted_bad_tokens: Option<bad_tokens::persisted::List>,
+    quote_cache_significant_digits: Option<u32>,
+    metrics_bad_token_detector_builder: bad_tokens::metrics::DetectorBuilder,
+}
+
+impl Mounter {
+    /// Builds the routes of the given solvers. Solutions and submission
+    /// accounts of the currently mounted solvers of the same name are shared
+    /// with the new ones so they can still be settled.
+    fn mount(
+        &self,
+        solvers: Vec<Solver>,
+        eth: &Ethereum,
+        mempools: &Mempools,
+        current: &Solvers,
+    ) -> HashMap<String, Mounted> {
+        solvers
+            .into_iter()
+            .map(|solver| {
+                let name = solver.name().clone();
+                let router = axum::Router::new();
+                let router = routes::info(router);
+                let router = routes::quote(router);
+                let router = routes::solve(router);
+                let router = routes::reveal(router);
+                let router = routes::settle(router);
+
+                let mut bad_tokens = bad_tokens::Detector::new(
+                    solver.bad_token_detection().tokens_supported.clone(),
+                );
+                let detection = solver.bad_token_detection();
+                if let Some(list) = &self.persisted_bad_tokens {
+                    if detection.enable_simulation_strategy || detection.enable_metrics_strategy {
+                        bad_tokens.with_persisted_tokens(list.clone());
+                    }
+                }
+                if solver.bad_token_detection().enable_simulation_strategy {
+                    bad_tokens.with_simulation_detector(self.bad_token_detector.clone());
+                }
+
+                if solver.bad_token_detection().enable_metrics_strategy {
+                    bad_tokens.with_metrics_detector(
+                        self.metrics_bad_token_detector_builder.clone().build(
+                            solver.bad_token_detection().metrics_strategy_failure_ratio,
+                            solver
+                                .bad_token_detection()
+                                .metrics_strategy_required_measurements,
+                        ),
+                    );
+                }
+
+                // Keep using the state of the solver this one replaces, as
+                // requests which are still in flight might add settlements or
+                // send transactions from its accounts.
+                let previous = current.get(&name.0);
+                let solver = match &previous {
+                    Some(previous) => solver.take_over_accounts(&previous.competition.solver),
+                    None => solver,
+                };
+                let settlements = previous
+                    .map(|previous| Arc::clone(&previous.competition.settlements))
+                    .unwrap_or_default();
+
+                let competition = domain::Competition::new(
+                    solver.clone(),
+                    eth.clone(),
+                    self.liquidity.clone(),
+                    self.simulator.clone(),
+                    mempools.clone(),
+                    Arc::new(bad_tokens),
+                    settlements,
+                );
+
+                let router = router.with_state(State(Arc::new(Inner {
+                    eth: eth.clone(),
+                    solver,
+                    competition: competition.clone(),
+                    liquidity: self.liquidity.clone(),
+                    tokens: self.tokens.clone(),
+                    pre_processor: self.pre_processor.clone(),
+                    quotes: domain::quote::Cache::new(self.quote_cache_significant_digits),
+                })));
+                let path = format!("/{name}");
+                infra::observe::mounting_solver(&name, &path);
+                (
+                    name.0,
+                    Mounted {
+                        router: router.layer(axum::extract::DefaultBodyLimit::disable()),
+                        competition,
+                    },
+                )
+            })
+            .collect()
+    }
+}
+
+/// The routes of a solver which are currently served.
+#[derive(Clone)]
+struct Mounted {
+    router: axum::Router,
+    competition: Arc<domain::Competition>,
+}
+
+/// All currently served solvers by name.
+#[derive(Clone, Default)]
+struct Solvers(Arc<RwLock<HashMap<String, Mounted>>>);
+
+impl Solvers {
+    fn get(&self, name: &str) -> Option<Mounted> {
+        self.0.read().unwrap().get(name).cloned()
+    }
+
+    fn replace(&self, solvers: HashMap<String, Mounted>) {
+        *self.0.write().unwrap() = solvers;
+    }
+}
+
+/// Forwards the request to the routes of the solver named by the first path
+/// segment (e.g. `/<solver>/solve`).
+async fn route_to_solver(
+    solvers: Solvers,
+    mut req: axum::http::Request<axum::body::Body>,
+) -> axum::response::Response {
+    let path = req.uri().path().trim_start_matches('/');
+    let (name, rest) = path.split_once('/').unwrap_or((path, ""));
+    let Some(solver) = solvers.get(name) else {
+        return axum::http::StatusCode::NOT_FOUND.into_response();
+    };
+
+    let path_and_query = match req.uri().query() {
+        Some(query) => format!("/{rest}?{query}"),
+        None => format!("/{rest}"),
+    };
+    let mut uri = req.uri().clone().into_parts();
+    uri.path_and_query = Some(path_and_query.parse().expect("valid path"));
+    *req.uri_mut() = axum::http::Uri::from_parts(uri).expect("valid uri");
+
+    match solver.router.oneshot(req).await {
+        Ok(response) => response,
+        Err(err) => match err {},
+    }
+}
+
 #[derive(Clone)]
 struct State(Arc<Inner>);
 
@@ -153,6 +326,10 @@ impl State {
         &self.0.pre_processor
     }
 
+    fn quotes(&self) -> &domain::quote::Cache {
+        &self.0.quotes
+    }
+
     fn timeouts(&self) -> Timeouts {
         self.0.solver.timeouts()
     }
@@ -165,4 +342,5 @@ struct Inner {
     liquidity: liquidity::Fetcher,
     tokens: tokens::Fetcher,
     pre_processor: domain::competition::AuctionProcessor,
+    quotes: domain::quote::Cache,
 }
@@ -0,0 +1,29 @@
+use {
+    crate::{domain::eth, infra::persistence::bad_tokens::Entry},
+    serde::{Deserialize, Serialize},
+};
+
+impl BadToken {
+    pub fn new(entry: Entry) -> Self {
+        Self {
+            token: entry.token.into(),
+            reason: entry.reason,
+            detected_at: entry.detected_at,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BadToken {
+    token: eth::H160,
+    reason: String,
+    detected_at: chrono::DateTime<chrono::Utc>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct NewBadToken {
+    pub token: eth::H160,
+    pub reason: String,
+}
@@ -0,0 +1,3 @@
+mod bad_token;
+
+pub use bad_token::{BadToken, NewBadToken};
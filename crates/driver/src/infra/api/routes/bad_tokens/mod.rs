@@ -0,0 +1,63 @@
+mod dto;
+
+use {
+    crate::{
+        domain::{competition::bad_tokens::persisted, eth},
+        infra::persistence::bad_tokens,
+    },
+    axum::{
+        extract::{Path, State},
+        http::StatusCode,
+        routing::{delete, get},
+        Json,
+    },
+};
+
+/// Admin routes to inspect and manage the tokens which got persistently
+/// detected as unsupported.
+pub(in crate::infra::api) fn bad_tokens(
+    app: axum::Router<()>,
+    list: persisted::List,
+) -> axum::Router<()> {
+    app.merge(
+        axum::Router::new()
+            .route("/bad-tokens", get(list_tokens).post(add).delete(clear))
+            .route("/bad-tokens/:token", delete(remove))
+            .with_state(list),
+    )
+}
+
+async fn list_tokens(list: State<persisted::List>) -> Json<Vec<dto::BadToken>> {
+    Json(list.entries().into_iter().map(dto::BadToken::new).collect())
+}
+
+async fn add(
+    list: State<persisted::List>,
+    req: Json<dto::NewBadToken>,
+) -> Result<Json<dto::BadToken>, StatusCode> {
+    let entry = list
+        .insert(req.0.token.into(), req.0.reason)
+        .await
+        .map_err(internal_error)?;
+    Ok(Json(dto::BadToken::new(entry)))
+}
+
+async fn remove(
+    list: State<persisted::List>,
+    token: Path<eth::H160>,
+) -> Result<StatusCode, StatusCode> {
+    match list.remove(token.0.into()).await.map_err(internal_error)? {
+        true => Ok(StatusCode::NO_CONTENT),
+        false => Err(StatusCode::NOT_FOUND),
+    }
+}
+
+async fn clear(list: State<persisted::List>) -> Result<StatusCode, StatusCode> {
+    list.clear().await.map_err(internal_error)?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+fn internal_error(err: bad_tokens::Error) -> StatusCode {
+    tracing::warn!(?err, "failed to update persisted bad tokens");
+    StatusCode::INTERNAL_SERVER_ERROR
+}
@@ -1,3 +1,4 @@
+mod bad_tokens;
 mod healthz;
 mod info;
 mod metrics;
@@ -7,6 +8,7 @@ mod settle;
 mod solve;
 
 pub(super) use {
+    bad_tokens::bad_tokens,
     healthz::healthz,
     info::info,
     metrics::metrics,
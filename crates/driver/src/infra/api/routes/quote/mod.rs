@@ -24,14 +24,22 @@ async fn route(
             observe::invalid_dto(err, "order");
         })?;
         observe::quoting(&order);
-        let quote = order
-            .quote(
-                state.eth(),
-                state.solver(),
-                state.liquidity(),
-                state.tokens(),
-            )
-            .await;
+        let block = state.eth().current_block().borrow().number;
+        let compute = {
+            let (state, order) = (state.clone(), order.clone());
+            async move {
+                order
+                    .quote(
+                        state.eth(),
+                        state.solver(),
+                        state.liquidity(),
+                        state.tokens(),
+                    )
+                    .await
+            }
+        };
+        let (quote, lookup) = state.quotes().get_or_quote(&order, block, compute).await;
+        observe::quote_cache(state.solver().name(), lookup);
         observe::quoted(state.solver().name(), &order, &quote);
         Ok(axum::response::Json(dto::Quote::new(quote?)))
     };
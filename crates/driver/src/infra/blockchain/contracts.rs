@@ -3,6 +3,7 @@ use {
     chain::Chain,
     ethcontract::dyns::DynWeb3,
     ethrpc::block_stream::CurrentBlockWatcher,
+    std::collections::HashMap,
     thiserror::Error,
     url::Url,
 };
@@ -17,6 +18,7 @@ pub struct Contracts {
     /// The domain separator for settlement contract used for signing orders.
     settlement_domain_separator: eth::DomainSeparator,
     cow_amm_registry: cow_amm::Registry,
+    flashloan_router: Option<FlashloanRouter>,
 }
 
 #[derive(Debug, Default, Clone)]
@@ -24,6 +26,17 @@ pub struct Addresses {
     pub settlement: Option<eth::ContractAddress>,
     pub weth: Option<eth::ContractAddress>,
     pub cow_amms: Vec<CowAmmConfig>,
+    pub flashloan_router: Option<FlashloanRouter>,
+}
+
+/// The contract wrapping settlements which need flash loans. It takes out all
+/// loans of a solution through the borrower contract associated with each
+/// lender and then calls into the settlement contract.
+#[derive(Debug, Clone)]
+pub struct FlashloanRouter {
+    pub address: eth::ContractAddress,
+    /// Borrower contract to use for each supported lender.
+    pub borrowers: HashMap<eth::ContractAddress, eth::ContractAddress>,
 }
 
 impl Contracts {
@@ -85,6 +98,7 @@ impl Contracts {
             weth,
             settlement_domain_separator,
             cow_amm_registry,
+            flashloan_router: addresses.flashloan_router,
         })
     }
 
@@ -115,6 +129,10 @@ impl Contracts {
     pub fn cow_amm_registry(&self) -> &cow_amm::Registry {
         &self.cow_amm_registry
     }
+
+    pub fn flashloan_router(&self) -> Option<&FlashloanRouter> {
+        self.flashloan_router.as_ref()
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -165,6 +183,12 @@ impl ContractAt for contracts::support::Balances {
     }
 }
 
+impl ContractAt for contracts::GPv2AllowListAuthentication {
+    fn at(eth: &Ethereum, address: eth::ContractAddress) -> Self {
+        Self::at(&eth.web3, address.into())
+    }
+}
+
 #[derive(Debug, Error)]
 pub enum Error {
     #[error("method error: {0:?}")]
@@ -50,6 +50,11 @@ impl GasPriceEstimator {
                     additional_tip_percentage,
                     ..
                 } => (max_additional_tip, additional_tip_percentage),
+                mempool::Kind::Bundle {
+                    max_additional_tip,
+                    additional_tip_percentage,
+                    ..
+                } => (max_additional_tip, additional_tip_percentage),
             })
             .next();
         // Use the lowest max_fee_per_gas of all mempools as the max_fee_per_gas
@@ -17,6 +17,7 @@ pub mod token;
 pub use self::{contracts::Contracts, gas::GasPriceEstimator};
 
 /// An Ethereum RPC connection.
+#[derive(Clone)]
 pub struct Rpc {
     web3: DynWeb3,
     chain: Chain,
@@ -124,6 +125,20 @@ impl Ethereum {
         }
     }
 
+    /// Clones self and returns an instance that estimates gas prices with the
+    /// provided estimator.
+    pub fn with_gas(&self, gas: Arc<GasPriceEstimator>) -> Self {
+        Self {
+            web3: self.web3.clone(),
+            inner: Arc::new(Inner {
+                chain: self.inner.chain,
+                contracts: self.inner.contracts.clone(),
+                gas,
+                current_block: self.inner.current_block.clone(),
+            }),
+        }
+    }
+
     /// Onchain smart contract bindings.
     pub fn contracts(&self) -> &Contracts {
         &self.inner.contracts
@@ -232,6 +247,27 @@ impl Ethereum {
             .map_err(Into::into)
     }
 
+    /// Returns the nonce the next transaction of the specified account should
+    /// use, taking transactions pending in the node's mempool into account.
+    pub async fn pending_nonce(&self, address: eth::Address) -> Result<eth::U256, Error> {
+        self.web3
+            .eth()
+            .transaction_count(address.into(), Some(web3::types::BlockNumber::Pending))
+            .await
+            .map_err(Into::into)
+    }
+
+    /// Checks whether the settlement contract's authenticator allows the
+    /// address to settle.
+    pub async fn is_solver(&self, address: eth::Address) -> Result<bool, Error> {
+        let authenticator = self.contracts().settlement().authenticator().call().await?;
+        self.contract_at::<::contracts::GPv2AllowListAuthentication>(authenticator.into())
+            .is_solver(address.into())
+            .call()
+            .await
+            .map_err(Into::into)
+    }
+
     /// Returns a [`token::Erc20`] for the specified address.
     pub fn erc20(&self, address: eth::TokenAddress) -> token::Erc20 {
         token::Erc20::new(self, address)
@@ -318,6 +354,21 @@ impl Error {
             Error::AccessList(_) => true,
         }
     }
+
+    /// Returns the data the transaction reverted with if the node included it
+    /// in the error response.
+    pub fn revert_data(&self) -> Option<Vec<u8>> {
+        let err = match self {
+            Error::Web3(web3::Error::Rpc(err)) => err,
+            Error::Method(ethcontract::errors::MethodError {
+                inner: ExecutionError::Web3(web3::Error::Rpc(err)),
+                ..
+            }) => err,
+            _ => return None,
+        };
+        let data = err.data.as_ref()?.as_str()?;
+        hex::decode(data.strip_prefix("0x").unwrap_or(data)).ok()
+    }
 }
 
 impl From<contracts::Error> for Error {
@@ -9,6 +9,12 @@ pub struct Args {
     #[clap(long, env, default_value = "0.0.0.0:11088")]
     pub addr: SocketAddr,
 
+    /// The address to serve the admin routes (e.g. `/bad-tokens`) on. They
+    /// are kept off the public address and not served at all if this is not
+    /// set.
+    #[clap(long, env)]
+    pub admin_addr: Option<SocketAddr>,
+
     /// The log filter.
     #[clap(
         long,
@@ -24,6 +30,9 @@ pub struct Args {
     /// Path to the driver configuration file. This file should be in TOML
     /// format. For an example see
     /// https://github.com/cowprotocol/services/blob/main/crates/driver/example.toml.
+    ///
+    /// Sending `SIGHUP` to the driver reloads the solver, submission and gas
+    /// estimator sections of this file without restarting.
     #[clap(long, env)]
     pub config: PathBuf,
 }
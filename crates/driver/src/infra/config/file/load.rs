@@ -7,12 +7,14 @@ use {
             config::file,
             liquidity,
             mempool,
+            persistence,
             simulator,
             solver::{self, BadTokenDetection, SolutionMerging},
         },
     },
+    anyhow::{anyhow, Context, Result},
     chain::Chain,
-    futures::future::join_all,
+    futures::future::try_join_all,
     number::conversions::big_decimal_to_big_rational,
     std::path::Path,
     tokio::fs,
@@ -25,109 +27,15 @@ use {
 ///
 /// This method panics if the config is invalid or on I/O errors.
 pub async fn load(chain: Chain, path: &Path) -> infra::Config {
-    let data = fs::read_to_string(path)
+    let config = parse(chain, path)
         .await
-        .unwrap_or_else(|e| panic!("I/O error while reading {path:?}: {e:?}"));
-
-    let config: file::Config = toml::de::from_str(&data).unwrap_or_else(|err| {
-        if std::env::var("TOML_TRACE_ERROR").is_ok_and(|v| v == "1") {
-            panic!("failed to parse TOML config at {path:?}: {err:#?}")
-        } else {
-            panic!(
-                "failed to parse TOML config at: {path:?}. Set TOML_TRACE_ERROR=1 to print \
-                 parsing error but this may leak secrets."
-            )
-        }
-    });
+        .unwrap_or_else(|err| panic!("{err:#}"));
 
-    assert_eq!(
-        config
-            .chain_id
-            .map(|id| Chain::try_from(id).expect("unsupported chain ID"))
-            .unwrap_or(chain),
-        chain,
-        "The configured chain ID does not match the connected Ethereum node"
-    );
     infra::Config {
-        solvers: join_all(config.solvers.into_iter().map(|config| async move {
-            let account = match config.account {
-                file::Account::PrivateKey(private_key) => ethcontract::Account::Offline(
-                    ethcontract::PrivateKey::from_raw(private_key.0).unwrap(),
-                    None,
-                ),
-                file::Account::Kms(key_id) => {
-                    let config = ethcontract::aws_config::load_from_env().await;
-                    let account =
-                        ethcontract::transaction::kms::Account::new((&config).into(), &key_id.0)
-                            .await
-                            .unwrap_or_else(|_| panic!("Unable to load KMS account {:?}", key_id));
-                    ethcontract::Account::Kms(account, None)
-                }
-                file::Account::Address(address) => ethcontract::Account::Local(address, None),
-            };
-            solver::Config {
-                endpoint: config.endpoint,
-                name: config.name.into(),
-                slippage: solver::Slippage {
-                    relative: big_decimal_to_big_rational(&config.slippage.relative),
-                    absolute: config.slippage.absolute.map(eth::Ether),
-                },
-                liquidity: if config.skip_liquidity {
-                    solver::Liquidity::Skip
-                } else {
-                    solver::Liquidity::Fetch
-                },
-                account,
-                timeouts: solver::Timeouts {
-                    http_delay: chrono::Duration::from_std(config.timeouts.http_time_buffer)
-                        .unwrap(),
-                    solving_share_of_deadline: config
-                        .timeouts
-                        .solving_share_of_deadline
-                        .try_into()
-                        .unwrap(),
-                },
-                request_headers: config.request_headers,
-                fee_handler: config.fee_handler,
-                quote_using_limit_orders: config.quote_using_limit_orders,
-                merge_solutions: match config.merge_solutions {
-                    true => SolutionMerging::Allowed,
-                    false => SolutionMerging::Forbidden,
-                },
-                s3: config.s3.map(Into::into),
-                solver_native_token: config.manage_native_token.to_domain(),
-                quote_tx_origin: config.quote_tx_origin.map(eth::Address),
-                response_size_limit_max_bytes: config.response_size_limit_max_bytes,
-                bad_token_detection: BadTokenDetection {
-                    tokens_supported: config
-                        .bad_token_detection
-                        .token_supported
-                        .iter()
-                        .map(|(token, supported)| {
-                            (
-                                eth::TokenAddress(eth::ContractAddress(*token)),
-                                match supported {
-                                    true => bad_tokens::Quality::Supported,
-                                    false => bad_tokens::Quality::Unsupported,
-                                },
-                            )
-                        })
-                        .collect(),
-                    enable_simulation_strategy: config
-                        .bad_token_detection
-                        .enable_simulation_strategy,
-                    enable_metrics_strategy: config.bad_token_detection.enable_metrics_strategy,
-                    metrics_strategy_failure_ratio: config
-                        .bad_token_detection
-                        .metrics_strategy_failure_ratio,
-                    metrics_strategy_required_measurements: config
-                        .bad_token_detection
-                        .metrics_strategy_required_measurements,
-                },
-                settle_queue_size: config.settle_queue_size,
-            }
-        }))
-        .await,
+        solvers: solvers(config.solvers)
+            .await
+            .unwrap_or_else(|err| panic!("{err:#}")),
+        mempools: mempools(&config.submission),
         liquidity: liquidity::Config {
             base_tokens: config
                 .liquidity
@@ -286,56 +194,8 @@ pub async fn load(chain: Chain, path: &Path) -> infra::Config {
                     http_timeout: config.http_timeout,
                 }),
         },
-        mempools: config
-            .submission
-            .mempools
-            .iter()
-            .map(|mempool| mempool::Config {
-                min_priority_fee: config.submission.min_priority_fee,
-                gas_price_cap: config.submission.gas_price_cap,
-                target_confirm_time: config.submission.target_confirm_time,
-                retry_interval: config.submission.retry_interval,
-                kind: match mempool {
-                    file::Mempool::Public {
-                        max_additional_tip,
-                        additional_tip_percentage,
-                    } => {
-                        // If there is no private mempool, revert protection is
-                        // disabled, otherwise driver would not even try to settle revertable
-                        // settlements
-                        let revert_protection = if config
-                            .submission
-                            .mempools
-                            .iter()
-                            .any(|pool| matches!(pool, file::Mempool::MevBlocker { .. }))
-                        {
-                            mempool::RevertProtection::Enabled
-                        } else {
-                            mempool::RevertProtection::Disabled
-                        };
-
-                        mempool::Kind::Public {
-                            max_additional_tip: *max_additional_tip,
-                            additional_tip_percentage: *additional_tip_percentage,
-                            revert_protection,
-                        }
-                    }
-                    file::Mempool::MevBlocker {
-                        url,
-                        max_additional_tip,
-                        additional_tip_percentage,
-                        use_soft_cancellations,
-                    } => mempool::Kind::MEVBlocker {
-                        url: url.to_owned(),
-                        max_additional_tip: *max_additional_tip,
-                        additional_tip_percentage: *additional_tip_percentage,
-                        use_soft_cancellations: *use_soft_cancellations,
-                    },
-                },
-            })
-            .collect(),
-        simulator: match (config.tenderly, config.enso) {
-            (Some(config), None) => {
+        simulator: match (config.tenderly, config.enso, config.fork_simulator) {
+            (Some(config), None, None) => {
                 Some(simulator::Config::Tenderly(simulator::tenderly::Config {
                     url: config.url,
                     api_key: config.api_key,
@@ -345,12 +205,15 @@ pub async fn load(chain: Chain, path: &Path) -> infra::Config {
                     save_if_fails: config.save_if_fails,
                 }))
             }
-            (None, Some(config)) => Some(simulator::Config::Enso(simulator::enso::Config {
+            (None, Some(config), None) => Some(simulator::Config::Enso(simulator::enso::Config {
                 url: config.url,
                 network_block_interval: config.network_block_interval,
             })),
-            (None, None) => None,
-            (Some(_), Some(_)) => panic!("Cannot configure both Tenderly and Enso"),
+            (None, None, Some(config)) => Some(simulator::Config::Fork(simulator::fork::Config {
+                network_block_interval: config.network_block_interval,
+            })),
+            (None, None, None) => None,
+            _ => panic!("Cannot configure more than one of Tenderly, Enso and the fork simulator"),
         },
         contracts: blockchain::contracts::Addresses {
             settlement: config.contracts.gp_v2_settlement.map(Into::into),
@@ -365,6 +228,16 @@ pub async fn load(chain: Chain, path: &Path) -> infra::Config {
                     helper: cfg.helper,
                 })
                 .collect(),
+            flashloan_router: config.contracts.flashloan_router.map(|router| {
+                blockchain::contracts::FlashloanRouter {
+                    address: router.address.into(),
+                    borrowers: router
+                        .lenders
+                        .into_iter()
+                        .map(|cfg| (cfg.lender.into(), cfg.borrower.into()))
+                        .collect(),
+                }
+            }),
         },
         disable_access_list_simulation: config.disable_access_list_simulation,
         disable_gas_simulation: config.disable_gas_simulation.map(Into::into),
@@ -372,5 +245,213 @@ pub async fn load(chain: Chain, path: &Path) -> infra::Config {
         order_priority_strategies: config.order_priority_strategies,
         archive_node_url: config.archive_node_url,
         simulation_bad_token_max_age: config.simulation_bad_token_max_age,
+        bad_token_persistence: config.bad_token_persistence.map(|config| {
+            persistence::bad_tokens::Config {
+                backend: match (config.file, config.database_url) {
+                    (Some(path), None) => persistence::bad_tokens::Backend::File(path),
+                    (None, Some(url)) => persistence::bad_tokens::Backend::Postgres(url),
+                    _ => panic!("bad token persistence needs exactly one of file and database-url"),
+                },
+                max_age: chrono::Duration::from_std(config.max_age)
+                    .expect("invalid bad token persistence max age"),
+            }
+        }),
+        quote_cache_significant_digits: config.quote_cache_significant_digits.inspect(|digits| {
+            assert!(
+                (1..=77).contains(digits),
+                "quote cache significant digits must be between 1 and 77"
+            )
+        }),
     }
 }
+
+/// Reload the parts of the driver configuration that can be changed while the
+/// driver is running.
+///
+/// Unlike [`load`] this returns an error instead of panicking if the config is
+/// invalid so that the driver can keep running with its current config.
+pub async fn reload(chain: Chain, path: &Path) -> Result<infra::config::Reloadable> {
+    let config = parse(chain, path).await?;
+    Ok(infra::config::Reloadable {
+        solvers: solvers(config.solvers).await?,
+        mempools: mempools(&config.submission),
+        gas_estimator: config.gas_estimator,
+    })
+}
+
+/// Parses the TOML config file and checks that it was written for the chain
+/// of the connected Ethereum node.
+async fn parse(chain: Chain, path: &Path) -> Result<file::Config> {
+    let data = fs::read_to_string(path)
+        .await
+        .with_context(|| format!("I/O error while reading {path:?}"))?;
+
+    let config: file::Config = toml::de::from_str(&data).map_err(|err| {
+        if std::env::var("TOML_TRACE_ERROR").is_ok_and(|v| v == "1") {
+            anyhow!("failed to parse TOML config at {path:?}: {err:#?}")
+        } else {
+            anyhow!(
+                "failed to parse TOML config at: {path:?}. Set TOML_TRACE_ERROR=1 to print \
+                 parsing error but this may leak secrets."
+            )
+        }
+    })?;
+
+    let configured_chain = match config.chain_id {
+        Some(id) => Chain::try_from(id).context("unsupported chain ID")?,
+        None => chain,
+    };
+    anyhow::ensure!(
+        configured_chain == chain,
+        "The configured chain ID does not match the connected Ethereum node"
+    );
+    Ok(config)
+}
+
+async fn solvers(configs: Vec<file::SolverConfig>) -> Result<Vec<solver::Config>> {
+    try_join_all(configs.into_iter().map(solver)).await
+}
+
+async fn solver(config: file::SolverConfig) -> Result<solver::Config> {
+    let submission_accounts =
+        try_join_all(config.submission_accounts.into_iter().map(account)).await?;
+    let account = account(config.account).await?;
+    Ok(solver::Config {
+        endpoint: config.endpoint,
+        name: config.name.into(),
+        slippage: solver::Slippage {
+            relative: big_decimal_to_big_rational(&config.slippage.relative),
+            absolute: config.slippage.absolute.map(eth::Ether),
+        },
+        liquidity: if config.skip_liquidity {
+            solver::Liquidity::Skip
+        } else {
+            solver::Liquidity::Fetch
+        },
+        account,
+        submission_accounts,
+        timeouts: solver::Timeouts {
+            http_delay: chrono::Duration::from_std(config.timeouts.http_time_buffer)
+                .context("invalid solver http time buffer")?,
+            solving_share_of_deadline: config
+                .timeouts
+                .solving_share_of_deadline
+                .try_into()
+                .map_err(|err| anyhow!("invalid solving share of deadline: {err:?}"))?,
+        },
+        request_headers: config.request_headers,
+        fee_handler: config.fee_handler,
+        quote_using_limit_orders: config.quote_using_limit_orders,
+        merge_solutions: match config.merge_solutions {
+            true => SolutionMerging::Allowed,
+            false => SolutionMerging::Forbidden,
+        },
+        s3: config.s3.map(Into::into),
+        solver_native_token: config.manage_native_token.to_domain(),
+        quote_tx_origin: config.quote_tx_origin.map(eth::Address),
+        response_size_limit_max_bytes: config.response_size_limit_max_bytes,
+        bad_token_detection: BadTokenDetection {
+            tokens_supported: config
+                .bad_token_detection
+                .token_supported
+                .iter()
+                .map(|(token, supported)| {
+                    (
+                        eth::TokenAddress(eth::ContractAddress(*token)),
+                        match supported {
+                            true => bad_tokens::Quality::Supported,
+                            false => bad_tokens::Quality::Unsupported,
+                        },
+                    )
+                })
+                .collect(),
+            enable_simulation_strategy: config.bad_token_detection.enable_simulation_strategy,
+            enable_metrics_strategy: config.bad_token_detection.enable_metrics_strategy,
+            metrics_strategy_failure_ratio: config
+                .bad_token_detection
+                .metrics_strategy_failure_ratio,
+            metrics_strategy_required_measurements: config
+                .bad_token_detection
+                .metrics_strategy_required_measurements,
+        },
+        settle_queue_size: config.settle_queue_size,
+        extended_balancer_pool_kinds: config.extended_balancer_pool_kinds,
+    })
+}
+
+async fn account(account: file::Account) -> Result<ethcontract::Account> {
+    Ok(match account {
+        file::Account::PrivateKey(private_key) => ethcontract::Account::Offline(
+            ethcontract::PrivateKey::from_raw(private_key.0)
+                .map_err(|err| anyhow!("invalid solver private key: {err:?}"))?,
+            None,
+        ),
+        file::Account::Kms(key_id) => {
+            let config = ethcontract::aws_config::load_from_env().await;
+            let account = ethcontract::transaction::kms::Account::new((&config).into(), &key_id.0)
+                .await
+                .map_err(|_| anyhow!("Unable to load KMS account {:?}", key_id))?;
+            ethcontract::Account::Kms(account, None)
+        }
+        file::Account::Address(address) => ethcontract::Account::Local(address, None),
+    })
+}
+
+fn mempools(submission: &file::SubmissionConfig) -> Vec<mempool::Config> {
+    submission
+        .mempools
+        .iter()
+        .map(|mempool| mempool::Config {
+            min_priority_fee: submission.min_priority_fee,
+            gas_price_cap: submission.gas_price_cap,
+            target_confirm_time: submission.target_confirm_time,
+            retry_interval: submission.retry_interval,
+            kind: match mempool {
+                file::Mempool::Public {
+                    max_additional_tip,
+                    additional_tip_percentage,
+                } => {
+                    // If there is no private mempool, revert protection is
+                    // disabled, otherwise driver would not even try to settle revertable
+                    // settlements
+                    let revert_protection = if submission.mempools.iter().any(|pool| {
+                        matches!(
+                            pool,
+                            file::Mempool::MevBlocker { .. } | file::Mempool::Bundle { .. }
+                        )
+                    }) {
+                        mempool::RevertProtection::Enabled
+                    } else {
+                        mempool::RevertProtection::Disabled
+                    };
+
+                    mempool::Kind::Public {
+                        max_additional_tip: *max_additional_tip,
+                        additional_tip_percentage: *additional_tip_percentage,
+                        revert_protection,
+                    }
+                }
+                file::Mempool::MevBlocker {
+                    url,
+                    max_additional_tip,
+                    additional_tip_percentage,
+                    use_soft_cancellations,
+                } => mempool::Kind::MEVBlocker {
+                    url: url.to_owned(),
+                    max_additional_tip: *max_additional_tip,
+                    additional_tip_percentage: *additional_tip_percentage,
+                    use_soft_cancellations: *use_soft_cancellations,
+                },
+                file::Mempool::Bundle {
+                    urls,
+                    max_additional_tip,
+                    additional_tip_percentage,
+                } => mempool::Kind::Bundle {
+                    urls: urls.to_owned(),
+                    max_additional_tip: *max_additional_tip,
+                    additional_tip_percentage: *additional_tip_percentage,
+                },
+            },
+        })
+        .collect()
+}
@@ -1,4 +1,4 @@
-pub use load::load;
+pub use load::{load, reload};
 use {
     crate::{domain::eth, infra, util::serialize},
     reqwest::Url,
@@ -49,6 +49,10 @@ struct Config {
     /// Use Enso for transaction simulation.
     enso: Option<EnsoConfig>,
 
+    /// Use a local EVM forking the state of the connected node for
+    /// transaction simulation.
+    fork_simulator: Option<ForkSimulatorConfig>,
+
     #[serde(rename = "solver")]
     solvers: Vec<SolverConfig>,
 
@@ -73,6 +77,18 @@ struct Config {
         default = "default_simulation_bad_token_max_age"
     )]
     simulation_bad_token_max_age: Duration,
+
+    /// Persist tokens detected as unsupported so they are still known after
+    /// a restart and can be shared between multiple drivers.
+    bad_token_persistence: Option<BadTokenPersistenceConfig>,
+
+    /// Identical quote requests of the same block share one quote. Quotes for
+    /// amounts which are equal when rounded down to this many significant
+    /// digits are considered identical as well. This increases the cache hit
+    /// rate at the cost of the quoted interactions being computed for a
+    /// slightly different amount. If not set, only quotes for identical
+    /// amounts get shared.
+    quote_cache_significant_digits: Option<u32>,
 }
 
 #[serde_as]
@@ -102,7 +118,8 @@ struct SubmissionConfig {
     retry_interval: Duration,
 
     /// The mempools to submit settlement transactions to. Can be the public
-    /// mempool of a node or the private MEVBlocker mempool.
+    /// mempool of a node, the private MEVBlocker mempool or relays accepting
+    /// bundles.
     #[serde(rename = "mempool", default)]
     mempools: Vec<Mempool>,
 }
@@ -147,6 +164,22 @@ enum Mempool {
         #[serde(default = "default_soft_cancellations_flag")]
         use_soft_cancellations: bool,
     },
+    #[serde(rename_all = "kebab-case")]
+    Bundle {
+        /// The relays (e.g. block builders) to send bundles to via
+        /// `eth_sendBundle`.
+        urls: Vec<Url>,
+        /// Maximum additional tip in Gwei that we are willing to give to
+        /// the builders above regular gas price estimation.
+        #[serde(default = "default_max_additional_tip")]
+        #[serde_as(as = "serialize::U256")]
+        max_additional_tip: eth::U256,
+        /// Additional tip in percentage of max_fee_per_gas we are giving to
+        /// the builders above regular gas price estimation. Expects a
+        /// floating point value between 0 and 1.
+        #[serde(default = "default_additional_tip_percentage")]
+        additional_tip_percentage: f64,
+    },
 }
 
 #[derive(Debug, Deserialize)]
@@ -232,6 +265,13 @@ struct SolverConfig {
     /// The account which should be used to sign settlements for this solver.
     account: Account,
 
+    /// Additional accounts which settlements can be submitted from. Each
+    /// account has at most one pending settlement at a time, so configuring
+    /// more accounts allows submitting multiple settlements in parallel
+    /// without a stuck transaction blocking all later ones.
+    #[serde(default)]
+    submission_accounts: Vec<Account>,
+
     /// Timeout configuration for the solver.
     #[serde(default, flatten)]
     timeouts: Timeouts,
@@ -277,6 +317,13 @@ struct SolverConfig {
     /// before the driver starts dropping new `/solve` requests.
     #[serde(default = "default_settle_queue_size")]
     settle_queue_size: usize,
+
+    /// Whether Balancer V2 composable stable and liquidity bootstrapping pools
+    /// are sent to the solver with the `composableStable` and
+    /// `liquidityBootstrapping` kinds. Otherwise they are sent as `stable` and
+    /// `weightedProduct` pools respectively since they use the same math.
+    #[serde(default)]
+    extended_balancer_pool_kinds: bool,
 }
 
 #[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq, Serialize)]
@@ -358,6 +405,30 @@ struct ContractsConfig {
     /// rebalancing orders for.
     #[serde(default)]
     cow_amms: Vec<CowAmmConfig>,
+
+    /// Router contract used to settle solutions which require flash loans.
+    /// Solutions with flash loans get discarded if this is not configured.
+    flashloan_router: Option<FlashloanRouterConfig>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+pub struct FlashloanRouterConfig {
+    /// Address of the flash loan router contract.
+    pub address: eth::H160,
+    /// Lenders solvers may borrow from.
+    #[serde(default)]
+    pub lenders: Vec<FlashloanLenderConfig>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+pub struct FlashloanLenderConfig {
+    /// The contract providing the flash loan.
+    pub lender: eth::H160,
+    /// The borrower contract the router uses to take out loans from this
+    /// lender.
+    pub borrower: eth::H160,
 }
 
 #[derive(Debug, Clone, Deserialize)]
@@ -405,6 +476,45 @@ struct EnsoConfig {
     network_block_interval: Option<Duration>,
 }
 
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+struct ForkSimulatorConfig {
+    /// How often the network produces a new block. If this is not set
+    /// settlements get simulated with the timestamp of the latest block.
+    #[serde(default, with = "humantime_serde")]
+    network_block_interval: Option<Duration>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+struct BadTokenPersistenceConfig {
+    /// Store the tokens in this JSON file.
+    file: Option<std::path::PathBuf>,
+
+    /// Store the tokens in the `bad_tokens` table of this Postgres database.
+    database_url: Option<Url>,
+
+    /// How long a token stays unsupported after it was detected.
+    #[serde(
+        with = "humantime_serde",
+        default = "default_bad_token_persistence_max_age"
+    )]
+    max_age: Duration,
+}
+
+impl std::fmt::Debug for BadTokenPersistenceConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("BadTokenPersistenceConfig")
+            .field("file", &self.file)
+            .field(
+                "database_url",
+                &self.database_url.as_ref().map(|_| "SECRET"),
+            )
+            .field("max_age", &self.max_age)
+            .finish()
+    }
+}
+
 #[derive(Clone, Debug, Default, Deserialize)]
 #[serde(rename_all = "kebab-case", deny_unknown_fields)]
 struct LiquidityConfig {
@@ -676,6 +786,10 @@ fn default_simulation_bad_token_max_age() -> Duration {
     Duration::from_secs(600)
 }
 
+fn default_bad_token_persistence_max_age() -> Duration {
+    Duration::from_secs(7 * 24 * 60 * 60)
+}
+
 #[serde_as]
 #[derive(Clone, Debug, Deserialize)]
 #[serde(rename_all = "kebab-case", deny_unknown_fields)]
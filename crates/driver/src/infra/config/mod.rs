@@ -6,6 +6,7 @@ use {
             config::file::{GasEstimatorType, OrderPriorityStrategy},
             liquidity,
             mempool,
+            persistence,
             simulator,
             solver,
         },
@@ -30,4 +31,15 @@ pub struct Config {
     pub order_priority_strategies: Vec<OrderPriorityStrategy>,
     pub archive_node_url: Option<Url>,
     pub simulation_bad_token_max_age: Duration,
+    pub bad_token_persistence: Option<persistence::bad_tokens::Config>,
+    pub quote_cache_significant_digits: Option<u32>,
+}
+
+/// The parts of the configuration that can be reloaded while the driver is
+/// running.
+#[derive(Debug)]
+pub struct Reloadable {
+    pub solvers: Vec<solver::Config>,
+    pub mempools: Vec<mempool::Config>,
+    pub gas_estimator: GasEstimatorType,
 }
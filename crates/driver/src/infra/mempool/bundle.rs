@@ -0,0 +1,118 @@
+//! Submission of signed transactions to relays (e.g. block builders) that
+//! accept bundles via `eth_sendBundle`.
+
+use {
+    crate::domain::{eth, BlockNo},
+    anyhow::{anyhow, Context},
+    futures::future::join_all,
+    serde::Serialize,
+    std::{
+        collections::HashMap,
+        sync::{Arc, Mutex},
+    },
+    web3::types::Bytes,
+};
+
+#[derive(Debug, Clone)]
+pub struct Relays {
+    client: reqwest::Client,
+    urls: Vec<reqwest::Url>,
+    /// Signed transactions which are still waiting to be included. Bundles
+    /// only target a single block so these have to be sent again for every
+    /// new block.
+    pending: Arc<Mutex<HashMap<eth::H256, Bytes>>>,
+}
+
+impl Relays {
+    pub fn new(urls: Vec<reqwest::Url>) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            urls,
+            pending: Default::default(),
+        }
+    }
+
+    /// Sends a bundle containing the signed transaction to all relays and
+    /// remembers it for subsequent blocks. Succeeds if at least one relay
+    /// accepted the bundle. Transactions no relay accepted are not remembered
+    /// as the caller gives up on them.
+    pub async fn submit(&self, tx: Bytes, hash: eth::H256, block: BlockNo) -> anyhow::Result<()> {
+        self.send(tx.clone(), block).await?;
+        self.pending.lock().unwrap().insert(hash, tx);
+        Ok(())
+    }
+
+    /// Sends the bundle of a previously submitted transaction again targeting
+    /// the given block.
+    pub async fn resubmit(&self, hash: &eth::H256, block: BlockNo) -> anyhow::Result<()> {
+        let tx = self
+            .pending
+            .lock()
+            .unwrap()
+            .get(hash)
+            .cloned()
+            .context("unknown bundle transaction")?;
+        self.send(tx, block).await
+    }
+
+    /// Stops tracking the transaction once it got included or dropped.
+    pub fn forget(&self, hash: &eth::H256) {
+        self.pending.lock().unwrap().remove(hash);
+    }
+
+    async fn send(&self, tx: Bytes, block: BlockNo) -> anyhow::Result<()> {
+        let request = Request {
+            jsonrpc: "2.0",
+            id: 1,
+            method: "eth_sendBundle",
+            params: [Bundle {
+                txs: vec![tx],
+                block_number: block.into(),
+            }],
+        };
+        let results = join_all(self.urls.iter().map(|url| async {
+            let result = self.send_to(url, &request).await;
+            if let Err(err) = &result {
+                tracing::warn!(%url, block, ?err, "relay rejected bundle");
+            }
+            result
+        }))
+        .await;
+
+        match results.iter().any(Result::is_ok) {
+            true => Ok(()),
+            false => Err(anyhow!("no relay accepted the bundle for block {block}")),
+        }
+    }
+
+    async fn send_to(&self, url: &reqwest::Url, request: &Request<'_>) -> anyhow::Result<()> {
+        let response: serde_json::Value = self
+            .client
+            .post(url.clone())
+            .json(request)
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+        match response.get("error") {
+            Some(err) => Err(anyhow!("{err}")),
+            None => Ok(()),
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct Request<'a> {
+    jsonrpc: &'a str,
+    id: u64,
+    method: &'a str,
+    params: [Bundle; 1],
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Bundle {
+    txs: Vec<Bytes>,
+    block_number: web3::types::U64,
+}
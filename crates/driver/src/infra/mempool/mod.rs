@@ -1,12 +1,14 @@
 use {
     crate::{
         boundary::unbuffered_web3_client,
-        domain::{competition, eth, mempools},
-        infra,
+        domain::{competition, eth, mempools, BlockNo},
     },
-    ethcontract::dyns::DynWeb3,
+    anyhow::Context,
+    ethcontract::{dyns::DynWeb3, transaction::Transaction},
 };
 
+mod bundle;
+
 #[derive(Debug, Clone)]
 pub struct Config {
     pub min_priority_fee: eth::U256,
@@ -31,6 +33,15 @@ pub enum Kind {
         additional_tip_percentage: f64,
         use_soft_cancellations: bool,
     },
+    /// Relays (e.g. block builders) accepting bundles via `eth_sendBundle`.
+    /// Settlements get signed locally and are sent as a bundle targeting the
+    /// next block until they get included or the submission deadline is
+    /// reached.
+    Bundle {
+        urls: Vec<reqwest::Url>,
+        max_additional_tip: eth::U256,
+        additional_tip_percentage: f64,
+    },
 }
 
 impl Kind {
@@ -39,6 +50,7 @@ impl Kind {
         match self {
             Kind::Public { .. } => "PublicMempool",
             Kind::MEVBlocker { .. } => "MEVBlocker",
+            Kind::Bundle { .. } => "Bundle",
         }
     }
 }
@@ -58,6 +70,7 @@ pub enum RevertProtection {
 pub struct Mempool {
     transport: DynWeb3,
     config: Config,
+    relays: Option<bundle::Relays>,
 }
 
 impl std::fmt::Display for Mempool {
@@ -69,23 +82,35 @@ impl std::fmt::Display for Mempool {
 impl Mempool {
     pub fn new(config: Config, transport: DynWeb3) -> Self {
         let transport = match &config.kind {
-            Kind::Public { .. } => transport,
+            // Bundles get sent to the relays directly, the node is only used
+            // to sign the transaction and to look up the current block.
+            Kind::Public { .. } | Kind::Bundle { .. } => transport,
             // Flashbots Protect RPC fallback doesn't support buffered transport
             Kind::MEVBlocker { url, .. } => unbuffered_web3_client(url),
         };
-        Self { config, transport }
+        let relays = match &config.kind {
+            Kind::Bundle { urls, .. } => Some(bundle::Relays::new(urls.clone())),
+            _ => None,
+        };
+        Self {
+            config,
+            transport,
+            relays,
+        }
     }
 
-    /// Submits a transaction to the mempool. Returns optimistically as soon as
-    /// the transaction is pending.
+    /// Submits a transaction from the given account at the given nonce to the
+    /// mempool. Returns optimistically as soon as the transaction is pending.
     pub async fn submit(
         &self,
         tx: eth::Tx,
         gas: competition::solution::settlement::Gas,
-        solver: &infra::Solver,
+        account: &ethcontract::Account,
+        nonce: eth::U256,
     ) -> Result<eth::TxId, mempools::Error> {
-        ethcontract::transaction::TransactionBuilder::new(self.transport.clone())
-            .from(solver.account().clone())
+        let tx = ethcontract::transaction::TransactionBuilder::new(self.transport.clone())
+            .from(account.clone())
+            .nonce(nonce)
             .to(tx.to.into())
             .gas_price(ethcontract::GasPrice::Eip1559 {
                 max_fee_per_gas: gas.price.max().into(),
@@ -94,12 +119,57 @@ impl Mempool {
             .data(tx.input.into())
             .value(tx.value.0)
             .gas(gas.limit.0)
-            .access_list(web3::types::AccessList::from(tx.access_list))
-            .resolve(ethcontract::transaction::ResolveCondition::Pending)
-            .send()
+            .access_list(web3::types::AccessList::from(tx.access_list));
+
+        let Some(relays) = &self.relays else {
+            return tx
+                .resolve(ethcontract::transaction::ResolveCondition::Pending)
+                .send()
+                .await
+                .map(|result| eth::TxId(result.hash()))
+                .map_err(|err| mempools::Error::Other(anyhow::Error::from(err)));
+        };
+
+        let (bytes, hash) = match tx.build().await.context("failed to sign transaction")? {
+            Transaction::Raw { bytes, hash } => (bytes, hash),
+            Transaction::Request(_) => {
+                return Err(mempools::Error::Other(anyhow::anyhow!(
+                    "bundles can only be submitted with locally signing solver accounts"
+                )))
+            }
+        };
+        let block = self
+            .transport
+            .eth()
+            .block_number()
             .await
-            .map(|result| eth::TxId(result.hash()))
-            .map_err(|err| mempools::Error::Other(anyhow::Error::from(err)))
+            .context("failed to fetch current block")?;
+        relays.submit(bytes, hash, block.as_u64() + 1).await?;
+        Ok(eth::TxId(hash))
+    }
+
+    /// Bundles only target a single block so they have to be submitted again
+    /// for every new block until they get included. Does nothing for other
+    /// mempools.
+    pub async fn resubmit(&self, hash: &eth::TxId, block: BlockNo) -> Result<(), mempools::Error> {
+        match &self.relays {
+            Some(relays) => Ok(relays.resubmit(&hash.0, block).await?),
+            None => Ok(()),
+        }
+    }
+
+    /// Stops resubmitting a bundle once it got included or dropped.
+    pub fn forget(&self, hash: &eth::TxId) {
+        if let Some(relays) = &self.relays {
+            relays.forget(&hash.0);
+        }
+    }
+
+    /// Whether pending transactions need to be cancelled once they should no
+    /// longer be included. Bundles simply get dropped by the relays if they
+    /// don't land in the targeted block.
+    pub fn requires_cancellation(&self) -> bool {
+        self.relays.is_none()
     }
 
     pub fn config(&self) -> &Config {
@@ -109,7 +179,7 @@ impl Mempool {
     pub fn may_revert(&self) -> bool {
         match &self.config.kind {
             Kind::Public { .. } => true,
-            Kind::MEVBlocker { .. } => false,
+            Kind::MEVBlocker { .. } | Kind::Bundle { .. } => false,
         }
     }
 }
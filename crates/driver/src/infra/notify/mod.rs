@@ -91,6 +91,7 @@ pub fn simulation_failed(
             error.block,
             error.tx.clone(),
             succeeded_at_least_once,
+            error.revert(),
         ),
         simulator::Error::Other(error) => notification::Kind::DriverError(error.to_string()),
     };
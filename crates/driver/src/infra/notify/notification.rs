@@ -1,7 +1,10 @@
 use {
-    crate::domain::{
-        competition::{auction, solution},
-        eth::{self, Ether, TokenAddress},
+    crate::{
+        domain::{
+            competition::{auction, solution},
+            eth::{self, Ether, TokenAddress},
+        },
+        infra::simulator::revert::Revert,
     },
     std::collections::BTreeSet,
 };
@@ -28,9 +31,15 @@ pub enum Kind {
     EmptySolution,
     /// Solution received from solver engine don't have unique id.
     DuplicatedSolutionId,
-    /// Failed simulation during competition. Last parameter is true
-    /// if has simulated at least once.
-    SimulationFailed(eth::BlockNo, Transaction, SimulationSucceededAtLeastOnce),
+    /// Failed simulation during competition. The third parameter is true
+    /// if has simulated at least once. The last one describes why and where
+    /// the settlement reverted as far as it could be determined.
+    SimulationFailed(
+        eth::BlockNo,
+        Transaction,
+        SimulationSucceededAtLeastOnce,
+        Revert,
+    ),
     /// No valid score could be computed for the solution.
     ScoringFailed(ScoreKind),
     /// Solution aimed to internalize tokens that are not considered safe to
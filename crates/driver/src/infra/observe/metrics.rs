@@ -16,9 +16,15 @@ pub struct Metrics {
     /// The results of the quoting process.
     #[metric(labels("solver", "result"))]
     pub quotes: prometheus::IntCounterVec,
+    /// How quote requests were answered by the quote cache.
+    #[metric(labels("solver", "result"))]
+    pub quote_cache: prometheus::IntCounterVec,
     /// The results of the mempool submission.
     #[metric(labels("mempool", "result"))]
     pub mempool_submission: prometheus::IntCounterVec,
+    /// The results of reloading the config file.
+    #[metric(labels("result"))]
+    pub config_reloads: prometheus::IntCounterVec,
 }
 
 /// Setup the metrics registry.
@@ -14,9 +14,9 @@ use {
                 Solution,
                 Solved,
             },
-            eth::{self, Gas},
+            eth,
             mempools,
-            quote::{self, Quote},
+            quote,
             time::{Deadline, Remaining},
             Liquidity,
         },
@@ -24,7 +24,10 @@ use {
         util::http,
     },
     ethrpc::block_stream::BlockInfo,
-    std::collections::{HashMap, HashSet},
+    std::{
+        collections::{HashMap, HashSet},
+        path::Path,
+    },
     url::Url,
 };
 
@@ -118,7 +121,15 @@ pub fn encoding(id: &solution::Id) {
 
 /// Observe that settlement encoding failed.
 pub fn encoding_failed(solver: &solver::Name, id: &solution::Id, err: &solution::Error) {
-    tracing::info!(?id, ?err, "discarded solution: settlement encoding");
+    match err {
+        solution::Error::Simulation(simulator::Error::Revert(revert)) => tracing::info!(
+            ?id,
+            revert = %revert.revert(),
+            ?err,
+            "discarded solution: settlement encoding"
+        ),
+        _ => tracing::info!(?id, ?err, "discarded solution: settlement encoding"),
+    }
     metrics::get()
         .dropped_solutions
         .with_label_values(&[solver.as_str(), "SettlementEncoding"])
@@ -165,7 +176,12 @@ pub fn score(settlement: &Settlement, score: &eth::Ether) {
 // Observe that the winning settlement started failing upon arrival of a new
 // block
 pub fn winner_voided(block: BlockInfo, err: &simulator::RevertError) {
-    tracing::warn!(block = block.number, ?err, "solution reverts on new block");
+    tracing::warn!(
+        block = block.number,
+        revert = %err.revert(),
+        ?err,
+        "solution reverts on new block"
+    );
 }
 
 pub fn revealing() {
@@ -244,7 +260,7 @@ pub fn solved(solver: &solver::Name, result: &Result<Option<Solved>, competition
 }
 
 /// Observe the result of quoting an auction.
-pub fn quoted(solver: &solver::Name, order: &quote::Order, result: &Result<Quote, quote::Error>) {
+pub fn quoted(solver: &solver::Name, order: &quote::Order, result: &quote::Outcome) {
     match result {
         Ok(quote) => {
             tracing::info!(?order, ?quote, "quoted order");
@@ -259,7 +275,7 @@ pub fn quoted(solver: &solver::Name, order: &quote::Order, result: &Result<Quote
                 .quotes
                 .with_label_values(&[
                     solver.as_str(),
-                    match err {
+                    match err.as_ref() {
                         quote::Error::QuotingFailed(quote::QuotingFailed::ClearingSellMissing) => {
                             "ClearingSellMissing"
                         }
@@ -285,6 +301,22 @@ pub fn quoted(solver: &solver::Name, order: &quote::Order, result: &Result<Quote
     }
 }
 
+/// Observe how a quote request was answered by the quote cache.
+pub fn quote_cache(solver: &solver::Name, lookup: quote::Lookup) {
+    tracing::debug!(?lookup, "quote cache lookup");
+    metrics::get()
+        .quote_cache
+        .with_label_values(&[
+            solver.as_str(),
+            match lookup {
+                quote::Lookup::Hit => "Hit",
+                quote::Lookup::Coalesced => "Coalesced",
+                quote::Lookup::Miss => "Miss",
+            },
+        ])
+        .inc();
+}
+
 /// Observe that the API routes for a solver are being mounted.
 pub fn mounting_solver(solver: &solver::Name, path: &str) {
     tracing::debug!(%solver, path, "mounting solver");
@@ -391,10 +423,35 @@ pub fn order_excluded_from_auction(
 }
 
 /// Observe that a settlement was simulated
-pub fn simulated(eth: &Ethereum, tx: &eth::Tx, gas: &Result<Gas, simulator::Error>) {
+pub fn simulated(
+    eth: &Ethereum,
+    tx: &eth::Tx,
+    simulation: &Result<simulator::Simulation, simulator::Error>,
+) {
     let block: eth::BlockNo = eth.current_block().borrow().number.into();
-    match gas {
-        Ok(gas) => tracing::debug!(block = ?block, gas = ?gas.0, ?tx, "simulated settlement"),
+    match simulation {
+        Ok(simulation) => {
+            tracing::debug!(block = ?block, gas = ?simulation.gas.0, ?tx, "simulated settlement");
+            if let Some(trace) = &simulation.trace {
+                tracing::trace!(block = ?block, ?trace, "simulated settlement calls");
+            }
+        }
         Err(err) => tracing::debug!(block = ?block, ?err, "simulated settlement"),
     }
 }
+
+/// Observe the result of reloading the config file.
+pub fn config_reloaded(path: &Path, res: &Result<(), anyhow::Error>) {
+    match res {
+        Ok(()) => tracing::info!(?path, "reloaded config"),
+        Err(err) => tracing::warn!(?path, ?err, "failed to reload config, keeping previous one"),
+    }
+    let result = match res {
+        Ok(_) => "Success",
+        Err(_) => "Failure",
+    };
+    metrics::get()
+        .config_reloads
+        .with_label_values(&[result])
+        .inc();
+}
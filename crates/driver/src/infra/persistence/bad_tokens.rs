@@ -0,0 +1,288 @@
+//! Storage for unsupported tokens which outlives the driver process and can be
+//! shared between multiple drivers.
+
+use {
+    crate::domain::eth,
+    chrono::{DateTime, Utc},
+    database::byte_array::ByteArray,
+    serde::{Deserialize, Serialize},
+    std::{
+        fmt::{self, Debug, Formatter},
+        io,
+        path::PathBuf,
+        sync::Arc,
+    },
+    thiserror::Error,
+    url::Url,
+};
+
+#[derive(Debug, Clone)]
+pub struct Config {
+    pub backend: Backend,
+    /// Entries detected longer ago than this get ignored and evicted, so
+    /// tokens get re-evaluated once in a while.
+    pub max_age: chrono::Duration,
+}
+
+#[derive(Clone)]
+pub enum Backend {
+    /// A JSON file on the local file system.
+    File(PathBuf),
+    /// The `bad_tokens` table of a Postgres database.
+    Postgres(Url),
+}
+
+impl Debug for Backend {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::File(path) => f.debug_tuple("File").field(path).finish(),
+            // The URL might contain credentials.
+            Self::Postgres(_) => f.debug_tuple("Postgres").field(&"SECRET").finish(),
+        }
+    }
+}
+
+/// A token that was detected to be unsupported.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Entry {
+    pub token: eth::TokenAddress,
+    pub reason: String,
+    pub detected_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Storage(Arc<Inner>);
+
+#[derive(Debug)]
+enum Inner {
+    File {
+        path: PathBuf,
+        /// Serializes the read-modify-write cycles on the file.
+        lock: tokio::sync::Mutex<()>,
+    },
+    Postgres(sqlx::PgPool),
+}
+
+impl Storage {
+    pub fn new(backend: Backend) -> Result<Self, Error> {
+        let inner = match backend {
+            Backend::File(path) => Inner::File {
+                path,
+                lock: Default::default(),
+            },
+            Backend::Postgres(url) => Inner::Postgres(sqlx::PgPool::connect_lazy(url.as_str())?),
+        };
+        Ok(Self(Arc::new(inner)))
+    }
+
+    /// Returns all entries detected at or after the given time.
+    pub async fn load(&self, since: DateTime<Utc>) -> Result<Vec<Entry>, Error> {
+        match self.0.as_ref() {
+            Inner::File { path, lock } => {
+                let _guard = lock.lock().await;
+                Ok(read(path)
+                    .await?
+                    .into_iter()
+                    .filter(|entry| entry.detected_at >= since)
+                    .collect())
+            }
+            Inner::Postgres(pool) => {
+                let mut ex = pool.acquire().await?;
+                Ok(database::bad_tokens::fetch_since(&mut ex, since)
+                    .await?
+                    .into_iter()
+                    .map(|row| Entry {
+                        token: eth::H160(row.token.0).into(),
+                        reason: row.reason,
+                        detected_at: row.detected_at,
+                    })
+                    .collect())
+            }
+        }
+    }
+
+    /// Stores the entry, replacing a previous entry of the same token.
+    pub async fn store(&self, entry: &Entry) -> Result<(), Error> {
+        match self.0.as_ref() {
+            Inner::File { path, lock } => {
+                let _guard = lock.lock().await;
+                let mut entries = read(path).await?;
+                entries.retain(|existing| existing.token != entry.token);
+                entries.push(entry.clone());
+                write(path, &entries).await
+            }
+            Inner::Postgres(pool) => {
+                let mut ex = pool.acquire().await?;
+                database::bad_tokens::upsert(
+                    &mut ex,
+                    &database::bad_tokens::BadToken {
+                        token: ByteArray(entry.token.0 .0 .0),
+                        reason: entry.reason.clone(),
+                        detected_at: entry.detected_at,
+                    },
+                )
+                .await?;
+                Ok(())
+            }
+        }
+    }
+
+    /// Removes the entry of the token. Returns whether the token was stored.
+    pub async fn remove(&self, token: eth::TokenAddress) -> Result<bool, Error> {
+        match self.0.as_ref() {
+            Inner::File { path, lock } => {
+                let _guard = lock.lock().await;
+                let mut entries = read(path).await?;
+                let len = entries.len();
+                entries.retain(|entry| entry.token != token);
+                let removed = entries.len() != len;
+                if removed {
+                    write(path, &entries).await?;
+                }
+                Ok(removed)
+            }
+            Inner::Postgres(pool) => {
+                let mut ex = pool.acquire().await?;
+                Ok(database::bad_tokens::delete(&mut ex, &ByteArray(token.0 .0 .0)).await?)
+            }
+        }
+    }
+
+    /// Removes all entries detected before the given time.
+    pub async fn evict(&self, before: DateTime<Utc>) -> Result<(), Error> {
+        match self.0.as_ref() {
+            Inner::File { path, lock } => {
+                let _guard = lock.lock().await;
+                let mut entries = read(path).await?;
+                let len = entries.len();
+                entries.retain(|entry| entry.detected_at >= before);
+                if entries.len() != len {
+                    write(path, &entries).await?;
+                }
+                Ok(())
+            }
+            Inner::Postgres(pool) => {
+                let mut ex = pool.acquire().await?;
+                database::bad_tokens::delete_before(&mut ex, before).await?;
+                Ok(())
+            }
+        }
+    }
+
+    /// Removes all entries.
+    pub async fn clear(&self) -> Result<(), Error> {
+        match self.0.as_ref() {
+            Inner::File { path, lock } => {
+                let _guard = lock.lock().await;
+                write(path, &[]).await
+            }
+            Inner::Postgres(pool) => {
+                let mut ex = pool.acquire().await?;
+                database::bad_tokens::delete_all(&mut ex).await?;
+                Ok(())
+            }
+        }
+    }
+}
+
+/// How an entry is represented in the JSON file.
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct FileEntry {
+    token: eth::H160,
+    reason: String,
+    /// Unix timestamp in seconds.
+    detected_at: i64,
+}
+
+/// Reads all entries of the file. A missing file is treated like an empty one
+/// so the driver can start without creating it first.
+async fn read(path: &PathBuf) -> Result<Vec<Entry>, Error> {
+    let data = match tokio::fs::read(path).await {
+        Ok(data) => data,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(Default::default()),
+        Err(err) => return Err(err.into()),
+    };
+    let entries: Vec<FileEntry> = serde_json::from_slice(&data)?;
+    Ok(entries
+        .into_iter()
+        .filter_map(|entry| {
+            Some(Entry {
+                token: entry.token.into(),
+                reason: entry.reason,
+                detected_at: DateTime::from_timestamp(entry.detected_at, 0)?,
+            })
+        })
+        .collect())
+}
+
+/// Replaces the content of the file. Writes to a temporary file first so a
+/// crash never leaves a partially written file behind.
+async fn write(path: &PathBuf, entries: &[Entry]) -> Result<(), Error> {
+    let entries = entries
+        .iter()
+        .map(|entry| FileEntry {
+            token: entry.token.0 .0,
+            reason: entry.reason.clone(),
+            detected_at: entry.detected_at.timestamp(),
+        })
+        .collect::<Vec<_>>();
+    let tmp = path.with_extension("tmp");
+    tokio::fs::write(&tmp, serde_json::to_vec_pretty(&entries)?).await?;
+    tokio::fs::rename(&tmp, path).await?;
+    Ok(())
+}
+
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("I/O error: {0:?}")]
+    Io(#[from] io::Error),
+    #[error("invalid bad token file: {0:?}")]
+    Json(#[from] serde_json::Error),
+    #[error("database error: {0:?}")]
+    Database(#[from] sqlx::Error),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn file_storage() {
+        let dir = tempfile::tempdir().unwrap();
+        let storage = Storage::new(Backend::File(dir.path().join("bad-tokens.json"))).unwrap();
+        let since = DateTime::from_timestamp(0, 0).unwrap();
+
+        // A missing file is empty.
+        assert!(storage.load(since).await.unwrap().is_empty());
+
+        let entry = |byte: u8, reason: &str, detected_at: i64| Entry {
+            token: eth::H160::repeat_byte(byte).into(),
+            reason: reason.to_owned(),
+            detected_at: DateTime::from_timestamp(detected_at, 0).unwrap(),
+        };
+        storage.store(&entry(1, "old", 100)).await.unwrap();
+        storage.store(&entry(2, "new", 200)).await.unwrap();
+        storage.store(&entry(1, "refreshed", 300)).await.unwrap();
+        assert_eq!(
+            storage.load(since).await.unwrap(),
+            vec![entry(2, "new", 200), entry(1, "refreshed", 300)]
+        );
+
+        storage
+            .evict(DateTime::from_timestamp(250, 0).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(
+            storage.load(since).await.unwrap(),
+            vec![entry(1, "refreshed", 300)]
+        );
+
+        assert!(storage.remove(entry(1, "", 0).token).await.unwrap());
+        assert!(!storage.remove(entry(1, "", 0).token).await.unwrap());
+
+        storage.store(&entry(3, "new", 400)).await.unwrap();
+        storage.clear().await.unwrap();
+        assert!(storage.load(since).await.unwrap().is_empty());
+    }
+}
@@ -9,6 +9,8 @@ use {
     tracing::Instrument,
 };
 
+pub mod bad_tokens;
+
 #[derive(Clone, Debug, Default)]
 pub struct S3 {
     /// Name of the AWS S3 bucket in which the auctions will be stored
@@ -1,5 +1,5 @@
 use {
-    crate::domain::eth,
+    crate::{domain::eth, util::Bytes},
     chain::Chain,
     ethrpc::block_stream::CurrentBlockWatcher,
     reqwest::ClientBuilder,
@@ -86,17 +86,19 @@ impl Enso {
 #[error("Enso tx simulation error")]
 pub enum Error {
     Http(#[from] reqwest::Error),
-    Revert(String),
+    Revert {
+        exit_reason: String,
+        return_data: Bytes<Vec<u8>>,
+    },
 }
 
 impl From<dto::Response> for Result<eth::Gas, Error> {
     fn from(response: dto::Response) -> Self {
         if !response.success {
-            return Err(Error::Revert(format!(
-                "{}: {}",
-                response.exit_reason,
-                hex::encode(&response.return_data)
-            )));
+            return Err(Error::Revert {
+                exit_reason: response.exit_reason,
+                return_data: response.return_data.into(),
+            });
         }
         Ok(response.gas_used.into())
     }
@@ -0,0 +1,147 @@
+//! State of the forked chain, fetched from the node on demand.
+
+use {
+    crate::domain::eth,
+    ethcontract::dyns::DynWeb3,
+    revm::{
+        db::DatabaseRef,
+        primitives::{AccountInfo, Address, Bytecode, B256, U256},
+    },
+    std::{
+        collections::HashMap,
+        sync::{Arc, Mutex},
+    },
+    web3::types::{BlockId, BlockNumber},
+};
+
+/// State fetched from the node for the most recent block. Gets discarded as
+/// soon as the first simulation on a newer block happens.
+#[derive(Debug, Clone, Default)]
+pub(super) struct Cache(Arc<Mutex<Arc<State>>>);
+
+#[derive(Debug, Default)]
+pub(super) struct State {
+    block: u64,
+    accounts: Mutex<HashMap<Address, AccountInfo>>,
+    storage: Mutex<HashMap<(Address, U256), U256>>,
+    block_hashes: Mutex<HashMap<U256, B256>>,
+}
+
+impl Cache {
+    /// Returns the cached state of the given block.
+    pub(super) fn at(&self, block: u64) -> Arc<State> {
+        let mut current = self.0.lock().unwrap();
+        if current.block != block {
+            *current = Arc::new(State {
+                block,
+                ..Default::default()
+            });
+        }
+        Arc::clone(&current)
+    }
+}
+
+/// Read-only view of the chain state at a specific block. State which is not
+/// cached yet gets fetched by blocking on the node requests, so this must only
+/// be used outside of the async runtime's worker threads.
+pub(super) struct Database {
+    web3: DynWeb3,
+    state: Arc<State>,
+    runtime: tokio::runtime::Handle,
+}
+
+impl Database {
+    pub(super) fn new(web3: DynWeb3, state: Arc<State>, runtime: tokio::runtime::Handle) -> Self {
+        Self {
+            web3,
+            state,
+            runtime,
+        }
+    }
+
+    fn block(&self) -> Option<BlockNumber> {
+        Some(BlockNumber::Number(self.state.block.into()))
+    }
+}
+
+impl DatabaseRef for Database {
+    type Error = web3::Error;
+
+    fn basic(&self, address: Address) -> Result<Option<AccountInfo>, Self::Error> {
+        if let Some(info) = self.state.accounts.lock().unwrap().get(&address) {
+            return Ok(Some(info.clone()));
+        }
+
+        let node = self.web3.eth();
+        let h160 = eth::H160::from_slice(address.as_slice());
+        let (balance, nonce, code) = self.runtime.block_on(async {
+            futures::try_join!(
+                node.balance(h160, self.block()),
+                node.transaction_count(h160, self.block()),
+                node.code(h160, self.block()),
+            )
+        })?;
+        let code = Bytecode::new_raw(code.0.into());
+        let info = AccountInfo::new(u256(balance), nonce.low_u64(), code.hash_slow(), code);
+
+        self.state
+            .accounts
+            .lock()
+            .unwrap()
+            .insert(address, info.clone());
+        Ok(Some(info))
+    }
+
+    fn code_by_hash(&self, _: B256) -> Result<Bytecode, Self::Error> {
+        // The code is always part of the fetched account info, so the EVM never
+        // has to look it up by its hash.
+        Ok(Bytecode::new())
+    }
+
+    fn storage(&self, address: Address, index: U256) -> Result<U256, Self::Error> {
+        if let Some(value) = self.state.storage.lock().unwrap().get(&(address, index)) {
+            return Ok(*value);
+        }
+
+        let value = self.runtime.block_on(self.web3.eth().storage(
+            eth::H160::from_slice(address.as_slice()),
+            eth::U256(index.into_limbs()),
+            self.block(),
+        ))?;
+        let value = U256::from_be_bytes(value.0);
+
+        self.state
+            .storage
+            .lock()
+            .unwrap()
+            .insert((address, index), value);
+        Ok(value)
+    }
+
+    fn block_hash(&self, number: U256) -> Result<B256, Self::Error> {
+        if let Some(hash) = self.state.block_hashes.lock().unwrap().get(&number) {
+            return Ok(*hash);
+        }
+
+        let block = self
+            .runtime
+            .block_on(self.web3.eth().block(BlockId::Number(BlockNumber::Number(
+                number.to::<u64>().into(),
+            ))))?;
+        let hash = block
+            .and_then(|block| block.hash)
+            .map(|hash| B256::new(hash.0))
+            .unwrap_or_default();
+
+        self.state.block_hashes.lock().unwrap().insert(number, hash);
+        Ok(hash)
+    }
+}
+
+pub(super) fn u256(value: eth::U256) -> U256 {
+    U256::from_limbs(value.0)
+}
+
+pub(super) fn address(value: eth::H160) -> Address {
+    Address::new(value.0)
+}
@@ -0,0 +1,313 @@
+//! An in-process EVM forked from the connected node. State gets fetched
+//! lazily the first time it's accessed and is cached for the remainder of the
+//! block, so simulating many settlements on top of the same block only fetches
+//! the touched state once.
+
+use {
+    crate::domain::eth,
+    chain::Chain,
+    ethcontract::dyns::DynWeb3,
+    ethrpc::block_stream::{BlockInfo, CurrentBlockWatcher},
+    revm::primitives::{Env, ExecutionResult, TransactTo},
+    std::time::Duration,
+    thiserror::Error,
+};
+
+mod database;
+mod tracer;
+
+pub use tracer::CallFrame;
+
+#[derive(Debug, Clone)]
+pub(super) struct Fork {
+    web3: DynWeb3,
+    chain: Chain,
+    current_block: CurrentBlockWatcher,
+    network_block_interval: Option<Duration>,
+    cache: database::Cache,
+}
+
+#[derive(Debug, Clone)]
+pub struct Config {
+    /// The time between new blocks in the network. Used to simulate with the
+    /// timestamp of the block the settlement would get included in.
+    pub network_block_interval: Option<Duration>,
+}
+
+/// The outcome of a successfully executed transaction.
+#[derive(Debug)]
+pub(super) struct Simulation {
+    pub gas: eth::Gas,
+    pub access_list: eth::AccessList,
+    pub trace: CallFrame,
+}
+
+impl Fork {
+    pub(super) fn new(
+        config: Config,
+        web3: DynWeb3,
+        chain: Chain,
+        current_block: CurrentBlockWatcher,
+    ) -> Self {
+        Self {
+            web3,
+            chain,
+            current_block,
+            network_block_interval: config.network_block_interval,
+            cache: Default::default(),
+        }
+    }
+
+    /// Executes the transaction on top of the current block.
+    pub(super) async fn simulate(&self, tx: &eth::Tx) -> Result<Simulation, Error> {
+        let block = *self.current_block.borrow();
+        let db = database::Database::new(
+            self.web3.clone(),
+            self.cache.at(block.number),
+            tokio::runtime::Handle::current(),
+        );
+        let env = self.env(tx, &block);
+        let (from, to) = (tx.from, tx.to);
+
+        // The EVM is synchronous and blocks on fetching missing state from the
+        // node, so it has to run outside of the async runtime's worker threads.
+        let (result, tracer) = tokio::task::spawn_blocking(move || {
+            let mut evm = revm::EVM::with_env(env);
+            evm.database(db);
+            let mut tracer = tracer::Tracer::default();
+            let result = evm.inspect_ref(&mut tracer);
+            (result, tracer)
+        })
+        .await
+        .map_err(|err| Error::Execution(format!("simulation task failed: {err}")))?;
+        let result = result
+            .map_err(|err| Error::Execution(format!("{err:?}")))?
+            .result;
+
+        let (access_list, trace) = tracer.finish(from, to);
+        match result {
+            ExecutionResult::Success { gas_used, .. } => Ok(Simulation {
+                gas: gas_used.into(),
+                access_list,
+                trace,
+            }),
+            ExecutionResult::Revert { output, .. } => Err(Error::Revert(Box::new(CallFrame {
+                output: output.to_vec().into(),
+                ..trace
+            }))),
+            ExecutionResult::Halt { reason, .. } => Err(Error::Halt {
+                reason: format!("{reason:?}"),
+                trace: Box::new(trace),
+            }),
+        }
+    }
+
+    fn env(&self, tx: &eth::Tx, block: &BlockInfo) -> Env {
+        let mut env = Env::default();
+        env.cfg.chain_id = self.chain.id();
+
+        // Simulate as if the transaction was included in the next block.
+        env.block.number = revm::primitives::U256::from(block.number + 1);
+        env.block.timestamp = revm::primitives::U256::from(
+            block.timestamp
+                + self
+                    .network_block_interval
+                    .map(|interval| interval.as_secs())
+                    .unwrap_or_default(),
+        );
+        env.block.gas_limit = database::u256(block.gas_limit);
+        env.block.basefee = database::u256(block.gas_price);
+
+        env.tx.caller = database::address(tx.from.0);
+        env.tx.transact_to = TransactTo::Call(database::address(tx.to.0));
+        env.tx.data = tx.input.0.clone().into();
+        env.tx.value = database::u256(tx.value.0);
+        env.tx.gas_limit = block.gas_limit.low_u64();
+        env.tx.gas_price = database::u256(block.gas_price);
+        env.tx.access_list = web3::types::AccessList::from(tx.access_list.clone())
+            .into_iter()
+            .map(|item| {
+                (
+                    database::address(item.address),
+                    item.storage_keys
+                        .into_iter()
+                        .map(|key| database::u256(eth::U256::from_big_endian(key.as_bytes())))
+                        .collect(),
+                )
+            })
+            .collect();
+        env
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum Error {
+    /// The transaction could not be executed, e.g. because state could not be
+    /// fetched from the node.
+    #[error("fork simulation failed: {0}")]
+    Execution(String),
+    /// The transaction reverted. The output of the outermost call is the
+    /// revert data.
+    #[error("fork simulation reverted with {:?}", .0.output)]
+    Revert(Box<CallFrame>),
+    /// The EVM stopped the execution, e.g. because the transaction ran out of
+    /// gas.
+    #[error("fork simulation halted: {reason}")]
+    Halt {
+        reason: String,
+        trace: Box<CallFrame>,
+    },
+}
+
+impl Error {
+    /// The calls made by the transaction up to the revert.
+    pub fn trace(&self) -> Option<&CallFrame> {
+        match self {
+            Error::Execution(_) => None,
+            Error::Revert(trace) | Error::Halt { trace, .. } => Some(trace),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        ethcontract::{dyns::DynTransport, Web3},
+        ethrpc::mock::MockTransport,
+        serde_json::{json, Value},
+        std::{
+            collections::HashMap,
+            sync::{Arc, Mutex},
+        },
+    };
+
+    type Requests = Arc<Mutex<Vec<(String, Vec<Value>)>>>;
+
+    /// A node which serves the given contracts, an empty balance and nonce
+    /// for every account and 42 for every storage slot. Records all requests
+    /// it receives.
+    fn node(contracts: HashMap<eth::H160, Vec<u8>>) -> (DynWeb3, Requests) {
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        let transport = MockTransport::new();
+        transport.mock().expect_execute().returning({
+            let requests = requests.clone();
+            move |method, params| {
+                requests
+                    .lock()
+                    .unwrap()
+                    .push((method.clone(), params.clone()));
+                Ok(match method.as_str() {
+                    "eth_getBalance" | "eth_getTransactionCount" => json!(eth::U256::zero()),
+                    "eth_getCode" => {
+                        let code = contracts
+                            .iter()
+                            .find(|(address, _)| json!(address) == params[0])
+                            .map(|(_, code)| code.clone())
+                            .unwrap_or_default();
+                        json!(web3::types::Bytes(code))
+                    }
+                    "eth_getStorageAt" => json!(eth::H256::from_low_u64_be(42)),
+                    method => panic!("unexpected request {method}"),
+                })
+            }
+        });
+        (Web3::new(DynTransport::new(transport)), requests)
+    }
+
+    fn tx(from: eth::H160, to: eth::H160) -> eth::Tx {
+        eth::Tx {
+            from: from.into(),
+            to: to.into(),
+            value: 0.into(),
+            input: Default::default(),
+            access_list: Default::default(),
+        }
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn simulates_against_node_state() {
+        let sender = eth::H160::repeat_byte(1);
+        let entry = eth::H160::repeat_byte(2);
+        let callee = eth::H160::repeat_byte(3);
+        let reverter = eth::H160::repeat_byte(4);
+
+        // SLOAD(2) and CALL the callee without any value or calldata.
+        let mut code = vec![
+            0x60, 0x02, 0x54, 0x50, 0x60, 0x00, 0x60, 0x00, 0x60, 0x00, 0x60, 0x00, 0x60, 0x00,
+            0x73,
+        ];
+        code.extend(callee.as_bytes());
+        code.extend([0x5a, 0xf1, 0x50, 0x00]);
+        let (web3, requests) = node(HashMap::from([
+            (entry, code),
+            // SLOAD(1).
+            (callee, vec![0x60, 0x01, 0x54, 0x50, 0x00]),
+            // REVERT(0, 0).
+            (reverter, vec![0x60, 0x00, 0x60, 0x00, 0xfd]),
+        ]));
+        let (block, current_block) = tokio::sync::watch::channel(BlockInfo {
+            number: 1,
+            gas_limit: 30_000_000.into(),
+            ..Default::default()
+        });
+        let fork = Fork::new(
+            Config {
+                network_block_interval: None,
+            },
+            web3,
+            Chain::Mainnet,
+            current_block,
+        );
+        let storage_requests = |requests: &Requests| {
+            requests
+                .lock()
+                .unwrap()
+                .iter()
+                .filter(|(method, _)| method == "eth_getStorageAt")
+                .map(|(_, params)| (params[0].clone(), params[1].clone()))
+                .collect::<Vec<_>>()
+        };
+
+        let simulation = fork.simulate(&tx(sender, entry)).await.unwrap();
+        assert!(simulation.gas > 21_000_u64.into());
+        assert_eq!(
+            web3::types::AccessList::from(simulation.access_list),
+            vec![web3::types::AccessListItem {
+                address: callee,
+                storage_keys: vec![eth::H256::from_low_u64_be(1)],
+            }]
+        );
+        assert_eq!(simulation.trace.calls.len(), 1);
+        assert_eq!(
+            storage_requests(&requests),
+            vec![
+                (json!(entry), json!(eth::U256::from(2))),
+                (json!(callee), json!(eth::U256::from(1))),
+            ]
+        );
+        for account in [sender, entry, callee] {
+            assert!(requests
+                .lock()
+                .unwrap()
+                .iter()
+                .any(|(method, params)| method == "eth_getCode" && params[0] == json!(account)));
+        }
+
+        // Simulating on the same block again only uses the cached state.
+        let fetched = requests.lock().unwrap().len();
+        fork.simulate(&tx(sender, entry)).await.unwrap();
+        assert_eq!(requests.lock().unwrap().len(), fetched);
+
+        // A new block invalidates the cached state.
+        block.send_modify(|block| block.number = 2);
+        fork.simulate(&tx(sender, entry)).await.unwrap();
+        assert_eq!(storage_requests(&requests).len(), 4);
+
+        let err = fork.simulate(&tx(sender, reverter)).await.unwrap_err();
+        assert!(
+            matches!(&err, Error::Revert(trace) if !trace.success && trace.output.0.is_empty()),
+            "{err:?}"
+        );
+    }
+}
@@ -0,0 +1,196 @@
+//! Records the calls and storage accesses of a simulated transaction.
+
+use {
+    super::database,
+    crate::{domain::eth, util},
+    revm::{
+        interpreter::{opcode, return_ok, CallInputs, Gas, InstructionResult, Interpreter},
+        primitives::{Address, Bytes, U256},
+        Database,
+        EVMData,
+        Inspector,
+    },
+    std::collections::{BTreeMap, BTreeSet},
+};
+
+/// A call made during the simulation together with all the calls it made in
+/// turn.
+#[derive(Debug, Clone, Default)]
+pub struct CallFrame {
+    pub from: eth::Address,
+    pub to: eth::Address,
+    pub value: eth::U256,
+    pub input: util::Bytes<Vec<u8>>,
+    pub output: util::Bytes<Vec<u8>>,
+    pub gas_used: u64,
+    pub success: bool,
+    pub calls: Vec<CallFrame>,
+}
+
+#[derive(Debug, Default)]
+pub(super) struct Tracer {
+    /// Calls which have not returned yet, with the innermost call last.
+    stack: Vec<CallFrame>,
+    root: Option<CallFrame>,
+    storage: BTreeMap<Address, BTreeSet<U256>>,
+}
+
+impl Tracer {
+    /// Returns the access list and call trace of the simulated transaction.
+    /// Like `eth_createAccessList` the sender, the recipient and precompiles
+    /// are excluded from the access list since they are always warm.
+    pub(super) fn finish(
+        self,
+        from: eth::Address,
+        to: eth::Address,
+    ) -> (eth::AccessList, CallFrame) {
+        let excluded = [database::address(from.0), database::address(to.0)];
+        let access_list: web3::types::AccessList = self
+            .storage
+            .into_iter()
+            .filter(|(address, _)| !excluded.contains(address) && !is_precompile(address))
+            .map(|(address, slots)| web3::types::AccessListItem {
+                address: eth::H160(address.into_array()),
+                storage_keys: slots
+                    .into_iter()
+                    .map(|slot| eth::H256(slot.to_be_bytes()))
+                    .collect(),
+            })
+            .collect();
+        (access_list.into(), self.root.unwrap_or_default())
+    }
+}
+
+impl<DB: Database> Inspector<DB> for Tracer {
+    fn step(&mut self, interp: &mut Interpreter, _: &mut EVMData<'_, DB>) -> InstructionResult {
+        if matches!(interp.current_opcode(), opcode::SLOAD | opcode::SSTORE) {
+            if let Ok(slot) = interp.stack().peek(0) {
+                self.storage
+                    .entry(interp.contract.address)
+                    .or_default()
+                    .insert(slot);
+            }
+        }
+        InstructionResult::Continue
+    }
+
+    fn call(
+        &mut self,
+        _: &mut EVMData<'_, DB>,
+        inputs: &mut CallInputs,
+    ) -> (InstructionResult, Gas, Bytes) {
+        self.storage.entry(inputs.contract).or_default();
+        self.stack.push(CallFrame {
+            from: eth::H160(inputs.context.caller.into_array()).into(),
+            to: eth::H160(inputs.contract.into_array()).into(),
+            value: eth::U256(inputs.transfer.value.into_limbs()),
+            input: inputs.input.to_vec().into(),
+            ..Default::default()
+        });
+        (InstructionResult::Continue, Gas::new(0), Bytes::new())
+    }
+
+    fn call_end(
+        &mut self,
+        _: &mut EVMData<'_, DB>,
+        _: &CallInputs,
+        remaining_gas: Gas,
+        ret: InstructionResult,
+        out: Bytes,
+    ) -> (InstructionResult, Gas, Bytes) {
+        if let Some(mut frame) = self.stack.pop() {
+            frame.output = out.to_vec().into();
+            frame.gas_used = remaining_gas.spend();
+            frame.success = matches!(ret, return_ok!());
+            match self.stack.last_mut() {
+                Some(parent) => parent.calls.push(frame),
+                None => self.root = Some(frame),
+            }
+        }
+        (ret, remaining_gas, out)
+    }
+}
+
+/// Precompiles occupy the lowest addresses.
+fn is_precompile(address: &Address) -> bool {
+    let bytes = address.as_slice();
+    bytes[..19].iter().all(|byte| *byte == 0) && bytes[19] <= 0x0a
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        revm::{
+            db::{CacheDB, EmptyDB},
+            primitives::{AccountInfo, Bytecode, Env, ExecutionResult, TransactTo},
+        },
+    };
+
+    fn address(address: Address) -> eth::Address {
+        eth::H160(address.into_array()).into()
+    }
+
+    #[test]
+    fn records_calls_and_storage_accesses() {
+        let sender = Address::repeat_byte(1);
+        let entry = Address::repeat_byte(2);
+        let callee = Address::repeat_byte(3);
+
+        let mut db = CacheDB::new(EmptyDB::default());
+        let mut deploy = |address, code: Vec<u8>| {
+            db.insert_account_info(
+                address,
+                AccountInfo {
+                    code: Some(Bytecode::new_raw(code.into())),
+                    ..Default::default()
+                },
+            )
+        };
+        // SLOAD(2) and CALL the callee without any value or calldata.
+        let mut code = vec![
+            0x60, 0x02, 0x54, 0x50, 0x60, 0x00, 0x60, 0x00, 0x60, 0x00, 0x60, 0x00, 0x60, 0x00,
+            0x73,
+        ];
+        code.extend(callee.as_slice());
+        code.extend([0x5a, 0xf1, 0x50, 0x00]);
+        deploy(entry, code);
+        // SLOAD(1).
+        deploy(callee, vec![0x60, 0x01, 0x54, 0x50, 0x00]);
+
+        let mut env = Env::default();
+        env.tx.caller = sender;
+        env.tx.transact_to = TransactTo::Call(entry);
+        env.tx.gas_limit = 1_000_000;
+        let mut evm = revm::EVM::with_env(env);
+        evm.database(db);
+        let mut tracer = Tracer::default();
+        let result = evm.inspect_ref(&mut tracer).unwrap().result;
+        assert!(matches!(result, ExecutionResult::Success { .. }));
+
+        let (access_list, trace) = tracer.finish(address(sender), address(entry));
+        assert_eq!(
+            web3::types::AccessList::from(access_list),
+            vec![web3::types::AccessListItem {
+                address: address(callee).0,
+                storage_keys: vec![eth::H256::from_low_u64_be(1)],
+            }]
+        );
+        assert_eq!(trace.from, address(sender));
+        assert_eq!(trace.to, address(entry));
+        assert!(trace.success);
+        assert_eq!(trace.calls.len(), 1);
+        assert_eq!(trace.calls[0].from, address(entry));
+        assert_eq!(trace.calls[0].to, address(callee));
+        assert!(trace.calls[0].success);
+        assert!(trace.calls[0].calls.is_empty());
+    }
+
+    #[test]
+    fn detects_precompiles() {
+        assert!(is_precompile(&Address::with_last_byte(1)));
+        assert!(is_precompile(&Address::with_last_byte(0x0a)));
+        assert!(!is_precompile(&Address::with_last_byte(0x0b)));
+        assert!(!is_precompile(&Address::repeat_byte(1)));
+    }
+}
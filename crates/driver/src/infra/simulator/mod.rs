@@ -7,6 +7,8 @@ use {
 };
 
 pub mod enso;
+pub mod fork;
+pub mod revert;
 pub mod tenderly;
 
 /// Ethereum transaction simulator.
@@ -25,6 +27,7 @@ pub struct Simulator {
 pub enum Config {
     Tenderly(tenderly::Config),
     Enso(enso::Config),
+    Fork(fork::Config),
 }
 
 impl Simulator {
@@ -66,6 +69,24 @@ impl Simulator {
         }
     }
 
+    /// Simulate transactions locally on an EVM forking the state of the
+    /// Ethereum node. Returns access lists and call traces without needing a
+    /// third-party service.
+    pub fn fork(config: fork::Config, eth: Ethereum) -> Self {
+        let eth = eth.with_metric_label("forkSimulator".into());
+        Self {
+            inner: Inner::Fork(fork::Fork::new(
+                config,
+                eth.web3().clone(),
+                eth.chain(),
+                eth.current_block().clone(),
+            )),
+            eth,
+            disable_access_lists: false,
+            disable_gas: None,
+        }
+    }
+
     /// Disable access list simulation. Some environments, such as less popular
     /// blockchains, don't support access list simulation.
     pub fn disable_access_lists(&mut self) {
@@ -78,6 +99,41 @@ impl Simulator {
         self.disable_gas = Some(fixed_gas);
     }
 
+    /// Simulate the access list and the gas needed by a transaction. The
+    /// returned access list is a superset of the one the transaction already
+    /// has. Simulators which execute the transaction themselves only run it
+    /// once and also return the calls it made.
+    ///
+    /// Note that the gas is measured without the access list found by the
+    /// same execution, which slightly overestimates it.
+    pub async fn simulate(&self, tx: &eth::Tx) -> Result<Simulation, Error> {
+        let Inner::Fork(fork) = &self.inner else {
+            let access_list = self.access_list(tx).await?;
+            let gas = self
+                .gas(&tx.clone().set_access_list(access_list.clone()))
+                .await?;
+            return Ok(Simulation {
+                access_list,
+                gas,
+                trace: None,
+            });
+        };
+        let block = self.eth.current_block().borrow().number.into();
+        let simulation = fork
+            .simulate(tx)
+            .measure("fork_simulate")
+            .await
+            .map_err(with(tx.clone(), block))?;
+        Ok(Simulation {
+            access_list: match self.disable_access_lists {
+                true => tx.access_list.clone(),
+                false => tx.access_list.clone().merge(simulation.access_list),
+            },
+            gas: self.disable_gas.unwrap_or(simulation.gas),
+            trace: Some(simulation.trace),
+        })
+    }
+
     /// Simulate the access list needed by a transaction. If the transaction
     /// already has an access list, the returned access list will be a
     /// superset of the existing one.
@@ -104,6 +160,13 @@ impl Simulator {
                 .create_access_list(tx.clone())
                 .await
                 .map_err(with(tx.clone(), block))?,
+            Inner::Fork(fork) => {
+                fork.simulate(tx)
+                    .measure("fork_simulate_access_list")
+                    .await
+                    .map_err(with(tx.clone(), block))?
+                    .access_list
+            }
         };
         Ok(tx.access_list.clone().merge(access_list))
     }
@@ -133,15 +196,32 @@ impl Simulator {
                 .measure("enso_simulate_gas")
                 .await
                 .map_err(with(tx.clone(), block))?,
+            Inner::Fork(fork) => {
+                fork.simulate(tx)
+                    .measure("fork_simulate_gas")
+                    .await
+                    .map_err(with(tx.clone(), block))?
+                    .gas
+            }
         })
     }
 }
 
+/// The outcome of simulating a transaction which didn't revert.
+#[derive(Debug)]
+pub struct Simulation {
+    pub access_list: eth::AccessList,
+    pub gas: eth::Gas,
+    /// The calls made by the transaction, if the simulator records them.
+    pub trace: Option<fork::CallFrame>,
+}
+
 #[derive(Debug, Clone)]
 enum Inner {
     Tenderly(tenderly::Tenderly),
     Ethereum,
     Enso(enso::Enso),
+    Fork(fork::Fork),
 }
 
 #[derive(Debug, thiserror::Error)]
@@ -152,6 +232,8 @@ pub enum SimulatorError {
     Blockchain(#[from] blockchain::Error),
     #[error("enso error: {0:?}")]
     Enso(#[from] enso::Error),
+    #[error("fork error: {0:?}")]
+    Fork(#[from] fork::Error),
     #[error("the simulated gas {0} exceeded the gas limit {1} provided in the solution")]
     GasExceeded(eth::Gas, eth::Gas),
 }
@@ -164,6 +246,27 @@ pub struct RevertError {
     pub block: eth::BlockNo,
 }
 
+impl RevertError {
+    /// Decodes why and where the transaction reverted as far as the simulator
+    /// provides the necessary information.
+    pub fn revert(&self) -> revert::Revert {
+        let (data, trace) = match &self.err {
+            SimulatorError::Blockchain(err) => (err.revert_data(), None),
+            SimulatorError::Enso(enso::Error::Revert { return_data, .. }) => {
+                (Some(return_data.0.clone()), None)
+            }
+            SimulatorError::Fork(fork::Error::Revert(trace)) => {
+                (Some(trace.output.0.clone()), Some(&**trace))
+            }
+            SimulatorError::Fork(err) => (None, err.trace()),
+            SimulatorError::Tenderly(_)
+            | SimulatorError::Enso(_)
+            | SimulatorError::GasExceeded(..) => (None, None),
+        };
+        revert::Revert::new(data.as_deref(), trace)
+    }
+}
+
 #[derive(Debug, thiserror::Error)]
 pub enum Error {
     /// If a transaction reverted, forward that transaction together with the
@@ -193,7 +296,9 @@ where
                 }
             }
             SimulatorError::Enso(enso::Error::Http(_)) => None,
-            SimulatorError::Enso(enso::Error::Revert(_)) => Some(tx),
+            SimulatorError::Enso(enso::Error::Revert { .. }) => Some(tx),
+            SimulatorError::Fork(fork::Error::Execution(_)) => None,
+            SimulatorError::Fork(fork::Error::Revert(_) | fork::Error::Halt { .. }) => Some(tx),
             SimulatorError::GasExceeded(..) => Some(tx),
         };
         match tx {
@@ -0,0 +1,369 @@
+//! Decoding of why and where a simulated settlement reverted.
+
+use {
+    super::fork::CallFrame,
+    crate::{domain::eth, util::Bytes},
+    ethabi::{ParamType, Token},
+    itertools::Itertools,
+    std::fmt::{self, Display, Formatter},
+};
+
+/// Everything that could be determined about a reverted simulation.
+#[derive(Debug, Clone, Default)]
+pub struct Revert {
+    /// The decoded revert data. [`None`] if the simulator did not provide any.
+    pub reason: Option<Reason>,
+    /// Which part of the settlement reverted. Only available for simulators
+    /// which provide a call trace.
+    pub location: Option<Location>,
+}
+
+impl Revert {
+    pub fn new(data: Option<&[u8]>, trace: Option<&CallFrame>) -> Self {
+        Self {
+            reason: data.map(Reason::decode),
+            location: trace.and_then(Location::find),
+        }
+    }
+}
+
+impl Display for Revert {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match &self.reason {
+            Some(reason) => write!(f, "{reason}")?,
+            None => f.write_str("unknown reason")?,
+        }
+        if let Some(location) = &self.location {
+            write!(f, " in {location}")?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Reason {
+    /// `revert("message")` or `require(condition, "message")`.
+    Error(String),
+    /// Failed assertions, arithmetic overflows, etc.
+    ///
+    /// https://docs.soliditylang.org/en/latest/control-structures.html#panic-via-assert-and-error-via-require
+    Panic(eth::U256),
+    /// A custom error of one of the known contracts, formatted like a call,
+    /// e.g. `CowAmm.OrderNotValid("not enough liquidity")`.
+    Custom(String),
+    /// Revert data that could not be decoded. Empty if the contract reverted
+    /// without any data.
+    Unknown(Bytes<Vec<u8>>),
+}
+
+const ERROR_SELECTOR: [u8; 4] = hex_literal::hex!("08c379a0");
+const PANIC_SELECTOR: [u8; 4] = hex_literal::hex!("4e487b71");
+
+impl Reason {
+    pub fn decode(data: &[u8]) -> Self {
+        Self::decode_builtin(data)
+            .or_else(|| Self::decode_custom(data))
+            .unwrap_or_else(|| Self::Unknown(data.to_vec().into()))
+    }
+
+    fn decode_builtin(data: &[u8]) -> Option<Self> {
+        if let Some(params) = data.strip_prefix(&ERROR_SELECTOR) {
+            let message = ethabi::decode(&[ParamType::String], params)
+                .ok()?
+                .pop()?
+                .into_string()?;
+            return Some(Self::Error(message));
+        }
+        if let Some(params) = data.strip_prefix(&PANIC_SELECTOR) {
+            let code = ethabi::decode(&[ParamType::Uint(256)], params)
+                .ok()?
+                .pop()?
+                .into_uint()?;
+            return Some(Self::Panic(code));
+        }
+        None
+    }
+
+    fn decode_custom(data: &[u8]) -> Option<Self> {
+        let (selector, params) = (data.get(..4)?, data.get(4..)?);
+        known_contracts().find_map(|(contract, abi)| {
+            abi.errors().find_map(|error| {
+                let types = error
+                    .inputs
+                    .iter()
+                    .map(|input| input.kind.clone())
+                    .collect_vec();
+                if ethabi::short_signature(&error.name, &types) != selector {
+                    return None;
+                }
+                let tokens = ethabi::decode(&types, params).ok()?;
+                Some(Self::Custom(format!(
+                    "{contract}.{}({})",
+                    error.name,
+                    tokens.iter().map(format_token).join(", ")
+                )))
+            })
+        })
+    }
+}
+
+impl Display for Reason {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Error(message) => write!(f, "{message:?}"),
+            Self::Panic(code) => write!(f, "Panic({code:#x})"),
+            Self::Custom(error) => f.write_str(error),
+            Self::Unknown(data) => write!(f, "{data:?}"),
+        }
+    }
+}
+
+/// Contracts commonly interacted with in settlements whose custom errors get
+/// decoded.
+fn known_contracts() -> impl Iterator<Item = (&'static str, &'static ethabi::Contract)> {
+    [
+        ("CowAmm", &contracts::CowAmm::raw_contract().interface.abi),
+        (
+            "CowAmmLegacyHelper",
+            &contracts::CowAmmLegacyHelper::raw_contract().interface.abi,
+        ),
+        (
+            "CoWSwapEthFlow",
+            &contracts::CoWSwapEthFlow::raw_contract().interface.abi,
+        ),
+        (
+            "HooksTrampoline",
+            &contracts::HooksTrampoline::raw_contract().interface.abi,
+        ),
+    ]
+    .into_iter()
+}
+
+fn format_token(token: &Token) -> String {
+    match token {
+        Token::String(value) => format!("{value:?}"),
+        Token::Address(address) => format!("{address:?}"),
+        Token::Bytes(bytes) | Token::FixedBytes(bytes) => format!("0x{}", hex::encode(bytes)),
+        other => other.to_string(),
+    }
+}
+
+/// The part of the settlement which caused the revert.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Location {
+    /// One of the interactions encoded in the settlement.
+    Interaction { phase: Phase, index: usize },
+    /// Transferring a token into or out of the settlement contract.
+    TokenTransfer { token: eth::Address },
+    /// The settlement contract itself, e.g. because a limit price was not
+    /// respected.
+    Settlement,
+}
+
+/// The interaction groups of a settlement in the order they get executed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Phase {
+    Pre,
+    Intra,
+    Post,
+}
+
+/// `GPv2VaultRelayer.transferFromAccounts(GPv2Transfer.Data[])`
+fn transfer_from_accounts_selector() -> [u8; 4] {
+    ethabi::short_signature(
+        "transferFromAccounts",
+        &[ParamType::Array(Box::new(ParamType::Tuple(vec![
+            ParamType::Address,
+            ParamType::Address,
+            ParamType::Uint(256),
+            ParamType::FixedBytes(32),
+        ])))],
+    )
+}
+
+/// `ERC20.transfer(address,uint256)`
+const TRANSFER_SELECTOR: [u8; 4] = hex_literal::hex!("a9059cbb");
+
+impl Location {
+    /// Finds the failing call of the settlement in the trace of the reverted
+    /// transaction.
+    fn find(trace: &CallFrame) -> Option<Self> {
+        let settle = contracts::GPv2Settlement::raw_contract()
+            .interface
+            .abi
+            .function("settle")
+            .expect("settlement contract has a settle function");
+        let settlement = find_call(trace, &settle.short_signature())?;
+        if settlement.success {
+            // The transaction reverted outside of the settlement, e.g. while
+            // repaying a flash loan.
+            return None;
+        }
+        let interactions = match settle.decode_input(&settlement.input.0[4..]).ok()?.pop()? {
+            Token::FixedArray(phases) => phases,
+            _ => return None,
+        };
+        let interactions = [Phase::Pre, Phase::Intra, Phase::Post]
+            .into_iter()
+            .zip(interactions)
+            .flat_map(|(phase, interactions)| {
+                interactions
+                    .into_array()
+                    .unwrap_or_default()
+                    .into_iter()
+                    .enumerate()
+                    .filter_map(move |(index, interaction)| {
+                        let mut fields = interaction.into_tuple()?.into_iter();
+                        let target = fields.next()?.into_address()?;
+                        let call_data = fields.nth(1)?.into_bytes()?;
+                        Some((phase, index, target, call_data))
+                    })
+            })
+            .collect_vec();
+
+        // Interactions get executed in order, so every call of the settlement
+        // contract is matched against the interactions which were not executed
+        // yet. This way identical interactions can still be told apart.
+        let mut next = 0;
+        let mut location = Location::Settlement;
+        for call in &settlement.calls {
+            let interaction = interactions[next..]
+                .iter()
+                .position(|(_, _, target, call_data)| {
+                    call.to.0 == *target && call.input.0 == *call_data
+                })
+                .map(|offset| next + offset);
+            if let Some(i) = interaction {
+                next = i + 1;
+            }
+            if call.success {
+                continue;
+            }
+
+            // The settlement contract doesn't catch reverts, so the last
+            // failing call is the one that caused the revert.
+            location = match interaction {
+                Some(i) => Location::Interaction {
+                    phase: interactions[i].0,
+                    index: interactions[i].1,
+                },
+                None if call.input.0.starts_with(&transfer_from_accounts_selector()) => {
+                    match call.calls.iter().rfind(|call| !call.success) {
+                        Some(transfer) => Location::TokenTransfer { token: transfer.to },
+                        None => Location::Settlement,
+                    }
+                }
+                None if call.input.0.starts_with(&TRANSFER_SELECTOR) => {
+                    Location::TokenTransfer { token: call.to }
+                }
+                None => Location::Settlement,
+            };
+        }
+        Some(location)
+    }
+}
+
+/// Finds the first call with the given selector, searching depth-first.
+fn find_call<'a>(frame: &'a CallFrame, selector: &[u8; 4]) -> Option<&'a CallFrame> {
+    if frame.input.0.starts_with(selector) {
+        return Some(frame);
+    }
+    frame
+        .calls
+        .iter()
+        .find_map(|call| find_call(call, selector))
+}
+
+impl Display for Location {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Interaction { phase, index } => write!(f, "{phase:?} interaction {index}"),
+            Self::TokenTransfer { token } => write!(f, "transfer of token {:?}", token.0),
+            Self::Settlement => f.write_str("settlement contract"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decodes_reasons() {
+        let error = hex_literal::hex!(
+            "08c379a0
+             0000000000000000000000000000000000000000000000000000000000000020
+             0000000000000000000000000000000000000000000000000000000000000010
+             475076323a206e6f7420736f6c76657200000000000000000000000000000000"
+        );
+        assert_eq!(
+            Reason::decode(&error),
+            Reason::Error("GPv2: not solver".to_owned())
+        );
+
+        let panic = hex_literal::hex!(
+            "4e487b71
+             0000000000000000000000000000000000000000000000000000000000000011"
+        );
+        assert_eq!(Reason::decode(&panic), Reason::Panic(0x11.into()));
+
+        let custom = ethabi::short_signature("NotASettlement", &[]);
+        assert_eq!(
+            Reason::decode(&custom),
+            Reason::Custom("HooksTrampoline.NotASettlement()".to_owned())
+        );
+
+        assert_eq!(Reason::decode(&[]), Reason::Unknown(vec![].into()));
+    }
+
+    #[test]
+    fn locates_failing_interaction() {
+        let settle = contracts::GPv2Settlement::raw_contract()
+            .interface
+            .abi
+            .function("settle")
+            .unwrap();
+        let interaction = |target: u8, call_data: &[u8]| {
+            Token::Tuple(vec![
+                Token::Address(eth::H160::repeat_byte(target)),
+                Token::Uint(0.into()),
+                Token::Bytes(call_data.to_vec()),
+            ])
+        };
+        let input = settle
+            .encode_input(&[
+                Token::Array(vec![]),
+                Token::Array(vec![]),
+                Token::Array(vec![]),
+                Token::FixedArray(vec![
+                    Token::Array(vec![interaction(1, &[1])]),
+                    Token::Array(vec![interaction(2, &[2]), interaction(2, &[2])]),
+                    Token::Array(vec![]),
+                ]),
+            ])
+            .unwrap();
+        let call = |target: u8, input: &[u8], success: bool| CallFrame {
+            to: eth::H160::repeat_byte(target).into(),
+            input: input.to_vec().into(),
+            success,
+            ..Default::default()
+        };
+        let trace = CallFrame {
+            input: input.into(),
+            calls: vec![
+                call(1, &[1], true),
+                call(2, &[2], true),
+                call(2, &[2], false),
+            ],
+            ..Default::default()
+        };
+
+        assert_eq!(
+            Location::find(&trace),
+            Some(Location::Interaction {
+                phase: Phase::Intra,
+                index: 1
+            })
+        );
+    }
+}
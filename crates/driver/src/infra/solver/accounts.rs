@@ -0,0 +1,186 @@
+//! The accounts a solver submits settlements from.
+
+use {
+    crate::{
+        domain::eth,
+        infra::blockchain::{self, Ethereum},
+    },
+    std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    tokio::sync::{Mutex, OwnedMutexGuard},
+};
+
+/// A pool of accounts which can be used to submit settlements. Every account
+/// has at most one settlement in flight at a time, so a stuck transaction only
+/// holds up the account which sent it while the other accounts keep settling
+/// in parallel.
+///
+/// A settlement gets simulated from the account it is assigned to when it is
+/// encoded and has to be submitted from that same account later on.
+#[derive(Debug, Clone)]
+pub struct Accounts {
+    slots: Arc<Vec<(eth::Address, Arc<Mutex<Slot>>)>>,
+    next: Arc<AtomicUsize>,
+}
+
+#[derive(Debug)]
+struct Slot {
+    account: ethcontract::Account,
+    /// The nonce following the last transaction of this account which is
+    /// known to be mined. Protects against nodes lagging behind and reporting
+    /// an outdated nonce right after a settlement got included.
+    next_nonce: Option<eth::U256>,
+}
+
+impl Accounts {
+    pub fn new(accounts: Vec<ethcontract::Account>) -> Self {
+        assert!(!accounts.is_empty(), "solver needs at least one account");
+        Self {
+            slots: Arc::new(
+                accounts
+                    .into_iter()
+                    .map(|account| {
+                        let address = account.address().into();
+                        let slot = Slot {
+                            account,
+                            next_nonce: None,
+                        };
+                        (address, Arc::new(Mutex::new(slot)))
+                    })
+                    .collect(),
+            ),
+            next: Default::default(),
+        }
+    }
+
+    /// Picks the account a new settlement should be submitted from. Accounts
+    /// get assigned round robin with idle accounts being preferred over
+    /// accounts which currently have a settlement in flight.
+    pub fn assign(&self) -> eth::Address {
+        let len = self.slots.len();
+        let start = self.next.fetch_add(1, Ordering::Relaxed);
+        let index = (0..len)
+            .map(|i| (start + i) % len)
+            .find(|i| self.slots[*i].1.try_lock().is_ok())
+            .unwrap_or(start % len);
+        self.slots[index].0
+    }
+
+    /// Reuses the state of the accounts which are also part of the previous
+    /// pool, so their nonces are tracked and they don't get leased twice.
+    pub fn take_over(self, previous: &Accounts) -> Self {
+        let slots = self
+            .slots
+            .iter()
+            .map(|(address, slot)| {
+                let slot = previous
+                    .slots
+                    .iter()
+                    .find(|(previous, _)| previous == address)
+                    .map_or(slot, |(_, previous)| previous);
+                (*address, Arc::clone(slot))
+            })
+            .collect();
+        Self {
+            slots: Arc::new(slots),
+            next: self.next,
+        }
+    }
+
+    /// Waits until the account is free and reserves it until the returned
+    /// [`Lease`] gets dropped. Returns [`None`] if the account is not part of
+    /// the pool.
+    pub async fn acquire(&self, address: eth::Address) -> Option<Lease> {
+        let (_, slot) = self.slots.iter().find(|(a, _)| *a == address)?;
+        Some(Lease(Arc::clone(slot).lock_owned().await))
+    }
+}
+
+/// Exclusive access to one of the accounts of the pool. The account gets
+/// returned to the pool when the lease is dropped.
+#[derive(Debug)]
+pub struct Lease(OwnedMutexGuard<Slot>);
+
+impl Lease {
+    pub fn account(&self) -> &ethcontract::Account {
+        &self.0.account
+    }
+
+    pub fn address(&self) -> eth::Address {
+        self.account().address().into()
+    }
+
+    /// The nonce the next transaction of this account should use.
+    pub async fn nonce(&self, eth: &Ethereum) -> Result<eth::U256, blockchain::Error> {
+        let pending = eth.pending_nonce(self.address()).await?;
+        Ok(self.0.next_nonce.map_or(pending, |next| next.max(pending)))
+    }
+
+    /// Marks the nonce as used by a transaction which got mined.
+    pub fn mined(&mut self, nonce: eth::U256) {
+        self.0.next_nonce = Some(nonce + 1);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, std::time::Duration};
+
+    fn account(byte: u8) -> ethcontract::Account {
+        ethcontract::Account::Local(eth::H160::repeat_byte(byte), None)
+    }
+
+    #[tokio::test]
+    async fn accounts_are_leased_exclusively() {
+        let accounts = Accounts::new(vec![account(1), account(2)]);
+
+        let first = accounts.assign();
+        let second = accounts.assign();
+        assert_ne!(first, second);
+
+        let lease = accounts.acquire(first).await.unwrap();
+        assert_eq!(lease.address(), first);
+
+        // The account is busy so the next settlement assigned to it has to wait.
+        let blocked =
+            tokio::time::timeout(Duration::from_millis(10), accounts.acquire(first)).await;
+        assert!(blocked.is_err());
+
+        // Idle accounts get assigned first.
+        assert_eq!(accounts.assign(), second);
+        assert_eq!(accounts.assign(), second);
+
+        drop(lease);
+        assert_eq!(accounts.acquire(first).await.unwrap().address(), first);
+        assert!(accounts
+            .acquire(account(3).address().into())
+            .await
+            .is_none());
+    }
+
+    #[tokio::test]
+    async fn reloaded_accounts_keep_their_leases() {
+        let previous = Accounts::new(vec![account(1), account(2)]);
+        let lease = previous.acquire(account(1).address().into()).await.unwrap();
+
+        let accounts = Accounts::new(vec![account(1), account(3)]).take_over(&previous);
+        let blocked = tokio::time::timeout(
+            Duration::from_millis(10),
+            accounts.acquire(account(1).address().into()),
+        )
+        .await;
+        assert!(blocked.is_err());
+        assert!(accounts
+            .acquire(account(3).address().into())
+            .await
+            .is_some());
+
+        drop(lease);
+        assert!(accounts
+            .acquire(account(1).address().into())
+            .await
+            .is_some());
+    }
+}
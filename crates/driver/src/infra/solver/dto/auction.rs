@@ -33,6 +33,7 @@ impl Auction {
         weth: eth::WethAddress,
         fee_handler: FeeHandler,
         solver_native_token: ManageNativeToken,
+        extended_balancer_pool_kinds: bool,
     ) -> Self {
         let mut tokens: HashMap<eth::H160, _> = auction
             .tokens()
@@ -60,6 +61,12 @@ impl Auction {
                 liquidity::Kind::UniswapV3(pool) => vec![pool.tokens.get().0, pool.tokens.get().1],
                 liquidity::Kind::BalancerV2Stable(pool) => pool.reserves.tokens().collect(),
                 liquidity::Kind::BalancerV2Weighted(pool) => pool.reserves.tokens().collect(),
+                liquidity::Kind::BalancerV2ComposableStable(pool) => {
+                    pool.reserves.tokens().collect()
+                }
+                liquidity::Kind::BalancerV2LiquidityBootstrapping(pool) => {
+                    pool.reserves.tokens().collect()
+                }
                 liquidity::Kind::Swapr(pool) => {
                     pool.base.reserves.iter().map(|r| r.token).collect()
                 }
@@ -212,60 +219,27 @@ impl Auction {
                             fee: rational_to_big_decimal(&pool.fee.0),
                         })
                     }
-                    liquidity::Kind::BalancerV2Stable(pool) => Liquidity::Stable(StablePool {
-                        id: liquidity.id.into(),
-                        address: pool.id.address().into(),
-                        balancer_pool_id: pool.id.into(),
-                        gas_estimate: liquidity.gas.into(),
-                        tokens: pool
-                            .reserves
-                            .iter()
-                            .map(|r| {
-                                (
-                                    r.asset.token.into(),
-                                    StableReserve {
-                                        balance: r.asset.amount.into(),
-                                        scaling_factor: scaling_factor_to_decimal(r.scale),
-                                    },
-                                )
-                            })
-                            .collect(),
-                        amplification_parameter: rational_to_big_decimal(&num::BigRational::new(
-                            pool.amplification_parameter.factor().to_big_int(),
-                            pool.amplification_parameter.precision().to_big_int(),
-                        )),
-                        fee: fee_to_decimal(pool.fee),
-                    }),
+                    liquidity::Kind::BalancerV2Stable(pool) => {
+                        Liquidity::Stable(StablePool::new(liquidity, pool))
+                    }
                     liquidity::Kind::BalancerV2Weighted(pool) => {
-                        Liquidity::WeightedProduct(WeightedProductPool {
-                            id: liquidity.id.into(),
-                            address: pool.id.address().into(),
-                            balancer_pool_id: pool.id.into(),
-                            gas_estimate: liquidity.gas.into(),
-                            tokens: pool
-                                .reserves
-                                .iter()
-                                .map(|r| {
-                                    (
-                                        r.asset.token.into(),
-                                        WeightedProductReserve {
-                                            balance: r.asset.amount.into(),
-                                            scaling_factor: scaling_factor_to_decimal(r.scale),
-                                            weight: weight_to_decimal(r.weight),
-                                        },
-                                    )
-                                })
-                                .collect(),
-                            fee: fee_to_decimal(pool.fee),
-                            version: match pool.version {
-                                liquidity::balancer::v2::weighted::Version::V0 => {
-                                    WeightedProductVersion::V0
-                                }
-                                liquidity::balancer::v2::weighted::Version::V3Plus => {
-                                    WeightedProductVersion::V3Plus
-                                }
-                            },
-                        })
+                        Liquidity::WeightedProduct(WeightedProductPool::new(liquidity, pool))
+                    }
+                    // Older solvers don't know about these kinds, so unless they opted
+                    // in the pools are sent as the pools they share their math with.
+                    liquidity::Kind::BalancerV2ComposableStable(pool) => {
+                        let pool = StablePool::new(liquidity, pool);
+                        match extended_balancer_pool_kinds {
+                            true => Liquidity::ComposableStable(pool),
+                            false => Liquidity::Stable(pool),
+                        }
+                    }
+                    liquidity::Kind::BalancerV2LiquidityBootstrapping(pool) => {
+                        let pool = WeightedProductPool::new(liquidity, pool);
+                        match extended_balancer_pool_kinds {
+                            true => Liquidity::LiquidityBootstrapping(pool),
+                            false => Liquidity::WeightedProduct(pool),
+                        }
                     }
                     liquidity::Kind::Swapr(pool) => {
                         Liquidity::ConstantProduct(ConstantProductPool {
@@ -468,6 +442,8 @@ enum Liquidity {
     ConstantProduct(ConstantProductPool),
     WeightedProduct(WeightedProductPool),
     Stable(StablePool),
+    ComposableStable(StablePool),
+    LiquidityBootstrapping(WeightedProductPool),
     ConcentratedLiquidity(ConcentratedLiquidityPool),
     LimitOrder(ForeignLimitOrder),
 }
@@ -510,6 +486,41 @@ struct WeightedProductPool {
     version: WeightedProductVersion,
 }
 
+impl WeightedProductPool {
+    fn new(
+        liquidity: &liquidity::Liquidity,
+        pool: &liquidity::balancer::v2::weighted::Pool,
+    ) -> Self {
+        Self {
+            id: liquidity.id.into(),
+            address: pool.id.address().into(),
+            balancer_pool_id: pool.id.into(),
+            gas_estimate: liquidity.gas.into(),
+            tokens: pool
+                .reserves
+                .iter()
+                .map(|r| {
+                    (
+                        r.asset.token.into(),
+                        WeightedProductReserve {
+                            balance: r.asset.amount.into(),
+                            scaling_factor: scaling_factor_to_decimal(r.scale),
+                            weight: weight_to_decimal(r.weight),
+                        },
+                    )
+                })
+                .collect(),
+            fee: fee_to_decimal(pool.fee),
+            version: match pool.version {
+                liquidity::balancer::v2::weighted::Version::V0 => WeightedProductVersion::V0,
+                liquidity::balancer::v2::weighted::Version::V3Plus => {
+                    WeightedProductVersion::V3Plus
+                }
+            },
+        }
+    }
+}
+
 #[serde_as]
 #[derive(Debug, Serialize)]
 #[serde(rename_all = "camelCase")]
@@ -546,6 +557,35 @@ struct StablePool {
     fee: bigdecimal::BigDecimal,
 }
 
+impl StablePool {
+    fn new(liquidity: &liquidity::Liquidity, pool: &liquidity::balancer::v2::stable::Pool) -> Self {
+        Self {
+            id: liquidity.id.into(),
+            address: pool.id.address().into(),
+            balancer_pool_id: pool.id.into(),
+            gas_estimate: liquidity.gas.into(),
+            tokens: pool
+                .reserves
+                .iter()
+                .map(|r| {
+                    (
+                        r.asset.token.into(),
+                        StableReserve {
+                            balance: r.asset.amount.into(),
+                            scaling_factor: scaling_factor_to_decimal(r.scale),
+                        },
+                    )
+                })
+                .collect(),
+            amplification_parameter: rational_to_big_decimal(&num::BigRational::new(
+                pool.amplification_parameter.factor().to_big_int(),
+                pool.amplification_parameter.precision().to_big_int(),
+            )),
+            fee: fee_to_decimal(pool.fee),
+        }
+    }
+}
+
 #[serde_as]
 #[derive(Debug, Serialize)]
 #[serde(rename_all = "camelCase")]
@@ -4,7 +4,7 @@ use {
             competition::{auction, solution},
             eth::{self},
         },
-        infra::notify,
+        infra::{notify, simulator::revert},
         util::serialize,
     },
     serde::Serialize,
@@ -25,7 +25,7 @@ impl Notification {
             kind: match kind {
                 notify::Kind::Timeout => Kind::Timeout,
                 notify::Kind::EmptySolution => Kind::EmptySolution,
-                notify::Kind::SimulationFailed(block, tx, succeeded_once) => {
+                notify::Kind::SimulationFailed(block, tx, succeeded_once, revert) => {
                     Kind::SimulationFailed {
                         block: block.0,
                         tx: Tx {
@@ -36,6 +36,7 @@ impl Notification {
                             access_list: tx.access_list.into(),
                         },
                         succeeded_once,
+                        revert: Revert::from_domain(revert),
                     }
                 }
                 notify::Kind::ScoringFailed(scoring) => scoring.into(),
@@ -117,6 +118,7 @@ pub enum Kind {
         block: BlockNo,
         tx: Tx,
         succeeded_once: bool,
+        revert: Revert,
     },
     InvalidClearingPrices,
     #[serde(rename_all = "camelCase")]
@@ -160,3 +162,71 @@ pub struct Tx {
     pub value: eth::U256,
     pub access_list: AccessList,
 }
+
+#[serde_as]
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Revert {
+    /// Human readable revert reason, e.g. the message of `Error(string)` or
+    /// the decoded custom error of a known contract.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    reason: Option<String>,
+    /// The raw revert data.
+    #[serde_as(as = "Option<serialize::Hex>")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    data: Option<Vec<u8>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    location: Option<RevertLocation>,
+}
+
+impl Revert {
+    fn from_domain(revert: revert::Revert) -> Self {
+        let data = match &revert.reason {
+            Some(revert::Reason::Unknown(data)) => Some(data.0.clone()),
+            _ => None,
+        };
+        Self {
+            reason: revert.reason.as_ref().and_then(|reason| match reason {
+                revert::Reason::Error(message) => Some(message.clone()),
+                revert::Reason::Unknown(_) => None,
+                other => Some(other.to_string()),
+            }),
+            data,
+            location: revert.location.map(|location| match location {
+                revert::Location::Interaction { phase, index } => RevertLocation::Interaction {
+                    phase: match phase {
+                        revert::Phase::Pre => InteractionPhase::Pre,
+                        revert::Phase::Intra => InteractionPhase::Intra,
+                        revert::Phase::Post => InteractionPhase::Post,
+                    },
+                    index,
+                },
+                revert::Location::TokenTransfer { token } => {
+                    RevertLocation::TokenTransfer { token: token.0 }
+                }
+                revert::Location::Settlement => RevertLocation::Settlement,
+            }),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase", tag = "kind")]
+pub enum RevertLocation {
+    Interaction {
+        phase: InteractionPhase,
+        index: usize,
+    },
+    TokenTransfer {
+        token: eth::H160,
+    },
+    Settlement,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum InteractionPhase {
+    Pre,
+    Intra,
+    Post,
+}
@@ -200,6 +200,15 @@ impl Solutions {
                             call_data: Bytes(interaction.call_data),
                         })
                         .collect(),
+                    solution
+                        .flashloans
+                        .into_iter()
+                        .map(|flashloan| competition::solution::Flashloan {
+                            lender: flashloan.lender.into(),
+                            token: flashloan.token.into(),
+                            amount: flashloan.amount.into(),
+                        })
+                        .collect(),
                     solver.clone(),
                     weth,
                     solution.gas.map(|gas| eth::Gas(gas.into())),
@@ -242,9 +251,21 @@ pub struct Solution {
     interactions: Vec<Interaction>,
     #[serde(default)]
     post_interactions: Vec<InteractionData>,
+    #[serde(default)]
+    flashloans: Vec<Flashloan>,
     gas: Option<u64>,
 }
 
+#[serde_as]
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Flashloan {
+    lender: eth::H160,
+    token: eth::H160,
+    #[serde_as(as = "serialize::U256")]
+    amount: eth::U256,
+}
+
 #[derive(Debug, Deserialize)]
 #[serde(tag = "kind", rename_all = "camelCase")]
 enum Trade {
@@ -28,8 +28,11 @@ use {
     tracing::Instrument,
 };
 
+mod accounts;
 pub mod dto;
 
+pub use accounts::{Accounts, Lease};
+
 // TODO At some point I should be checking that the names are unique, I don't
 // think I'm doing that.
 /// The solver name. The user can configure this to be anything that they like.
@@ -93,6 +96,7 @@ pub struct Solver {
     config: Config,
     eth: Ethereum,
     persistence: Persistence,
+    accounts: Accounts,
 }
 
 #[derive(Debug, Clone)]
@@ -106,6 +110,9 @@ pub struct Config {
     pub liquidity: Liquidity,
     /// The private key of this solver, used for settlement submission.
     pub account: ethcontract::Account,
+    /// Additional accounts settlements can be submitted from so multiple
+    /// settlements can be pending at the same time.
+    pub submission_accounts: Vec<ethcontract::Account>,
     /// How much time to spend for each step of the solving and competition.
     pub timeouts: Timeouts,
     /// HTTP headers that should be added to every request.
@@ -127,6 +134,9 @@ pub struct Config {
     pub bad_token_detection: BadTokenDetection,
     /// Max size of the pending settlements queue.
     pub settle_queue_size: usize,
+    /// Whether Balancer V2 composable stable and liquidity bootstrapping pools
+    /// are sent with their own liquidity kinds.
+    pub extended_balancer_pool_kinds: bool,
 }
 
 impl Solver {
@@ -144,6 +154,11 @@ impl Solver {
         }
 
         let persistence = Persistence::build(&config).await;
+        let accounts = Accounts::new(
+            std::iter::once(config.account.clone())
+                .chain(allowed_submission_accounts(&config, &eth).await?)
+                .collect(),
+        );
 
         Ok(Self {
             client: reqwest::ClientBuilder::new()
@@ -152,6 +167,7 @@ impl Solver {
             config,
             eth,
             persistence,
+            accounts,
         })
     }
 
@@ -187,6 +203,18 @@ impl Solver {
         self.config.account.clone()
     }
 
+    /// The pool of accounts settlements of this solver get submitted from.
+    pub fn accounts(&self) -> &Accounts {
+        &self.accounts
+    }
+
+    /// Continues using the state of the accounts which are also used by the
+    /// solver this one replaces, so settlements in flight keep blocking them.
+    pub fn take_over_accounts(mut self, previous: &Solver) -> Self {
+        self.accounts = self.accounts.take_over(&previous.accounts);
+        self
+    }
+
     /// Timeout configuration for this solver.
     pub fn timeouts(&self) -> Timeouts {
         self.config.timeouts
@@ -228,6 +256,7 @@ impl Solver {
             weth,
             self.config.fee_handler,
             self.config.solver_native_token,
+            self.config.extended_balancer_pool_kinds,
         );
         // Only auctions with IDs are real auctions (/quote requests don't have an ID,
         // and it makes no sense to store them)
@@ -281,6 +310,29 @@ impl Solver {
     }
 }
 
+/// Returns the configured submission accounts which are allowed to settle.
+/// Settlements sent from any other account would revert, so such accounts
+/// are left out of the pool.
+async fn allowed_submission_accounts(
+    config: &Config,
+    eth: &Ethereum,
+) -> Result<Vec<ethcontract::Account>> {
+    let mut allowed = Vec::new();
+    for account in &config.submission_accounts {
+        let address = account.address().into();
+        if eth.is_solver(address).await? {
+            allowed.push(account.clone());
+        } else {
+            tracing::warn!(
+                solver = %config.name,
+                ?address,
+                "ignoring submission account which is not allow-listed"
+            );
+        }
+    }
+    Ok(allowed)
+}
+
 /// Controls whether or not the driver is allowed to merge multiple solutions
 /// of the same solver to produce an overall better solution.
 #[derive(Debug, Clone, Copy)]
@@ -1,8 +1,9 @@
 use {
     crate::{
-        domain::{competition::bad_tokens, Mempools},
+        domain::{competition::bad_tokens, mempools::NoMempools, Mempools},
         infra::{
             self,
+            api,
             blockchain::{self, Ethereum},
             cli,
             config,
@@ -12,10 +13,17 @@ use {
             Api,
         },
     },
+    anyhow::Context,
     clap::Parser,
-    futures::future::join_all,
-    std::{net::SocketAddr, sync::Arc, time::Duration},
-    tokio::sync::oneshot,
+    ethcontract::dyns::DynWeb3,
+    futures::future::{join_all, try_join_all},
+    std::{
+        net::SocketAddr,
+        path::{Path, PathBuf},
+        sync::Arc,
+        time::Duration,
+    },
+    tokio::sync::{mpsc, oneshot},
 };
 
 /// The driver entry-point. This function exists in order to be able to run the
@@ -50,29 +58,39 @@ async fn run_with(args: cli::Args, addr_sender: Option<oneshot::Sender<SocketAdd
     tracing::info!("running driver with {config:#?}");
 
     let (shutdown_sender, shutdown_receiver) = tokio::sync::oneshot::channel();
-    let eth = ethereum(&config, ethrpc).await;
+    let (reload_sender, reload_receiver) = tokio::sync::mpsc::unbounded_channel();
+    let eth = ethereum(&config, ethrpc.clone()).await;
+    tokio::spawn(reload_on_signal(
+        args.config.clone(),
+        ethrpc,
+        eth.clone(),
+        reload_sender,
+    ));
+    let persisted_bad_tokens = match config.bad_token_persistence.clone() {
+        Some(config) => Some(
+            bad_tokens::persisted::List::load(config)
+                .await
+                .expect("load persisted bad tokens"),
+        ),
+        None => None,
+    };
     let serve = Api {
         solvers: solvers(&config, &eth).await,
         liquidity: liquidity(&config, &eth).await,
         simulator: simulator(&config, &eth),
-        mempools: Mempools::new(
-            config
-                .mempools
-                .iter()
-                .map(|mempool| {
-                    crate::infra::mempool::Mempool::new(mempool.to_owned(), web3.clone())
-                })
-                .collect(),
-            eth.clone(),
-        )
-        .unwrap(),
+        mempools: mempools(&config.mempools, &web3, &eth).unwrap(),
         bad_token_detector: bad_tokens::simulation::Detector::new(
             config.simulation_bad_token_max_age,
             &eth,
+            persisted_bad_tokens.clone(),
         ),
+        persisted_bad_tokens,
+        quote_cache_significant_digits: config.quote_cache_significant_digits,
         eth,
         addr: args.addr,
+        admin_addr: args.admin_addr,
         addr_sender,
+        reloads: reload_receiver,
     }
     .serve(
         async {
@@ -116,6 +134,12 @@ fn simulator(config: &infra::Config, eth: &Ethereum) -> Simulator {
             },
             eth.to_owned(),
         ),
+        Some(infra::simulator::Config::Fork(fork)) => Simulator::fork(
+            simulator::fork::Config {
+                network_block_interval: fork.network_block_interval,
+            },
+            eth.to_owned(),
+        ),
         None => Simulator::ethereum(eth.to_owned()),
     };
     if config.disable_access_list_simulation {
@@ -159,6 +183,78 @@ async fn solvers(config: &config::Config, eth: &Ethereum) -> Vec<Solver> {
     .await
 }
 
+fn mempools(
+    config: &[infra::mempool::Config],
+    web3: &DynWeb3,
+    eth: &Ethereum,
+) -> Result<Mempools, NoMempools> {
+    Mempools::new(
+        config
+            .iter()
+            .map(|mempool| infra::mempool::Mempool::new(mempool.to_owned(), web3.clone()))
+            .collect(),
+        eth.clone(),
+    )
+}
+
+/// Reloads the solvers and mempools from the config file whenever the driver
+/// receives a SIGHUP. Invalid configs get rejected and the driver keeps
+/// running with the previous config.
+#[cfg(unix)]
+async fn reload_on_signal(
+    path: PathBuf,
+    ethrpc: blockchain::Rpc,
+    eth: Ethereum,
+    reloads: mpsc::UnboundedSender<api::Reload>,
+) {
+    let mut sighup =
+        tokio::signal::unix::signal(tokio::signal::unix::SignalKind::hangup()).unwrap();
+    while sighup.recv().await.is_some() {
+        let result = reload(&path, &ethrpc, &eth).await.and_then(|reload| {
+            reloads
+                .send(reload)
+                .map_err(|_| anyhow::anyhow!("API is no longer running"))
+        });
+        infra::observe::config_reloaded(&path, &result);
+    }
+}
+
+#[cfg(windows)]
+async fn reload_on_signal(
+    _: PathBuf,
+    _: blockchain::Rpc,
+    _: Ethereum,
+    _: mpsc::UnboundedSender<api::Reload>,
+) {
+    // No support for signal handling on Windows.
+}
+
+async fn reload(
+    path: &Path,
+    ethrpc: &blockchain::Rpc,
+    eth: &Ethereum,
+) -> anyhow::Result<api::Reload> {
+    let config = config::file::reload(ethrpc.chain(), path).await?;
+    let gas =
+        blockchain::GasPriceEstimator::new(ethrpc.web3(), &config.gas_estimator, &config.mempools)
+            .await
+            .context("initialize gas price estimator")?;
+    let eth = eth.with_gas(Arc::new(gas));
+    let solvers = try_join_all(
+        config
+            .solvers
+            .into_iter()
+            .map(|config| Solver::new(config, eth.clone())),
+    )
+    .await?;
+    let mempools = mempools(&config.mempools, ethrpc.web3(), &eth)?;
+    Ok(api::Reload {
+        solvers,
+        mempools,
+        eth,
+    })
+}
+
 async fn liquidity(config: &config::Config, eth: &Ethereum) -> liquidity::Fetcher {
     liquidity::Fetcher::new(eth, &config.liquidity)
         .await
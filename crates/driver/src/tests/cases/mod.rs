@@ -20,6 +20,7 @@ pub mod order_prioritization;
 pub mod parallel_auctions;
 pub mod protocol_fees;
 pub mod quote;
+pub mod reload;
 pub mod settle;
 pub mod solver_balance;
 
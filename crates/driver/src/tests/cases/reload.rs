@@ -0,0 +1,35 @@
+//! Tests that reloading the driver config doesn't interfere with auctions
+//! which are in progress.
+
+use crate::tests::setup::{ab_order, ab_pool, ab_solution, setup, Gate};
+
+/// Checks that a solution which the solver returned while the driver config
+/// got reloaded can still be settled.
+#[tokio::test]
+#[ignore]
+async fn settles_solution_computed_during_reload() {
+    let gate = Gate::default();
+    let test = setup()
+        .name("solve during reload")
+        .pool(ab_pool())
+        .order(ab_order())
+        .solution(ab_solution())
+        .solver_gate(gate.clone())
+        .done()
+        .await;
+
+    let reload = async {
+        gate.requested().await;
+        test.reload_driver("reloaded").await;
+        gate.release();
+    };
+    let (solved, ()) = tokio::join!(test.solve(), reload);
+
+    let id = solved.ok().id();
+    test.settle(id)
+        .await
+        .ok()
+        .await
+        .ab_order_executed(&test)
+        .await;
+}
@@ -4,7 +4,7 @@ use {
         tests::{
             self,
             cases::{EtherExt, DEFAULT_SOLVER_FEE},
-            setup::{ab_order, ab_pool, ab_solution},
+            setup::{ab_order, ab_pool, ab_solution, test_solver},
         },
     },
     futures::future::join_all,
@@ -58,6 +58,48 @@ async fn solution_not_available() {
     test.settle(99).await.err().kind("SolutionNotAvailable");
 }
 
+/// Checks that settlements get submitted from the account which was reported
+/// as the submission address of the solution.
+#[tokio::test]
+#[ignore]
+async fn submission_accounts() {
+    let submission_account = ethcontract::PrivateKey::from_slice([0x42; 32]).unwrap();
+    let submission_address = submission_account.public_address();
+    let test = tests::setup()
+        .name("submission accounts")
+        .allow_multiple_solve_requests()
+        .solvers(vec![test_solver().submission_account(submission_account)])
+        .pool(ab_pool())
+        .order(ab_order())
+        .solution(ab_solution())
+        .done()
+        .await;
+
+    // Settlements get assigned to the accounts of the pool in turn.
+    let first = test.solve().await.ok().submission_address();
+    let solved = test.solve().await.ok();
+    assert_eq!(first, test_solver().address());
+    assert_eq!(solved.submission_address(), submission_address);
+
+    test.settle(solved.id())
+        .await
+        .ok()
+        .await
+        .ab_order_executed(&test)
+        .await;
+    let block = test
+        .web3()
+        .eth()
+        .block_with_txs(web3::types::BlockId::Number(
+            web3::types::BlockNumber::Latest,
+        ))
+        .await
+        .unwrap()
+        .unwrap();
+    let settlement = block.transactions.last().unwrap();
+    assert_eq!(settlement.from, Some(solved.submission_address()));
+}
+
 /// Checks that settlements with revert risk are not submitted via public
 /// mempool.
 #[tokio::test]
@@ -82,6 +124,28 @@ async fn private_rpc_with_high_risk_solution() {
     test.settle(id).await.err().kind("FailedToSubmit");
 }
 
+/// Checks that settlements get submitted as bundles to relays.
+#[tokio::test]
+#[ignore]
+async fn bundle_relay() {
+    let test = tests::setup()
+        .name("bundle relay")
+        .pool(ab_pool())
+        .order(ab_order())
+        .solution(ab_solution())
+        .mempools(vec![tests::setup::Mempool::Bundle])
+        .done()
+        .await;
+
+    let id = test.solve().await.ok().id();
+    test.settle(id)
+        .await
+        .ok()
+        .await
+        .ab_order_executed(&test)
+        .await;
+}
+
 #[tokio::test]
 #[ignore]
 async fn too_much_gas() {
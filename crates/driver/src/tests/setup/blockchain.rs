@@ -324,27 +324,29 @@ impl Blockchain {
 
         let mut trader_accounts = Vec::new();
         for config in config.solvers {
-            wait_for(
-                &web3,
-                authenticator
-                    .add_solver(config.address())
-                    .from(main_trader_account.clone())
-                    .send(),
-            )
-            .await
-            .unwrap();
-            wait_for(
-                &web3,
-                web3.eth()
-                    .send_transaction(web3::types::TransactionRequest {
-                        from: primary_address(&web3).await,
-                        to: Some(config.address()),
-                        value: Some(config.balance),
-                        ..Default::default()
-                    }),
-            )
-            .await
-            .unwrap();
+            for address in std::iter::once(config.address()).chain(config.submission_addresses()) {
+                wait_for(
+                    &web3,
+                    authenticator
+                        .add_solver(address)
+                        .from(main_trader_account.clone())
+                        .send(),
+                )
+                .await
+                .unwrap();
+                wait_for(
+                    &web3,
+                    web3.eth()
+                        .send_transaction(web3::types::TransactionRequest {
+                            from: primary_address(&web3).await,
+                            to: Some(address),
+                            value: Some(config.balance),
+                            ..Default::default()
+                        }),
+                )
+                .await
+                .unwrap();
+            }
 
             if !config.balance.is_zero() {
                 let trader_account = ethcontract::Account::Offline(
@@ -5,6 +5,7 @@ use {
         infra::config::file::OrderPriorityStrategy,
         tests::{hex_address, setup::blockchain::Trade},
     },
+    itertools::Itertools,
     rand::seq::SliceRandom,
     serde_json::json,
     std::{io::Write, net::SocketAddr, path::PathBuf},
@@ -22,6 +23,8 @@ pub struct Config {
 
 pub struct Driver {
     pub addr: SocketAddr,
+    config_file: PathBuf,
+    solvers: Vec<(Solver, SocketAddr)>,
     _delete_on_drop: Option<tempfile::TempPath>,
 }
 
@@ -53,9 +56,27 @@ impl Driver {
         let addr = addr_receiver.await.unwrap();
         Self {
             addr,
+            config_file,
+            solvers: solvers.clone(),
             _delete_on_drop: config_temp_path,
         }
     }
+
+    /// Adds a copy of the first solver under another name to the config file
+    /// and makes the driver reload it.
+    pub fn reload_with_alias(&self, alias: &str) {
+        let (solver, addr) = self.solvers.first().expect("driver serves a solver");
+        let mut file = std::fs::OpenOptions::new()
+            .append(true)
+            .open(&self.config_file)
+            .unwrap();
+        write_solver(&mut file, &solver.clone().name(alias), addr);
+        let status = std::process::Command::new("kill")
+            .args(["-HUP", &std::process::id().to_string()])
+            .status()
+            .unwrap();
+        assert!(status.success(), "failed to send SIGHUP");
+    }
 }
 
 /// Create a request for the driver /solve endpoint.
@@ -242,6 +263,19 @@ async fn create_config_file(
                 )
                 .unwrap();
             }
+            Mempool::Bundle => {
+                let relay = super::relay::Relay::new(&blockchain.web3_url).await;
+                write!(
+                    file,
+                    r#"[[submission.mempool]]
+                    mempool = "bundle"
+                    additional-tip-percentage = 0.0
+                    urls = ["http://{}"]
+                    "#,
+                    relay.addr,
+                )
+                .unwrap();
+            }
         }
     }
 
@@ -286,34 +320,45 @@ async fn create_config_file(
     }
 
     for (solver, addr) in solvers {
-        write!(
-            file,
-            r#"[[solver]]
-               name = "{}"
-               endpoint = "http://{}"
-               absolute-slippage = "{}"
-               relative-slippage = "{}"
-               account = "0x{}"
-               solving-share-of-deadline = {}
-               http-time-buffer = "{}ms"
-               fee-handler = {}
-               merge-solutions = {}
-               "#,
-            solver.name,
-            addr,
-            solver
-                .slippage
-                .absolute
-                .map(|abs| abs.0)
-                .unwrap_or_default(),
-            solver.slippage.relative,
-            hex::encode(solver.private_key.secret_bytes()),
-            solver.timeouts.solving_share_of_deadline.get(),
-            solver.timeouts.http_delay.num_milliseconds(),
-            serde_json::to_string(&solver.fee_handler).unwrap(),
-            solver.merge_solutions,
-        )
-        .unwrap();
+        write_solver(&mut file, solver, addr);
     }
     file.into_temp_path()
 }
+
+/// Write the config section of a solver.
+fn write_solver(file: &mut impl Write, solver: &Solver, addr: &SocketAddr) {
+    write!(
+        file,
+        r#"[[solver]]
+           name = "{}"
+           endpoint = "http://{}"
+           absolute-slippage = "{}"
+           relative-slippage = "{}"
+           account = "0x{}"
+           solving-share-of-deadline = {}
+           http-time-buffer = "{}ms"
+           fee-handler = {}
+           merge-solutions = {}
+           submission-accounts = [{}]
+           "#,
+        solver.name,
+        addr,
+        solver
+            .slippage
+            .absolute
+            .map(|abs| abs.0)
+            .unwrap_or_default(),
+        solver.slippage.relative,
+        hex::encode(solver.private_key.secret_bytes()),
+        solver.timeouts.solving_share_of_deadline.get(),
+        solver.timeouts.http_delay.num_milliseconds(),
+        serde_json::to_string(&solver.fee_handler).unwrap(),
+        solver.merge_solutions,
+        solver
+            .submission_accounts
+            .iter()
+            .map(|key| format!("\"0x{}\"", hex::encode(key.secret_bytes())))
+            .join(", "),
+    )
+    .unwrap();
+}
@@ -51,8 +51,11 @@ use {
 pub mod blockchain;
 mod driver;
 pub mod fee;
+mod relay;
 mod solver;
 
+pub use solver::Gate;
+
 #[derive(Debug, Clone, Copy)]
 pub struct Asset {
     token: &'static str,
@@ -345,6 +348,8 @@ pub struct Solver {
     /// Whether or not solver is allowed to combine multiple solutions into a
     /// new one.
     merge_solutions: bool,
+    /// Additional accounts settlements can be submitted from.
+    submission_accounts: Vec<ethcontract::PrivateKey>,
 }
 
 #[derive(Debug, Clone)]
@@ -372,6 +377,7 @@ pub fn test_solver() -> Solver {
         },
         fee_handler: FeeHandler::default(),
         merge_solutions: false,
+        submission_accounts: Default::default(),
     }
 }
 
@@ -410,6 +416,19 @@ impl Solver {
         self.merge_solutions = true;
         self
     }
+
+    /// Adds an allow-listed and funded account to the pool of accounts the
+    /// solver submits settlements from.
+    pub fn submission_account(mut self, private_key: ethcontract::PrivateKey) -> Self {
+        self.submission_accounts.push(private_key);
+        self
+    }
+
+    pub fn submission_addresses(&self) -> impl Iterator<Item = eth::H160> + '_ {
+        self.submission_accounts
+            .iter()
+            .map(|key| key.public_address())
+    }
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -495,6 +514,8 @@ pub enum Mempool {
         /// Uses ethrpc node if None
         url: Option<String>,
     },
+    /// Submits bundles to a stand-in relay which includes them right away.
+    Bundle,
 }
 
 /// Create a builder for the setup process.
@@ -543,6 +564,8 @@ pub struct Setup {
     /// The maximum number of blocks to wait for a settlement to appear on
     /// chain.
     settle_submission_deadline: u64,
+    /// Holds back the responses of the solvers until released by the test.
+    solver_gate: Option<solver::Gate>,
 }
 
 /// The validity of a solution.
@@ -951,6 +974,7 @@ impl Setup {
                 expected_surplus_capturing_jit_order_owners: surplus_capturing_jit_order_owners
                     .clone(),
                 allow_multiple_solve_requests: self.allow_multiple_solve_requests,
+                gate: self.solver_gate.clone(),
             })
             .await;
 
@@ -1007,6 +1031,12 @@ impl Setup {
         self.allow_multiple_solve_requests = true;
         self
     }
+
+    /// Hold back the responses of the solvers until the gate gets released.
+    pub fn solver_gate(mut self, gate: solver::Gate) -> Self {
+        self.solver_gate = Some(gate);
+        self
+    }
 }
 
 pub struct Test {
@@ -1027,6 +1057,29 @@ pub struct Test {
 }
 
 impl Test {
+    /// Reloads the driver config with the solver additionally being served
+    /// under the given name. Returns once the reloaded config is in use.
+    pub async fn reload_driver(&self, alias: &str) {
+        self.driver.reload_with_alias(alias);
+        let url = format!("http://{}/{alias}/", self.driver.addr);
+        let reloaded = async {
+            while !self
+                .client
+                .get(&url)
+                .send()
+                .await
+                .unwrap()
+                .status()
+                .is_success()
+            {
+                tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+            }
+        };
+        tokio::time::timeout(std::time::Duration::from_secs(10), reloaded)
+            .await
+            .expect("driver did not reload its config");
+    }
+
     /// Call the /solve endpoint.
     pub async fn solve(&self) -> Solve {
         self.solve_with_solver(solver::NAME).await
@@ -1229,6 +1282,17 @@ impl SolveOk<'_> {
         serde_json::from_str::<Body>(&self.body).unwrap().solutions
     }
 
+    /// Extracts the address the first solution will be submitted from.
+    pub fn submission_address(&self) -> eth::H160 {
+        self.solution()
+            .get("submissionAddress")
+            .unwrap()
+            .as_str()
+            .unwrap()
+            .parse()
+            .unwrap()
+    }
+
     /// Extracts the solution id from the response. Since response can contain
     /// multiple solutions, it takes the id from the first solution.
     pub fn id(&self) -> u64 {
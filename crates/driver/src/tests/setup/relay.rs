@@ -0,0 +1,61 @@
+//! A stand-in for a relay (e.g. a block builder) accepting bundles via
+//! `eth_sendBundle`. Every received bundle gets included right away by
+//! forwarding its transactions to the test node.
+
+use {
+    serde_json::{json, Value},
+    std::net::SocketAddr,
+};
+
+pub struct Relay {
+    pub addr: SocketAddr,
+}
+
+impl Relay {
+    pub async fn new(web3_url: &str) -> Self {
+        let node: reqwest::Url = web3_url.parse().unwrap();
+        let client = reqwest::Client::new();
+        let app = axum::Router::new().route(
+            "/",
+            axum::routing::post(
+                move |axum::extract::Json(req): axum::extract::Json<Value>| {
+                    let node = node.clone();
+                    let client = client.clone();
+                    async move {
+                        assert_eq!(req["method"], "eth_sendBundle", "unexpected relay request");
+                        let bundle = &req["params"][0];
+                        assert!(
+                            bundle["blockNumber"].is_string(),
+                            "bundle does not target a block"
+                        );
+                        for tx in bundle["txs"].as_array().unwrap() {
+                            // Resubmitted bundles contain transactions the node already knows
+                            // about which results in errors that can be ignored.
+                            client
+                                .post(node.clone())
+                                .json(&json!({
+                                    "jsonrpc": "2.0",
+                                    "id": 1,
+                                    "method": "eth_sendRawTransaction",
+                                    "params": [tx],
+                                }))
+                                .send()
+                                .await
+                                .unwrap();
+                        }
+                        axum::response::Json(json!({
+                            "jsonrpc": "2.0",
+                            "id": req["id"],
+                            "result": { "bundleHash": "0x" },
+                        }))
+                    }
+                },
+            ),
+        );
+        let server =
+            axum::Server::bind(&"0.0.0.0:0".parse().unwrap()).serve(app.into_make_service());
+        let addr = server.local_addr();
+        tokio::spawn(async move { server.await.unwrap() });
+        Self { addr }
+    }
+}
@@ -21,6 +21,7 @@ use {
         net::SocketAddr,
         sync::{Arc, Mutex},
     },
+    tokio::sync::Notify,
     web3::signing::Key,
 };
 
@@ -43,6 +44,32 @@ pub struct Config<'a> {
     pub private_key: ethcontract::PrivateKey,
     pub expected_surplus_capturing_jit_order_owners: Vec<H160>,
     pub allow_multiple_solve_requests: bool,
+    pub gate: Option<Gate>,
+}
+
+/// Holds back the responses of the solver until the test releases them. This
+/// allows changing the driver while a `/solve` request is in flight.
+#[derive(Debug, Clone, Default)]
+pub struct Gate {
+    requested: Arc<Notify>,
+    released: Arc<Notify>,
+}
+
+impl Gate {
+    /// Waits until the solver received a `/solve` request.
+    pub async fn requested(&self) {
+        self.requested.notified().await
+    }
+
+    /// Lets the solver respond to the pending `/solve` request.
+    pub fn release(&self) {
+        self.released.notify_one()
+    }
+
+    async fn pass(&self) {
+        self.requested.notify_one();
+        self.released.notified().await
+    }
 }
 
 impl Solver {
@@ -434,6 +461,7 @@ impl Solver {
                 settlement: Some(config.blockchain.settlement.address().into()),
                 weth: Some(config.blockchain.weth.address().into()),
                 cow_amms: vec![],
+                flashloan_router: None,
             },
             gas,
             None,
@@ -468,6 +496,9 @@ impl Solver {
                         "surplusCapturingJitOrderOwners": config.expected_surplus_capturing_jit_order_owners,
                     });
                     assert_eq!(req, expected, "unexpected /solve request");
+                    if let Some(gate) = &config.gate {
+                        gate.pass().await;
+                    }
                     let mut state = state.0.lock().unwrap();
                     assert!(
                         !state.called || state.allow_multiple_solve_requests,
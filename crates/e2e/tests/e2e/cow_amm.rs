@@ -338,6 +338,7 @@ async fn cow_amm_jit(web3: Web3) {
         pre_interactions: vec![cow_amm_commitment],
         interactions: vec![],
         post_interactions: vec![],
+        flashloans: vec![],
         gas: None,
     }));
 
@@ -906,6 +907,7 @@ async fn cow_amm_opposite_direction(web3: Web3) {
             pre_interactions: vec![cow_amm_commitment.clone()],
             interactions: vec![],
             post_interactions: vec![],
+            flashloans: vec![],
             gas: None,
         }
     };
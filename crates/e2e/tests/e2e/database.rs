@@ -47,7 +47,7 @@ pub struct Cip20Data {
     pub txs: Vec<AuctionTransaction>,
     pub participants: Vec<database::auction_participants::Participant>,
     pub prices: Vec<database::auction_prices::AuctionPrice>,
-    pub score: database::settlement_scores::Score,
+    pub scores: Vec<database::settlement_scores::Score>,
     pub competition: serde_json::Value,
 }
 
@@ -84,9 +84,12 @@ SELECT * FROM settlements WHERE auction_id = $1";
     let prices = database::auction_prices::fetch(&mut db, auction_id)
         .await
         .unwrap();
-    let score = database::settlement_scores::fetch(&mut db, auction_id)
+    let scores = database::settlement_scores::fetch(&mut db, auction_id)
         .await
-        .unwrap()?;
+        .unwrap();
+    if scores.is_empty() {
+        return None;
+    }
     let competition = database::solver_competition::load_by_id(&mut db, auction_id)
         .await
         .unwrap()?
@@ -97,7 +100,7 @@ SELECT * FROM settlements WHERE auction_id = $1";
         txs,
         participants,
         prices,
-        score,
+        scores,
         competition,
     })
 }
@@ -163,6 +163,7 @@ async fn single_limit_order_test(web3: Web3) {
         pre_interactions: vec![],
         interactions: vec![],
         post_interactions: vec![],
+        flashloans: vec![],
         gas: None,
     }));
 
@@ -44,4 +44,8 @@ pub struct ExecutedProtocolFee {
     #[serde_as(as = "HexOrDecimalU256")]
     pub amount: U256,
     pub token: H160,
+    /// The partner the fee was charged for. Not set for fees charged by the
+    /// protocol itself.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub partner_fee_recipient: Option<H160>,
 }
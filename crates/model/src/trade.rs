@@ -74,7 +74,8 @@ mod tests {
                         "volume": {
                             "factor": 0.9
                         }
-                    }
+                    },
+                    "partnerFeeRecipient": "0x0000000000000000000000000000000000000002"
                 },
                 {
                     "amount": "5",
@@ -112,11 +113,13 @@ mod tests {
                         factor: 1.1,
                         max_volume_factor: 2.2,
                     },
+                    partner_fee_recipient: None,
                 },
                 ExecutedProtocolFee {
                     amount: U256::from(5u64),
                     token: H160::from_low_u64_be(10),
                     policy: FeePolicy::Volume { factor: 0.9 },
+                    partner_fee_recipient: Some(H160::from_low_u64_be(2)),
                 },
                 ExecutedProtocolFee {
                     amount: U256::from(5u64),
@@ -130,6 +133,7 @@ mod tests {
                             fee: U256::from(5u64),
                         },
                     },
+                    partner_fee_recipient: None,
                 },
             ],
         };
@@ -1,8 +1,19 @@
 use {
-    crate::{app_data, database::Postgres, orderbook::Orderbook, quoter::QuoteHandler},
+    crate::{
+        app_data,
+        database::Postgres,
+        order_updates::OrderUpdates,
+        orderbook::Orderbook,
+        quoter::QuoteHandler,
+    },
     anyhow::Result,
+    ethrpc::Web3,
     serde::{de::DeserializeOwned, Serialize},
-    shared::price_estimation::{native::NativePriceEstimating, PriceEstimationError},
+    shared::price_estimation::{
+        historical::HistoricalNativePriceEstimating,
+        native::NativePriceEstimating,
+        PriceEstimationError,
+    },
     std::{convert::Infallible, fmt::Debug, sync::Arc, time::Instant},
     warp::{
         filters::BoxedFilter,
@@ -18,9 +29,11 @@ mod cancel_order;
 mod cancel_orders;
 mod get_app_data;
 mod get_auction;
+mod get_historical_native_price;
 mod get_native_price;
 mod get_order_by_uid;
 mod get_order_status;
+mod get_order_timeline;
 mod get_orders_by_tx;
 mod get_solver_competition;
 mod get_total_surplus;
@@ -29,6 +42,7 @@ mod get_user_orders;
 mod post_order;
 mod post_quote;
 mod put_app_data;
+mod stream_order_updates;
 mod version;
 
 pub fn handle_all_routes(
@@ -37,6 +51,9 @@ pub fn handle_all_routes(
     quotes: Arc<QuoteHandler>,
     app_data: Arc<app_data::Registry>,
     native_price_estimator: Arc<dyn NativePriceEstimating>,
+    historical_native_price_estimator: Arc<dyn HistoricalNativePriceEstimating>,
+    web3: Web3,
+    order_updates: Arc<OrderUpdates>,
 ) -> impl Filter<Extract = (impl Reply,), Error = Rejection> + Clone {
     // Note that we add a string with endpoint's name to all responses.
     // This string will be used later to report metrics.
@@ -55,10 +72,22 @@ pub fn handle_all_routes(
             "v1/get_order_status",
             box_filter(get_order_status::get_status(orderbook.clone())),
         ),
+        (
+            "v1/get_order_timeline",
+            box_filter(get_order_timeline::get_timeline(orderbook.clone())),
+        ),
+        (
+            "v1/stream_order_updates",
+            stream_order_updates::stream_order_updates(order_updates).boxed(),
+        ),
         (
             "v1/get_trades",
             box_filter(get_trades::get_trades(database.clone())),
         ),
+        (
+            "v2/get_trades",
+            box_filter(get_trades::get_trades_page(database.clone())),
+        ),
         (
             "v1/cancel_order",
             box_filter(cancel_order::cancel_order(orderbook.clone())),
@@ -71,6 +100,10 @@ pub fn handle_all_routes(
             "v1/get_user_orders",
             box_filter(get_user_orders::get_user_orders(orderbook.clone())),
         ),
+        (
+            "v2/get_user_orders",
+            box_filter(get_user_orders::get_user_orders_page(orderbook.clone())),
+        ),
         (
             "v1/get_orders_by_tx",
             box_filter(get_orders_by_tx::get_orders_by_tx(orderbook.clone())),
@@ -95,6 +128,13 @@ pub fn handle_all_routes(
             "v1/get_native_price",
             box_filter(get_native_price::get_native_price(native_price_estimator)),
         ),
+        (
+            "v1/get_historical_native_price",
+            box_filter(get_historical_native_price::get_historical_native_price(
+                historical_native_price_estimator,
+                web3,
+            )),
+        ),
         (
             "v1/get_app_data",
             get_app_data::get(database.clone()).boxed(),
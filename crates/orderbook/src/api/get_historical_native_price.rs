@@ -0,0 +1,141 @@
+use {
+    crate::api::{error, ApiReply, IntoWarpReply},
+    anyhow::Result,
+    ethcontract::H160,
+    ethrpc::Web3,
+    serde::{Deserialize, Serialize},
+    shared::price_estimation::historical::{self, HistoricalNativePriceEstimating},
+    std::{convert::Infallible, sync::Arc},
+    tokio::sync::Semaphore,
+    warp::{hyper::StatusCode, reply::with_status, Filter, Rejection},
+};
+
+#[derive(Debug, Deserialize, Eq, PartialEq)]
+#[serde(deny_unknown_fields)]
+struct Query {
+    block: Option<u64>,
+    timestamp: Option<u64>,
+}
+
+#[derive(Serialize)]
+struct PriceResponse {
+    price: f64,
+    block: u64,
+}
+
+fn get_historical_native_price_request(
+) -> impl Filter<Extract = (H160, Query), Error = Rejection> + Clone {
+    warp::path!("v1" / "token" / H160 / "historical_native_price")
+        .and(warp::get())
+        .and(warp::query::<Query>())
+}
+
+/// Finding the block of a timestamp takes several requests to the node, so
+/// only a few of these lookups are served at the same time.
+const MAX_CONCURRENT_TIMESTAMP_LOOKUPS: usize = 4;
+
+pub fn get_historical_native_price(
+    estimator: Arc<dyn HistoricalNativePriceEstimating>,
+    web3: Web3,
+) -> impl Filter<Extract = (ApiReply,), Error = Rejection> + Clone {
+    let timestamp_lookups = Arc::new(Semaphore::new(MAX_CONCURRENT_TIMESTAMP_LOOKUPS));
+    get_historical_native_price_request().and_then(move |token: H160, query: Query| {
+        let estimator = estimator.clone();
+        let web3 = web3.clone();
+        let timestamp_lookups = timestamp_lookups.clone();
+        async move {
+            let block = match query {
+                Query {
+                    block: Some(block),
+                    timestamp: None,
+                } => block,
+                Query {
+                    block: None,
+                    timestamp: Some(timestamp),
+                } => {
+                    let Ok(_permit) = timestamp_lookups.try_acquire() else {
+                        return Result::<_, Infallible>::Ok(with_status(
+                            error("TooManyRequests", "too many concurrent timestamp lookups"),
+                            StatusCode::TOO_MANY_REQUESTS,
+                        ));
+                    };
+                    match historical::block_at_timestamp(&web3, timestamp).await {
+                        Ok(Some(block)) => block,
+                        Ok(None) => {
+                            return Ok(with_status(
+                                error("InvalidTimestamp", "timestamp is not covered by the chain"),
+                                StatusCode::BAD_REQUEST,
+                            ))
+                        }
+                        Err(err) => {
+                            tracing::error!(?err, timestamp, "failed to find block at timestamp");
+                            return Ok(crate::api::internal_error_reply());
+                        }
+                    }
+                }
+                _ => {
+                    return Ok(with_status(
+                        error(
+                            "InvalidQuery",
+                            "must specify exactly one of block and timestamp",
+                        ),
+                        StatusCode::BAD_REQUEST,
+                    ))
+                }
+            };
+
+            let result = estimator.estimate_native_price_at(token, block).await;
+            let reply = match result {
+                Ok(price) => with_status(
+                    warp::reply::json(&PriceResponse { price, block }),
+                    StatusCode::OK,
+                ),
+                Err(err) => err.into_warp_reply(),
+            };
+            Ok(reply)
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, futures::FutureExt, hex_literal::hex, warp::test::request};
+
+    #[test]
+    fn historical_native_price_query() {
+        let path = "/v1/token/0xdac17f958d2ee523a2206206994597c13d831ec7/historical_native_price?\
+                    block=20000000";
+        let request = request().path(path).method("GET");
+        let result = request
+            .filter(&get_historical_native_price_request())
+            .now_or_never()
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            result,
+            (
+                H160(hex!("dac17f958d2ee523a2206206994597c13d831ec7")),
+                Query {
+                    block: Some(20_000_000),
+                    timestamp: None,
+                }
+            )
+        );
+
+        let path = "/v1/token/0xdac17f958d2ee523a2206206994597c13d831ec7/historical_native_price?\
+                    timestamp=1700000000";
+        let request = request().path(path).method("GET");
+        let (_, query) = request
+            .filter(&get_historical_native_price_request())
+            .now_or_never()
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            query,
+            Query {
+                block: None,
+                timestamp: Some(1_700_000_000),
+            }
+        );
+    }
+}
@@ -0,0 +1,35 @@
+use {
+    crate::{api::ApiReply, orderbook::Orderbook},
+    anyhow::Result,
+    model::order::OrderUid,
+    std::{convert::Infallible, sync::Arc},
+    warp::{hyper::StatusCode, Filter, Rejection},
+};
+
+fn get_timeline_request() -> impl Filter<Extract = (OrderUid,), Error = Rejection> + Clone {
+    warp::path!("v1" / "orders" / OrderUid / "timeline").and(warp::get())
+}
+
+pub fn get_timeline(
+    orderbook: Arc<Orderbook>,
+) -> impl Filter<Extract = (ApiReply,), Error = Rejection> + Clone {
+    get_timeline_request().and_then(move |uid| {
+        let orderbook = orderbook.clone();
+        async move {
+            let timeline = orderbook.get_order_timeline(&uid).await;
+            Result::<_, Infallible>::Ok(match timeline {
+                Ok(Some(timeline)) => {
+                    warp::reply::with_status(warp::reply::json(&timeline), StatusCode::OK)
+                }
+                Ok(None) => warp::reply::with_status(
+                    super::error("OrderNotFound", "Order not located in database"),
+                    StatusCode::NOT_FOUND,
+                ),
+                Err(err) => {
+                    tracing::error!(?err, "get_order_timeline");
+                    *Box::new(crate::api::internal_error_reply())
+                }
+            })
+        }
+    })
+}
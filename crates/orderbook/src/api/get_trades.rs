@@ -2,9 +2,10 @@ use {
     crate::{
         api::{error, ApiReply},
         database::{
-            trades::{TradeFilter, TradeRetrieving},
+            trades::{TradeFilter, TradePageFilter, TradeRetrieving},
             Postgres,
         },
+        dto::page::{Page, TradeCursor},
     },
     anyhow::{Context, Result},
     model::order::OrderUid,
@@ -44,6 +45,130 @@ impl Query {
     }
 }
 
+const DEFAULT_LIMIT: u64 = 10;
+const MIN_LIMIT: u64 = 1;
+const MAX_LIMIT: u64 = 1000;
+/// Bounds the number of trades a request without an owner or order filter
+/// has to consider.
+const MAX_BLOCK_RANGE: u64 = 10_000;
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PageQuery {
+    pub order_uid: Option<OrderUid>,
+    pub owner: Option<H160>,
+    pub from_block: Option<u64>,
+    pub to_block: Option<u64>,
+    pub cursor: Option<String>,
+    pub limit: Option<u64>,
+}
+
+#[derive(Debug, Eq, PartialEq)]
+struct PageRequest {
+    filter: TradePageFilter,
+    cursor: Option<TradeCursor>,
+    limit: u64,
+}
+
+impl PageQuery {
+    fn validate(self) -> Result<PageRequest, TradeFilterError> {
+        let limit = self.limit.unwrap_or(DEFAULT_LIMIT);
+        if !(MIN_LIMIT..=MAX_LIMIT).contains(&limit) {
+            return Err(TradeFilterError::InvalidFilter(format!(
+                "The pagination limit is [{MIN_LIMIT},{MAX_LIMIT}]."
+            )));
+        }
+        if let (Some(from_block), Some(to_block)) = (self.from_block, self.to_block) {
+            if from_block > to_block {
+                return Err(TradeFilterError::InvalidFilter(
+                    "fromBlock must not be greater than toBlock.".to_owned(),
+                ));
+            }
+        }
+        let bounded_range = match (self.from_block, self.to_block) {
+            (Some(from_block), Some(to_block)) => to_block - from_block <= MAX_BLOCK_RANGE,
+            _ => false,
+        };
+        if self.owner.is_none() && self.order_uid.is_none() && !bounded_range {
+            return Err(TradeFilterError::InvalidFilter(format!(
+                "Must specify owner, orderUid or a block range of at most {MAX_BLOCK_RANGE} \
+                 blocks."
+            )));
+        }
+        let cursor = self
+            .cursor
+            .as_deref()
+            .map(str::parse)
+            .transpose()
+            .map_err(|err| TradeFilterError::InvalidFilter(format!("Invalid cursor: {err:#}")))?;
+        Ok(PageRequest {
+            filter: TradePageFilter {
+                owner: self.owner,
+                order_uid: self.order_uid,
+                from_block: self.from_block,
+                to_block: self.to_block,
+            },
+            cursor,
+            limit,
+        })
+    }
+}
+
+fn get_trades_page_request(
+) -> impl Filter<Extract = (Result<PageRequest, TradeFilterError>,), Error = Rejection> + Clone {
+    warp::path!("v2" / "trades")
+        .and(warp::get())
+        .and(warp::query::<PageQuery>())
+        .map(|query: PageQuery| query.validate())
+}
+
+pub fn get_trades_page(
+    db: Postgres,
+) -> impl Filter<Extract = (ApiReply,), Error = Rejection> + Clone {
+    get_trades_page_request().and_then(move |request_result| {
+        let database = db.clone();
+        async move {
+            Result::<_, Infallible>::Ok(match request_result {
+                Ok(PageRequest {
+                    filter,
+                    cursor,
+                    limit,
+                }) => {
+                    let result = database
+                        .trades_page(&filter, cursor, limit)
+                        .await
+                        .context("get_trades_page");
+                    match result {
+                        Ok(items) => {
+                            // A full page indicates that there might be more trades.
+                            let next_cursor = (items.len() as u64 == limit)
+                                .then(|| items.last())
+                                .flatten()
+                                .map(|trade| {
+                                    TradeCursor {
+                                        block_number: trade.block_number,
+                                        log_index: trade.log_index,
+                                    }
+                                    .to_string()
+                                });
+                            let page = Page { items, next_cursor };
+                            with_status(warp::reply::json(&page), StatusCode::OK)
+                        }
+                        Err(err) => {
+                            tracing::error!(?err, "get_trades_page");
+                            crate::api::internal_error_reply()
+                        }
+                    }
+                }
+                Err(TradeFilterError::InvalidFilter(msg)) => {
+                    let err = error("InvalidTradeFilter", msg);
+                    with_status(err, StatusCode::BAD_REQUEST)
+                }
+            })
+        }
+    })
+}
+
 fn get_trades_request(
 ) -> impl Filter<Extract = (Result<TradeFilter, TradeFilterError>,), Error = Rejection> + Clone {
     warp::path!("v1" / "trades")
@@ -129,4 +254,75 @@ mod tests {
         let result = trade_filter(request().path(path)).await.unwrap();
         assert!(result.is_err());
     }
+
+    #[tokio::test]
+    async fn get_trades_page_request_ok() {
+        let trade_filter = |request: RequestBuilder| async move {
+            let filter = get_trades_page_request();
+            request.method("GET").filter(&filter).await
+        };
+
+        let result = trade_filter(request().path("/v2/trades?fromBlock=1&toBlock=10001"))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            result,
+            PageRequest {
+                filter: TradePageFilter {
+                    from_block: Some(1),
+                    to_block: Some(10_001),
+                    ..Default::default()
+                },
+                cursor: None,
+                limit: DEFAULT_LIMIT,
+            }
+        );
+
+        let owner = H160::from_slice(&hex!("0000000000000000000000000000000000000001"));
+        let path =
+            format!("/v2/trades?owner=0x{owner:x}&fromBlock=1&toBlock=2&cursor=2_3&limit=50");
+        let result = trade_filter(request().path(path.as_str()))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            result,
+            PageRequest {
+                filter: TradePageFilter {
+                    owner: Some(owner),
+                    order_uid: None,
+                    from_block: Some(1),
+                    to_block: Some(2),
+                },
+                cursor: Some(TradeCursor {
+                    block_number: 2,
+                    log_index: 3,
+                }),
+                limit: 50,
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn get_trades_page_request_err() {
+        let trade_filter = |request: RequestBuilder| async move {
+            let filter = get_trades_page_request();
+            request.method("GET").filter(&filter).await
+        };
+
+        let owner = "0x0000000000000000000000000000000000000001";
+        for path in [
+            format!("/v2/trades?owner={owner}&limit=0"),
+            format!("/v2/trades?owner={owner}&limit=1001"),
+            format!("/v2/trades?owner={owner}&fromBlock=2&toBlock=1"),
+            format!("/v2/trades?owner={owner}&cursor=invalid"),
+            "/v2/trades".to_owned(),
+            "/v2/trades?fromBlock=1".to_owned(),
+            "/v2/trades?fromBlock=1&toBlock=10002".to_owned(),
+        ] {
+            let result = trade_filter(request().path(&path)).await.unwrap();
+            assert!(result.is_err(), "{path}");
+        }
+    }
 }
@@ -1,18 +1,68 @@
 use {
-    crate::{api::ApiReply, orderbook::Orderbook},
+    crate::{
+        api::ApiReply,
+        database::orders::UserOrderFilter,
+        dto::page::OrderCursor,
+        orderbook::Orderbook,
+    },
     anyhow::Result,
+    chrono::{DateTime, Utc},
+    model::order::{OrderClass, OrderStatus},
     primitive_types::H160,
     serde::Deserialize,
+    serde_with::{serde_as, DisplayFromStr},
     std::{convert::Infallible, sync::Arc},
     warp::{hyper::StatusCode, reply::with_status, Filter, Rejection},
 };
 
+const DEFAULT_LIMIT: u64 = 10;
+const MIN_LIMIT: u64 = 1;
+const MAX_LIMIT: u64 = 1000;
+
 #[derive(Clone, Copy, Debug, Deserialize)]
 struct Query {
     offset: Option<u64>,
     limit: Option<u64>,
 }
 
+#[serde_as]
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PageQuery {
+    cursor: Option<String>,
+    limit: Option<u64>,
+    status: Option<OrderStatus>,
+    #[serde_as(as = "Option<DisplayFromStr>")]
+    class: Option<OrderClass>,
+    sell_token: Option<H160>,
+    buy_token: Option<H160>,
+    created_after: Option<DateTime<Utc>>,
+    created_before: Option<DateTime<Utc>>,
+}
+
+impl PageQuery {
+    fn filter(&self) -> UserOrderFilter {
+        UserOrderFilter {
+            class: self.class,
+            status: self.status,
+            sell_token: self.sell_token,
+            buy_token: self.buy_token,
+            created_after: self.created_after,
+            created_before: self.created_before,
+        }
+    }
+}
+
+fn limit_out_of_bounds() -> ApiReply {
+    with_status(
+        super::error(
+            "LIMIT_OUT_OF_BOUNDS",
+            format!("The pagination limit is [{MIN_LIMIT},{MAX_LIMIT}]."),
+        ),
+        StatusCode::BAD_REQUEST,
+    )
+}
+
 fn request() -> impl Filter<Extract = (H160, Query), Error = Rejection> + Clone {
     warp::path!("v1" / "account" / H160 / "orders")
         .and(warp::get())
@@ -26,19 +76,10 @@ pub fn get_user_orders(
         let orderbook = orderbook.clone();
         async move {
             const DEFAULT_OFFSET: u64 = 0;
-            const DEFAULT_LIMIT: u64 = 10;
-            const MIN_LIMIT: u64 = 1;
-            const MAX_LIMIT: u64 = 1000;
             let offset = query.offset.unwrap_or(DEFAULT_OFFSET);
             let limit = query.limit.unwrap_or(DEFAULT_LIMIT);
             if !(MIN_LIMIT..=MAX_LIMIT).contains(&limit) {
-                return Ok(with_status(
-                    super::error(
-                        "LIMIT_OUT_OF_BOUNDS",
-                        format!("The pagination limit is [{MIN_LIMIT},{MAX_LIMIT}]."),
-                    ),
-                    StatusCode::BAD_REQUEST,
-                ));
+                return Ok(limit_out_of_bounds());
             }
             let result = orderbook.get_user_orders(&owner, offset, limit).await;
             Result::<_, Infallible>::Ok(match result {
@@ -52,6 +93,46 @@ pub fn get_user_orders(
     })
 }
 
+fn page_request() -> impl Filter<Extract = (H160, PageQuery), Error = Rejection> + Clone {
+    warp::path!("v2" / "account" / H160 / "orders")
+        .and(warp::get())
+        .and(warp::query::<PageQuery>())
+}
+
+pub fn get_user_orders_page(
+    orderbook: Arc<Orderbook>,
+) -> impl Filter<Extract = (ApiReply,), Error = Rejection> + Clone {
+    page_request().and_then(move |owner: H160, query: PageQuery| {
+        let orderbook = orderbook.clone();
+        async move {
+            let limit = query.limit.unwrap_or(DEFAULT_LIMIT);
+            if !(MIN_LIMIT..=MAX_LIMIT).contains(&limit) {
+                return Ok(limit_out_of_bounds());
+            }
+            let cursor = match query.cursor.as_deref().map(str::parse::<OrderCursor>) {
+                None => None,
+                Some(Ok(cursor)) => Some(cursor),
+                Some(Err(err)) => {
+                    return Ok(with_status(
+                        super::error("INVALID_CURSOR", format!("{err:#}")),
+                        StatusCode::BAD_REQUEST,
+                    ));
+                }
+            };
+            let result = orderbook
+                .get_user_orders_page(&owner, &query.filter(), cursor, limit)
+                .await;
+            Result::<_, Infallible>::Ok(match result {
+                Ok(reply) => with_status(warp::reply::json(&reply), StatusCode::OK),
+                Err(err) => {
+                    tracing::error!(?err, "get_user_orders_page");
+                    crate::api::internal_error_reply()
+                }
+            })
+        }
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use {super::*, shared::addr};
@@ -79,4 +160,41 @@ mod tests {
         assert_eq!(result.1.offset, Some(1));
         assert_eq!(result.1.limit, Some(2));
     }
+
+    #[tokio::test]
+    async fn page_request_() {
+        let path = "/v2/account/0x0000000000000000000000000000000000000001/orders";
+        let (owner, query) = warp::test::request()
+            .path(path)
+            .method("GET")
+            .filter(&page_request())
+            .await
+            .unwrap();
+        assert_eq!(owner, addr!("0000000000000000000000000000000000000001"));
+        assert_eq!(query.filter(), UserOrderFilter::default());
+        assert_eq!(query.cursor, None);
+
+        let path = "/v2/account/0x0000000000000000000000000000000000000001/orders?cursor=1_0x01&\
+                    limit=5&status=fulfilled&class=limit&\
+                    sellToken=0x0000000000000000000000000000000000000002&\
+                    createdAfter=2024-01-01T00:00:00Z";
+        let (_, query) = warp::test::request()
+            .path(path)
+            .method("GET")
+            .filter(&page_request())
+            .await
+            .unwrap();
+        assert_eq!(query.cursor.as_deref(), Some("1_0x01"));
+        assert_eq!(query.limit, Some(5));
+        assert_eq!(
+            query.filter(),
+            UserOrderFilter {
+                class: Some(OrderClass::Limit),
+                status: Some(OrderStatus::Fulfilled),
+                sell_token: Some(addr!("0000000000000000000000000000000000000002")),
+                created_after: Some("2024-01-01T00:00:00Z".parse().unwrap()),
+                ..Default::default()
+            }
+        );
+    }
 }
@@ -174,6 +174,10 @@ impl IntoWarpReply for ValidationErrorWrapper {
                 ),
                 StatusCode::BAD_REQUEST,
             ),
+            ValidationError::InvalidPermit(err) => with_status(
+                error("InvalidPermit", err.to_string()),
+                StatusCode::BAD_REQUEST,
+            ),
             ValidationError::InvalidSignature => with_status(
                 error("InvalidSignature", "invalid signature"),
                 StatusCode::BAD_REQUEST,
@@ -0,0 +1,120 @@
+use {
+    crate::{dto, order_updates::OrderUpdates},
+    futures::Stream,
+    model::order::OrderUid,
+    primitive_types::H160,
+    std::{convert::Infallible, sync::Arc},
+    tokio::sync::broadcast::{self, error::RecvError},
+    warp::{sse, Filter, Rejection, Reply},
+};
+
+/// Which order updates a client subscribed to.
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Subscription {
+    Order(OrderUid),
+    Owner(H160),
+}
+
+impl Subscription {
+    fn matches(&self, update: &dto::order::Update) -> bool {
+        match self {
+            Self::Order(uid) => update.uid == *uid,
+            Self::Owner(owner) => update.owner == *owner,
+        }
+    }
+}
+
+fn request() -> impl Filter<Extract = (Subscription,), Error = Rejection> + Clone {
+    let order =
+        warp::path!("v1" / "orders" / OrderUid / "status" / "stream").map(Subscription::Order);
+    let owner = warp::path!("v1" / "account" / H160 / "orders" / "stream").map(Subscription::Owner);
+    order.or(owner).unify().and(warp::get())
+}
+
+pub fn stream_order_updates(
+    updates: Arc<OrderUpdates>,
+) -> impl Filter<Extract = (Box<dyn Reply>,), Error = Rejection> + Clone {
+    request().map(move |subscription: Subscription| {
+        let events = events(updates.subscribe(), subscription);
+        Box::new(sse::reply(sse::keep_alive().stream(events))) as Box<dyn Reply>
+    })
+}
+
+/// Turns all updates matching the subscription into server-sent events.
+fn events(
+    receiver: broadcast::Receiver<Arc<dto::order::Update>>,
+    subscription: Subscription,
+) -> impl Stream<Item = Result<sse::Event, Infallible>> {
+    futures::stream::unfold(receiver, move |mut receiver| async move {
+        loop {
+            let update = match receiver.recv().await {
+                Ok(update) => update,
+                Err(RecvError::Lagged(skipped)) => {
+                    tracing::debug!(skipped, "order update subscriber lagged behind");
+                    continue;
+                }
+                Err(RecvError::Closed) => return None,
+            };
+            if !subscription.matches(&update) {
+                continue;
+            }
+            match sse::Event::default().event("update").json_data(&*update) {
+                Ok(event) => return Some((Ok(event), receiver)),
+                Err(err) => tracing::warn!(?err, "failed to serialize order update"),
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, chrono::Utc, futures::StreamExt, shared::addr};
+
+    #[tokio::test]
+    async fn request_() {
+        let uid = OrderUid([1; 56]);
+        let path = format!("/v1/orders/{uid}/status/stream");
+        let result = warp::test::request()
+            .path(&path)
+            .method("GET")
+            .filter(&request())
+            .await
+            .unwrap();
+        assert_eq!(result, Subscription::Order(uid));
+
+        let path = "/v1/account/0x0000000000000000000000000000000000000001/orders/stream";
+        let result = warp::test::request()
+            .path(path)
+            .method("GET")
+            .filter(&request())
+            .await
+            .unwrap();
+        assert_eq!(
+            result,
+            Subscription::Owner(addr!("0000000000000000000000000000000000000001"))
+        );
+    }
+
+    #[tokio::test]
+    async fn only_streams_matching_updates() {
+        let update = |uid: OrderUid| {
+            let (_, owner, _) = uid.parts();
+            Arc::new(dto::order::Update {
+                uid,
+                owner,
+                event: dto::order::Event::Ready,
+                timestamp: Utc::now(),
+                status: dto::order::Status::Active,
+            })
+        };
+        let (sender, receiver) = broadcast::channel(10);
+        let mut events = Box::pin(events(receiver, Subscription::Order(OrderUid([2; 56]))));
+
+        sender.send(update(OrderUid([1; 56]))).unwrap();
+        sender.send(update(OrderUid([2; 56]))).unwrap();
+        drop(sender);
+
+        assert!(events.next().await.unwrap().is_ok());
+        assert!(events.next().await.is_none());
+    }
+}
@@ -137,6 +137,11 @@ pub struct Arguments {
     /// The maximum gas amount a single order can use for getting settled.
     #[clap(long, env, default_value = "8000000")]
     pub max_gas_per_order: u64,
+
+    /// Stored auction prices more than this many blocks away from the block
+    /// a historical native price is requested for are not used.
+    #[clap(long, env, default_value = "300")]
+    pub historical_native_price_max_block_distance: u64,
 }
 
 impl std::fmt::Display for Arguments {
@@ -167,6 +172,7 @@ impl std::fmt::Display for Arguments {
             app_data_size_limit,
             db_url,
             max_gas_per_order,
+            historical_native_price_max_block_distance,
         } = self;
 
         write!(f, "{}", shared)?;
@@ -227,6 +233,11 @@ impl std::fmt::Display for Arguments {
         )?;
         writeln!(f, "app_data_size_limit: {}", app_data_size_limit)?;
         writeln!(f, "max_gas_per_order: {}", max_gas_per_order)?;
+        writeln!(
+            f,
+            "historical_native_price_max_block_distance: {}",
+            historical_native_price_max_block_distance
+        )?;
 
         Ok(())
     }
@@ -1,8 +1,13 @@
 use {
     super::Postgres,
-    anyhow::Result,
+    anyhow::{Context, Result},
     bigdecimal::BigDecimal,
+    database::byte_array::ByteArray,
     primitive_types::H160,
+    shared::price_estimation::{
+        historical::{AuctionPriceFetching, BlockPrice},
+        native::from_normalized_price,
+    },
     std::collections::HashMap,
 };
 
@@ -21,3 +26,36 @@ impl Postgres {
             .collect::<HashMap<_, _>>())
     }
 }
+
+#[async_trait::async_trait]
+impl AuctionPriceFetching for Postgres {
+    async fn surrounding_prices(
+        &self,
+        token: H160,
+        block: u64,
+    ) -> Result<(Option<BlockPrice>, Option<BlockPrice>)> {
+        let _timer = super::Metrics::get()
+            .database_queries
+            .with_label_values(&["fetch_surrounding_auction_prices"])
+            .start_timer();
+
+        let mut ex = self.pool.acquire().await?;
+        let (before, after) = database::auction_prices::fetch_surrounding_block(
+            &mut ex,
+            &ByteArray(token.0),
+            block.try_into().context("block out of range")?,
+        )
+        .await?;
+        Ok((
+            before.map(block_price).transpose()?,
+            after.map(block_price).transpose()?,
+        ))
+    }
+}
+
+fn block_price(price: database::auction_prices::BlockPrice) -> Result<BlockPrice> {
+    Ok(BlockPrice {
+        block: price.block.try_into().context("negative block")?,
+        price: from_normalized_price(price.price).context("invalid stored auction price")?,
+    })
+}
@@ -70,6 +70,9 @@ impl super::Postgres {
                             .context("executed fee amount")?,
                         token: primitive_types::H160(executed_fee.token.0),
                         policy: fee_policy_from(policy.clone(), quotes.get(&key.1), key.1)?,
+                        partner_fee_recipient: policy
+                            .partner_fee_recipient
+                            .map(|recipient| primitive_types::H160(recipient.0)),
                     };
                     result
                         .entry(key)
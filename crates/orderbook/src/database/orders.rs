@@ -1,13 +1,13 @@
 use {
     super::Postgres,
-    crate::orderbook::AddOrderError,
+    crate::{dto, orderbook::AddOrderError},
     anyhow::{Context as _, Result},
     app_data::AppDataHash,
     async_trait::async_trait,
     chrono::{DateTime, Utc},
     database::{
         byte_array::ByteArray,
-        order_events::{insert_order_event, OrderEvent, OrderEventLabel},
+        order_events::{insert_order_event, OrderEvent, OrderEventLabel, OrderEventReason},
         orders::{self, FullOrder, OrderKind as DbOrderKind},
     },
     ethcontract::H256,
@@ -492,6 +492,106 @@ impl Postgres {
             .map(full_order_into_model_order)
             .collect::<Result<Vec<_>>>()
     }
+
+    /// Retrieve all order events registered after the given timestamp, oldest
+    /// first.
+    pub async fn order_events_after(&self, after: DateTime<Utc>) -> Result<Vec<OrderEvent>> {
+        let _timer = super::Metrics::get()
+            .database_queries
+            .with_label_values(&["order_events_after"])
+            .start_timer();
+
+        let mut ex = self.pool.acquire().await?;
+        database::order_events::get_after(&mut ex, after)
+            .await
+            .context("order_events::get_after")
+    }
+
+    /// Retrieve all events registered for an order together with the reasons
+    /// why they happened, oldest first.
+    pub async fn order_events(
+        &self,
+        uid: &OrderUid,
+    ) -> Result<Vec<(OrderEvent, Option<OrderEventReason>)>> {
+        let _timer = super::Metrics::get()
+            .database_queries
+            .with_label_values(&["order_events"])
+            .start_timer();
+
+        let mut ex = self.pool.acquire().await?;
+        database::order_events::get_all(&mut ex, &ByteArray(uid.0))
+            .await
+            .context("order_events::get_all")
+    }
+
+    /// Retrieve the ids of all auctions an order was part of in ascending
+    /// order.
+    pub async fn order_auctions(&self, uid: &OrderUid) -> Result<Vec<dto::AuctionId>> {
+        let _timer = super::Metrics::get()
+            .database_queries
+            .with_label_values(&["order_auctions"])
+            .start_timer();
+
+        let mut ex = self.pool.acquire().await?;
+        database::auction_orders::fetch_auctions_of_order(&mut ex, &ByteArray(uid.0))
+            .await
+            .context("auction_orders::fetch_auctions_of_order")
+    }
+
+    /// Retrieve a single page of a user's orders ordered by creation date
+    /// descending. The page starts right after the order identified by the
+    /// `cursor`.
+    pub async fn user_orders_page(
+        &self,
+        owner: &H160,
+        filter: &UserOrderFilter,
+        cursor: Option<dto::page::OrderCursor>,
+        limit: u64,
+    ) -> Result<Vec<Order>> {
+        let _timer = super::Metrics::get()
+            .database_queries
+            .with_label_values(&["user_orders_page"])
+            .start_timer();
+
+        let filter = database::order_history::UserOrderFilter {
+            class: filter.class.as_ref().map(order_class_into),
+            status: filter.status.map(order_status_into),
+            sell_token: filter.sell_token.map(|token| ByteArray(token.0)),
+            buy_token: filter.buy_token.map(|token| ByteArray(token.0)),
+            created_after: filter.created_after,
+            created_before: filter.created_before,
+        };
+        let cursor = cursor.map(|cursor| database::order_history::UserOrderCursor {
+            creation_timestamp: cursor.creation_timestamp,
+            uid: ByteArray(cursor.uid.0),
+        });
+        let mut ex = self.pool.acquire().await?;
+        database::order_history::user_orders_page(
+            &mut ex,
+            &ByteArray(owner.0),
+            &filter,
+            cursor,
+            i64::try_from(limit).unwrap_or(i64::MAX),
+        )
+        .map(|result| match result {
+            Ok(order) => full_order_into_model_order(order),
+            Err(err) => Err(anyhow::Error::from(err)),
+        })
+        .try_collect()
+        .await
+    }
+}
+
+/// Filters for paginating through a user's orders. Any default value means
+/// that this field is unfiltered.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct UserOrderFilter {
+    pub class: Option<OrderClass>,
+    pub status: Option<OrderStatus>,
+    pub sell_token: Option<H160>,
+    pub buy_token: Option<H160>,
+    pub created_after: Option<DateTime<Utc>>,
+    pub created_before: Option<DateTime<Utc>>,
 }
 
 #[async_trait]
@@ -539,6 +639,19 @@ impl LimitOrderCounting for Postgres {
     }
 }
 
+fn order_status_into(status: OrderStatus) -> database::order_history::OrderStatus {
+    use database::order_history::OrderStatus as DbOrderStatus;
+    match status {
+        OrderStatus::PresignaturePending => DbOrderStatus::PresignaturePending,
+        OrderStatus::Open => DbOrderStatus::Open,
+        OrderStatus::Fulfilled => DbOrderStatus::Fulfilled,
+        OrderStatus::Cancelled => DbOrderStatus::Cancelled,
+        OrderStatus::Expired => DbOrderStatus::Expired,
+    }
+}
+
+/// Must be kept in sync with the status filter of
+/// `database::order_history::user_orders_page`.
 fn calculate_status(order: &FullOrder) -> OrderStatus {
     match order.kind {
         DbOrderKind::Buy => {
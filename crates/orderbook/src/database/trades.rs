@@ -1,7 +1,7 @@
 use {
-    crate::database::Postgres,
+    crate::{database::Postgres, dto},
     anyhow::{Context, Result},
-    database::{byte_array::ByteArray, trades::TradesQueryRow},
+    database::{byte_array::ByteArray, events::EventIndex, trades::TradesQueryRow},
     ethcontract::H160,
     futures::stream::TryStreamExt,
     model::{fee_policy::ExecutedProtocolFee, order::OrderUid, trade::Trade},
@@ -41,6 +41,58 @@ impl TradeRetrieving for Postgres {
         .await?;
         timer.stop_and_record();
 
+        self.with_executed_protocol_fees(trades).await
+    }
+}
+
+/// Filters for paginating through trades. Any default value means that this
+/// field is unfiltered.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct TradePageFilter {
+    pub owner: Option<H160>,
+    pub order_uid: Option<OrderUid>,
+    /// Only trades in this block or later.
+    pub from_block: Option<u64>,
+    /// Only trades in this block or earlier.
+    pub to_block: Option<u64>,
+}
+
+impl Postgres {
+    /// Retrieve a single page of trades ordered from newest to oldest. The page
+    /// starts right after the trade identified by the `cursor`.
+    pub async fn trades_page(
+        &self,
+        filter: &TradePageFilter,
+        cursor: Option<dto::page::TradeCursor>,
+        limit: u64,
+    ) -> Result<Vec<Trade>> {
+        let timer = super::Metrics::get()
+            .database_queries
+            .with_label_values(&["trades_page"])
+            .start_timer();
+
+        let to_i64 = |value: u64| i64::try_from(value).unwrap_or(i64::MAX);
+        let filter = database::trades::TradeFilter {
+            owner: filter.owner.map(|owner| ByteArray(owner.0)),
+            order_uid: filter.order_uid.map(|uid| ByteArray(uid.0)),
+            from_block: filter.from_block.map(to_i64),
+            to_block: filter.to_block.map(to_i64),
+        };
+        let cursor = cursor.map(|cursor| EventIndex {
+            block_number: to_i64(cursor.block_number),
+            log_index: to_i64(cursor.log_index),
+        });
+        let mut ex = self.pool.acquire().await?;
+        let trades = database::trades::trades_page(&mut ex, &filter, cursor, to_i64(limit))
+            .map_err(anyhow::Error::from)
+            .try_collect::<Vec<TradesQueryRow>>()
+            .await?;
+        timer.stop_and_record();
+
+        self.with_executed_protocol_fees(trades).await
+    }
+
+    async fn with_executed_protocol_fees(&self, trades: Vec<TradesQueryRow>) -> Result<Vec<Trade>> {
         let auction_order_uids = trades
             .iter()
             .filter_map(|t| t.auction_id.map(|auction_id| (auction_id, t.order_uid)))
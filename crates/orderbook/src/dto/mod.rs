@@ -1,5 +1,6 @@
 pub mod auction;
 pub mod order;
+pub mod page;
 
 pub use {
     auction::{Auction, AuctionId, AuctionWithId},
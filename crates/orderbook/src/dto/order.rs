@@ -1,12 +1,15 @@
 use {
+    super::AuctionId,
     app_data::AppDataHash,
+    chrono::{DateTime, Utc},
+    database::order_events::OrderEventLabel,
     model::{
         interaction::InteractionData,
         order::{BuyTokenDestination, OrderClass, OrderKind, OrderUid, SellTokenSource},
         signature::Signature,
     },
     number::serialization::HexOrDecimalU256,
-    primitive_types::{H160, U256},
+    primitive_types::{H160, H256, U256},
     serde::{Deserialize, Serialize},
     serde_with::serde_as,
 };
@@ -119,3 +122,76 @@ pub enum Status {
     /// The user cancelled the order. It will no longer show up in any auctions.
     Cancelled,
 }
+
+/// Lifecycle event of an order as recorded by the protocol.
+#[derive(Serialize, PartialEq, Eq, Debug, Clone, Copy)]
+#[cfg_attr(any(test, feature = "e2e"), derive(serde::Deserialize))]
+#[serde(rename_all = "camelCase")]
+pub enum Event {
+    Created,
+    Ready,
+    Filtered,
+    Invalid,
+    Executing,
+    Considered,
+    Traded,
+    Cancelled,
+}
+
+impl From<OrderEventLabel> for Event {
+    fn from(label: OrderEventLabel) -> Self {
+        match label {
+            OrderEventLabel::Created => Self::Created,
+            OrderEventLabel::Ready => Self::Ready,
+            OrderEventLabel::Filtered => Self::Filtered,
+            OrderEventLabel::Invalid => Self::Invalid,
+            OrderEventLabel::Executing => Self::Executing,
+            OrderEventLabel::Considered => Self::Considered,
+            OrderEventLabel::Traded => Self::Traded,
+            OrderEventLabel::Cancelled => Self::Cancelled,
+        }
+    }
+}
+
+/// Pushed to subscribers of the order update stream whenever a new lifecycle
+/// event gets registered for an order.
+#[derive(Serialize, PartialEq, Debug, Clone)]
+#[cfg_attr(any(test, feature = "e2e"), derive(serde::Deserialize))]
+#[serde(rename_all = "camelCase")]
+pub struct Update {
+    pub uid: OrderUid,
+    pub owner: H160,
+    pub event: Event,
+    pub timestamp: DateTime<Utc>,
+    pub status: Status,
+}
+
+/// The full lifecycle of an order as recorded by the protocol.
+#[derive(Serialize, PartialEq, Debug, Clone)]
+#[cfg_attr(any(test, feature = "e2e"), derive(serde::Deserialize))]
+#[serde(rename_all = "camelCase")]
+pub struct Timeline {
+    pub uid: OrderUid,
+    /// All lifecycle events of the order, oldest first.
+    pub events: Vec<TimelineEvent>,
+    /// The ids of all auctions the order was part of, in ascending order.
+    pub auctions: Vec<AuctionId>,
+    /// Hashes of the settlement transactions that traded the order.
+    pub settlements: Vec<H256>,
+}
+
+/// A single event of an order's timeline.
+#[derive(Serialize, PartialEq, Debug, Clone)]
+#[cfg_attr(any(test, feature = "e2e"), derive(serde::Deserialize))]
+#[serde(rename_all = "camelCase")]
+pub struct TimelineEvent {
+    pub event: Event,
+    pub timestamp: DateTime<Utc>,
+    /// Why the order was filtered from the auction or deemed invalid.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+    /// Additional context of the reason (e.g. the token missing a native
+    /// price).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub details: Option<serde_json::Value>,
+}
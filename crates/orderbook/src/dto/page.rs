@@ -0,0 +1,113 @@
+//! Types for cursor based pagination.
+//!
+//! Cursors are opaque strings for API consumers. They encode the sort key of
+//! the last item of a page so the next page can continue right after it even
+//! if new items got added in the mean time.
+
+use {
+    anyhow::{Context, Result},
+    chrono::{DateTime, Utc},
+    model::order::OrderUid,
+    serde::Serialize,
+    std::{fmt, str::FromStr},
+};
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Page<T> {
+    pub items: Vec<T>,
+    /// Cursor to fetch the next page with. `None` if there are no more items.
+    pub next_cursor: Option<String>,
+}
+
+/// Position in the list of a user's orders which are sorted by creation time
+/// and uid (both descending).
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct OrderCursor {
+    pub creation_timestamp: DateTime<Utc>,
+    pub uid: OrderUid,
+}
+
+impl fmt::Display for OrderCursor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}_{}",
+            self.creation_timestamp.timestamp_micros(),
+            self.uid
+        )
+    }
+}
+
+impl FromStr for OrderCursor {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let (timestamp, uid) = s.split_once('_').context("missing separator")?;
+        let micros = timestamp.parse().context("invalid timestamp")?;
+        Ok(Self {
+            creation_timestamp: DateTime::from_timestamp_micros(micros)
+                .context("timestamp out of range")?,
+            uid: uid.parse().context("invalid order uid")?,
+        })
+    }
+}
+
+/// Position in the list of trades which are sorted by their event index
+/// (descending).
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct TradeCursor {
+    pub block_number: u64,
+    pub log_index: u64,
+}
+
+impl fmt::Display for TradeCursor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}_{}", self.block_number, self.log_index)
+    }
+}
+
+impl FromStr for TradeCursor {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let (block_number, log_index) = s.split_once('_').context("missing separator")?;
+        Ok(Self {
+            block_number: block_number.parse().context("invalid block number")?,
+            log_index: log_index.parse().context("invalid log index")?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn order_cursor_roundtrip() {
+        let cursor = OrderCursor {
+            creation_timestamp: DateTime::from_timestamp_micros(1_700_000_000_123_456).unwrap(),
+            uid: OrderUid([0x42; 56]),
+        };
+        assert_eq!(cursor.to_string().parse::<OrderCursor>().unwrap(), cursor);
+    }
+
+    #[test]
+    fn trade_cursor_roundtrip() {
+        let cursor = TradeCursor {
+            block_number: 19_000_000,
+            log_index: 12,
+        };
+        assert_eq!(cursor.to_string(), "19000000_12");
+        assert_eq!(cursor.to_string().parse::<TradeCursor>().unwrap(), cursor);
+    }
+
+    #[test]
+    fn rejects_malformed_cursors() {
+        assert!("".parse::<OrderCursor>().is_err());
+        assert!("123".parse::<OrderCursor>().is_err());
+        assert!("abc_0x01".parse::<OrderCursor>().is_err());
+        assert!("1_".parse::<TradeCursor>().is_err());
+        assert!("-1_2".parse::<TradeCursor>().is_err());
+    }
+}
@@ -5,6 +5,7 @@ pub mod database;
 pub mod dto;
 mod ipfs;
 mod ipfs_app_data;
+pub mod order_updates;
 pub mod orderbook;
 mod quoter;
 pub mod run;
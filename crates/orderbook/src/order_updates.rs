@@ -0,0 +1,227 @@
+//! Fans out order lifecycle updates to all subscribers of the streaming API.
+//!
+//! Instead of every subscriber polling the database for its orders, a single
+//! background task fetches all newly registered order events once per block
+//! and broadcasts them to all subscribers which then filter for the orders
+//! they are interested in.
+
+use {
+    crate::{
+        database::Postgres,
+        dto,
+        orderbook::{status_from_event, status_requires_competition},
+        solver_competition::{LoadSolverCompetitionError, SolverCompetitionStoring},
+    },
+    anyhow::Result,
+    chrono::{DateTime, Duration, Utc},
+    database::order_events::OrderEvent,
+    ethrpc::block_stream::{self, CurrentBlockWatcher},
+    futures::StreamExt,
+    model::order::OrderUid,
+    std::{collections::HashSet, sync::Arc},
+    tokio::sync::broadcast,
+    tracing::Instrument,
+};
+
+/// How many updates a slow subscriber may lag behind before it starts missing
+/// updates.
+const CHANNEL_CAPACITY: usize = 4096;
+
+/// Event timestamps are assigned before the inserting transaction commits, so
+/// events can show up with a timestamp older than the newest event we have
+/// already seen. To not miss them we always re-read this window before the
+/// cursor and drop the events we already broadcast.
+const OVERLAP: Duration = Duration::seconds(30);
+
+pub struct OrderUpdates {
+    sender: broadcast::Sender<Arc<dto::order::Update>>,
+}
+
+impl OrderUpdates {
+    /// Spawns the background task polling the database for new order events
+    /// on every new block.
+    pub fn spawn(database: Postgres, blocks: CurrentBlockWatcher) -> Arc<Self> {
+        let (sender, _) = broadcast::channel(CHANNEL_CAPACITY);
+        let updates = Arc::new(Self { sender });
+        tokio::task::spawn(
+            Self::run(updates.clone(), database, blocks)
+                .instrument(tracing::info_span!("order_updates")),
+        );
+        updates
+    }
+
+    /// Returns a receiver yielding all order updates registered from now on.
+    /// Updates registered shortly before subscribing might also be included.
+    pub fn subscribe(&self) -> broadcast::Receiver<Arc<dto::order::Update>> {
+        self.sender.subscribe()
+    }
+
+    async fn run(self: Arc<Self>, database: Postgres, blocks: CurrentBlockWatcher) {
+        let mut cursor = Cursor::new(Utc::now());
+        let mut blocks = block_stream::into_stream(blocks);
+        while blocks.next().await.is_some() {
+            let subscribers = self.sender.receiver_count();
+            Metrics::get()
+                .subscribers
+                .set(i64::try_from(subscribers).unwrap_or(i64::MAX));
+            if subscribers == 0 {
+                // Nobody is listening so there is no need to query the DB.
+                cursor = Cursor::new(Utc::now());
+                continue;
+            }
+            match self.fetch_updates(&database, &mut cursor).await {
+                Ok(updates) => {
+                    Metrics::get()
+                        .updates
+                        .inc_by(updates.len().try_into().unwrap_or(u64::MAX));
+                    for update in updates {
+                        // Only fails if there are no subscribers left.
+                        let _ = self.sender.send(Arc::new(update));
+                    }
+                }
+                Err(err) => tracing::warn!(?err, "failed to fetch order updates"),
+            }
+        }
+        tracing::error!("block stream terminated unexpectedly");
+    }
+
+    /// Fetches all order events not yet seen by the `cursor` and converts
+    /// them into updates.
+    async fn fetch_updates(
+        &self,
+        database: &Postgres,
+        cursor: &mut Cursor,
+    ) -> Result<Vec<dto::order::Update>> {
+        let events = database.order_events_after(cursor.query_from()).await?;
+        let events = cursor.advance(events);
+
+        // Only load the latest competition (at most once per block) if some
+        // status actually references it.
+        let competition = match events
+            .iter()
+            .any(|event| status_requires_competition(event.label))
+        {
+            true => match database.load_latest_competition().await {
+                Ok(competition) => Some(competition),
+                Err(LoadSolverCompetitionError::NotFound) => None,
+                Err(LoadSolverCompetitionError::Other(err)) => return Err(err),
+            },
+            false => None,
+        };
+
+        let updates = events
+            .into_iter()
+            .map(|event| {
+                let uid = OrderUid(event.order_uid.0);
+                let (_, owner, _) = uid.parts();
+                let status = status_from_event(
+                    &uid,
+                    event.label,
+                    status_requires_competition(event.label)
+                        .then(|| competition.clone())
+                        .flatten(),
+                );
+                dto::order::Update {
+                    uid,
+                    owner,
+                    event: event.label.into(),
+                    timestamp: event.timestamp,
+                    status,
+                }
+            })
+            .collect();
+        Ok(updates)
+    }
+}
+
+/// Tracks which order events have already been broadcast.
+struct Cursor {
+    /// Timestamp of the most recent event seen so far.
+    position: DateTime<Utc>,
+    /// All events seen within the overlap window before `position`.
+    seen: HashSet<OrderEvent>,
+}
+
+impl Cursor {
+    fn new(position: DateTime<Utc>) -> Self {
+        Self {
+            position,
+            seen: Default::default(),
+        }
+    }
+
+    /// Timestamp after which events need to be fetched.
+    fn query_from(&self) -> DateTime<Utc> {
+        self.position - OVERLAP
+    }
+
+    /// Moves the cursor past the fetched events and returns the ones that
+    /// haven't been seen before.
+    fn advance(&mut self, events: Vec<OrderEvent>) -> Vec<OrderEvent> {
+        let new: Vec<_> = events
+            .into_iter()
+            .filter(|event| self.seen.insert(*event))
+            .collect();
+        if let Some(latest) = new.iter().map(|event| event.timestamp).max() {
+            self.position = self.position.max(latest);
+        }
+        let cutoff = self.query_from();
+        self.seen.retain(|event| event.timestamp >= cutoff);
+        new
+    }
+}
+
+#[derive(prometheus_metric_storage::MetricStorage)]
+#[metric(subsystem = "order_updates")]
+struct Metrics {
+    /// Number of currently connected order update subscribers.
+    subscribers: prometheus::IntGauge,
+
+    /// Number of order updates broadcast to subscribers.
+    updates: prometheus::IntCounter,
+}
+
+impl Metrics {
+    fn get() -> &'static Self {
+        Metrics::instance(observe::metrics::get_storage_registry()).unwrap()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        database::{byte_array::ByteArray, order_events::OrderEventLabel},
+    };
+
+    fn event(uid: u8, timestamp: DateTime<Utc>) -> OrderEvent {
+        OrderEvent {
+            order_uid: ByteArray([uid; 56]),
+            timestamp,
+            label: OrderEventLabel::Created,
+        }
+    }
+
+    #[test]
+    fn cursor_yields_late_events_once() {
+        let start = DateTime::<Utc>::from_timestamp(1_000_000, 0).unwrap();
+        let mut cursor = Cursor::new(start);
+        assert_eq!(cursor.query_from(), start - OVERLAP);
+
+        let first = event(1, start + Duration::seconds(10));
+        assert_eq!(cursor.advance(vec![first]), vec![first]);
+        assert_eq!(cursor.query_from(), first.timestamp - OVERLAP);
+
+        // An event committed late with an older timestamp and an event sharing
+        // the timestamp of the latest seen event still get picked up.
+        let late = event(2, start + Duration::seconds(5));
+        let same = event(3, first.timestamp);
+        assert_eq!(cursor.advance(vec![late, first, same]), vec![late, same]);
+        assert_eq!(cursor.query_from(), first.timestamp - OVERLAP);
+
+        // Events outside of the overlap window are forgotten.
+        let next = event(4, first.timestamp + OVERLAP + Duration::seconds(1));
+        assert_eq!(cursor.advance(vec![late, first, same, next]), vec![next]);
+        assert_eq!(cursor.seen, HashSet::from([next]));
+    }
+}
@@ -1,7 +1,7 @@
 use {
     crate::{
         database::{
-            orders::{InsertionError, OrderStoring, OrderWithQuote},
+            orders::{InsertionError, OrderStoring, OrderWithQuote, UserOrderFilter},
             trades::{TradeFilter, TradeRetrieving},
         },
         dto,
@@ -458,37 +458,34 @@ impl Orderbook {
             .context("get_user_orders error")
     }
 
-    pub async fn get_order_status(&self, uid: &OrderUid) -> Result<Option<dto::order::Status>> {
-        let solutions = |competition: SolverCompetitionAPI| {
-            competition
-                .common
-                .solutions
-                .into_iter()
-                .map(|solution| {
-                    let executed_amounts = solution.orders.iter().find_map(|o| match o {
-                        solver_competition::Order::Legacy { .. } => None,
-                        solver_competition::Order::Colocated {
-                            id,
-                            sell_amount,
-                            buy_amount,
-                        } => (id == uid).then_some(dto::order::ExecutedAmounts {
-                            sell: *sell_amount,
-                            buy: *buy_amount,
-                        }),
-                    });
-                    dto::order::SolutionInclusion {
-                        solver: solution.solver,
-                        executed_amounts,
-                    }
-                })
-                .collect::<Vec<_>>()
-        };
-
-        let latest_competition = async {
-            let competition = self.database.load_latest_competition().await?;
-            Ok::<_, anyhow::Error>(solutions(competition))
+    /// Returns a page of at most `limit` orders of the user matching the
+    /// filter. A full page comes with a cursor pointing to the next page.
+    pub async fn get_user_orders_page(
+        &self,
+        owner: &H160,
+        filter: &UserOrderFilter,
+        cursor: Option<dto::page::OrderCursor>,
+        limit: u64,
+    ) -> Result<dto::page::Page<Order>> {
+        let items = self
+            .database
+            .user_orders_page(owner, filter, cursor, limit)
+            .await
+            .context("get_user_orders_page error")?;
+        let next_cursor = match items.len() as u64 >= limit {
+            true => items.last().map(|order| {
+                dto::page::OrderCursor {
+                    creation_timestamp: order.metadata.creation_date,
+                    uid: order.metadata.uid,
+                }
+                .to_string()
+            }),
+            false => None,
         };
+        Ok(dto::page::Page { items, next_cursor })
+    }
 
+    pub async fn get_order_status(&self, uid: &OrderUid) -> Result<Option<dto::order::Status>> {
         // Once an order was executed we always want to return `Traded` with the
         // competition data of the **first** time it was traded for a stable result.
         // Under some circumstances it can happen that the latest state of an already
@@ -508,29 +505,137 @@ impl Orderbook {
                     .database
                     .load_competition(Identifier::Transaction(tx_hash))
                     .await?;
-                return Ok(Some(dto::order::Status::Traded(solutions(competition))));
+                return Ok(Some(dto::order::Status::Traded(solution_inclusions(
+                    uid,
+                    competition,
+                ))));
             }
             // order executed but not fully indexed and processed
             Some(None) => {
-                return Ok(Some(dto::order::Status::Traded(latest_competition.await?)));
+                let competition = self.database.load_latest_competition().await?;
+                return Ok(Some(dto::order::Status::Traded(solution_inclusions(
+                    uid,
+                    competition,
+                ))));
             }
             None => (),
         }
 
-        let latest_event = self.database.latest_order_event(uid).await?;
-        let status = match latest_event.context("no event")?.label {
-            OrderEventLabel::Ready => dto::order::Status::Active,
-            OrderEventLabel::Created => dto::order::Status::Scheduled,
-            OrderEventLabel::Considered => dto::order::Status::Solved(latest_competition.await?),
-            OrderEventLabel::Executing => dto::order::Status::Executing(latest_competition.await?),
-            // order executed but not fully indexed and processed
-            OrderEventLabel::Traded => dto::order::Status::Traded(latest_competition.await?),
-            OrderEventLabel::Cancelled => dto::order::Status::Cancelled,
-            OrderEventLabel::Filtered => dto::order::Status::Open,
-            OrderEventLabel::Invalid => dto::order::Status::Open,
+        let label = self
+            .database
+            .latest_order_event(uid)
+            .await?
+            .context("no event")?
+            .label;
+        let competition = match status_requires_competition(label) {
+            true => Some(self.database.load_latest_competition().await?),
+            false => None,
         };
-        Ok(Some(status))
+        Ok(Some(status_from_event(uid, label, competition)))
     }
+
+    /// Returns the full lifecycle of an order, or `None` if the order does not
+    /// exist.
+    pub async fn get_order_timeline(&self, uid: &OrderUid) -> Result<Option<dto::order::Timeline>> {
+        let (order, events, auctions, trades) = futures::try_join!(
+            self.database.single_order(uid),
+            self.database.order_events(uid),
+            self.database.order_auctions(uid),
+            self.database.trades(&TradeFilter {
+                owner: None,
+                order_uid: Some(*uid),
+            }),
+        )?;
+        if order.is_none() {
+            return Ok(None);
+        }
+
+        Ok(Some(dto::order::Timeline {
+            uid: *uid,
+            events: events
+                .into_iter()
+                .map(|(event, reason)| {
+                    let (reason, details) = match reason {
+                        Some(reason) => (Some(reason.reason), reason.details),
+                        None => (None, None),
+                    };
+                    dto::order::TimelineEvent {
+                        event: event.label.into(),
+                        timestamp: event.timestamp,
+                        reason,
+                        details,
+                    }
+                })
+                .collect(),
+            auctions,
+            // Trades that are not fully indexed yet don't have a transaction
+            // hash.
+            settlements: trades.iter().filter_map(|trade| trade.tx_hash).collect(),
+        }))
+    }
+}
+
+/// Returns whether the status of an order with the given latest event
+/// contains data of the latest solver competition.
+pub fn status_requires_competition(label: OrderEventLabel) -> bool {
+    matches!(
+        label,
+        OrderEventLabel::Considered | OrderEventLabel::Executing | OrderEventLabel::Traded
+    )
+}
+
+/// Maps the latest event of an order to its status. `competition` is expected
+/// to contain the latest solver competition if
+/// [`status_requires_competition`] returns true for the event.
+pub fn status_from_event(
+    uid: &OrderUid,
+    label: OrderEventLabel,
+    competition: Option<SolverCompetitionAPI>,
+) -> dto::order::Status {
+    let solutions = || {
+        competition
+            .map(|competition| solution_inclusions(uid, competition))
+            .unwrap_or_default()
+    };
+    match label {
+        OrderEventLabel::Ready => dto::order::Status::Active,
+        OrderEventLabel::Created => dto::order::Status::Scheduled,
+        OrderEventLabel::Considered => dto::order::Status::Solved(solutions()),
+        OrderEventLabel::Executing => dto::order::Status::Executing(solutions()),
+        // order executed but not fully indexed and processed
+        OrderEventLabel::Traded => dto::order::Status::Traded(solutions()),
+        OrderEventLabel::Cancelled => dto::order::Status::Cancelled,
+        OrderEventLabel::Filtered => dto::order::Status::Open,
+        OrderEventLabel::Invalid => dto::order::Status::Open,
+    }
+}
+
+fn solution_inclusions(
+    uid: &OrderUid,
+    competition: SolverCompetitionAPI,
+) -> Vec<dto::order::SolutionInclusion> {
+    competition
+        .common
+        .solutions
+        .into_iter()
+        .map(|solution| {
+            let executed_amounts = solution.orders.iter().find_map(|o| match o {
+                solver_competition::Order::Legacy { .. } => None,
+                solver_competition::Order::Colocated {
+                    id,
+                    sell_amount,
+                    buy_amount,
+                } => (id == uid).then_some(dto::order::ExecutedAmounts {
+                    sell: *sell_amount,
+                    buy: *buy_amount,
+                }),
+            });
+            dto::order::SolutionInclusion {
+                solver: solution.solver,
+                executed_amounts,
+            }
+        })
+        .collect()
 }
 
 #[async_trait::async_trait]
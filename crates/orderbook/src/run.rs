@@ -5,6 +5,7 @@ use {
         database::Postgres,
         ipfs::Ipfs,
         ipfs_app_data::IpfsAppData,
+        order_updates::OrderUpdates,
         orderbook::Orderbook,
         quoter::QuoteHandler,
     },
@@ -14,6 +15,7 @@ use {
     clap::Parser,
     contracts::{BalancerV2Vault, GPv2Settlement, HooksTrampoline, IUniswapV3Factory, WETH9},
     ethcontract::errors::DeployError,
+    ethrpc::Web3,
     futures::{FutureExt, StreamExt},
     model::{order::BUY_ETH_ADDRESS, DomainSeparator},
     observe::metrics::{serve_metrics, DEFAULT_METRICS_PORT},
@@ -33,8 +35,10 @@ use {
         http_client::HttpClientFactory,
         order_quoting::{self, OrderQuoter},
         order_validation::{OrderValidPeriodConfiguration, OrderValidator},
+        permit_validator,
         price_estimation::{
             factory::{self, PriceEstimatorFactory},
+            historical::{HistoricalNativePriceEstimating, HistoricalNativePriceEstimator},
             native::NativePriceEstimating,
             PriceEstimating,
             QuoteVerificationMode,
@@ -271,6 +275,7 @@ pub async fn run(args: Arguments) {
             bad_token_detector: bad_token_detector.clone(),
             tokens: token_info_fetcher.clone(),
             code_fetcher: code_fetcher.clone(),
+            balance_override_storage: Arc::new(postgres.pool.clone()),
         },
     )
     .await
@@ -286,6 +291,21 @@ pub async fn run(args: Arguments) {
         .unwrap();
     let prices = postgres.fetch_latest_prices().await.unwrap();
     native_price_estimator.initialize_cache(prices).await;
+    let historical_native_price_estimator = Arc::new(HistoricalNativePriceEstimator::new(
+        Arc::new(postgres.clone()),
+        price_estimator_factory
+            .historical_native_price_fallback()
+            .await
+            .unwrap_or_else(|err| {
+                tracing::error!(
+                    ?err,
+                    "failed to initialize historical native price fallback, only using auction \
+                     prices"
+                );
+                None
+            }),
+        args.historical_native_price_max_block_distance,
+    ));
 
     let price_estimator = price_estimator_factory
         .price_estimator(
@@ -343,25 +363,28 @@ pub async fn run(args: Arguments) {
 
     let app_data_validator = Validator::new(args.app_data_size_limit);
     let chainalysis_oracle = contracts::ChainalysisOracle::deployed(&web3).await.ok();
-    let order_validator = Arc::new(OrderValidator::new(
-        native_token.clone(),
-        Arc::new(order_validation::banned::Users::new(
-            chainalysis_oracle,
-            args.banned_users,
-        )),
-        validity_configuration,
-        args.eip1271_skip_creation_validation,
-        bad_token_detector.clone(),
-        hooks_contract,
-        optimal_quoter.clone(),
-        balance_fetcher,
-        signature_validator,
-        Arc::new(postgres.clone()),
-        args.max_limit_orders_per_user,
-        code_fetcher,
-        app_data_validator.clone(),
-        args.max_gas_per_order,
-    ));
+    let order_validator = Arc::new(
+        OrderValidator::new(
+            native_token.clone(),
+            Arc::new(order_validation::banned::Users::new(
+                chainalysis_oracle,
+                args.banned_users,
+            )),
+            validity_configuration,
+            args.eip1271_skip_creation_validation,
+            bad_token_detector.clone(),
+            hooks_contract,
+            optimal_quoter.clone(),
+            balance_fetcher,
+            signature_validator,
+            Arc::new(postgres.clone()),
+            args.max_limit_orders_per_user,
+            code_fetcher,
+            app_data_validator.clone(),
+            args.max_gas_per_order,
+        )
+        .with_permit_validator(permit_validator::validator(&web3, vault_relayer)),
+    );
     let ipfs = args
         .ipfs_gateway
         .map(|url| {
@@ -392,6 +415,8 @@ pub async fn run(args: Arguments) {
             .with_fast_quoter(fast_quoter),
     );
 
+    let order_updates = OrderUpdates::spawn(postgres.clone(), current_block_stream);
+
     let (shutdown_sender, shutdown_receiver) = tokio::sync::oneshot::channel();
     let serve_api = serve_api(
         postgres,
@@ -403,6 +428,9 @@ pub async fn run(args: Arguments) {
             let _ = shutdown_receiver.await;
         },
         native_price_estimator,
+        historical_native_price_estimator,
+        web3,
+        order_updates,
     );
 
     let mut metrics_address = args.bind_address;
@@ -468,6 +496,9 @@ fn serve_api(
     address: SocketAddr,
     shutdown_receiver: impl Future<Output = ()> + Send + 'static,
     native_price_estimator: Arc<dyn NativePriceEstimating>,
+    historical_native_price_estimator: Arc<dyn HistoricalNativePriceEstimating>,
+    web3: Web3,
+    order_updates: Arc<OrderUpdates>,
 ) -> JoinHandle<()> {
     let filter = api::handle_all_routes(
         database,
@@ -475,6 +506,9 @@ fn serve_api(
         quotes,
         app_data,
         native_price_estimator,
+        historical_native_price_estimator,
+        web3,
+        order_updates,
     )
     .boxed();
     tracing::info!(%address, "serving order book");
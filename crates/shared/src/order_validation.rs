@@ -10,6 +10,7 @@ use {
             QuoteParameters,
             QuoteSearchParameters,
         },
+        permit_validator::{PermitCheck, PermitValidating, PermitValidationError},
         price_estimation::{PriceEstimationError, Verification},
         signature_validator::{SignatureCheck, SignatureValidating, SignatureValidationError},
         trade_finding,
@@ -135,6 +136,9 @@ pub enum ValidationError {
     NonZeroFee,
     InsufficientBalance,
     InsufficientAllowance,
+    /// A permit in the pre-hooks would not grant the allowance the order
+    /// needs.
+    InvalidPermit(PermitValidationError),
     InvalidSignature,
     /// If fee and sell amount overflow u256
     SellAmountOverflow,
@@ -220,6 +224,7 @@ pub struct OrderValidator {
     quoter: Arc<dyn OrderQuoting>,
     balance_fetcher: Arc<dyn BalanceFetching>,
     signature_validator: Arc<dyn SignatureValidating>,
+    permit_validator: Option<Arc<dyn PermitValidating>>,
     limit_order_counter: Arc<dyn LimitOrderCounting>,
     max_limit_orders_per_user: u64,
     pub code_fetcher: Arc<dyn CodeFetching>,
@@ -307,6 +312,7 @@ impl OrderValidator {
             quoter,
             balance_fetcher,
             signature_validator,
+            permit_validator: None,
             limit_order_counter,
             max_limit_orders_per_user,
             code_fetcher,
@@ -315,6 +321,13 @@ impl OrderValidator {
         }
     }
 
+    /// Validates EIP-2612 and Permit2 permits in the pre-hooks of orders
+    /// before checking whether the sell token can be transferred.
+    pub fn with_permit_validator(mut self, permit_validator: Arc<dyn PermitValidating>) -> Self {
+        self.permit_validator = Some(permit_validator);
+        self
+    }
+
     async fn check_max_limit_orders(&self, owner: H160) -> Result<(), ValidationError> {
         let num_limit_orders = self
             .limit_order_counter
@@ -585,6 +598,26 @@ impl OrderValidating for OrderValidator {
             }
             Err(err) => match err {
                 TransferSimulationError::InsufficientAllowance => {
+                    // A permit in the pre-hooks can explain why the allowance
+                    // is missing more precisely.
+                    if let Some(permit_validator) = &self.permit_validator {
+                        let check = PermitCheck {
+                            owner,
+                            sell_token: data.sell_token,
+                            amount: data
+                                .sell_amount
+                                .checked_add(data.fee_amount)
+                                .ok_or(ValidationError::SellAmountOverflow)?,
+                            pre_hooks: app_data.inner.protocol.hooks.pre.clone(),
+                        };
+                        match permit_validator.validate_permits(check).await {
+                            Ok(()) => (),
+                            Err(PermitValidationError::Unverifiable(err)) => {
+                                tracing::debug!(?err, "could not verify permit");
+                            }
+                            Err(err) => return Err(ValidationError::InvalidPermit(err)),
+                        }
+                    }
                     return Err(ValidationError::InsufficientAllowance);
                 }
                 TransferSimulationError::InsufficientBalance => {
@@ -945,6 +978,7 @@ mod tests {
             bad_token::{MockBadTokenDetecting, TokenQuality},
             code_fetching::MockCodeFetching,
             order_quoting::{FindQuoteError, MockOrderQuoting},
+            permit_validator::MockPermitValidating,
             signature_validator::MockSignatureValidating,
         },
         contracts::dummy_contract,
@@ -1778,6 +1812,83 @@ mod tests {
         assert!(matches!(result, Err(ValidationError::InsufficientBalance)));
     }
 
+    #[tokio::test]
+    async fn post_validate_err_invalid_permit() {
+        let mut bad_token_detector = MockBadTokenDetecting::new();
+        bad_token_detector
+            .expect_detect()
+            .returning(|_| Ok(TokenQuality::Good));
+        let mut balance_fetcher = MockBalanceFetching::new();
+        balance_fetcher
+            .expect_can_transfer()
+            .returning(|_, _| Err(TransferSimulationError::InsufficientAllowance));
+        let mut permit_validator = MockPermitValidating::new();
+        permit_validator
+            .expect_validate_permits()
+            .withf(|check| {
+                check.sell_token == H160::from_low_u64_be(1)
+                    && check.amount == U256::from(2)
+                    && check.pre_hooks.len() == 1
+            })
+            .returning(|_| {
+                Err(PermitValidationError::WrongSpender {
+                    actual: H160([2; 20]),
+                })
+            });
+        let validator = OrderValidator::new(
+            dummy_contract!(WETH9, [0xef; 20]),
+            Arc::new(order_validation::banned::Users::none()),
+            OrderValidPeriodConfiguration::any(),
+            false,
+            Arc::new(bad_token_detector),
+            dummy_contract!(HooksTrampoline, [0xcf; 20]),
+            Arc::new(MockOrderQuoting::new()),
+            Arc::new(balance_fetcher),
+            Arc::new(MockSignatureValidating::new()),
+            Arc::new(MockLimitOrderCounting::new()),
+            0,
+            Arc::new(MockCodeFetching::new()),
+            Default::default(),
+            u64::MAX,
+        )
+        .with_permit_validator(Arc::new(permit_validator));
+        let order = OrderCreation {
+            valid_to: time::now_in_epoch_seconds() + 2,
+            sell_token: H160::from_low_u64_be(1),
+            buy_token: H160::from_low_u64_be(2),
+            buy_amount: U256::from(1),
+            sell_amount: U256::from(1),
+            fee_amount: U256::from(1),
+            signature: Signature::Eip712(EcdsaSignature::non_zero()),
+            app_data: OrderCreationAppData::Full {
+                full: json!({
+                    "metadata": {
+                        "hooks": {
+                            "pre": [
+                                {
+                                    "target": "0x1111111111111111111111111111111111111111",
+                                    "callData": "0x112233",
+                                    "gasLimit": "42",
+                                }
+                            ],
+                        },
+                    },
+                })
+                .to_string(),
+            },
+            ..Default::default()
+        };
+        let result = validator
+            .validate_and_construct_order(order, &Default::default(), Default::default(), None)
+            .await;
+        assert!(matches!(
+            result,
+            Err(ValidationError::InvalidPermit(
+                PermitValidationError::WrongSpender { .. }
+            ))
+        ));
+    }
+
     #[tokio::test]
     async fn post_validate_err_invalid_eip1271_signature() {
         let mut order_quoter = MockOrderQuoting::new();
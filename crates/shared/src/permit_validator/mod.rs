@@ -0,0 +1,373 @@
+//! Recognizes token permits in the pre-hooks of an order and validates them
+//! before the order gets accepted. Without this, orders relying on a permit to
+//! grant the allowance would only fail with a generic insufficient allowance
+//! error if anything about the permit is wrong.
+
+use {
+    app_data::Hook,
+    ethrpc::Web3,
+    hex_literal::hex,
+    model::signature::EcdsaSignature,
+    primitive_types::{H160, H256, U256},
+    std::sync::Arc,
+    thiserror::Error,
+    web3::ethabi::{self, ParamType, Token},
+};
+
+mod onchain;
+
+/// The canonical Uniswap Permit2 deployment, which has the same address on all
+/// supported chains.
+pub const PERMIT2: H160 = H160(hex!("000000000022D473030F116dDEE9F6B43aC78BA3"));
+
+/// A permit granting a token allowance which was found in a pre-hook.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Permit {
+    /// `permit(owner, spender, value, deadline, v, r, s)` of an EIP-2612
+    /// token.
+    ///
+    /// <https://eips.ethereum.org/EIPS/eip-2612>
+    Eip2612 {
+        token: H160,
+        owner: H160,
+        spender: H160,
+        value: U256,
+        deadline: U256,
+        signature: EcdsaSignature,
+    },
+    /// `permit(owner, permitSingle, signature)` of the Permit2 contract.
+    ///
+    /// <https://github.com/Uniswap/permit2>
+    Permit2 {
+        token: H160,
+        owner: H160,
+        spender: H160,
+        amount: U256,
+        expiration: U256,
+        nonce: U256,
+        sig_deadline: U256,
+    },
+}
+
+fn eip2612_params() -> Vec<ParamType> {
+    vec![
+        ParamType::Address,
+        ParamType::Address,
+        ParamType::Uint(256),
+        ParamType::Uint(256),
+        ParamType::Uint(8),
+        ParamType::FixedBytes(32),
+        ParamType::FixedBytes(32),
+    ]
+}
+
+fn permit2_params() -> Vec<ParamType> {
+    vec![
+        ParamType::Address,
+        ParamType::Tuple(vec![
+            ParamType::Tuple(vec![
+                ParamType::Address,
+                ParamType::Uint(160),
+                ParamType::Uint(48),
+                ParamType::Uint(48),
+            ]),
+            ParamType::Address,
+            ParamType::Uint(256),
+        ]),
+        ParamType::Bytes,
+    ]
+}
+
+impl Permit {
+    /// Decodes the hook if it calls `permit` on an EIP-2612 token or on the
+    /// Permit2 contract. Returns [`None`] for all other hooks.
+    pub fn decode(hook: &Hook) -> Option<Self> {
+        let (selector, params) = (hook.call_data.get(..4)?, hook.call_data.get(4..)?);
+        if hook.target == PERMIT2 {
+            let types = permit2_params();
+            if ethabi::short_signature("permit", &types) != selector {
+                return None;
+            }
+            let mut tokens = ethabi::decode(&types, params).ok()?.into_iter();
+            let owner = tokens.next()?.into_address()?;
+            let mut single = tokens.next()?.into_tuple()?.into_iter();
+            let mut details = single.next()?.into_tuple()?.into_iter();
+            return Some(Self::Permit2 {
+                token: details.next()?.into_address()?,
+                amount: details.next()?.into_uint()?,
+                expiration: details.next()?.into_uint()?,
+                nonce: details.next()?.into_uint()?,
+                owner,
+                spender: single.next()?.into_address()?,
+                sig_deadline: single.next()?.into_uint()?,
+            });
+        }
+
+        let types = eip2612_params();
+        if ethabi::short_signature("permit", &types) != selector {
+            return None;
+        }
+        let mut tokens = ethabi::decode(&types, params).ok()?.into_iter();
+        let bytes32 = |token: Token| Some(H256::from_slice(&token.into_fixed_bytes()?));
+        Some(Self::Eip2612 {
+            token: hook.target,
+            owner: tokens.next()?.into_address()?,
+            spender: tokens.next()?.into_address()?,
+            value: tokens.next()?.into_uint()?,
+            deadline: tokens.next()?.into_uint()?,
+            signature: EcdsaSignature {
+                v: tokens.next()?.into_uint()?.low_u32() as u8,
+                r: bytes32(tokens.next()?)?,
+                s: bytes32(tokens.next()?)?,
+            },
+        })
+    }
+
+    /// Whether the permit grants an allowance of the order's sell token from
+    /// the owner. Other permits in the pre-hooks serve a different purpose and
+    /// are not checked.
+    fn applies_to(&self, check: &PermitCheck) -> bool {
+        let (token, owner) = match self {
+            Self::Eip2612 { token, owner, .. } | Self::Permit2 { token, owner, .. } => {
+                (token, owner)
+            }
+        };
+        *token == check.sell_token && *owner == check.owner
+    }
+
+    /// Performs all checks that don't require any on-chain state.
+    fn validate(
+        &self,
+        check: &PermitCheck,
+        vault_relayer: H160,
+        now: u32,
+    ) -> Result<(), PermitValidationError> {
+        let (spender, amount, deadlines) = match self {
+            Self::Eip2612 {
+                spender,
+                value,
+                deadline,
+                ..
+            } => (spender, value, vec![*deadline]),
+            Self::Permit2 {
+                spender,
+                amount,
+                expiration,
+                sig_deadline,
+                ..
+            } => (
+                spender,
+                amount,
+                // An expiration of 0 means the allowance expires within the
+                // block the permit gets used in.
+                std::iter::once(*sig_deadline)
+                    .chain((!expiration.is_zero()).then_some(*expiration))
+                    .collect(),
+            ),
+        };
+
+        if *spender != vault_relayer {
+            return Err(PermitValidationError::WrongSpender { actual: *spender });
+        }
+        if let Some(deadline) = deadlines
+            .into_iter()
+            .find(|deadline| *deadline < U256::from(now))
+        {
+            return Err(PermitValidationError::Expired { deadline });
+        }
+        if *amount < check.amount {
+            return Err(PermitValidationError::InsufficientAmount { amount: *amount });
+        }
+        Ok(())
+    }
+}
+
+/// The order a permit has to grant the allowance for.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PermitCheck {
+    pub owner: H160,
+    pub sell_token: H160,
+    /// The total amount which gets transferred from the owner.
+    pub amount: U256,
+    pub pre_hooks: Vec<Hook>,
+}
+
+#[derive(Debug, Error)]
+pub enum PermitValidationError {
+    #[error("permit grants the allowance to {actual:?} instead of the vault relayer")]
+    WrongSpender { actual: H160 },
+    #[error("permit expired at {deadline}")]
+    Expired { deadline: U256 },
+    #[error("permit allowance of {amount} does not cover the sell amount")]
+    InsufficientAmount { amount: U256 },
+    #[error("permit nonce {actual} does not match the current nonce {expected}")]
+    InvalidNonce { expected: U256, actual: U256 },
+    /// The signature doesn't recover to the owner. For EIP-2612 permits this
+    /// also happens if the nonce was already used since the nonce is not part
+    /// of the call data.
+    #[error("permit signature is invalid or its nonce was already used")]
+    InvalidSignature,
+    /// The permit could not be checked, e.g. because the node failed or the
+    /// token doesn't implement `DOMAIN_SEPARATOR`.
+    #[error("permit could not be verified: {0:?}")]
+    Unverifiable(#[from] anyhow::Error),
+}
+
+#[mockall::automock]
+#[async_trait::async_trait]
+pub trait PermitValidating: Send + Sync {
+    /// Validates all permits in the pre-hooks which grant an allowance of the
+    /// sell token from the owner. All other pre-hooks are ignored.
+    async fn validate_permits(&self, check: PermitCheck) -> Result<(), PermitValidationError>;
+}
+
+/// Creates the default [`PermitValidating`] instance.
+pub fn validator(web3: &Web3, vault_relayer: H160) -> Arc<dyn PermitValidating> {
+    Arc::new(onchain::Validator::new(web3, vault_relayer))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const OWNER: H160 = H160([1; 20]);
+    const TOKEN: H160 = H160([2; 20]);
+    const VAULT_RELAYER: H160 = H160([3; 20]);
+
+    fn eip2612(spender: H160, value: u64, deadline: u64) -> Hook {
+        Hook {
+            target: TOKEN,
+            call_data: [
+                ethabi::short_signature("permit", &eip2612_params()).to_vec(),
+                ethabi::encode(&[
+                    Token::Address(OWNER),
+                    Token::Address(spender),
+                    Token::Uint(value.into()),
+                    Token::Uint(deadline.into()),
+                    Token::Uint(27.into()),
+                    Token::FixedBytes(vec![4; 32]),
+                    Token::FixedBytes(vec![5; 32]),
+                ]),
+            ]
+            .concat(),
+            gas_limit: 50_000,
+        }
+    }
+
+    fn permit2(token: H160, amount: u64, expiration: u64, sig_deadline: u64) -> Hook {
+        Hook {
+            target: PERMIT2,
+            call_data: [
+                ethabi::short_signature("permit", &permit2_params()).to_vec(),
+                ethabi::encode(&[
+                    Token::Address(OWNER),
+                    Token::Tuple(vec![
+                        Token::Tuple(vec![
+                            Token::Address(token),
+                            Token::Uint(amount.into()),
+                            Token::Uint(expiration.into()),
+                            Token::Uint(7.into()),
+                        ]),
+                        Token::Address(VAULT_RELAYER),
+                        Token::Uint(sig_deadline.into()),
+                    ]),
+                    Token::Bytes(vec![0; 65]),
+                ]),
+            ]
+            .concat(),
+            gas_limit: 50_000,
+        }
+    }
+
+    fn check() -> PermitCheck {
+        PermitCheck {
+            owner: OWNER,
+            sell_token: TOKEN,
+            amount: 100.into(),
+            pre_hooks: Default::default(),
+        }
+    }
+
+    fn validate(hook: &Hook) -> Result<(), PermitValidationError> {
+        Permit::decode(hook)
+            .unwrap()
+            .validate(&check(), VAULT_RELAYER, 1_000)
+    }
+
+    #[test]
+    fn decodes_permits() {
+        assert_eq!(
+            Permit::decode(&eip2612(VAULT_RELAYER, 100, 2_000)),
+            Some(Permit::Eip2612 {
+                token: TOKEN,
+                owner: OWNER,
+                spender: VAULT_RELAYER,
+                value: 100.into(),
+                deadline: 2_000.into(),
+                signature: EcdsaSignature {
+                    r: H256([4; 32]),
+                    s: H256([5; 32]),
+                    v: 27,
+                },
+            })
+        );
+        assert_eq!(
+            Permit::decode(&permit2(TOKEN, 100, 0, 2_000)),
+            Some(Permit::Permit2 {
+                token: TOKEN,
+                owner: OWNER,
+                spender: VAULT_RELAYER,
+                amount: 100.into(),
+                expiration: 0.into(),
+                nonce: 7.into(),
+                sig_deadline: 2_000.into(),
+            })
+        );
+
+        // An EIP-2612 call on the Permit2 contract is not a permit.
+        let hook = Hook {
+            target: PERMIT2,
+            ..eip2612(VAULT_RELAYER, 100, 2_000)
+        };
+        assert_eq!(Permit::decode(&hook), None);
+        let hook = Hook {
+            call_data: vec![1, 2, 3],
+            ..eip2612(VAULT_RELAYER, 100, 2_000)
+        };
+        assert_eq!(Permit::decode(&hook), None);
+    }
+
+    #[test]
+    fn validates_permits() {
+        assert!(validate(&eip2612(VAULT_RELAYER, 100, 2_000)).is_ok());
+        assert!(validate(&permit2(TOKEN, 100, 0, 2_000)).is_ok());
+
+        assert!(matches!(
+            validate(&eip2612(H160([9; 20]), 100, 2_000)),
+            Err(PermitValidationError::WrongSpender { actual }) if actual == H160([9; 20])
+        ));
+        assert!(matches!(
+            validate(&eip2612(VAULT_RELAYER, 100, 999)),
+            Err(PermitValidationError::Expired { .. })
+        ));
+        assert!(matches!(
+            validate(&eip2612(VAULT_RELAYER, 99, 2_000)),
+            Err(PermitValidationError::InsufficientAmount { .. })
+        ));
+        assert!(matches!(
+            validate(&permit2(TOKEN, 100, 999, 2_000)),
+            Err(PermitValidationError::Expired { deadline }) if deadline == 999.into()
+        ));
+    }
+
+    #[test]
+    fn ignores_permits_for_other_tokens_and_owners() {
+        let permit = |hook: &Hook| Permit::decode(hook).unwrap();
+        assert!(permit(&permit2(TOKEN, 100, 0, 2_000)).applies_to(&check()));
+        assert!(!permit(&permit2(H160([9; 20]), 100, 0, 2_000)).applies_to(&check()));
+
+        let mut check = check();
+        check.owner = H160([9; 20]);
+        assert!(!permit(&eip2612(VAULT_RELAYER, 100, 2_000)).applies_to(&check));
+    }
+}
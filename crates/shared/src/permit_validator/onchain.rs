@@ -0,0 +1,193 @@
+//! Validates permits against the current on-chain state of the token and the
+//! Permit2 contract.
+
+use {
+    super::{Permit, PermitCheck, PermitValidating, PermitValidationError, PERMIT2},
+    anyhow::{Context, Result},
+    ethrpc::Web3,
+    model::{signature::EcdsaSigningScheme, DomainSeparator},
+    primitive_types::{H160, U256},
+    web3::{
+        ethabi::{self, ParamType, Token},
+        signing::keccak256,
+        types::CallRequest,
+    },
+};
+
+pub struct Validator {
+    web3: Web3,
+    vault_relayer: H160,
+}
+
+impl Validator {
+    pub fn new(web3: &Web3, vault_relayer: H160) -> Self {
+        let web3 = ethrpc::instrumented::instrument_with_label(web3, "permitValidation".into());
+        Self {
+            web3,
+            vault_relayer,
+        }
+    }
+
+    async fn validate(
+        &self,
+        permit: &Permit,
+        check: &PermitCheck,
+    ) -> Result<(), PermitValidationError> {
+        permit.validate(
+            check,
+            self.vault_relayer,
+            model::time::now_in_epoch_seconds(),
+        )?;
+        match permit {
+            Permit::Eip2612 {
+                token,
+                owner,
+                spender,
+                value,
+                deadline,
+                signature,
+            } => {
+                // The nonce is not part of the call data, so the signature can
+                // only be checked against the token's current nonce.
+                let domain_separator = self
+                    .call(*token, "DOMAIN_SEPARATOR", &[], ParamType::FixedBytes(32))
+                    .await?
+                    .into_fixed_bytes()
+                    .and_then(|bytes| bytes.try_into().ok())
+                    .context("invalid DOMAIN_SEPARATOR")?;
+                let nonce = self.nonce(*token, &[Token::Address(*owner)]).await?;
+                let struct_hash = keccak256(&ethabi::encode(&[
+                    Token::FixedBytes(EIP2612_PERMIT_TYPE_HASH.to_vec()),
+                    Token::Address(*owner),
+                    Token::Address(*spender),
+                    Token::Uint(*value),
+                    Token::Uint(nonce),
+                    Token::Uint(*deadline),
+                ]));
+                let signer = signature
+                    .recover(
+                        EcdsaSigningScheme::Eip712,
+                        &DomainSeparator(domain_separator),
+                        &struct_hash,
+                    )
+                    .map_err(|_| PermitValidationError::InvalidSignature)?
+                    .signer;
+                if signer != *owner {
+                    return Err(PermitValidationError::InvalidSignature);
+                }
+            }
+            Permit::Permit2 {
+                token,
+                owner,
+                spender,
+                nonce,
+                ..
+            } => {
+                let expected = self
+                    .nonce(
+                        PERMIT2,
+                        &[
+                            Token::Address(*owner),
+                            Token::Address(*token),
+                            Token::Address(*spender),
+                        ],
+                    )
+                    .await?;
+                if *nonce != expected {
+                    return Err(PermitValidationError::InvalidNonce {
+                        expected,
+                        actual: *nonce,
+                    });
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Fetches the nonce which has to be signed by the next permit. That is
+    /// `nonces(owner)` for EIP-2612 tokens and the nonce returned by
+    /// `allowance(owner, token, spender)` for Permit2.
+    async fn nonce(&self, target: H160, params: &[Token]) -> Result<U256> {
+        let nonce = if target == PERMIT2 {
+            let output = self
+                .call(
+                    target,
+                    "allowance",
+                    params,
+                    ParamType::Tuple(vec![
+                        ParamType::Uint(160),
+                        ParamType::Uint(48),
+                        ParamType::Uint(48),
+                    ]),
+                )
+                .await?;
+            output
+                .into_tuple()
+                .and_then(|mut fields| fields.pop())
+                .and_then(Token::into_uint)
+        } else {
+            self.call(target, "nonces", params, ParamType::Uint(256))
+                .await?
+                .into_uint()
+        };
+        nonce.context("invalid nonce returned")
+    }
+
+    async fn call(
+        &self,
+        target: H160,
+        function: &str,
+        params: &[Token],
+        output: ParamType,
+    ) -> Result<Token> {
+        let types = params.iter().map(param_type).collect::<Vec<_>>();
+        let data = [
+            ethabi::short_signature(function, &types).to_vec(),
+            ethabi::encode(params),
+        ]
+        .concat();
+        let request = CallRequest {
+            to: Some(target),
+            data: Some(data.into()),
+            ..Default::default()
+        };
+        let result = self
+            .web3
+            .eth()
+            .call(request, None)
+            .await
+            .with_context(|| format!("{function} call failed"))?;
+        ethabi::decode(&[output], &result.0)
+            .ok()
+            .and_then(|mut tokens| tokens.pop())
+            .with_context(|| format!("invalid {function} output"))
+    }
+}
+
+/// `keccak256("Permit(address owner,address spender,uint256 value,uint256
+/// nonce,uint256 deadline)")`
+const EIP2612_PERMIT_TYPE_HASH: [u8; 32] =
+    hex_literal::hex!("6e71edae12b1b97f4d1f60370fef10105fa2faae0126114a169c64845d6126c9");
+
+fn param_type(token: &Token) -> ParamType {
+    match token {
+        Token::Address(_) => ParamType::Address,
+        _ => ParamType::Uint(256),
+    }
+}
+
+#[async_trait::async_trait]
+impl PermitValidating for Validator {
+    async fn validate_permits(&self, check: PermitCheck) -> Result<(), PermitValidationError> {
+        let permits = check
+            .pre_hooks
+            .iter()
+            .filter_map(Permit::decode)
+            .filter(|permit| permit.applies_to(&check));
+        for permit in permits {
+            tracing::debug!(?permit, "validating permit");
+            self.validate(&permit, &check).await?;
+        }
+        Ok(())
+    }
+}
@@ -151,7 +151,7 @@ impl<T: Send + Sync + 'static> CompetitionEstimator<T> {
     }
 }
 
-fn compare_error(a: &PriceEstimationError, b: &PriceEstimationError) -> Ordering {
+pub(crate) fn compare_error(a: &PriceEstimationError, b: &PriceEstimationError) -> Ordering {
     // Errors are sorted by recoverability. E.g. a rate-limited estimation may
     // succeed if tried again, whereas unsupported order types can never recover
     // unless code changes. This can be used to decide which errors we want to
@@ -2,11 +2,13 @@ use {
     super::{
         competition::CompetitionEstimator,
         external::ExternalPriceEstimator,
+        historical::HistoricalNativePriceEstimating,
         instrumented::InstrumentedPriceEstimator,
         native::{self, NativePriceEstimator},
+        native_price_aggregation::{AggregatingNativePriceEstimator, Smoothing},
         native_price_cache::CachingNativePriceEstimator,
         sanitized::SanitizedPriceEstimator,
-        trade_verifier::{TradeVerifier, TradeVerifying},
+        trade_verifier::{balance_overrides::StrategyStoring, TradeVerifier, TradeVerifying},
         Arguments,
         NativePriceEstimator as NativePriceEstimatorSource,
         PriceEstimating,
@@ -24,6 +26,7 @@ use {
             competition::PriceRanking,
             native::NativePriceEstimating,
         },
+        sources::uniswap_v3::graph_api::UniV3SubgraphClient,
         token_info::TokenInfoFetching,
     },
     anyhow::{Context as _, Result},
@@ -43,6 +46,7 @@ pub struct PriceEstimatorFactory<'a> {
     components: Components,
     trade_verifier: Option<Arc<dyn TradeVerifying>>,
     estimators: HashMap<String, EstimatorEntry>,
+    uniswap_v3_twap: Option<native::UniswapV3Twap>,
 }
 
 #[derive(Clone)]
@@ -70,6 +74,7 @@ pub struct Components {
     pub bad_token_detector: Arc<dyn BadTokenDetecting>,
     pub tokens: Arc<dyn TokenInfoFetching>,
     pub code_fetcher: Arc<CachedCodeFetcher>,
+    pub balance_override_storage: Arc<dyn StrategyStoring>,
 }
 
 impl<'a> PriceEstimatorFactory<'a> {
@@ -85,6 +90,7 @@ impl<'a> PriceEstimatorFactory<'a> {
             network,
             components,
             estimators: HashMap::new(),
+            uniswap_v3_twap: None,
         })
     }
 
@@ -113,7 +119,11 @@ impl<'a> PriceEstimatorFactory<'a> {
             None => Arc::new(web3.clone()),
         };
 
-        let balance_overrides = args.balance_overrides.init(simulator.clone());
+        let balance_overrides = args.balance_overrides.init(
+            simulator.clone(),
+            components.balance_override_storage.clone(),
+            components.code_fetcher.clone(),
+        );
 
         let verifier = TradeVerifier::new(
             web3,
@@ -270,7 +280,66 @@ impl<'a> PriceEstimatorFactory<'a> {
 
                 Ok((name, coin_gecko))
             }
+            NativePriceEstimatorSource::UniswapV3Twap => {
+                let name = "UniswapV3Twap".to_string();
+                Ok((
+                    name.clone(),
+                    Arc::new(InstrumentedPriceEstimator::new(
+                        self.uniswap_v3_twap().await?,
+                        name,
+                    )),
+                ))
+            }
+        }
+    }
+
+    /// Returns the Uniswap V3 TWAP estimator which gets created on first use
+    /// since loading the pools is expensive.
+    async fn uniswap_v3_twap(&mut self) -> Result<native::UniswapV3Twap> {
+        if let Some(twap) = &self.uniswap_v3_twap {
+            return Ok(twap.clone());
+        }
+        let args = &self.args.uniswap_v3_twap;
+        let subgraph_url = args
+            .uniswap_v3_twap_subgraph_url
+            .as_ref()
+            .context("UniswapV3Twap native price estimator requires a subgraph URL")?;
+        let twap = native::UniswapV3Twap::new(
+            &self.network.web3,
+            UniV3SubgraphClient::from_subgraph_url(
+                subgraph_url,
+                self.components.http_factory.create(),
+            )?,
+            self.network.native_token,
+            self.network.base_tokens.clone(),
+            args.uniswap_v3_twap_window,
+            args.uniswap_v3_twap_min_liquidity,
+            args.uniswap_v3_twap_pool_refresh_interval,
+        )
+        .await?;
+        self.uniswap_v3_twap = Some(twap.clone());
+        Ok(twap)
+    }
+
+    /// Creates the estimator for historical native prices which are not
+    /// covered by stored auction prices. Uses Uniswap V3 TWAPs if a subgraph
+    /// is configured for them.
+    pub async fn historical_native_price_fallback(
+        &mut self,
+    ) -> Result<Option<Arc<dyn HistoricalNativePriceEstimating>>> {
+        if self
+            .args
+            .uniswap_v3_twap
+            .uniswap_v3_twap_subgraph_url
+            .is_none()
+        {
+            return Ok(None);
         }
+        let twap = self
+            .uniswap_v3_twap()
+            .await
+            .context("failed to create Uniswap V3 TWAP estimator")?;
+        Ok(Some(Arc::new(twap)))
     }
 
     fn get_estimator(&mut self, solver: &ExternalSolver) -> Result<&EstimatorEntry> {
@@ -365,12 +434,14 @@ impl<'a> PriceEstimatorFactory<'a> {
             estimators.push(stages);
         }
 
-        let competition_estimator =
-            CompetitionEstimator::new(estimators, PriceRanking::MaxOutAmount)
-                .with_verification(self.args.quote_verification)
-                .with_early_return(results_required);
+        let estimator = combine_native_estimators(
+            self.args,
+            estimators,
+            results_required,
+            self.network.block_stream.clone(),
+        );
         let native_estimator = Arc::new(CachingNativePriceEstimator::new(
-            Box::new(competition_estimator),
+            estimator,
             self.args.native_price_cache_max_age,
             self.args.native_price_cache_refresh,
             Some(self.args.native_price_cache_max_update_size),
@@ -418,9 +489,88 @@ impl PriceEstimatorCreating for ExternalPriceEstimator {
     }
 }
 
+/// Combines the stages of native price estimators into a single estimator
+/// which either aggregates their prices or picks the best one.
+fn combine_native_estimators(
+    args: &Arguments,
+    stages: Vec<Vec<(String, Arc<dyn NativePriceEstimating>)>>,
+    results_required: NonZeroUsize,
+    current_block: CurrentBlockWatcher,
+) -> Box<dyn NativePriceEstimating> {
+    if args.native_price_aggregation {
+        Box::new(AggregatingNativePriceEstimator::new(
+            stages,
+            results_required,
+            args.native_price_aggregation_max_deviation,
+            args.native_price_aggregation_smoothing_blocks
+                .map(|blocks| Smoothing {
+                    blocks,
+                    current_block,
+                }),
+        ))
+    } else {
+        Box::new(
+            CompetitionEstimator::new(stages, PriceRanking::MaxOutAmount)
+                .with_verification(args.quote_verification)
+                .with_early_return(results_required),
+        )
+    }
+}
+
 fn instrument<T: PriceEstimating>(
     estimator: T,
     name: impl Into<String>,
 ) -> Arc<InstrumentedPriceEstimator<T>> {
     Arc::new(InstrumentedPriceEstimator::new(estimator, name.into()))
 }
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::price_estimation::native::MockNativePriceEstimating,
+        clap::Parser,
+        ethrpc::block_stream::BlockInfo,
+        futures::FutureExt,
+    };
+
+    fn source(price: Option<f64>) -> (String, Arc<dyn NativePriceEstimating>) {
+        let mut source = MockNativePriceEstimating::new();
+        match price {
+            Some(price) => {
+                source
+                    .expect_estimate_native_price()
+                    .returning(move |_| futures::future::ready(Ok(price)).boxed());
+            }
+            None => {
+                source.expect_estimate_native_price().never();
+            }
+        }
+        ("source".to_owned(), Arc::new(source))
+    }
+
+    #[tokio::test]
+    async fn aggregates_native_prices_of_stages_with_default_results_required() {
+        let args =
+            Arguments::try_parse_from(["test", "--native-price-aggregation", "true"]).unwrap();
+        // The autopilot's default for `--native-price-estimation-results-required`.
+        let results_required = NonZeroUsize::new(2).unwrap();
+        let (_, current_block) = tokio::sync::watch::channel(BlockInfo::default());
+
+        // The outlier of the first stage gets rejected instead of winning the
+        // competition and the fallback stage never gets queried.
+        let estimator = combine_native_estimators(
+            &args,
+            vec![
+                vec![source(Some(1.0)), source(Some(1.0)), source(Some(10.0))],
+                vec![source(None)],
+            ],
+            results_required,
+            current_block,
+        );
+        assert_eq!(
+            estimator.estimate_native_price(H160::zero()).await.unwrap(),
+            1.0
+        );
+    }
+}
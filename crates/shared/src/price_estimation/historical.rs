@@ -0,0 +1,276 @@
+//! Native prices of tokens at past blocks. These are needed to value past
+//! trades and fees in the native token. Prices are primarily taken from the
+//! native prices stored with every auction and interpolated between the
+//! auctions surrounding the requested block.
+
+use {
+    super::{
+        native::{NativePrice, NativePriceEstimateResult},
+        PriceEstimationError,
+    },
+    anyhow::{Context as _, Result},
+    ethrpc::Web3,
+    futures::{future::BoxFuture, FutureExt},
+    primitive_types::H160,
+    std::{future::Future, sync::Arc},
+    web3::types::{BlockId, BlockNumber},
+};
+
+/// Native price of a token in an auction created on top of `block`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct BlockPrice {
+    pub block: u64,
+    pub price: NativePrice,
+}
+
+/// Provides the native prices stored with past auctions.
+#[mockall::automock]
+#[async_trait::async_trait]
+pub trait AuctionPriceFetching: Send + Sync + 'static {
+    /// Returns the native price of the token in the latest auction created at
+    /// or before the block and in the earliest auction created after it.
+    async fn surrounding_prices(
+        &self,
+        token: H160,
+        block: u64,
+    ) -> Result<(Option<BlockPrice>, Option<BlockPrice>)>;
+}
+
+#[mockall::automock]
+pub trait HistoricalNativePriceEstimating: Send + Sync {
+    /// Like `NativePriceEstimating::estimate_native_price` but for the state
+    /// of the chain at the given block.
+    fn estimate_native_price_at(
+        &self,
+        token: H160,
+        block: u64,
+    ) -> BoxFuture<'_, NativePriceEstimateResult>;
+}
+
+/// Interpolates historical native prices from the stored auction prices and
+/// falls back to another estimator if there are no auctions close enough to
+/// the requested block.
+pub struct HistoricalNativePriceEstimator {
+    auction_prices: Arc<dyn AuctionPriceFetching>,
+    fallback: Option<Arc<dyn HistoricalNativePriceEstimating>>,
+    /// Auction prices more than this many blocks away from the requested
+    /// block are not used.
+    max_block_distance: u64,
+}
+
+impl HistoricalNativePriceEstimator {
+    pub fn new(
+        auction_prices: Arc<dyn AuctionPriceFetching>,
+        fallback: Option<Arc<dyn HistoricalNativePriceEstimating>>,
+        max_block_distance: u64,
+    ) -> Self {
+        Self {
+            auction_prices,
+            fallback,
+            max_block_distance,
+        }
+    }
+
+    async fn estimate(&self, token: H160, block: u64) -> NativePriceEstimateResult {
+        let (before, after) = self
+            .auction_prices
+            .surrounding_prices(token, block)
+            .await
+            .map_err(PriceEstimationError::ProtocolInternal)?;
+        if let Some(price) = interpolate(before, after, block, self.max_block_distance) {
+            return Ok(price);
+        }
+
+        match &self.fallback {
+            Some(fallback) => fallback.estimate_native_price_at(token, block).await,
+            None => Err(PriceEstimationError::NoLiquidity),
+        }
+    }
+}
+
+impl HistoricalNativePriceEstimating for HistoricalNativePriceEstimator {
+    fn estimate_native_price_at(
+        &self,
+        token: H160,
+        block: u64,
+    ) -> BoxFuture<'_, NativePriceEstimateResult> {
+        self.estimate(token, block).boxed()
+    }
+}
+
+/// Linearly interpolates the price at the block between the surrounding
+/// prices. If only one of them is close enough to the block its price is used
+/// as is.
+fn interpolate(
+    before: Option<BlockPrice>,
+    after: Option<BlockPrice>,
+    block: u64,
+    max_block_distance: u64,
+) -> Option<NativePrice> {
+    let before = before.filter(|price| block.saturating_sub(price.block) <= max_block_distance);
+    let after = after.filter(|price| price.block.saturating_sub(block) <= max_block_distance);
+    match (before, after) {
+        (Some(before), Some(after)) if before.block < after.block => {
+            let weight =
+                block.saturating_sub(before.block) as f64 / (after.block - before.block) as f64;
+            Some(before.price + (after.price - before.price) * weight)
+        }
+        (Some(price), _) | (None, Some(price)) => Some(price.price),
+        (None, None) => None,
+    }
+}
+
+/// Returns the latest block mined at or before the timestamp. Returns
+/// [`None`] if the timestamp is before the genesis block or after the latest
+/// block.
+pub async fn block_at_timestamp(web3: &Web3, timestamp: u64) -> Result<Option<u64>> {
+    let block_timestamp = |block: BlockNumber| async move {
+        let block = web3
+            .eth()
+            .block(BlockId::Number(block))
+            .await?
+            .context("missing block")?;
+        let number = block.number.context("missing block number")?.as_u64();
+        Ok::<_, anyhow::Error>((number, block.timestamp.as_u64()))
+    };
+
+    let latest = block_timestamp(BlockNumber::Latest).await?;
+    if timestamp >= latest.1 {
+        return Ok((timestamp == latest.1).then_some(latest.0));
+    }
+    let genesis = block_timestamp(BlockNumber::Earliest).await?;
+    if timestamp < genesis.1 {
+        return Ok(None);
+    }
+    let block = search_block(genesis, latest, timestamp, |block| async move {
+        Ok(block_timestamp(BlockNumber::Number(block.into())).await?.1)
+    })
+    .await?;
+    Ok(Some(block))
+}
+
+/// Searches the latest block mined at or before the timestamp between the
+/// `low` and `high` blocks, given as block number and timestamp, where `low`
+/// was mined at or before the timestamp and `high` after it.
+///
+/// Since block times are roughly constant, the block is first estimated by
+/// interpolating between the surrounding blocks, which usually finds it in a
+/// handful of requests. Bisection takes over if that doesn't converge.
+async fn search_block<F, Fut>(
+    mut low: (u64, u64),
+    mut high: (u64, u64),
+    timestamp: u64,
+    block_timestamp: F,
+) -> Result<u64>
+where
+    F: Fn(u64) -> Fut,
+    Fut: Future<Output = Result<u64>>,
+{
+    const MAX_INTERPOLATIONS: usize = 8;
+
+    let mut steps = 0;
+    while high.0 - low.0 > 1 {
+        let block = if steps < MAX_INTERPOLATIONS {
+            let elapsed = u128::from(timestamp - low.1);
+            let estimate =
+                low.0 + (elapsed * u128::from(high.0 - low.0) / u128::from(high.1 - low.1)) as u64;
+            estimate.clamp(low.0 + 1, high.0 - 1)
+        } else {
+            low.0 + (high.0 - low.0) / 2
+        };
+        steps += 1;
+        let block_timestamp = block_timestamp(block).await?;
+        if block_timestamp <= timestamp {
+            low = (block, block_timestamp);
+        } else {
+            high = (block, block_timestamp);
+        }
+    }
+    Ok(low.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn price(block: u64, price: f64) -> Option<BlockPrice> {
+        Some(BlockPrice { block, price })
+    }
+
+    #[test]
+    fn interpolates_between_surrounding_prices() {
+        assert_eq!(interpolate(price(10, 1.), price(20, 2.), 15, 10), Some(1.5));
+        assert_eq!(interpolate(price(10, 1.), price(20, 2.), 10, 10), Some(1.));
+        assert_eq!(interpolate(price(10, 1.), price(30, 2.), 15, 10), Some(1.));
+        assert_eq!(interpolate(price(0, 1.), price(20, 2.), 15, 10), Some(2.));
+        assert_eq!(interpolate(None, price(20, 2.), 15, 10), Some(2.));
+        assert_eq!(interpolate(price(0, 1.), price(30, 2.), 15, 10), None);
+        assert_eq!(interpolate(None, None, 15, 10), None);
+    }
+
+    #[tokio::test]
+    async fn falls_back_without_close_auction_prices() {
+        let mut auction_prices = MockAuctionPriceFetching::new();
+        auction_prices
+            .expect_surrounding_prices()
+            .returning(|_, block| Ok((price(block - 100, 1.), None)));
+        let mut fallback = MockHistoricalNativePriceEstimating::new();
+        fallback
+            .expect_estimate_native_price_at()
+            .withf(|_, block| *block == 1_000)
+            .returning(|_, _| futures::future::ready(Ok(3.)).boxed());
+        let auction_prices: Arc<dyn AuctionPriceFetching> = Arc::new(auction_prices);
+
+        let estimator = HistoricalNativePriceEstimator::new(auction_prices.clone(), None, 100);
+        assert_eq!(
+            estimator
+                .estimate_native_price_at(H160::zero(), 1_000)
+                .await
+                .unwrap(),
+            1.
+        );
+
+        let estimator = HistoricalNativePriceEstimator::new(auction_prices.clone(), None, 10);
+        assert!(matches!(
+            estimator
+                .estimate_native_price_at(H160::zero(), 1_000)
+                .await,
+            Err(PriceEstimationError::NoLiquidity)
+        ));
+
+        let estimator =
+            HistoricalNativePriceEstimator::new(auction_prices, Some(Arc::new(fallback)), 10);
+        assert_eq!(
+            estimator
+                .estimate_native_price_at(H160::zero(), 1_000)
+                .await
+                .unwrap(),
+            3.
+        );
+    }
+
+    #[test]
+    fn searches_block_at_timestamp() {
+        // Block times between 2 and 22 seconds.
+        let timestamps: Vec<u64> = (0..10_000).map(|b| 1_000 + 12 * b + (b * b) % 11).collect();
+        let latest = timestamps.len() as u64 - 1;
+        for timestamp in (timestamps[0]..timestamps[latest as usize]).step_by(7) {
+            let requests = std::cell::Cell::new(0);
+            let block = search_block(
+                (0, timestamps[0]),
+                (latest, timestamps[latest as usize]),
+                timestamp,
+                |block| {
+                    requests.set(requests.get() + 1);
+                    futures::future::ready(Ok(timestamps[block as usize]))
+                },
+            )
+            .now_or_never()
+            .unwrap()
+            .unwrap();
+            let expected = timestamps.partition_point(|t| *t <= timestamp) as u64 - 1;
+            assert_eq!(block, expected);
+            assert!(requests.get() <= 4, "{} requests", requests.get());
+        }
+    }
+}
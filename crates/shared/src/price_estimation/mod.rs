@@ -31,8 +31,10 @@ pub mod competition;
 pub mod external;
 pub mod factory;
 pub mod gas;
+pub mod historical;
 pub mod instrumented;
 pub mod native;
+pub mod native_price_aggregation;
 pub mod native_price_cache;
 pub mod sanitized;
 pub mod trade_finder;
@@ -46,6 +48,7 @@ pub enum NativePriceEstimator {
     Driver(ExternalSolver),
     OneInchSpotPriceApi,
     CoinGecko,
+    UniswapV3Twap,
 }
 
 impl Display for NativePriceEstimator {
@@ -54,6 +57,7 @@ impl Display for NativePriceEstimator {
             NativePriceEstimator::Driver(s) => format!("{}|{}", &s.name, s.url),
             NativePriceEstimator::OneInchSpotPriceApi => "OneInchSpotPriceApi".into(),
             NativePriceEstimator::CoinGecko => "CoinGecko".into(),
+            NativePriceEstimator::UniswapV3Twap => "UniswapV3Twap".into(),
         };
         write!(f, "{}", formatter)
     }
@@ -104,6 +108,7 @@ impl FromStr for NativePriceEstimator {
         match s {
             "OneInchSpotPriceApi" => Ok(NativePriceEstimator::OneInchSpotPriceApi),
             "CoinGecko" => Ok(NativePriceEstimator::CoinGecko),
+            "UniswapV3Twap" => Ok(NativePriceEstimator::UniswapV3Twap),
             estimator => Ok(NativePriceEstimator::Driver(ExternalSolver::from_str(
                 estimator,
             )?)),
@@ -165,6 +170,24 @@ pub struct Arguments {
     #[clap(long, env, default_value = "1")]
     pub native_price_cache_concurrent_requests: usize,
 
+    /// Combine the prices of the native price estimators instead of using the
+    /// best one. Prices that deviate too much from the median of all prices
+    /// get rejected as outliers. Later stages only get queried if the earlier
+    /// ones didn't produce enough prices which agree with each other.
+    #[clap(long, env, action = clap::ArgAction::Set, default_value = "false")]
+    pub native_price_aggregation: bool,
+
+    /// How many median absolute deviations a native price may deviate from the
+    /// median of all estimators before it gets rejected as an outlier.
+    #[clap(long, env, default_value = "3")]
+    pub native_price_aggregation_max_deviation: f64,
+
+    /// Number of recent blocks aggregated native prices get averaged over,
+    /// weighted by how many blocks each price was the latest one. Smoothing
+    /// is disabled if not set.
+    #[clap(long, env)]
+    pub native_price_aggregation_smoothing_blocks: Option<u64>,
+
     /// The amount in native tokens atoms to use for price estimation. Should be
     /// reasonably large so that small pools do not influence the prices. If
     /// not set a reasonable default is used based on network id.
@@ -187,6 +210,10 @@ pub struct Arguments {
     #[clap(flatten)]
     pub coin_gecko: CoinGecko,
 
+    /// The Uniswap V3 TWAP native price configuration
+    #[clap(flatten)]
+    pub uniswap_v3_twap: UniswapV3Twap,
+
     /// How inaccurate a quote must be before it gets discarded provided as a
     /// factor.
     /// E.g. a value of `0.01` means at most 1 percent of the sell or buy tokens
@@ -263,6 +290,28 @@ pub struct CoinGeckoBuffered {
     pub coin_gecko_broadcast_channel_capacity: Option<usize>,
 }
 
+#[derive(clap::Parser)]
+pub struct UniswapV3Twap {
+    /// The Uniswap V3 subgraph the pools for TWAP native prices are loaded
+    /// from. Required when using the `UniswapV3Twap` native price estimator.
+    #[clap(long, env)]
+    pub uniswap_v3_twap_subgraph_url: Option<Url>,
+
+    /// The time window Uniswap V3 pool prices get averaged over.
+    #[clap(long, env, default_value = "30m", value_parser = humantime::parse_duration)]
+    pub uniswap_v3_twap_window: Duration,
+
+    /// The minimum total value locked in a Uniswap V3 pool, denominated in the
+    /// native token, for the pool to be used for TWAP native prices.
+    #[clap(long, env, default_value = "10")]
+    pub uniswap_v3_twap_min_liquidity: f64,
+
+    /// How often the Uniswap V3 pools and their liquidity get reloaded from
+    /// the subgraph.
+    #[clap(long, env, default_value = "1h", value_parser = humantime::parse_duration)]
+    pub uniswap_v3_twap_pool_refresh_interval: Duration,
+}
+
 /// Controls which level of quote verification gets applied.
 #[derive(Copy, Clone, Debug, clap::ValueEnum)]
 #[clap(rename_all = "kebab-case")]
@@ -286,11 +335,15 @@ impl Display for Arguments {
             native_price_prefetch_time,
             native_price_cache_max_update_size,
             native_price_cache_concurrent_requests,
+            native_price_aggregation,
+            native_price_aggregation_max_deviation,
+            native_price_aggregation_smoothing_blocks,
             amount_to_estimate_prices_with,
             balancer_sor_url,
             one_inch_api_key,
             one_inch_url,
             coin_gecko,
+            uniswap_v3_twap,
             quote_inaccuracy_limit,
             quote_verification,
             quote_timeout,
@@ -327,6 +380,17 @@ impl Display for Arguments {
             "native_price_cache_concurrent_requests: {}",
             native_price_cache_concurrent_requests
         )?;
+        writeln!(f, "native_price_aggregation: {}", native_price_aggregation)?;
+        writeln!(
+            f,
+            "native_price_aggregation_max_deviation: {}",
+            native_price_aggregation_max_deviation
+        )?;
+        display_option(
+            f,
+            "native_price_aggregation_smoothing_blocks",
+            native_price_aggregation_smoothing_blocks,
+        )?;
         display_option(
             f,
             "amount_to_estimate_prices_with: {}",
@@ -369,6 +433,26 @@ impl Display for Arguments {
                 |coin_gecko_buffered| coin_gecko_buffered.coin_gecko_broadcast_channel_capacity
             ),
         )?;
+        display_option(
+            f,
+            "uniswap_v3_twap_subgraph_url",
+            &uniswap_v3_twap.uniswap_v3_twap_subgraph_url,
+        )?;
+        writeln!(
+            f,
+            "uniswap_v3_twap_window: {:?}",
+            uniswap_v3_twap.uniswap_v3_twap_window
+        )?;
+        writeln!(
+            f,
+            "uniswap_v3_twap_min_liquidity: {}",
+            uniswap_v3_twap.uniswap_v3_twap_min_liquidity
+        )?;
+        writeln!(
+            f,
+            "uniswap_v3_twap_pool_refresh_interval: {:?}",
+            uniswap_v3_twap.uniswap_v3_twap_pool_refresh_interval
+        )?;
         writeln!(f, "quote_inaccuracy_limit: {}", quote_inaccuracy_limit)?;
         writeln!(f, "quote_verification: {:?}", quote_verification)?;
         writeln!(f, "quote_timeout: {:?}", quote_timeout)?;
@@ -570,6 +654,7 @@ mod tests {
             )
             .to_string(),
             &NativePriceEstimator::OneInchSpotPriceApi.to_string(),
+            &NativePriceEstimator::UniswapV3Twap.to_string(),
             "one|http://localhost:1111/,two|http://localhost:2222/;three|http://localhost:3333/,four|http://localhost:4444/",
             &format!("one|http://localhost:1111/,two|http://localhost:2222/;{},four|http://localhost:4444/", NativePriceEstimator::OneInchSpotPriceApi),
         ] {
@@ -11,8 +11,9 @@ use {
 
 mod coingecko;
 mod oneinch;
+mod uniswap_v3_twap;
 
-pub use self::{coingecko::CoinGecko, oneinch::OneInch};
+pub use self::{coingecko::CoinGecko, oneinch::OneInch, uniswap_v3_twap::UniswapV3Twap};
 
 pub type NativePrice = f64;
 pub type NativePriceEstimateResult = Result<NativePrice, PriceEstimationError>;
@@ -0,0 +1,360 @@
+//! Native prices from the time weighted average prices of Uniswap V3 pools.
+//! The prices are read on-chain so they remain available when external price
+//! APIs are down and are hard to manipulate within a single block.
+
+use {
+    super::{NativePriceEstimateResult, NativePriceEstimating},
+    crate::{
+        baseline_solver::BaseTokens,
+        price_estimation::{historical::HistoricalNativePriceEstimating, PriceEstimationError},
+        sources::uniswap_v3::graph_api::{PoolData, UniV3SubgraphClient},
+    },
+    anyhow::{Context, Result},
+    contracts::{errors::EthcontractErrorType, UniswapV3Pool},
+    ethrpc::Web3,
+    futures::{future::BoxFuture, FutureExt},
+    model::TokenPair,
+    primitive_types::H160,
+    std::{
+        collections::HashMap,
+        sync::{Arc, Mutex},
+        time::Duration,
+    },
+    web3::types::{BlockId, BlockNumber},
+};
+
+/// The pool with the most value locked of a token pair.
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct Pool {
+    address: H160,
+    token0: H160,
+    /// Total value locked in the pool denominated in the native token.
+    liquidity: f64,
+}
+
+/// Cheap to clone since the loaded pools are shared between clones.
+#[derive(Clone)]
+pub struct UniswapV3Twap {
+    web3: Web3,
+    native_token: H160,
+    base_tokens: Arc<BaseTokens>,
+    pools: Arc<Mutex<HashMap<TokenPair, Pool>>>,
+    /// Number of seconds prices get averaged over.
+    window: u32,
+}
+
+impl UniswapV3Twap {
+    /// Loads all pools from the subgraph and keeps the most liquid pool of
+    /// every token pair which has at least `min_liquidity` native token
+    /// locked. The pools and their liquidity get reloaded every
+    /// `refresh_interval`.
+    pub async fn new(
+        web3: &Web3,
+        subgraph: UniV3SubgraphClient,
+        native_token: H160,
+        base_tokens: Arc<BaseTokens>,
+        window: Duration,
+        min_liquidity: f64,
+        refresh_interval: Duration,
+    ) -> Result<Self> {
+        anyhow::ensure!(!window.is_zero(), "TWAP window must not be zero");
+        let pools = load_pools(&subgraph, min_liquidity)
+            .await
+            .context("failed to load Uniswap V3 pools for TWAP prices")?;
+        let instance = Self {
+            web3: ethrpc::instrumented::instrument_with_label(web3, "uniswapV3Twap".into()),
+            native_token,
+            base_tokens,
+            pools: Arc::new(Mutex::new(pools)),
+            window: window
+                .as_secs()
+                .try_into()
+                .context("TWAP window too large")?,
+        };
+        instance.refresh_pools_in_background(subgraph, min_liquidity, refresh_interval);
+        Ok(instance)
+    }
+
+    fn refresh_pools_in_background(
+        &self,
+        subgraph: UniV3SubgraphClient,
+        min_liquidity: f64,
+        refresh_interval: Duration,
+    ) {
+        let pools = self.pools.clone();
+        tokio::task::spawn(async move {
+            loop {
+                tokio::time::sleep(refresh_interval).await;
+                match load_pools(&subgraph, min_liquidity).await {
+                    Ok(loaded) => *pools.lock().unwrap() = loaded,
+                    Err(err) => {
+                        tracing::warn!(?err, "failed to refresh Uniswap V3 pools for TWAP prices")
+                    }
+                }
+            }
+        });
+    }
+
+    /// Returns the pools (together with the token sold into them) along the
+    /// most liquid path from the token to the native token. Paths either use
+    /// a direct pool or a single intermediate base token and are compared by
+    /// the liquidity of their shallowest pool.
+    fn route(&self, token: H160) -> Option<Vec<(Pool, H160)>> {
+        let pools = self.pools.lock().unwrap();
+        let pool = |a: H160, b: H160| pools.get(&TokenPair::new(a, b)?).copied();
+        let direct = pool(token, self.native_token).map(|direct| vec![(direct, token)]);
+        let via_base_tokens = self
+            .base_tokens
+            .tokens()
+            .iter()
+            .filter(|base| ![token, self.native_token].contains(base))
+            .filter_map(|base| {
+                let first = pool(token, *base)?;
+                let second = pool(*base, self.native_token)?;
+                Some(vec![(first, token), (second, *base)])
+            });
+        let liquidity = |route: &Vec<(Pool, H160)>| {
+            route
+                .iter()
+                .map(|(pool, _)| pool.liquidity)
+                .fold(f64::INFINITY, f64::min)
+        };
+        direct.into_iter().chain(via_base_tokens).max_by(|a, b| {
+            liquidity(a)
+                .total_cmp(&liquidity(b))
+                // Prefer shorter routes if they are equally liquid.
+                .then(b.len().cmp(&a.len()))
+        })
+    }
+
+    async fn estimate(&self, token: H160, block: BlockId) -> NativePriceEstimateResult {
+        if token == self.native_token {
+            return Ok(1.);
+        }
+        let route = self.route(token).ok_or(PriceEstimationError::NoLiquidity)?;
+        let prices = futures::future::try_join_all(
+            route
+                .into_iter()
+                .map(|(pool, sell_token)| self.twap(pool, sell_token, block)),
+        )
+        .await?;
+        Ok(prices.into_iter().product())
+    }
+
+    /// Returns the time weighted average price of the sell token denominated
+    /// in the other token of the pool.
+    async fn twap(
+        &self,
+        pool: Pool,
+        sell_token: H160,
+        block: BlockId,
+    ) -> Result<f64, PriceEstimationError> {
+        let (tick_cumulatives, _) = match UniswapV3Pool::at(&self.web3, pool.address)
+            .observe(vec![self.window, 0])
+            .block(block)
+            .call()
+            .await
+        {
+            Ok(observation) => observation,
+            // The pool doesn't store observations reaching back far enough.
+            Err(err) if EthcontractErrorType::is_contract_err(&err) => {
+                tracing::debug!(?err, pool = ?pool.address, "failed to observe pool");
+                return Err(PriceEstimationError::NoLiquidity);
+            }
+            Err(err) => return Err(anyhow::Error::from(err).into()),
+        };
+        let [start, end] = tick_cumulatives[..] else {
+            return Err(anyhow::anyhow!("unexpected number of observations").into());
+        };
+        let price = tick_price(average_tick(start, end, self.window));
+        Ok(if sell_token == pool.token0 {
+            price
+        } else {
+            1. / price
+        })
+    }
+}
+
+impl NativePriceEstimating for UniswapV3Twap {
+    fn estimate_native_price(&self, token: H160) -> BoxFuture<'_, NativePriceEstimateResult> {
+        self.estimate(token, BlockId::Number(BlockNumber::Latest))
+            .boxed()
+    }
+}
+
+impl HistoricalNativePriceEstimating for UniswapV3Twap {
+    fn estimate_native_price_at(
+        &self,
+        token: H160,
+        block: u64,
+    ) -> BoxFuture<'_, NativePriceEstimateResult> {
+        self.estimate(token, BlockId::Number(block.into())).boxed()
+    }
+}
+
+async fn load_pools(
+    subgraph: &UniV3SubgraphClient,
+    min_liquidity: f64,
+) -> Result<HashMap<TokenPair, Pool>> {
+    let pools = subgraph.get_registered_pools().await?.pools;
+    tracing::debug!(
+        pools = pools.len(),
+        "loaded Uniswap V3 pools for TWAP prices"
+    );
+    Ok(most_liquid_pools(pools, min_liquidity))
+}
+
+fn most_liquid_pools(pools: Vec<PoolData>, min_liquidity: f64) -> HashMap<TokenPair, Pool> {
+    let mut result = HashMap::<TokenPair, Pool>::new();
+    for pool in pools {
+        let Some(pair) = TokenPair::new(pool.token0.id, pool.token1.id) else {
+            continue;
+        };
+        if pool.total_value_locked_eth < min_liquidity
+            || result
+                .get(&pair)
+                .is_some_and(|best| best.liquidity >= pool.total_value_locked_eth)
+        {
+            continue;
+        }
+        result.insert(
+            pair,
+            Pool {
+                address: pool.id,
+                token0: pool.token0.id,
+                liquidity: pool.total_value_locked_eth,
+            },
+        );
+    }
+    result
+}
+
+/// Computes the average tick between two tick cumulatives like Uniswap's
+/// `OracleLibrary.consult`, i.e. rounding towards negative infinity.
+fn average_tick(start: i64, end: i64, window: u32) -> i64 {
+    let delta = end - start;
+    delta.div_euclid(window.into())
+}
+
+/// The price of token0 denominated in token1 at the given tick. Since ticks
+/// are based on token atoms this is already the price per atom.
+fn tick_price(tick: i64) -> f64 {
+    1.0001_f64.powf(tick as f64)
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, crate::sources::uniswap_v3::graph_api::Token};
+
+    const NATIVE: H160 = H160([1; 20]);
+    const BASE: H160 = H160([2; 20]);
+    const TOKEN: H160 = H160([3; 20]);
+
+    fn pool(id: u8, token0: H160, token1: H160, liquidity: f64) -> PoolData {
+        PoolData {
+            id: H160([id; 20]),
+            token0: Token {
+                id: token0,
+                decimals: 18,
+            },
+            token1: Token {
+                id: token1,
+                decimals: 18,
+            },
+            total_value_locked_eth: liquidity,
+            ..Default::default()
+        }
+    }
+
+    fn estimator(pools: Vec<PoolData>) -> UniswapV3Twap {
+        UniswapV3Twap {
+            web3: Web3::new(ethrpc::Web3Transport::new(
+                ethrpc::mock::MockTransport::new(),
+            )),
+            native_token: NATIVE,
+            base_tokens: Arc::new(BaseTokens::new(NATIVE, &[BASE])),
+            pools: Arc::new(Mutex::new(most_liquid_pools(pools, 10.))),
+            window: 1800,
+        }
+    }
+
+    #[test]
+    fn averages_ticks_rounding_down() {
+        assert_eq!(average_tick(0, 18_000, 1800), 10);
+        assert_eq!(average_tick(100, 18_100, 1800), 10);
+        assert_eq!(average_tick(0, -18_000, 1800), -10);
+        assert_eq!(average_tick(0, -18_001, 1800), -11);
+        assert_eq!(average_tick(0, 17_999, 1800), 9);
+    }
+
+    #[test]
+    fn converts_ticks_to_prices() {
+        assert_eq!(tick_price(0), 1.);
+        assert!((tick_price(6932) - 2.).abs() < 0.001);
+        assert!((tick_price(-6932) - 0.5).abs() < 0.001);
+    }
+
+    #[test]
+    fn picks_most_liquid_pools() {
+        let pools = most_liquid_pools(
+            vec![
+                pool(1, TOKEN, NATIVE, 20.),
+                pool(2, NATIVE, TOKEN, 50.),
+                pool(3, TOKEN, BASE, 5.),
+            ],
+            10.,
+        );
+        assert_eq!(
+            pools,
+            HashMap::from([(
+                TokenPair::new(TOKEN, NATIVE).unwrap(),
+                Pool {
+                    address: H160([2; 20]),
+                    token0: NATIVE,
+                    liquidity: 50.,
+                }
+            )])
+        );
+    }
+
+    #[test]
+    fn routes_through_base_tokens() {
+        let twap = estimator(vec![pool(1, TOKEN, NATIVE, 20.)]);
+        let route = twap.route(TOKEN).unwrap();
+        assert_eq!(route.len(), 1);
+        assert_eq!(route[0].1, TOKEN);
+
+        let twap = estimator(vec![pool(1, TOKEN, BASE, 20.), pool(2, BASE, NATIVE, 100.)]);
+        let route = twap.route(TOKEN).unwrap();
+        assert_eq!(
+            route
+                .iter()
+                .map(|(pool, sell_token)| (pool.address, *sell_token))
+                .collect::<Vec<_>>(),
+            [(H160([1; 20]), TOKEN), (H160([2; 20]), BASE)]
+        );
+
+        let twap = estimator(vec![pool(1, TOKEN, BASE, 20.)]);
+        assert!(twap.route(TOKEN).is_none());
+
+        // A shallow direct pool loses against a deeper route via a base token.
+        let twap = estimator(vec![
+            pool(1, TOKEN, NATIVE, 15.),
+            pool(2, TOKEN, BASE, 20.),
+            pool(3, BASE, NATIVE, 100.),
+        ]);
+        let route = twap.route(TOKEN).unwrap();
+        assert_eq!(route.len(), 2);
+        assert_eq!(route[0].0.address, H160([2; 20]));
+
+        // Equally liquid routes prefer the direct pool.
+        let twap = estimator(vec![
+            pool(1, TOKEN, NATIVE, 20.),
+            pool(2, TOKEN, BASE, 20.),
+            pool(3, BASE, NATIVE, 100.),
+        ]);
+        let route = twap.route(TOKEN).unwrap();
+        assert_eq!(route.len(), 1);
+        assert_eq!(route[0].0.address, H160([1; 20]));
+    }
+}
@@ -0,0 +1,417 @@
+//! Combines the native prices of multiple sources into a single price which is
+//! robust against individual sources returning bad prices. Instead of picking
+//! a winner, prices that deviate too far from the median of all sources get
+//! rejected as outliers and the median of the remaining prices is used.
+//!
+//! Like the competition estimator, sources are grouped into stages which get
+//! queried one after another until enough of the prices agree with each other.
+
+use {
+    super::{
+        competition::compare_error,
+        native::{is_price_malformed, NativePriceEstimateResult, NativePriceEstimating},
+        PriceEstimationError,
+    },
+    ethrpc::block_stream::CurrentBlockWatcher,
+    futures::{future::BoxFuture, FutureExt},
+    primitive_types::H160,
+    std::{
+        collections::{HashMap, VecDeque},
+        num::NonZeroUsize,
+        sync::{Arc, Mutex},
+    },
+};
+
+/// Deviations (in log space) below this are never considered to be outliers,
+/// even if all other sources agree exactly. This is roughly 1%.
+const MIN_ABSOLUTE_DEVIATION: f64 = 0.01;
+
+/// Smooths aggregated prices over recent blocks.
+pub struct Smoothing {
+    /// Number of recent blocks the aggregated prices get averaged over.
+    pub blocks: u64,
+    pub current_block: CurrentBlockWatcher,
+}
+
+pub struct AggregatingNativePriceEstimator {
+    stages: Vec<Vec<(String, Arc<dyn NativePriceEstimating>)>>,
+    /// Later stages only get queried if the earlier stages produced fewer
+    /// than this many prices which are not outliers.
+    results_required: NonZeroUsize,
+    /// How many median absolute deviations a price may deviate from the
+    /// median of all prices before it gets rejected as an outlier.
+    max_deviation: f64,
+    smoothing: Option<Smoothing>,
+    /// Recent aggregated prices of every token with the block they were
+    /// computed in.
+    history: Mutex<HashMap<H160, VecDeque<(u64, f64)>>>,
+}
+
+impl AggregatingNativePriceEstimator {
+    pub fn new(
+        stages: Vec<Vec<(String, Arc<dyn NativePriceEstimating>)>>,
+        results_required: NonZeroUsize,
+        max_deviation: f64,
+        smoothing: Option<Smoothing>,
+    ) -> Self {
+        Self {
+            stages,
+            results_required,
+            max_deviation,
+            smoothing,
+            history: Default::default(),
+        }
+    }
+
+    async fn estimate(&self, token: H160) -> NativePriceEstimateResult {
+        let mut prices = Vec::new();
+        let mut errors = Vec::new();
+        let mut inliers = Vec::new();
+        for stage in &self.stages {
+            let results =
+                futures::future::join_all(stage.iter().map(|(name, source)| async move {
+                    (name, source.estimate_native_price(token).await)
+                }))
+                .await;
+            for (name, result) in results {
+                match result {
+                    Ok(price) if is_price_malformed(price) => {
+                        let err = anyhow::anyhow!("estimator returned malformed price: {price}");
+                        errors.push(PriceEstimationError::EstimatorInternal(err));
+                    }
+                    Ok(price) => prices.push((name.as_str(), price)),
+                    Err(err) => errors.push(err),
+                }
+            }
+
+            inliers = self::inliers(
+                &prices.iter().map(|(_, price)| *price).collect::<Vec<_>>(),
+                self.max_deviation,
+            );
+            if inliers.iter().filter(|inlier| **inlier).count() >= self.results_required.get() {
+                break;
+            }
+        }
+
+        let Some(price) = median(
+            prices
+                .iter()
+                .zip(&inliers)
+                .filter(|(_, inlier)| **inlier)
+                .map(|((_, price), _)| *price)
+                .collect(),
+        ) else {
+            return Err(errors.into_iter().max_by(compare_error).unwrap_or_else(|| {
+                PriceEstimationError::ProtocolInternal(anyhow::anyhow!(
+                    "no native price sources configured"
+                ))
+            }));
+        };
+
+        let metrics = metrics();
+        for ((name, source_price), inlier) in prices.iter().zip(&inliers) {
+            metrics
+                .deviation
+                .with_label_values(&[*name])
+                .observe((source_price / price - 1.).abs());
+            if !inlier {
+                tracing::debug!(
+                    ?token,
+                    source = *name,
+                    source_price,
+                    price,
+                    "rejected outlier"
+                );
+                metrics.outliers.with_label_values(&[*name]).inc();
+            }
+        }
+
+        Ok(self.smoothed(token, price))
+    }
+
+    /// Records the price and returns the average of the prices of the recent
+    /// blocks, weighted by the number of blocks each price was the latest
+    /// one.
+    fn smoothed(&self, token: H160, price: f64) -> f64 {
+        let Some(smoothing) = &self.smoothing else {
+            return price;
+        };
+        let block = smoothing.current_block.borrow().number;
+        let oldest = block.saturating_sub(smoothing.blocks.saturating_sub(1));
+
+        let mut history = self.history.lock().unwrap();
+        let prices = history.entry(token).or_default();
+        if prices.back().is_some_and(|(b, _)| *b == block) {
+            prices.pop_back();
+        }
+        prices.push_back((block, price));
+        // The last price before the window was still the latest price at the
+        // start of the window, so it is kept.
+        while prices.get(1).is_some_and(|(b, _)| *b <= oldest) {
+            prices.pop_front();
+        }
+
+        let (weighted_sum, total_weight) = prices
+            .iter()
+            .zip(
+                prices
+                    .iter()
+                    .skip(1)
+                    .map(|(b, _)| *b)
+                    .chain(std::iter::once(block + 1)),
+            )
+            .fold((0., 0.), |(sum, total), ((b, price), next)| {
+                let weight = (next - (*b).max(oldest)) as f64;
+                (sum + price * weight, total + weight)
+            });
+        // Forget tokens which weren't priced within the window so the history
+        // doesn't grow without bound.
+        history.retain(|_, prices| prices.back().is_some_and(|(b, _)| *b >= oldest));
+        weighted_sum / total_weight
+    }
+}
+
+impl NativePriceEstimating for AggregatingNativePriceEstimator {
+    fn estimate_native_price(&self, token: H160) -> BoxFuture<'_, NativePriceEstimateResult> {
+        self.estimate(token).boxed()
+    }
+}
+
+/// Returns for every price whether it is within `max_deviation` median
+/// absolute deviations of the median price. Deviations are computed in log
+/// space since prices are ratios.
+fn inliers(prices: &[f64], max_deviation: f64) -> Vec<bool> {
+    let logs = prices.iter().map(|price| price.ln()).collect::<Vec<_>>();
+    let Some(median_log) = median(logs.clone()) else {
+        return Vec::new();
+    };
+    let deviations = logs
+        .iter()
+        .map(|log| (log - median_log).abs())
+        .collect::<Vec<_>>();
+    let mad = median(deviations.clone())
+        .unwrap_or_default()
+        .max(MIN_ABSOLUTE_DEVIATION);
+    deviations
+        .into_iter()
+        .map(|deviation| deviation <= max_deviation * mad)
+        .collect()
+}
+
+fn median(mut values: Vec<f64>) -> Option<f64> {
+    values.sort_by(f64::total_cmp);
+    let mid = values.len() / 2;
+    match values.len() {
+        0 => None,
+        len if len % 2 == 1 => Some(values[mid]),
+        _ => Some((values[mid - 1] + values[mid]) / 2.),
+    }
+}
+
+#[derive(prometheus_metric_storage::MetricStorage)]
+#[metric(subsystem = "native_price_aggregation")]
+struct Metrics {
+    /// Relative deviation of the native price of a source from the aggregated
+    /// native price.
+    #[metric(
+        labels("source"),
+        buckets(0.001, 0.005, 0.01, 0.02, 0.05, 0.1, 0.2, 0.5, 1.)
+    )]
+    deviation: prometheus::HistogramVec,
+    /// Number of native prices of a source rejected as outliers.
+    #[metric(labels("source"))]
+    outliers: prometheus::IntCounterVec,
+}
+
+fn metrics() -> &'static Metrics {
+    Metrics::instance(observe::metrics::get_storage_registry())
+        .expect("unexpected error getting metrics instance")
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::price_estimation::native::MockNativePriceEstimating,
+        ethrpc::block_stream::BlockInfo,
+    };
+
+    fn aggregating(
+        stages: Vec<Vec<(String, Arc<dyn NativePriceEstimating>)>>,
+        results_required: usize,
+        smoothing: Option<Smoothing>,
+    ) -> AggregatingNativePriceEstimator {
+        AggregatingNativePriceEstimator::new(
+            stages,
+            NonZeroUsize::new(results_required).unwrap(),
+            3.,
+            smoothing,
+        )
+    }
+
+    fn source(result: NativePriceEstimateResult) -> (String, Arc<dyn NativePriceEstimating>) {
+        let mut source = MockNativePriceEstimating::new();
+        source
+            .expect_estimate_native_price()
+            .returning(move |_| futures::future::ready(result.clone()).boxed());
+        ("source".to_owned(), Arc::new(source))
+    }
+
+    #[test]
+    fn rejects_outliers() {
+        assert_eq!(
+            inliers(&[1.0, 1.01, 0.99, 1.5, 0.5], 3.),
+            [true, true, true, false, false]
+        );
+        // Small deviations are fine even if the other sources agree exactly.
+        assert_eq!(inliers(&[1.0, 1.0, 1.02], 3.), [true, true, true]);
+        assert_eq!(inliers(&[1.0, 1.0, 1.1], 3.), [true, true, false]);
+        assert!(inliers(&[], 3.).is_empty());
+    }
+
+    #[tokio::test]
+    async fn aggregates_median_of_inliers() {
+        let estimator = aggregating(
+            vec![vec![
+                source(Ok(1.0)),
+                source(Ok(1.5)),
+                source(Ok(10.0)),
+                source(Err(PriceEstimationError::NoLiquidity)),
+            ]],
+            usize::MAX,
+            None,
+        );
+        assert_eq!(
+            estimator.estimate_native_price(H160::zero()).await.unwrap(),
+            1.25
+        );
+    }
+
+    #[tokio::test]
+    async fn queries_later_stages_until_enough_prices_agree() {
+        let never = || -> (String, Arc<dyn NativePriceEstimating>) {
+            let mut source = MockNativePriceEstimating::new();
+            source.expect_estimate_native_price().never();
+            ("never".to_owned(), Arc::new(source))
+        };
+
+        // The first stage already has two prices which agree.
+        let estimator = aggregating(
+            vec![
+                vec![source(Ok(1.0)), source(Ok(1.0)), source(Ok(10.0))],
+                vec![never()],
+            ],
+            2,
+            None,
+        );
+        assert_eq!(
+            estimator.estimate_native_price(H160::zero()).await.unwrap(),
+            1.0
+        );
+
+        // The first stage only produced one price so the next stage gets
+        // queried as well.
+        let estimator = aggregating(
+            vec![
+                vec![
+                    source(Ok(1.0)),
+                    source(Err(PriceEstimationError::NoLiquidity)),
+                ],
+                vec![source(Ok(1.02))],
+                vec![never()],
+            ],
+            2,
+            None,
+        );
+        assert_eq!(
+            estimator.estimate_native_price(H160::zero()).await.unwrap(),
+            1.01
+        );
+    }
+
+    #[tokio::test]
+    async fn returns_most_recoverable_error() {
+        let estimator = aggregating(
+            vec![
+                vec![source(Err(PriceEstimationError::NoLiquidity))],
+                vec![
+                    source(Err(PriceEstimationError::RateLimited)),
+                    source(Ok(f64::NAN)),
+                ],
+            ],
+            1,
+            None,
+        );
+        assert!(matches!(
+            estimator.estimate_native_price(H160::zero()).await,
+            Err(PriceEstimationError::RateLimited)
+        ));
+    }
+
+    #[tokio::test]
+    async fn smooths_over_recent_blocks() {
+        let (sender, current_block) = tokio::sync::watch::channel(BlockInfo {
+            number: 10,
+            ..Default::default()
+        });
+        let mut source = MockNativePriceEstimating::new();
+        let mut prices = vec![1.0, 2.0, 4.0].into_iter();
+        source
+            .expect_estimate_native_price()
+            .returning(move |_| futures::future::ready(Ok(prices.next().unwrap())).boxed());
+        let estimator = aggregating(
+            vec![vec![("source".to_owned(), Arc::new(source))]],
+            1,
+            Some(Smoothing {
+                blocks: 4,
+                current_block,
+            }),
+        );
+        let token = H160::zero();
+
+        assert_eq!(estimator.estimate_native_price(token).await.unwrap(), 1.0);
+        // 1.0 was the latest price in blocks 10 and 11, 2.0 in block 12.
+        sender.send_modify(|block| block.number = 12);
+        assert_eq!(
+            estimator.estimate_native_price(token).await.unwrap(),
+            (1.0 * 2. + 2.0) / 3.
+        );
+        // Only blocks 12 to 15 are considered.
+        sender.send_modify(|block| block.number = 15);
+        assert_eq!(
+            estimator.estimate_native_price(token).await.unwrap(),
+            (2.0 * 3. + 4.0) / 4.
+        );
+    }
+
+    #[tokio::test]
+    async fn smoothing_weighs_latest_price_before_the_window() {
+        let (sender, current_block) = tokio::sync::watch::channel(BlockInfo {
+            number: 10,
+            ..Default::default()
+        });
+        let mut source = MockNativePriceEstimating::new();
+        let mut prices = vec![1.0, 4.0].into_iter();
+        source
+            .expect_estimate_native_price()
+            .returning(move |_| futures::future::ready(Ok(prices.next().unwrap())).boxed());
+        let estimator = aggregating(
+            vec![vec![("source".to_owned(), Arc::new(source))]],
+            1,
+            Some(Smoothing {
+                blocks: 4,
+                current_block,
+            }),
+        );
+        let token = H160::zero();
+
+        assert_eq!(estimator.estimate_native_price(token).await.unwrap(), 1.0);
+        // 1.0 recorded in block 10 was still the latest price in blocks 12 to
+        // 14.
+        sender.send_modify(|block| block.number = 15);
+        assert_eq!(
+            estimator.estimate_native_price(token).await.unwrap(),
+            (1.0 * 3. + 4.0) / 4.
+        );
+    }
+}
@@ -1,22 +1,30 @@
 mod detector;
+mod storage;
 
 use {
-    self::detector::{DetectionError, Detector},
-    crate::code_simulation::CodeSimulating,
+    self::{
+        detector::{DetectionError, Detector},
+        storage::Persistence,
+    },
+    crate::{code_fetching::CodeFetching, code_simulation::CodeSimulating},
     anyhow::Context as _,
     cached::{Cached, SizedCache},
     ethcontract::{Address, H256, U256},
     ethrpc::extensions::StateOverride,
     maplit::hashmap,
+    primitive_types::U512,
     std::{
         collections::HashMap,
         fmt::{self, Display, Formatter},
         str::FromStr,
         sync::{Arc, Mutex},
+        time::Duration,
     },
     web3::signing,
 };
 
+pub use self::storage::{StoredStrategy, StrategyStoring};
+
 /// Balance override configuration arguments.
 #[derive(clap::Parser)]
 #[group(skip)]
@@ -25,10 +33,17 @@ pub struct Arguments {
     /// allows the quote verification system to produce verified quotes for
     /// traders without sufficient balance for the configured token pairs.
     ///
-    /// The expected format is a comma separated list of `${ADDR}@${SLOT}`,
-    /// where `ADDR` is the token address and `SLOT` is the Solidity storage
-    /// slot for the balances mapping. For example for WETH:
-    /// `0xC02aaA39b223FE8D0A0e5C4F27eAD9083C756Cc2@3`.
+    /// The expected format is a comma separated list of `${ADDR}@${STRATEGY}`,
+    /// where `ADDR` is the token address and `STRATEGY` is one of:
+    /// - `${SLOT}`: a Solidity balances mapping at storage slot `SLOT`. For
+    ///   example for WETH: `0xC02aaA39b223FE8D0A0e5C4F27eAD9083C756Cc2@3`.
+    /// - `vyper:${SLOT}`: a Vyper balances mapping at storage slot `SLOT`.
+    /// - `solady`: the balance slots of the Solady ERC20 implementation.
+    /// - `shares:${SLOT}:${SHARES}:${BALANCE}`: a Solidity mapping of shares at
+    ///   storage slot `SLOT` of a rebasing token, where `SHARES` shares are
+    ///   worth a balance of `BALANCE`.
+    ///
+    /// All numbers are hexadecimal.
     #[clap(long, env, default_value_t)]
     pub quote_token_balance_overrides: TokenConfiguration,
 
@@ -37,21 +52,59 @@ pub struct Arguments {
     /// will take precedence.
     #[clap(long, env, action = clap::ArgAction::Set, default_value_t)]
     pub quote_autodetect_token_balance_overrides: bool,
+
+    /// Auto-detected strategies get persisted in the database. For tokens
+    /// without a detected strategy the persisted result is only used for this
+    /// long before detection gets retried.
+    #[clap(
+        long,
+        env,
+        default_value = "1d",
+        value_parser = humantime::parse_duration,
+    )]
+    pub quote_autodetect_token_balance_overrides_not_found_ttl: Duration,
+
+    /// How long persisted auto-detected strategies are used before detection
+    /// gets retried. Upgrading a proxy changes where its balances are stored
+    /// without changing the code of the token, so even detected strategies
+    /// have to expire eventually.
+    #[clap(
+        long,
+        env,
+        default_value = "7d",
+        value_parser = humantime::parse_duration,
+    )]
+    pub quote_autodetect_token_balance_overrides_found_ttl: Duration,
 }
 
 impl Arguments {
     const CACHE_SIZE: usize = 1000;
 
     /// Creates a balance overrides instance from the current configuration.
-    pub fn init(&self, simulator: Arc<dyn CodeSimulating>) -> Arc<dyn BalanceOverriding> {
+    pub fn init(
+        &self,
+        simulator: Arc<dyn CodeSimulating>,
+        storage: Arc<dyn StrategyStoring>,
+        code_fetcher: Arc<dyn CodeFetching>,
+    ) -> Arc<dyn BalanceOverriding> {
+        let autodetect = self.quote_autodetect_token_balance_overrides;
         Arc::new(BalanceOverrides {
             hardcoded: self.quote_token_balance_overrides.0.clone(),
-            detector: self.quote_autodetect_token_balance_overrides.then(|| {
+            detector: autodetect.then(|| {
                 (
                     Detector::new(simulator),
                     Mutex::new(SizedCache::with_size(Self::CACHE_SIZE)),
                 )
             }),
+            persistence: autodetect.then(|| {
+                let ttl = |ttl| chrono::Duration::from_std(ttl).unwrap_or(chrono::Duration::MAX);
+                Persistence::new(
+                    storage,
+                    code_fetcher,
+                    ttl(self.quote_autodetect_token_balance_overrides_found_ttl),
+                    ttl(self.quote_autodetect_token_balance_overrides_not_found_ttl),
+                )
+            }),
         })
     }
 }
@@ -61,6 +114,8 @@ impl Display for Arguments {
         let Self {
             quote_token_balance_overrides,
             quote_autodetect_token_balance_overrides,
+            quote_autodetect_token_balance_overrides_not_found_ttl,
+            quote_autodetect_token_balance_overrides_found_ttl,
         } = self;
 
         writeln!(
@@ -73,6 +128,16 @@ impl Display for Arguments {
             "quote_autodetect_token_balance_overrides: {:?}",
             quote_autodetect_token_balance_overrides
         )?;
+        writeln!(
+            f,
+            "quote_autodetect_token_balance_overrides_not_found_ttl: {:?}",
+            quote_autodetect_token_balance_overrides_not_found_ttl
+        )?;
+        writeln!(
+            f,
+            "quote_autodetect_token_balance_overrides_found_ttl: {:?}",
+            quote_autodetect_token_balance_overrides_found_ttl
+        )?;
 
         Ok(())
     }
@@ -84,10 +149,9 @@ pub struct TokenConfiguration(HashMap<Address, Strategy>);
 
 impl Display for TokenConfiguration {
     fn fmt(&self, f: &mut Formatter) -> fmt::Result {
-        let format_entry =
-            |f: &mut Formatter, (addr, strategy): (&Address, &Strategy)| match strategy {
-                Strategy::Mapping { slot } => write!(f, "{addr:?}@{slot}"),
-            };
+        let format_entry = |f: &mut Formatter, (addr, strategy): (&Address, &Strategy)| {
+            write!(f, "{addr:?}@{strategy}")
+        };
 
         let mut entries = self.0.iter();
 
@@ -116,15 +180,10 @@ impl FromStr for TokenConfiguration {
         let entries = s
             .split(',')
             .map(|part| -> Result<_, Self::Err> {
-                let (addr, slot) = part
+                let (addr, strategy) = part
                     .split_once('@')
-                    .context("expected {addr}@{slot} format")?;
-                Ok((
-                    addr.parse()?,
-                    Strategy::Mapping {
-                        slot: slot.parse()?,
-                    },
-                ))
+                    .context("expected {addr}@{strategy} format")?;
+                Ok((addr.parse()?, strategy.parse()?))
             })
             .collect::<Result<_, _>>()?;
         Ok(Self(entries))
@@ -151,41 +210,138 @@ pub struct BalanceOverrideRequest {
 }
 
 /// Balance override strategy for a token.
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Eq, PartialEq)]
 pub enum Strategy {
     /// Balance override strategy for tokens whose balances are stored in a
     /// direct Solidity mapping from token holder to balance amount in the
     /// form `mapping(address holder => uint256 amount)`.
     ///
     /// The strategy is configured with the storage slot [^1] of the mapping.
+    /// This also covers upgradeable tokens using namespaced storage [^2] since
+    /// the namespace simply determines the slot of the mapping.
     ///
     /// [^1]: <https://docs.soliditylang.org/en/latest/internals/layout_in_storage.html#mappings-and-dynamic-arrays>
+    /// [^2]: <https://eips.ethereum.org/EIPS/eip-7201>
     Mapping { slot: U256 },
+    /// Balance override strategy for tokens whose balances are stored in a
+    /// Vyper `HashMap[address, uint256]`. Vyper hashes the storage slot of the
+    /// mapping before the key, i.e. in the opposite order of Solidity.
+    VyperMapping { slot: U256 },
+    /// Balance override strategy for tokens based on the Solady ERC20
+    /// implementation [^1], which packs the holder and a fixed seed into a
+    /// single word to compute the balance slot.
+    ///
+    /// [^1]: <https://github.com/Vectorized/solady/blob/main/src/tokens/ERC20.sol>
+    SoladyMapping,
+    /// Balance override strategy for rebasing tokens which store the shares of
+    /// every holder in a Solidity mapping and derive the balance from the
+    /// shares with a conversion rate that changes over time (like stETH).
+    ///
+    /// The strategy is configured with the storage slot of the shares mapping
+    /// and a sample conversion of `shares` into a `balance`, which is used to
+    /// compute the number of shares needed for a balance.
+    SharesMapping {
+        slot: U256,
+        shares: U256,
+        balance: U256,
+    },
 }
 
 impl Strategy {
+    /// The seed Solady's ERC20 implementation packs with the holder address
+    /// to compute its balance slot.
+    const SOLADY_BALANCE_SLOT_SEED: [u8; 4] = [0x87, 0xa2, 0x11, 0xa2];
+
     /// Computes the storage slot and value to override for a particular token
     /// holder and amount.
     fn state_override(&self, holder: &Address, amount: &U256) -> (H256, H256) {
-        match self {
-            Self::Mapping { slot } => {
-                let key = {
-                    let mut buf = [0; 64];
-                    buf[12..32].copy_from_slice(holder.as_fixed_bytes());
-                    slot.to_big_endian(&mut buf[32..64]);
-                    H256(signing::keccak256(&buf))
-                };
-                let value = {
-                    let mut buf = [0; 32];
-                    amount.to_big_endian(&mut buf);
-                    H256(buf)
-                };
-                (key, value)
+        let key = match self {
+            Self::Mapping { slot } | Self::SharesMapping { slot, .. } => {
+                let mut buf = [0; 64];
+                buf[12..32].copy_from_slice(holder.as_fixed_bytes());
+                slot.to_big_endian(&mut buf[32..64]);
+                H256(signing::keccak256(&buf))
+            }
+            Self::VyperMapping { slot } => {
+                let mut buf = [0; 64];
+                slot.to_big_endian(&mut buf[0..32]);
+                buf[44..64].copy_from_slice(holder.as_fixed_bytes());
+                H256(signing::keccak256(&buf))
             }
+            Self::SoladyMapping => {
+                let mut buf = [0; 32];
+                buf[0..20].copy_from_slice(holder.as_fixed_bytes());
+                buf[28..32].copy_from_slice(&Self::SOLADY_BALANCE_SLOT_SEED);
+                H256(signing::keccak256(&buf))
+            }
+        };
+        let value = match self {
+            Self::SharesMapping {
+                shares, balance, ..
+            } => shares_for_balance(amount, shares, balance),
+            _ => *amount,
+        };
+        let value = {
+            let mut buf = [0; 32];
+            value.to_big_endian(&mut buf);
+            H256(buf)
+        };
+        (key, value)
+    }
+}
+
+/// Computes the number of shares needed for a balance of at least `amount`
+/// given a sample conversion of `shares` into `balance`. Adds a buffer of 1%
+/// since the conversion rate of rebasing tokens changes over time.
+fn shares_for_balance(amount: &U256, shares: &U256, balance: &U256) -> U256 {
+    if balance.is_zero() {
+        return U256::MAX;
+    }
+    let balance = U512::from(*balance);
+    let needed = (amount.full_mul(*shares) + balance - U512::one()) / balance;
+    let needed = needed + needed / 100;
+    U256::try_from(needed).unwrap_or(U256::MAX)
+}
+
+impl Display for Strategy {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            Self::Mapping { slot } => write!(f, "{slot:#x}"),
+            Self::VyperMapping { slot } => write!(f, "vyper:{slot:#x}"),
+            Self::SoladyMapping => f.write_str("solady"),
+            Self::SharesMapping {
+                slot,
+                shares,
+                balance,
+            } => write!(f, "shares:{slot:#x}:{shares:#x}:{balance:#x}"),
         }
     }
 }
 
+impl FromStr for Strategy {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parts = s.split(':');
+        let strategy = match parts.next().unwrap_or_default() {
+            "vyper" => Self::VyperMapping {
+                slot: parts.next().context("missing slot")?.parse()?,
+            },
+            "solady" => Self::SoladyMapping,
+            "shares" => Self::SharesMapping {
+                slot: parts.next().context("missing slot")?.parse()?,
+                shares: parts.next().context("missing shares")?.parse()?,
+                balance: parts.next().context("missing balance")?.parse()?,
+            },
+            slot => Self::Mapping {
+                slot: slot.parse()?,
+            },
+        };
+        anyhow::ensure!(parts.next().is_none(), "unexpected strategy parameters");
+        Ok(strategy)
+    }
+}
+
 type DetectorCache = Mutex<SizedCache<Address, Option<Strategy>>>;
 
 /// The default balance override provider.
@@ -200,6 +356,9 @@ pub struct BalanceOverrides {
     /// The balance override detector and its cache. Set to `None` if
     /// auto-detection is not enabled.
     detector: Option<(Detector, DetectorCache)>,
+    /// Persists auto-detected strategies so they outlive the cache. Set to
+    /// `None` if auto-detection is not enabled.
+    persistence: Option<Persistence>,
 }
 
 impl BalanceOverrides {
@@ -215,6 +374,13 @@ impl BalanceOverrides {
             }
         }
 
+        let code_hash = self.code_hash(token).await;
+        if let Some(strategy) = self.persisted(token, code_hash).await {
+            tracing::debug!(?token, ?strategy, "using persisted strategy");
+            cache.lock().unwrap().cache_set(token, strategy.clone());
+            return strategy;
+        }
+
         let strategy = detector.detect(token).await;
 
         // Only cache when we successfully detect the token, or we can't find
@@ -223,7 +389,11 @@ impl BalanceOverrides {
         if matches!(&strategy, Ok(_) | Err(DetectionError::NotFound)) {
             tracing::debug!(?token, ?strategy, "caching auto-detected strategy");
             let cached_strategy = strategy.as_ref().ok().cloned();
-            cache.lock().unwrap().cache_set(token, cached_strategy);
+            cache
+                .lock()
+                .unwrap()
+                .cache_set(token, cached_strategy.clone());
+            self.persist(token, code_hash, cached_strategy).await;
         } else {
             tracing::warn!(
                 ?token,
@@ -234,6 +404,42 @@ impl BalanceOverrides {
 
         strategy.ok()
     }
+
+    /// Computes the code hash of the token persisted results get validated
+    /// against. Returns `None` if persistence is disabled or the code could
+    /// not be fetched.
+    async fn code_hash(&self, token: Address) -> Option<H256> {
+        let persistence = self.persistence.as_ref()?;
+        match persistence.code_hash(token).await {
+            Ok(code_hash) => Some(code_hash),
+            Err(err) => {
+                tracing::warn!(?token, ?err, "failed to fetch token code");
+                None
+            }
+        }
+    }
+
+    /// Returns the persisted detection result of the token if it's still
+    /// valid.
+    async fn persisted(&self, token: Address, code_hash: Option<H256>) -> Option<Option<Strategy>> {
+        let persistence = self.persistence.as_ref()?;
+        match persistence.load(token, code_hash?).await {
+            Ok(strategy) => strategy,
+            Err(err) => {
+                tracing::warn!(?token, ?err, "failed to load persisted strategy");
+                None
+            }
+        }
+    }
+
+    async fn persist(&self, token: Address, code_hash: Option<H256>, strategy: Option<Strategy>) {
+        let (Some(persistence), Some(code_hash)) = (&self.persistence, code_hash) else {
+            return;
+        };
+        if let Err(err) = persistence.save(token, code_hash, strategy).await {
+            tracing::warn!(?token, ?err, "failed to persist strategy");
+        }
+    }
 }
 
 #[async_trait::async_trait]
@@ -258,7 +464,14 @@ impl BalanceOverriding for BalanceOverrides {
 
 #[cfg(test)]
 mod tests {
-    use {super::*, hex_literal::hex};
+    use {
+        super::{storage::MockStrategyStoring, *},
+        crate::{code_fetching::MockCodeFetching, code_simulation::SimulationError},
+        ethcontract::H160,
+        ethrpc::extensions::StateOverrides,
+        hex_literal::hex,
+        web3::types::CallRequest,
+    };
 
     #[tokio::test]
     async fn balance_override_computation() {
@@ -313,6 +526,100 @@ mod tests {
         // ```
     }
 
+    #[test]
+    fn strategy_state_overrides() {
+        let holder = addr!("d8dA6BF26964aF9D7eEd9e03E53415D37aA96045");
+        let amount = U256::from(1_000_000);
+
+        assert_eq!(
+            Strategy::VyperMapping { slot: 1.into() }.state_override(&holder, &amount),
+            (
+                H256(hex!(
+                    "58a6614e8877e0941c8f738272c6dffaeccbc03efaf75c33059cff9f8a236b48"
+                )),
+                H256::from_low_u64_be(1_000_000),
+            ),
+        );
+        assert_eq!(
+            Strategy::SoladyMapping.state_override(&holder, &amount),
+            (
+                H256(hex!(
+                    "f6a6656ed2d14bad3cdd3e8871db3f535a136a1b6cd5ae2dced8eb813f3d4e4f"
+                )),
+                H256::from_low_u64_be(1_000_000),
+            ),
+        );
+        // OpenZeppelin's upgradeable ERC20 with namespaced storage.
+        assert_eq!(
+            Strategy::Mapping {
+                slot: U256::from_big_endian(&hex!(
+                    "52c63247e1f47db19d5ce0460030c497f067ca4cebf71ba98eeadabe20bace00"
+                )),
+            }
+            .state_override(&holder, &amount),
+            (
+                H256(hex!(
+                    "d1c18dff8cded65a0c764166337132c784ca3fdb8a600b60b812205278d53038"
+                )),
+                H256::from_low_u64_be(1_000_000),
+            ),
+        );
+        // 2 shares are worth 3 tokens, so 666_667 shares are needed plus a
+        // buffer of 1%.
+        assert_eq!(
+            Strategy::SharesMapping {
+                slot: 0.into(),
+                shares: 2.into(),
+                balance: 3.into(),
+            }
+            .state_override(&holder, &amount),
+            (
+                H256(hex!(
+                    "fca351f4d96129454cfc8ef7930b638ac71fea35eb69ee3b8d959496beb04a33"
+                )),
+                H256::from_low_u64_be(673_333),
+            ),
+        );
+    }
+
+    #[test]
+    fn token_configuration_roundtrip() {
+        let config = "0xc02aaa39b223fe8d0a0e5c4f27ead9083c756cc2@3,\
+                      0xdef1ca1fb7fbcdc777520aa7f396b4e015f497ab@vyper:0x1,\
+                      0x0000000000000000000000000000000000000001@solady,\
+                      0x0000000000000000000000000000000000000002@shares:0:2:3"
+            .parse::<TokenConfiguration>()
+            .unwrap();
+        assert_eq!(
+            config.0,
+            hashmap! {
+                addr!("c02aaa39b223fe8d0a0e5c4f27ead9083c756cc2") => Strategy::Mapping {
+                    slot: 3.into(),
+                },
+                addr!("def1ca1fb7fbcdc777520aa7f396b4e015f497ab") => Strategy::VyperMapping {
+                    slot: 1.into(),
+                },
+                addr!("0000000000000000000000000000000000000001") => Strategy::SoladyMapping,
+                addr!("0000000000000000000000000000000000000002") => Strategy::SharesMapping {
+                    slot: 0.into(),
+                    shares: 2.into(),
+                    balance: 3.into(),
+                },
+            },
+        );
+        assert_eq!(
+            config.to_string().parse::<TokenConfiguration>().unwrap().0,
+            config.0,
+        );
+
+        assert!("0x0000000000000000000000000000000000000001@solady:1"
+            .parse::<TokenConfiguration>()
+            .is_err());
+        assert!("0x0000000000000000000000000000000000000001@shares:0:2"
+            .parse::<TokenConfiguration>()
+            .is_err());
+    }
+
     #[tokio::test]
     async fn balance_overrides_none_for_unknown_tokens() {
         let balance_overrides = BalanceOverrides::default();
@@ -327,4 +634,124 @@ mod tests {
             None,
         );
     }
+
+    /// A simulator for a token whose balances can't be overridden.
+    struct NoOverrides;
+
+    #[async_trait::async_trait]
+    impl CodeSimulating for NoOverrides {
+        async fn simulate(
+            &self,
+            _: CallRequest,
+            _: StateOverrides,
+            _: Option<u64>,
+        ) -> Result<Vec<u8>, SimulationError> {
+            Ok(vec![0; 32])
+        }
+    }
+
+    const TOKEN: Address = H160([1; 20]);
+
+    fn code_hash() -> H256 {
+        H256(signing::keccak256(&[1, 2, 3]))
+    }
+
+    fn persisted(storage: MockStrategyStoring) -> BalanceOverrides {
+        let mut code_fetcher = MockCodeFetching::new();
+        code_fetcher
+            .expect_code()
+            .returning(|_| Ok(vec![1, 2, 3].into()));
+        BalanceOverrides {
+            detector: Some((
+                Detector::new(Arc::new(NoOverrides)),
+                Mutex::new(SizedCache::with_size(10)),
+            )),
+            persistence: Some(Persistence::new(
+                Arc::new(storage),
+                Arc::new(code_fetcher),
+                chrono::Duration::days(30),
+                chrono::Duration::days(1),
+            )),
+            ..Default::default()
+        }
+    }
+
+    fn stored(
+        code_hash: H256,
+        strategy: Option<Strategy>,
+        age: chrono::Duration,
+    ) -> StoredStrategy {
+        StoredStrategy {
+            token: TOKEN,
+            code_hash,
+            strategy,
+            detected_at: chrono::Utc::now() - age,
+        }
+    }
+
+    #[tokio::test]
+    async fn uses_persisted_strategy() {
+        let strategy = Strategy::Mapping { slot: 3.into() };
+        let mut storage = MockStrategyStoring::new();
+        let stored = stored(
+            code_hash(),
+            Some(strategy.clone()),
+            chrono::Duration::days(7),
+        );
+        storage
+            .expect_find()
+            .times(1)
+            .returning(move |_| Ok(Some(stored.clone())));
+        storage.expect_save().never();
+
+        let balance_overrides = persisted(storage);
+        // The second lookup is served from the in-memory cache.
+        for _ in 0..2 {
+            assert_eq!(
+                balance_overrides.cached_detection(TOKEN).await,
+                Some(strategy.clone())
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn redetects_invalid_persisted_strategy() {
+        for stored in [
+            // The code of the token changed.
+            stored(
+                H256::zero(),
+                Some(Strategy::Mapping { slot: 3.into() }),
+                chrono::Duration::zero(),
+            ),
+            // Negative results expire.
+            stored(code_hash(), None, chrono::Duration::days(2)),
+            // Detected strategies expire too since proxies can be upgraded.
+            stored(
+                code_hash(),
+                Some(Strategy::Mapping { slot: 3.into() }),
+                chrono::Duration::days(31),
+            ),
+        ] {
+            let mut storage = MockStrategyStoring::new();
+            storage
+                .expect_find()
+                .returning(move |_| Ok(Some(stored.clone())));
+            storage
+                .expect_save()
+                .times(1)
+                .withf(|stored| stored.code_hash == code_hash() && stored.strategy.is_none())
+                .returning(|_| Ok(()));
+
+            assert_eq!(persisted(storage).cached_detection(TOKEN).await, None);
+        }
+
+        // A recent negative result is still valid.
+        let mut storage = MockStrategyStoring::new();
+        let stored = stored(code_hash(), None, chrono::Duration::hours(1));
+        storage
+            .expect_find()
+            .returning(move |_| Ok(Some(stored.clone())));
+        storage.expect_save().never();
+        assert_eq!(persisted(storage).cached_detection(TOKEN).await, None);
+    }
 }
@@ -22,6 +22,9 @@ pub struct Detector {
 }
 
 impl Detector {
+    /// ERC-7201 namespaces of popular upgradeable token implementations which
+    /// store the balances mapping as the first member of the namespace.
+    const NAMESPACES: &'static [&'static str] = &["openzeppelin.storage.ERC20"];
     /// Number of different slots to try out.
     const TRIES: u8 = 25;
 
@@ -35,34 +38,75 @@ impl Detector {
     /// simulation fails.
     pub async fn detect(&self, token: Address) -> Result<Strategy, DetectionError> {
         // This is a pretty unsophisticated strategy where we basically try a
-        // bunch of different slots and see which one sticks. We try balance
-        // mappings for the first `TRIES` slots; each with a unique value.
-        let mut tries = (0..Self::TRIES).map(|i| {
-            let strategy = Strategy::Mapping {
+        // bunch of different storage layouts and see which one sticks. We try
+        // Solidity and Vyper balance mappings for the first `TRIES` slots,
+        // Solidity mappings of well known storage namespaces and the Solady
+        // balance slots; each with a unique value.
+        let candidates = (0..Self::TRIES)
+            .map(|i| Strategy::Mapping {
                 slot: U256::from(i),
+            })
+            .chain((0..Self::TRIES).map(|i| Strategy::VyperMapping {
+                slot: U256::from(i),
+            }))
+            .chain(Self::NAMESPACES.iter().map(|namespace| Strategy::Mapping {
+                slot: erc7201(namespace),
+            }))
+            .chain([Strategy::SoladyMapping]);
+        let tries = candidates
+            .enumerate()
+            .map(|(i, strategy)| {
+                // Use an exact value which isn't too large or too small. This
+                // helps not have false positives for cases where the token
+                // balances in some other denomination from the actual token
+                // balance (such as stETH for example) and not run into issues
+                // with overflows. The value must not be 0 since that is the
+                // balance of the holder when no override is picked up.
+                let i = u8::try_from(i + 1).expect("too many candidates");
+                let amount = U256::from(u64::from_be_bytes([i; 8]));
+
+                (strategy, amount)
+            })
+            .collect::<Vec<_>>();
+
+        let balance = self.balance_of(token, &tries).await?;
+        if let Some((strategy, _)) = tries.iter().find(|(_, amount)| *amount == balance) {
+            return Ok(strategy.clone());
+        }
+        if balance.is_zero() {
+            return Err(DetectionError::NotFound);
+        }
+
+        // The token picked up one of the overrides but converted the value,
+        // which is what rebasing tokens storing shares instead of balances do.
+        // Probe the Solidity mappings one by one to find the shares mapping
+        // and a sample of the conversion rate.
+        for (strategy, shares) in &tries {
+            let Strategy::Mapping { slot } = strategy else {
+                continue;
             };
-            // Use an exact value which isn't too large or too small. This helps
-            // not have false positives for cases where the token balances in
-            // some other denomination from the actual token balance (such as
-            // stETH for example) and not run into issues with overflows.
-            let amount = U256::from(u64::from_be_bytes([i; 8]));
-
-            (strategy, amount)
-        });
-
-        // On a technical note, Ethereum public addresses are, for the most
-        // part, generated by taking the 20 last bytes of a Keccak-256 hash (for
-        // things like contract creation, public address derivation from a
-        // Secp256k1 public key, etc.), so we use one for our heuristics from a
-        // 32-byte digest with no know pre-image, to prevent any weird
-        // interactions with the weird tokens of the world.
-        let holder = {
-            let mut address = Address::default();
-            address.0.copy_from_slice(&keccak256(b"Moo!")[12..]);
-            address.0[19] = address.0[19].wrapping_sub(1);
-            address
-        };
+            let balance = self
+                .balance_of(token, &[(strategy.clone(), *shares)])
+                .await?;
+            if !balance.is_zero() {
+                return Ok(Strategy::SharesMapping {
+                    slot: *slot,
+                    shares: *shares,
+                    balance,
+                });
+            }
+        }
+        Err(DetectionError::NotFound)
+    }
 
+    /// Simulates `balanceOf` of the probing holder with the state overrides of
+    /// all specified strategies applied.
+    async fn balance_of(
+        &self,
+        token: Address,
+        tries: &[(Strategy, U256)],
+    ) -> Result<U256, DetectionError> {
+        let holder = holder();
         let token = dummy_contract!(ERC20, token);
         let call = CallRequest {
             to: Some(token.address()),
@@ -73,8 +117,8 @@ impl Detector {
             token.address() => StateOverride {
                 state_diff: Some(
                     tries
-                        .clone()
-                        .map(|(strategy, amount)| strategy.state_override(&holder, &amount))
+                        .iter()
+                        .map(|(strategy, amount)| strategy.state_override(&holder, amount))
                         .collect(),
                 ),
                 ..Default::default()
@@ -82,17 +126,38 @@ impl Detector {
         };
 
         let output = self.simulator.simulate(call, overrides, None).await?;
-        let balance = (output.len() == 32)
+        (output.len() == 32)
             .then(|| U256::from_big_endian(&output))
-            .ok_or(DetectionError::Decode)?;
-
-        let strategy = tries
-            .find_map(|(strategy, amount)| (amount == balance).then_some(strategy))
-            .ok_or(DetectionError::NotFound)?;
-        Ok(strategy)
+            .ok_or(DetectionError::Decode)
     }
 }
 
+/// The token holder whose balance gets probed.
+fn holder() -> Address {
+    // On a technical note, Ethereum public addresses are, for the most part,
+    // generated by taking the 20 last bytes of a Keccak-256 hash (for things
+    // like contract creation, public address derivation from a Secp256k1
+    // public key, etc.), so we use one for our heuristics from a 32-byte
+    // digest with no know pre-image, to prevent any weird interactions with
+    // the weird tokens of the world.
+    let mut address = Address::default();
+    address.0.copy_from_slice(&keccak256(b"Moo!")[12..]);
+    address.0[19] = address.0[19].wrapping_sub(1);
+    address
+}
+
+/// Computes the storage slot of an ERC-7201 namespace.
+///
+/// <https://eips.ethereum.org/EIPS/eip-7201>
+fn erc7201(namespace: &str) -> U256 {
+    let id = U256::from_big_endian(&keccak256(namespace.as_bytes())) - 1;
+    let mut buf = [0; 32];
+    id.to_big_endian(&mut buf);
+    let mut slot = keccak256(&buf);
+    slot[31] = 0;
+    U256::from_big_endian(&slot)
+}
+
 impl Debug for Detector {
     fn fmt(&self, f: &mut Formatter) -> fmt::Result {
         f.debug_struct("Detector")
@@ -111,3 +176,163 @@ pub enum DetectionError {
     #[error(transparent)]
     Simulation(#[from] SimulationError),
 }
+
+#[cfg(test)]
+mod tests {
+    use {super::*, ethcontract::H256, ethrpc::extensions::StateOverrides, hex_literal::hex};
+
+    /// A token whose `balanceOf` reads the storage slot computed by `key` and
+    /// converts the stored value with the given rate.
+    struct Token {
+        key: fn(&Address) -> H256,
+        rate: (u64, u64),
+    }
+
+    #[async_trait::async_trait]
+    impl CodeSimulating for Token {
+        async fn simulate(
+            &self,
+            call: CallRequest,
+            overrides: StateOverrides,
+            _: Option<u64>,
+        ) -> Result<Vec<u8>, SimulationError> {
+            let holder = Address::from_slice(&call.data.unwrap().0[16..36]);
+            let stored = overrides
+                .get(&call.to.unwrap())
+                .and_then(|state| state.state_diff.as_ref()?.get(&(self.key)(&holder)))
+                .map(|value| U256::from_big_endian(&value.0))
+                .unwrap_or_default();
+            let mut output = vec![0; 32];
+            (stored * self.rate.0 / self.rate.1).to_big_endian(&mut output);
+            Ok(output)
+        }
+    }
+
+    async fn detect(
+        key: fn(&Address) -> H256,
+        rate: (u64, u64),
+    ) -> Result<Strategy, DetectionError> {
+        Detector::new(Arc::new(Token { key, rate }))
+            .detect(addr!("DEf1CA1fb7FBcDC777520aa7f396b4E015F497aB"))
+            .await
+    }
+
+    fn hash(parts: &[&[u8]]) -> H256 {
+        H256(keccak256(&parts.concat()))
+    }
+
+    #[test]
+    fn erc7201_slot() {
+        assert_eq!(
+            erc7201("openzeppelin.storage.ERC20"),
+            U256::from_big_endian(&hex!(
+                "52c63247e1f47db19d5ce0460030c497f067ca4cebf71ba98eeadabe20bace00"
+            )),
+        );
+    }
+
+    #[tokio::test]
+    async fn detects_solidity_mapping() {
+        let strategy = detect(
+            |holder| {
+                hash(&[
+                    H256::from(*holder).as_bytes(),
+                    H256::from_low_u64_be(3).as_bytes(),
+                ])
+            },
+            (1, 1),
+        )
+        .await
+        .unwrap();
+        assert_eq!(strategy, Strategy::Mapping { slot: 3.into() });
+    }
+
+    #[tokio::test]
+    async fn detects_vyper_mapping() {
+        let strategy = detect(
+            |holder| {
+                hash(&[
+                    H256::from_low_u64_be(3).as_bytes(),
+                    H256::from(*holder).as_bytes(),
+                ])
+            },
+            (1, 1),
+        )
+        .await
+        .unwrap();
+        assert_eq!(strategy, Strategy::VyperMapping { slot: 3.into() });
+    }
+
+    #[tokio::test]
+    async fn detects_namespaced_mapping() {
+        let strategy = detect(
+            |holder| {
+                hash(&[
+                    H256::from(*holder).as_bytes(),
+                    &hex!("52c63247e1f47db19d5ce0460030c497f067ca4cebf71ba98eeadabe20bace00"),
+                ])
+            },
+            (1, 1),
+        )
+        .await
+        .unwrap();
+        assert_eq!(
+            strategy,
+            Strategy::Mapping {
+                slot: erc7201("openzeppelin.storage.ERC20"),
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn detects_solady_mapping() {
+        let strategy = detect(
+            |holder| hash(&[holder.as_bytes(), &[0; 8], &hex!("87a211a2")]),
+            (1, 1),
+        )
+        .await
+        .unwrap();
+        assert_eq!(strategy, Strategy::SoladyMapping);
+    }
+
+    #[tokio::test]
+    async fn detects_shares_mapping() {
+        let strategy = detect(
+            |holder| {
+                hash(&[
+                    H256::from(*holder).as_bytes(),
+                    H256::from_low_u64_be(2).as_bytes(),
+                ])
+            },
+            (3, 2),
+        )
+        .await
+        .unwrap();
+        let Strategy::SharesMapping {
+            slot,
+            shares,
+            balance,
+        } = strategy
+        else {
+            panic!("unexpected strategy {strategy:?}");
+        };
+        assert_eq!(slot, 2.into());
+        assert_eq!(shares * 3 / 2, balance);
+
+        // The override results in a balance of at least the requested amount.
+        let amount = U256::from(1_000_000);
+        let (_, value) = Strategy::SharesMapping {
+            slot,
+            shares,
+            balance,
+        }
+        .state_override(&holder(), &amount);
+        assert!(U256::from_big_endian(&value.0) * 3 / 2 >= amount);
+    }
+
+    #[tokio::test]
+    async fn unknown_layout_not_found() {
+        let result = detect(|_| H256::zero(), (1, 1)).await;
+        assert!(matches!(result, Err(DetectionError::NotFound)));
+    }
+}
@@ -0,0 +1,165 @@
+use {
+    super::Strategy,
+    crate::code_fetching::CodeFetching,
+    anyhow::{Context as _, Result},
+    chrono::{DateTime, Utc},
+    database::{balance_override_strategies::BalanceOverrideStrategy, byte_array::ByteArray},
+    ethcontract::{Address, H256},
+    sqlx::PgPool,
+    std::{
+        fmt::{self, Debug, Formatter},
+        sync::Arc,
+    },
+    web3::signing::keccak256,
+};
+
+/// The persisted result of auto-detecting the balance override strategy of a
+/// token.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct StoredStrategy {
+    pub token: Address,
+    /// The hash of the token's code at the time of the detection.
+    pub code_hash: H256,
+    /// [`None`] if no strategy could be detected for the token.
+    pub strategy: Option<Strategy>,
+    pub detected_at: DateTime<Utc>,
+}
+
+impl TryFrom<BalanceOverrideStrategy> for StoredStrategy {
+    type Error = anyhow::Error;
+
+    fn try_from(row: BalanceOverrideStrategy) -> Result<Self> {
+        Ok(Self {
+            token: Address(row.token.0),
+            code_hash: H256(row.code_hash.0),
+            strategy: row
+                .strategy
+                .map(|strategy| strategy.parse())
+                .transpose()
+                .context("invalid balance override strategy")?,
+            detected_at: row.detected_at,
+        })
+    }
+}
+
+impl From<StoredStrategy> for BalanceOverrideStrategy {
+    fn from(stored: StoredStrategy) -> Self {
+        Self {
+            token: ByteArray(stored.token.0),
+            code_hash: ByteArray(stored.code_hash.0),
+            strategy: stored.strategy.map(|strategy| strategy.to_string()),
+            detected_at: stored.detected_at,
+        }
+    }
+}
+
+/// Stores auto-detected balance override strategies so they survive restarts
+/// and can be shared between services.
+#[mockall::automock]
+#[async_trait::async_trait]
+pub trait StrategyStoring: Send + Sync + 'static {
+    /// Returns the stored detection result of the token.
+    async fn find(&self, token: Address) -> Result<Option<StoredStrategy>>;
+
+    /// Stores the detection result, replacing a previous result of the token.
+    async fn save(&self, strategy: StoredStrategy) -> Result<()>;
+}
+
+#[async_trait::async_trait]
+impl StrategyStoring for PgPool {
+    async fn find(&self, token: Address) -> Result<Option<StoredStrategy>> {
+        let mut ex = self.acquire().await?;
+        let strategy =
+            database::balance_override_strategies::fetch(&mut ex, &ByteArray(token.0)).await?;
+        strategy.map(TryFrom::try_from).transpose()
+    }
+
+    async fn save(&self, strategy: StoredStrategy) -> Result<()> {
+        let mut ex = self.acquire().await?;
+        database::balance_override_strategies::upsert(&mut ex, &strategy.into()).await?;
+        Ok(())
+    }
+}
+
+/// Persists detection results and discards them once the code of the token
+/// changed or they expired.
+pub struct Persistence {
+    storage: Arc<dyn StrategyStoring>,
+    code_fetcher: Arc<dyn CodeFetching>,
+    /// How long detected strategies are used before detection gets retried.
+    /// The code of proxies doesn't change when they get upgraded, so this is
+    /// the only way to pick up the storage layout of a new implementation.
+    found_ttl: chrono::Duration,
+    /// How long the result of tokens without a detected strategy is used
+    /// before detection gets retried.
+    not_found_ttl: chrono::Duration,
+}
+
+impl Persistence {
+    pub fn new(
+        storage: Arc<dyn StrategyStoring>,
+        code_fetcher: Arc<dyn CodeFetching>,
+        found_ttl: chrono::Duration,
+        not_found_ttl: chrono::Duration,
+    ) -> Self {
+        Self {
+            storage,
+            code_fetcher,
+            found_ttl,
+            not_found_ttl,
+        }
+    }
+
+    /// Computes the hash the stored results of the token are checked against.
+    pub async fn code_hash(&self, token: Address) -> Result<H256> {
+        let code = self.code_fetcher.code(token).await?;
+        Ok(H256(keccak256(&code.0)))
+    }
+
+    /// Returns the stored detection result of the token if it is still valid.
+    pub async fn load(&self, token: Address, code_hash: H256) -> Result<Option<Option<Strategy>>> {
+        let Some(stored) = self.storage.find(token).await? else {
+            return Ok(None);
+        };
+        if stored.code_hash != code_hash {
+            tracing::debug!(?token, "token code changed since detection");
+            return Ok(None);
+        }
+        let ttl = match stored.strategy {
+            Some(_) => self.found_ttl,
+            None => self.not_found_ttl,
+        };
+        if Utc::now() - stored.detected_at > ttl {
+            return Ok(None);
+        }
+        Ok(Some(stored.strategy))
+    }
+
+    /// Stores the detection result of the token.
+    pub async fn save(
+        &self,
+        token: Address,
+        code_hash: H256,
+        strategy: Option<Strategy>,
+    ) -> Result<()> {
+        self.storage
+            .save(StoredStrategy {
+                token,
+                code_hash,
+                strategy,
+                detected_at: Utc::now(),
+            })
+            .await
+    }
+}
+
+impl Debug for Persistence {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.debug_struct("Persistence")
+            .field("storage", &format_args!("Arc<dyn StrategyStoring>"))
+            .field("code_fetcher", &format_args!("Arc<dyn CodeFetching>"))
+            .field("found_ttl", &self.found_ttl)
+            .field("not_found_ttl", &self.not_found_ttl)
+            .finish()
+    }
+}
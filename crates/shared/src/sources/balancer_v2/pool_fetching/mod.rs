@@ -128,6 +128,8 @@ impl StablePool {
 pub struct FetchedBalancerPools {
     pub stable_pools: Vec<StablePool>,
     pub weighted_pools: Vec<WeightedPool>,
+    pub composable_stable_pools: Vec<StablePool>,
+    pub liquidity_bootstrapping_pools: Vec<WeightedPool>,
 }
 
 impl FetchedBalancerPools {
@@ -136,11 +138,13 @@ impl FetchedBalancerPools {
         tokens.extend(
             self.stable_pools
                 .iter()
+                .chain(&self.composable_stable_pools)
                 .flat_map(|pool| pool.reserves.keys().copied()),
         );
         tokens.extend(
             self.weighted_pools
                 .iter()
+                .chain(&self.liquidity_bootstrapping_pools)
                 .flat_map(|pool| pool.reserves.keys().copied()),
         );
         tokens
@@ -363,6 +367,12 @@ impl BalancerPoolFetching for BalancerPoolFetcher {
                     PoolKind::Stable(state) => fetched_pools
                         .stable_pools
                         .push(StablePool::new_unpaused(pool.id, state)),
+                    PoolKind::ComposableStable(state) => fetched_pools
+                        .composable_stable_pools
+                        .push(StablePool::new_unpaused(pool.id, state.0)),
+                    PoolKind::LiquidityBootstrapping(state) => fetched_pools
+                        .liquidity_bootstrapping_pools
+                        .push(WeightedPool::new_unpaused(pool.id, state.0)),
                 }
                 fetched_pools
             },
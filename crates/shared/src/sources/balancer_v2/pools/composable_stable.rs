@@ -1,7 +1,7 @@
 //! Module implementing composable stable pool specific indexing logic.
 
 use {
-    super::{common, FactoryIndexing, PoolIndexing},
+    super::{common, stable, FactoryIndexing, PoolIndexing},
     crate::sources::balancer_v2::{
         graph_api::{PoolData, PoolType},
         swap::fixed_point::Bfp,
@@ -12,7 +12,13 @@ use {
     futures::{future::BoxFuture, FutureExt as _},
 };
 
-pub use super::stable::{AmplificationParameter, PoolState};
+pub use super::stable::AmplificationParameter;
+
+/// Composable stable pools use the same math as regular stable pools, their
+/// state is kept as a separate type so that they can be told apart when
+/// providing liquidity.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PoolState(pub stable::PoolState);
 
 #[derive(Clone, Debug, Default, Eq, PartialEq)]
 pub struct PoolInfo {
@@ -69,7 +75,7 @@ impl FactoryIndexing for BalancerV2ComposableStablePoolFactory {
                 AmplificationParameter::new(factor, precision)?
             };
 
-            Ok(Some(PoolState {
+            Ok(Some(PoolState(stable::PoolState {
                 tokens: common
                     .tokens
                     .into_iter()
@@ -86,7 +92,7 @@ impl FactoryIndexing for BalancerV2ComposableStablePoolFactory {
                     .collect(),
                 swap_fee: common.swap_fee,
                 amplification_parameter,
-            }))
+            })))
         }
         .boxed()
     }
@@ -1,7 +1,7 @@
 //! Module implementing liquidity bootstrapping pool specific indexing logic.
 
 use {
-    super::{common, FactoryIndexing, PoolIndexing},
+    super::{common, weighted, FactoryIndexing, PoolIndexing},
     crate::sources::balancer_v2::{
         graph_api::{PoolData, PoolType},
         swap::fixed_point::Bfp,
@@ -15,7 +15,13 @@ use {
     futures::{future::BoxFuture, FutureExt as _},
 };
 
-pub use super::weighted::{PoolState, TokenState, Version};
+pub use super::weighted::{TokenState, Version};
+
+/// Liquidity bootstrapping pools use the same math as regular weighted pools,
+/// their state is kept as a separate type so that they can be told apart when
+/// providing liquidity.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PoolState(pub weighted::PoolState);
 
 #[derive(Clone, Debug, Default, Eq, PartialEq)]
 pub struct PoolInfo {
@@ -87,11 +93,11 @@ impl FactoryIndexing for BalancerV2LiquidityBootstrappingPoolFactory {
                 .collect();
             let swap_fee = common.swap_fee;
 
-            Ok(Some(PoolState {
+            Ok(Some(PoolState(weighted::PoolState {
                 tokens,
                 swap_fee,
                 version: Version::V0,
-            }))
+            })))
         }
         .boxed()
     }
@@ -34,6 +34,8 @@ pub struct Pool {
 pub enum PoolKind {
     Weighted(weighted::PoolState),
     Stable(stable::PoolState),
+    ComposableStable(composable_stable::PoolState),
+    LiquidityBootstrapping(liquidity_bootstrapping::PoolState),
 }
 
 macro_rules! impl_from_state {
@@ -48,6 +50,8 @@ macro_rules! impl_from_state {
 
 impl_from_state!(weighted::PoolState, Weighted);
 impl_from_state!(stable::PoolState, Stable);
+impl_from_state!(composable_stable::PoolState, ComposableStable);
+impl_from_state!(liquidity_bootstrapping::PoolState, LiquidityBootstrapping);
 
 #[derive(Clone, Debug, Eq, PartialEq)]
 /// Balancer pool status.
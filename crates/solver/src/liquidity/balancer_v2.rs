@@ -24,7 +24,7 @@ use {
         ethrpc::Web3,
         http_solver::model::TokenAmount,
         recent_block_cache::Block,
-        sources::balancer_v2::pool_fetching::BalancerPoolFetching,
+        sources::balancer_v2::pool_fetching::{BalancerPoolFetching, StablePool, WeightedPool},
     },
     std::{collections::HashSet, sync::Arc},
 };
@@ -53,11 +53,7 @@ impl BalancerV2Liquidity {
         }
     }
 
-    async fn get_orders(
-        &self,
-        pairs: HashSet<TokenPair>,
-        block: Block,
-    ) -> Result<(Vec<StablePoolOrder>, Vec<WeightedProductOrder>)> {
+    async fn get_orders(&self, pairs: HashSet<TokenPair>, block: Block) -> Result<Orders> {
         let pools = self.pool_fetcher.fetch(pairs, block).await?;
 
         let tokens = pools.relevant_tokens();
@@ -73,39 +69,60 @@ impl BalancerV2Liquidity {
             vault: self.vault.clone(),
         });
 
-        let weighted_product_orders: Vec<_> = pools
-            .weighted_pools
-            .into_iter()
-            .map(|pool| WeightedProductOrder {
-                address: pool.common.address,
-                reserves: pool.reserves,
-                fee: pool.common.swap_fee,
-                version: pool.version,
-                settlement_handling: Arc::new(SettlementHandler {
-                    pool_id: pool.common.id,
-                    inner: inner.clone(),
-                }),
-            })
-            .collect();
-        let stable_pool_orders: Vec<_> = pools
-            .stable_pools
-            .into_iter()
-            .map(|pool| StablePoolOrder {
-                address: pool.common.address,
-                reserves: pool.reserves,
-                fee: pool.common.swap_fee,
-                amplification_parameter: pool.amplification_parameter,
-                settlement_handling: Arc::new(SettlementHandler {
-                    pool_id: pool.common.id,
-                    inner: inner.clone(),
-                }),
-            })
-            .collect();
+        let weighted_product_order = |pool: WeightedPool| WeightedProductOrder {
+            address: pool.common.address,
+            reserves: pool.reserves,
+            fee: pool.common.swap_fee,
+            version: pool.version,
+            settlement_handling: Arc::new(SettlementHandler {
+                pool_id: pool.common.id,
+                inner: inner.clone(),
+            }),
+        };
+        let stable_pool_order = |pool: StablePool| StablePoolOrder {
+            address: pool.common.address,
+            reserves: pool.reserves,
+            fee: pool.common.swap_fee,
+            amplification_parameter: pool.amplification_parameter,
+            settlement_handling: Arc::new(SettlementHandler {
+                pool_id: pool.common.id,
+                inner: inner.clone(),
+            }),
+        };
 
-        Ok((stable_pool_orders, weighted_product_orders))
+        Ok(Orders {
+            stable: pools
+                .stable_pools
+                .into_iter()
+                .map(stable_pool_order)
+                .collect(),
+            weighted: pools
+                .weighted_pools
+                .into_iter()
+                .map(weighted_product_order)
+                .collect(),
+            composable_stable: pools
+                .composable_stable_pools
+                .into_iter()
+                .map(stable_pool_order)
+                .collect(),
+            liquidity_bootstrapping: pools
+                .liquidity_bootstrapping_pools
+                .into_iter()
+                .map(weighted_product_order)
+                .collect(),
+        })
     }
 }
 
+/// Balancer V2 pool orders grouped by the kind of pool they were created for.
+struct Orders {
+    stable: Vec<StablePoolOrder>,
+    weighted: Vec<WeightedProductOrder>,
+    composable_stable: Vec<StablePoolOrder>,
+    liquidity_bootstrapping: Vec<WeightedProductOrder>,
+}
+
 #[async_trait::async_trait]
 impl LiquidityCollecting for BalancerV2Liquidity {
     /// Returns relevant Balancer V2 weighted pools given a list of off-chain
@@ -115,11 +132,24 @@ impl LiquidityCollecting for BalancerV2Liquidity {
         pairs: HashSet<TokenPair>,
         block: Block,
     ) -> Result<Vec<Liquidity>> {
-        let (stable, weighted) = self.get_orders(pairs, block).await?;
-        let liquidity = stable
+        let orders = self.get_orders(pairs, block).await?;
+        let liquidity = orders
+            .stable
             .into_iter()
             .map(Liquidity::BalancerStable)
-            .chain(weighted.into_iter().map(Liquidity::BalancerWeighted))
+            .chain(orders.weighted.into_iter().map(Liquidity::BalancerWeighted))
+            .chain(
+                orders
+                    .composable_stable
+                    .into_iter()
+                    .map(Liquidity::BalancerComposableStable),
+            )
+            .chain(
+                orders
+                    .liquidity_bootstrapping
+                    .into_iter()
+                    .map(Liquidity::BalancerLiquidityBootstrapping),
+            )
             .collect();
         Ok(liquidity)
     }
@@ -352,6 +382,52 @@ mod tests {
             },
         }];
 
+        let composable_stable_pools = vec![StablePool {
+            common: CommonPoolState {
+                id: H256([0x93; 32]),
+                address: H160([0x93; 20]),
+                swap_fee: "0.0004".parse().unwrap(),
+                paused: false,
+            },
+            amplification_parameter: AmplificationParameter::new(200.into(), 1.into()).unwrap(),
+            reserves: btreemap! {
+                H160([0x70; 20]) => TokenState {
+                        balance: 1_000_000_000_000_000_000u128.into(),
+                        scaling_factor: Bfp::exp10(0),
+                    },
+                H160([0x71; 20]) => TokenState {
+                        balance: 1_000_000_000_000_000_000u128.into(),
+                        scaling_factor: Bfp::exp10(0),
+                    }
+            },
+        }];
+
+        let liquidity_bootstrapping_pools = vec![WeightedPool {
+            common: CommonPoolState {
+                id: H256([0x94; 32]),
+                address: H160([0x94; 20]),
+                swap_fee: "0.01".parse().unwrap(),
+                paused: false,
+            },
+            reserves: btreemap! {
+                H160([0x73; 20]) => WeightedTokenState {
+                    common: TokenState {
+                        balance: 1_000_000_000_000_000_000u128.into(),
+                        scaling_factor: Bfp::exp10(0),
+                    },
+                    weight: "0.9".parse().unwrap(),
+                },
+                H160([0xb0; 20]) => WeightedTokenState {
+                    common: TokenState {
+                        balance: 1_000_000_000_000_000_000u128.into(),
+                        scaling_factor: Bfp::exp10(0),
+                    },
+                    weight: "0.1".parse().unwrap(),
+                },
+            },
+            version: WeightedPoolVersion::V0,
+        }];
+
         // Fetches pools for all relevant tokens, in this example, there is no
         // pool for token 0x72..72.
         pool_fetcher
@@ -370,10 +446,14 @@ mod tests {
             .returning({
                 let weighted_pools = weighted_pools.clone();
                 let stable_pools = stable_pools.clone();
+                let composable_stable_pools = composable_stable_pools.clone();
+                let liquidity_bootstrapping_pools = liquidity_bootstrapping_pools.clone();
                 move |_, _| {
                     Ok(FetchedBalancerPools {
                         stable_pools: stable_pools.clone(),
                         weighted_pools: weighted_pools.clone(),
+                        composable_stable_pools: composable_stable_pools.clone(),
+                        liquidity_bootstrapping_pools: liquidity_bootstrapping_pools.clone(),
                     })
                 }
             });
@@ -407,13 +487,16 @@ mod tests {
             pool_fetcher: Arc::new(pool_fetcher),
             allowance_manager: Box::new(allowance_manager),
         };
-        let (stable_orders, weighted_orders) = liquidity_provider
+        let orders = liquidity_provider
             .get_orders(pairs, Block::Recent)
             .await
             .unwrap();
+        let (stable_orders, weighted_orders) = (&orders.stable, &orders.weighted);
 
         assert_eq!(weighted_orders.len(), 2);
         assert_eq!(stable_orders.len(), 1);
+        assert_eq!(orders.composable_stable.len(), 1);
+        assert_eq!(orders.liquidity_bootstrapping.len(), 1);
 
         assert_eq!(
             (
@@ -443,6 +526,30 @@ mod tests {
             (&stable_orders[0].reserves, &stable_orders[0].fee),
             (&stable_pools[0].reserves, &"0.002".parse().unwrap()),
         );
+        assert_eq!(
+            (
+                &orders.composable_stable[0].reserves,
+                &orders.composable_stable[0].fee,
+                &orders.composable_stable[0].amplification_parameter
+            ),
+            (
+                &composable_stable_pools[0].reserves,
+                &"0.0004".parse().unwrap(),
+                &composable_stable_pools[0].amplification_parameter
+            ),
+        );
+        assert_eq!(
+            (
+                &orders.liquidity_bootstrapping[0].reserves,
+                &orders.liquidity_bootstrapping[0].fee,
+                orders.liquidity_bootstrapping[0].version
+            ),
+            (
+                &liquidity_bootstrapping_pools[0].reserves,
+                &"0.01".parse().unwrap(),
+                WeightedPoolVersion::V0
+            ),
+        );
     }
 
     #[test]
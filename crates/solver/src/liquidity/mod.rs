@@ -43,6 +43,8 @@ pub enum Liquidity {
     ConstantProduct(ConstantProductOrder),
     BalancerWeighted(WeightedProductOrder),
     BalancerStable(StablePoolOrder),
+    BalancerComposableStable(StablePoolOrder),
+    BalancerLiquidityBootstrapping(WeightedProductOrder),
     LimitOrder(LimitOrder),
     Concentrated(ConcentratedLiquidity),
 }
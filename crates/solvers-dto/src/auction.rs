@@ -161,6 +161,8 @@ pub enum Liquidity {
     ConstantProduct(ConstantProductPool),
     WeightedProduct(WeightedProductPool),
     Stable(StablePool),
+    ComposableStable(StablePool),
+    LiquidityBootstrapping(WeightedProductPool),
     ConcentratedLiquidity(ConcentratedLiquidityPool),
     LimitOrder(ForeignLimitOrder),
 }
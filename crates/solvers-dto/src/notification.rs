@@ -38,6 +38,8 @@ pub enum Kind {
         block: BlockNo,
         tx: Tx,
         succeeded_once: bool,
+        #[serde(default)]
+        revert: Option<Revert>,
     },
     InvalidClearingPrices,
     #[serde(rename_all = "camelCase")]
@@ -80,3 +82,35 @@ pub struct Tx {
     pub value: U256,
     pub access_list: AccessList,
 }
+
+#[serde_as]
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Revert {
+    pub reason: Option<String>,
+    #[serde_as(as = "Option<serialize::Hex>")]
+    #[serde(default)]
+    pub data: Option<Vec<u8>>,
+    pub location: Option<RevertLocation>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase", tag = "kind")]
+pub enum RevertLocation {
+    Interaction {
+        phase: InteractionPhase,
+        index: usize,
+    },
+    TokenTransfer {
+        token: H160,
+    },
+    Settlement,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum InteractionPhase {
+    Pre,
+    Intra,
+    Post,
+}
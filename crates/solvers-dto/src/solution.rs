@@ -26,10 +26,25 @@ pub struct Solution {
     pub interactions: Vec<Interaction>,
     #[serde(default)]
     pub post_interactions: Vec<Call>,
+    /// Flash loans that have to be taken out for the settlement to succeed.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub flashloans: Vec<Flashloan>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub gas: Option<u64>,
 }
 
+/// A flash loan of `amount` `token`s from the `lender`. The borrowed funds are
+/// available for the whole settlement and have to be repaid by its end.
+#[serde_as]
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Flashloan {
+    pub lender: H160,
+    pub token: H160,
+    #[serde_as(as = "HexOrDecimalU256")]
+    pub amount: U256,
+}
+
 #[derive(Clone, Debug, Serialize)]
 #[serde(tag = "kind", rename_all = "camelCase")]
 pub enum Trade {
@@ -68,9 +68,18 @@ pub fn to_domain(auction: &Auction) -> Result<auction::Auction, Error> {
                     constant_product_pool::to_domain(liquidity)
                 }
                 Liquidity::WeightedProduct(liquidity) => {
-                    weighted_product_pool::to_domain(liquidity)
+                    weighted_product_pool::to_domain(liquidity, liquidity::State::WeightedProduct)
                 }
-                Liquidity::Stable(liquidity) => stable_pool::to_domain(liquidity),
+                Liquidity::Stable(liquidity) => {
+                    stable_pool::to_domain(liquidity, liquidity::State::Stable)
+                }
+                Liquidity::ComposableStable(liquidity) => {
+                    stable_pool::to_domain(liquidity, liquidity::State::ComposableStable)
+                }
+                Liquidity::LiquidityBootstrapping(liquidity) => weighted_product_pool::to_domain(
+                    liquidity,
+                    liquidity::State::LiquidityBootstrapping,
+                ),
                 Liquidity::ConcentratedLiquidity(liquidity) => {
                     concentrated_liquidity_pool::to_domain(liquidity)
                 }
@@ -114,7 +123,10 @@ mod constant_product_pool {
 
 mod weighted_product_pool {
     use super::*;
-    pub fn to_domain(pool: &WeightedProductPool) -> Result<liquidity::Liquidity, Error> {
+    pub fn to_domain(
+        pool: &WeightedProductPool,
+        state: fn(liquidity::weighted_product::Pool) -> liquidity::State,
+    ) -> Result<liquidity::Liquidity, Error> {
         let reserves = {
             let entries = pool
                 .tokens
@@ -141,7 +153,7 @@ mod weighted_product_pool {
             id: liquidity::Id(pool.id.clone()),
             address: pool.address,
             gas: eth::Gas(pool.gas_estimate),
-            state: liquidity::State::WeightedProduct(liquidity::weighted_product::Pool {
+            state: state(liquidity::weighted_product::Pool {
                 reserves,
                 fee: conv::decimal_to_rational(&pool.fee).ok_or("invalid weighted product fee")?,
                 version: match pool.version {
@@ -156,7 +168,10 @@ mod weighted_product_pool {
 mod stable_pool {
     use super::*;
 
-    pub fn to_domain(pool: &StablePool) -> Result<liquidity::Liquidity, Error> {
+    pub fn to_domain(
+        pool: &StablePool,
+        state: fn(liquidity::stable::Pool) -> liquidity::State,
+    ) -> Result<liquidity::Liquidity, Error> {
         let reserves = {
             let entries = pool
                 .tokens
@@ -180,7 +195,7 @@ mod stable_pool {
             id: liquidity::Id(pool.id.clone()),
             address: pool.address,
             gas: eth::Gas(pool.gas_estimate),
-            state: liquidity::State::Stable(liquidity::stable::Pool {
+            state: state(liquidity::stable::Pool {
                 reserves,
                 amplification_parameter: conv::decimal_to_rational(&pool.amplification_parameter)
                     .ok_or("invalid amplification parameter")?,
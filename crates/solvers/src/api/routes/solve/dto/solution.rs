@@ -65,6 +65,7 @@ pub fn from_domain(solutions: &[solution::Solution]) -> super::Solutions {
                     .collect(),
                 pre_interactions: interaction_data_from_domain(&solution.pre_interactions),
                 post_interactions: interaction_data_from_domain(&solution.post_interactions),
+                flashloans: Default::default(),
                 interactions: solution
                     .interactions
                     .iter()
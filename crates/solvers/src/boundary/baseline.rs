@@ -3,7 +3,7 @@
 use {
     crate::{
         boundary,
-        domain::{eth, liquidity, order, solver},
+        domain::{auction, eth, liquidity, order, solver},
     },
     ethereum_types::{H160, U256},
     model::TokenPair,
@@ -11,6 +11,10 @@ use {
     std::collections::{HashMap, HashSet},
 };
 
+/// The number of equally sized parts an order gets divided into when splitting
+/// it across multiple routes.
+const SPLIT_PARTS: usize = 20;
+
 pub struct Solver<'a> {
     base_tokens: BaseTokens,
     onchain_liquidity: HashMap<TokenPair, Vec<OnchainLiquidity>>,
@@ -33,7 +37,11 @@ impl<'a> Solver<'a> {
         }
     }
 
-    pub fn route(&self, request: solver::Request, max_hops: usize) -> Option<solver::Route<'a>> {
+    pub fn route(
+        &self,
+        request: solver::baseline::Request,
+        max_hops: usize,
+    ) -> Option<solver::baseline::Route<'a>> {
         let candidates = self.base_tokens.path_candidates_with_hops(
             request.sell.token.0,
             request.buy.token.0,
@@ -91,7 +99,178 @@ impl<'a> Solver<'a> {
                 .max_by_key(|(_, buy)| buy.value)?,
         };
 
-        solver::Route::new(segments)
+        solver::baseline::Route::new(segments)
+    }
+
+    /// Splits the request across up to `max_paths` routes that don't share
+    /// any liquidity in order to reduce the price impact of large orders.
+    ///
+    /// Paths get picked greedily: the path with the best price for a single
+    /// part of the order is selected, its liquidity is removed and the process
+    /// is repeated on the remaining liquidity. The order is then distributed
+    /// across these paths in [`SPLIT_PARTS`] equally sized parts, each part
+    /// going to the path with the best marginal price for it.
+    ///
+    /// Returns `None` if the deadline is reached or the request can't be
+    /// covered by the paths.
+    pub fn route_split(
+        &self,
+        request: &solver::baseline::Request,
+        max_hops: usize,
+        max_paths: usize,
+        deadline: &auction::Deadline,
+    ) -> Option<Vec<solver::baseline::Route<'a>>> {
+        let total = match request.side {
+            order::Side::Buy => request.buy.amount,
+            order::Side::Sell => request.sell.amount,
+        };
+        let part = total / SPLIT_PARTS;
+        if part.is_zero() {
+            return None;
+        }
+
+        let paths = self.disjoint_paths(request, part, max_hops, max_paths);
+        // The amount assigned to each path and the resulting quote.
+        let mut allocations = vec![(U256::zero(), U256::zero()); paths.len()];
+        for i in 0..SPLIT_PARTS {
+            deadline.remaining()?;
+            let amount = if i == SPLIT_PARTS - 1 {
+                total - part * (SPLIT_PARTS - 1)
+            } else {
+                part
+            };
+            let candidates = paths.iter().zip(&allocations).enumerate().filter_map(
+                |(index, (path, (allocated, quoted)))| {
+                    let quote = self.quote(path, request, allocated.checked_add(amount)?)?;
+                    Some((index, quote, quote.saturating_sub(*quoted)))
+                },
+            );
+            let (index, quote, _) = match request.side {
+                order::Side::Buy => candidates.min_by_key(|(_, _, marginal)| *marginal)?,
+                order::Side::Sell => candidates.max_by_key(|(_, _, marginal)| *marginal)?,
+            };
+            allocations[index] = (allocations[index].0 + amount, quote);
+        }
+
+        let routes = paths
+            .iter()
+            .zip(&allocations)
+            .filter(|(_, (allocated, _))| !allocated.is_zero())
+            .map(|(path, (allocated, quoted))| {
+                let segments = match request.side {
+                    order::Side::Buy => {
+                        let segments = self.traverse_path(path, request.sell.token.0, *quoted)?;
+                        let buy = segments.last().map(|segment| segment.output.amount);
+                        if buy.map(|buy| buy >= *allocated) != Some(true) {
+                            tracing::warn!(
+                                ?request,
+                                ?segments,
+                                "invalid buy estimate does not cover split"
+                            );
+                            return None;
+                        }
+                        segments
+                    }
+                    order::Side::Sell => {
+                        self.traverse_path(path, request.sell.token.0, *allocated)?
+                    }
+                };
+                solver::baseline::Route::new(segments)
+            })
+            .collect::<Option<Vec<_>>>()?;
+
+        let (sell, buy) = routes
+            .iter()
+            .fold((U256::zero(), U256::zero()), |acc, route| {
+                (
+                    acc.0.saturating_add(route.input().amount),
+                    acc.1.saturating_add(route.output().amount),
+                )
+            });
+        (sell <= request.sell.amount && buy >= request.buy.amount).then_some(routes)
+    }
+
+    /// Returns up to `max_paths` paths for the request that don't share any
+    /// liquidity, ordered from best to worst price for the specified amount.
+    fn disjoint_paths(
+        &self,
+        request: &solver::baseline::Request,
+        amount: U256,
+        max_hops: usize,
+        max_paths: usize,
+    ) -> Vec<Vec<&OnchainLiquidity>> {
+        let candidates = self.base_tokens.path_candidates_with_hops(
+            request.sell.token.0,
+            request.buy.token.0,
+            max_hops,
+        );
+
+        let mut used = HashSet::new();
+        let mut paths = Vec::new();
+        while paths.len() < max_paths {
+            let available = self
+                .onchain_liquidity
+                .iter()
+                .map(|(pair, liquidity)| {
+                    let liquidity = liquidity
+                        .iter()
+                        .filter(|liquidity| !used.contains(&liquidity.id))
+                        .collect::<Vec<_>>();
+                    (*pair, liquidity)
+                })
+                .collect::<HashMap<_, _>>();
+            let estimates = candidates.iter();
+            let best = match request.side {
+                order::Side::Buy => estimates
+                    .filter_map(|path| {
+                        baseline_solver::estimate_sell_amount(amount, path, &available)
+                    })
+                    .min_by_key(|estimate| estimate.value),
+                order::Side::Sell => estimates
+                    .filter_map(|path| {
+                        baseline_solver::estimate_buy_amount(amount, path, &available)
+                    })
+                    .max_by_key(|estimate| estimate.value),
+            };
+            let Some(best) = best else {
+                break;
+            };
+            let path = best.path.into_iter().copied().collect::<Vec<_>>();
+            used.extend(path.iter().map(|liquidity| liquidity.id.clone()));
+            paths.push(path);
+        }
+        paths
+    }
+
+    /// Quotes the path for the specified amount. This is the output amount for
+    /// sell requests and the input amount for buy requests.
+    fn quote(
+        &self,
+        path: &[&OnchainLiquidity],
+        request: &solver::baseline::Request,
+        amount: U256,
+    ) -> Option<U256> {
+        let (_, amount) = match request.side {
+            order::Side::Buy => path.iter().rev().try_fold(
+                (request.buy.token.0, amount),
+                |(buy_token, buy_amount), liquidity| {
+                    let sell_token = liquidity.token_pair.other(&buy_token)?;
+                    let sell_amount =
+                        liquidity.get_amount_in(sell_token, (buy_amount, buy_token))?;
+                    Some((sell_token, sell_amount))
+                },
+            )?,
+            order::Side::Sell => path.iter().try_fold(
+                (request.sell.token.0, amount),
+                |(sell_token, sell_amount), liquidity| {
+                    let buy_token = liquidity.token_pair.other(&sell_token)?;
+                    let buy_amount =
+                        liquidity.get_amount_out(buy_token, (sell_amount, sell_token))?;
+                    Some((buy_token, buy_amount))
+                },
+            )?,
+        };
+        Some(amount)
     }
 
     fn traverse_path(
@@ -99,7 +278,7 @@ impl<'a> Solver<'a> {
         path: &[&OnchainLiquidity],
         mut sell_token: H160,
         mut sell_amount: U256,
-    ) -> Option<Vec<solver::Segment<'a>>> {
+    ) -> Option<Vec<solver::baseline::Segment<'a>>> {
         let mut segments = Vec::new();
         for liquidity in path {
             let reference_liquidity = self
@@ -113,7 +292,7 @@ impl<'a> Solver<'a> {
                 .expect("Inconsistent path");
             let buy_amount = liquidity.get_amount_out(buy_token, (sell_amount, sell_token))?;
 
-            segments.push(solver::Segment {
+            segments.push(solver::baseline::Segment {
                 liquidity: reference_liquidity,
                 input: eth::Asset {
                     token: eth::TokenAddress(sell_token),
@@ -157,7 +336,8 @@ fn to_boundary_liquidity(
                             });
                     }
                 }
-                liquidity::State::WeightedProduct(pool) => {
+                liquidity::State::WeightedProduct(pool)
+                | liquidity::State::LiquidityBootstrapping(pool) => {
                     if let Some(boundary_pool) =
                         boundary::liquidity::weighted_product::to_boundary_pool(
                             liquidity.address,
@@ -176,7 +356,7 @@ fn to_boundary_liquidity(
                         }
                     }
                 }
-                liquidity::State::Stable(pool) => {
+                liquidity::State::Stable(pool) | liquidity::State::ComposableStable(pool) => {
                     if let Some(boundary_pool) =
                         boundary::liquidity::stable::to_boundary_pool(liquidity.address, pool)
                     {
@@ -192,6 +372,21 @@ fn to_boundary_liquidity(
                         }
                     }
                 }
+                liquidity::State::Concentrated(pool) => {
+                    if let Some(boundary_pool) =
+                        boundary::liquidity::concentrated::to_boundary_pool(pool, liquidity.gas)
+                    {
+                        let token_pair = to_boundary_token_pair(&pool.tokens);
+                        onchain_liquidity
+                            .entry(token_pair)
+                            .or_default()
+                            .push(OnchainLiquidity {
+                                id: liquidity.id.clone(),
+                                token_pair,
+                                source: LiquiditySource::Concentrated(boundary_pool),
+                            });
+                    }
+                }
                 liquidity::State::LimitOrder(limit_order) => {
                     if let Some(token_pair) =
                         TokenPair::new(limit_order.maker.token.0, limit_order.taker.token.0)
@@ -206,8 +401,6 @@ fn to_boundary_liquidity(
                             })
                     }
                 }
-                // The baseline solver does not currently support other AMMs.
-                _ => {}
             };
             onchain_liquidity
         })
@@ -225,6 +418,7 @@ enum LiquiditySource {
     ConstantProduct(boundary::liquidity::constant_product::Pool),
     WeightedProduct(boundary::liquidity::weighted_product::Pool),
     Stable(boundary::liquidity::stable::Pool),
+    Concentrated(boundary::liquidity::concentrated::Pool),
     LimitOrder(liquidity::limit_order::LimitOrder),
 }
 
@@ -234,6 +428,7 @@ impl BaselineSolvable for OnchainLiquidity {
             LiquiditySource::ConstantProduct(pool) => pool.get_amount_out(out_token, input),
             LiquiditySource::WeightedProduct(pool) => pool.get_amount_out(out_token, input),
             LiquiditySource::Stable(pool) => pool.get_amount_out(out_token, input),
+            LiquiditySource::Concentrated(pool) => pool.get_amount_out(out_token, input),
             LiquiditySource::LimitOrder(limit_order) => {
                 limit_order.get_amount_out(out_token, input)
             }
@@ -245,6 +440,7 @@ impl BaselineSolvable for OnchainLiquidity {
             LiquiditySource::ConstantProduct(pool) => pool.get_amount_in(in_token, out),
             LiquiditySource::WeightedProduct(pool) => pool.get_amount_in(in_token, out),
             LiquiditySource::Stable(pool) => pool.get_amount_in(in_token, out),
+            LiquiditySource::Concentrated(pool) => pool.get_amount_in(in_token, out),
             LiquiditySource::LimitOrder(limit_order) => limit_order.get_amount_in(in_token, out),
         }
     }
@@ -254,11 +450,28 @@ impl BaselineSolvable for OnchainLiquidity {
             LiquiditySource::ConstantProduct(pool) => pool.gas_cost(),
             LiquiditySource::WeightedProduct(pool) => pool.gas_cost(),
             LiquiditySource::Stable(pool) => pool.gas_cost(),
+            LiquiditySource::Concentrated(pool) => pool.gas_cost(),
             LiquiditySource::LimitOrder(limit_order) => limit_order.gas_cost(),
         }
     }
 }
 
+/// Allows estimating amounts over a subset of the available liquidity without
+/// having to clone it.
+impl BaselineSolvable for &OnchainLiquidity {
+    fn get_amount_out(&self, out_token: H160, input: (U256, H160)) -> Option<U256> {
+        (**self).get_amount_out(out_token, input)
+    }
+
+    fn get_amount_in(&self, in_token: H160, out: (U256, H160)) -> Option<U256> {
+        (**self).get_amount_in(in_token, out)
+    }
+
+    fn gas_cost(&self) -> usize {
+        (**self).gas_cost()
+    }
+}
+
 fn to_boundary_base_tokens(
     weth: &eth::WethAddress,
     base_tokens: &HashSet<eth::TokenAddress>,
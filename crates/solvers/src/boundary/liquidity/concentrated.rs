@@ -0,0 +1,642 @@
+//! Swap math for UniswapV3-like concentrated liquidity pools.
+//!
+//! This is a port of the swap loop in `UniswapV3Pool.swap` together with the
+//! `TickMath`, `SqrtPriceMath` and `SwapMath` libraries it depends on. The
+//! computations (including rounding) are identical to the onchain contracts
+//! so that the computed amounts match what the pool will actually return.
+//!
+//! <https://github.com/Uniswap/v3-core/tree/main/contracts>
+
+use {
+    crate::domain::{eth, liquidity::concentrated},
+    ethereum_types::{H160, U256, U512},
+    shared::baseline_solver::BaselineSolvable,
+    std::ops::Bound,
+};
+
+/// A concentrated liquidity pool that can be used for baseline routing.
+#[derive(Clone, Debug)]
+pub struct Pool {
+    state: concentrated::Pool,
+    fee: u32,
+    tick_spacing: i32,
+    gas: eth::Gas,
+}
+
+/// Converts a domain pool into a pool that can be used for routing. Returns
+/// `None` for pools with a fee tier that does not have a known tick spacing,
+/// since the exact swap amounts depend on it.
+pub fn to_boundary_pool(pool: &concentrated::Pool, gas: eth::Gas) -> Option<Pool> {
+    let fee = pool
+        .fee
+        .0
+        .numer()
+        .checked_mul(U256::from(FEE_DENOMINATOR))?
+        .checked_div(*pool.fee.0.denom())?
+        .try_into()
+        .ok()?;
+    // Tick spacings of the fee tiers enabled by the Uniswap V3 factory.
+    let tick_spacing = match fee {
+        100 => 1,
+        500 => 10,
+        3000 => 60,
+        10000 => 200,
+        _ => return None,
+    };
+    Some(Pool {
+        state: pool.clone(),
+        fee,
+        tick_spacing,
+        gas,
+    })
+}
+
+impl Pool {
+    /// Simulates a swap of the pool. Positive `amount`s specify an exact input
+    /// and negative `amount`s an exact output amount. Returns the total input
+    /// and output amounts or `None` if the pool can't fill the full amount.
+    fn swap(&self, zero_for_one: bool, amount: Amount) -> Option<(U256, U256)> {
+        let limit = if zero_for_one {
+            MIN_SQRT_RATIO + 1
+        } else {
+            MAX_SQRT_RATIO - 1
+        };
+
+        let mut remaining = amount;
+        let mut calculated = U256::zero();
+        let mut sqrt_price = self.state.sqrt_price.0;
+        let mut tick = self.state.tick.0;
+        let mut liquidity = self.state.liquidity.0;
+
+        while !remaining.value.is_zero() && sqrt_price != limit {
+            let (tick_next, initialized) = self.next_initialized_tick(tick, zero_for_one);
+            let tick_next = tick_next.clamp(MIN_TICK, MAX_TICK);
+            let sqrt_price_next = sqrt_ratio_at_tick(tick_next)?;
+            let target = if (zero_for_one && sqrt_price_next < limit)
+                || (!zero_for_one && sqrt_price_next > limit)
+            {
+                limit
+            } else {
+                sqrt_price_next
+            };
+
+            let step = compute_swap_step(sqrt_price, target, liquidity, remaining, self.fee)?;
+            sqrt_price = step.sqrt_price_next;
+            if remaining.exact_in {
+                remaining.value = remaining
+                    .value
+                    .checked_sub(step.amount_in.checked_add(step.fee_amount)?)?;
+                calculated = calculated.checked_add(step.amount_out)?;
+            } else {
+                remaining.value = remaining.value.checked_sub(step.amount_out)?;
+                calculated =
+                    calculated.checked_add(step.amount_in.checked_add(step.fee_amount)?)?;
+            }
+
+            if sqrt_price == sqrt_price_next {
+                if initialized {
+                    let net = self
+                        .state
+                        .liquidity_net
+                        .get(&concentrated::Tick(tick_next))?
+                        .0;
+                    let net = if zero_for_one {
+                        net.checked_neg()?
+                    } else {
+                        net
+                    };
+                    liquidity = liquidity.checked_add_signed(net)?;
+                }
+                tick = if zero_for_one {
+                    tick_next - 1
+                } else {
+                    tick_next
+                };
+            }
+            // Otherwise the swap stopped within the current tick range and the
+            // loop is done since the remaining amount is zero.
+        }
+
+        if !remaining.value.is_zero() {
+            return None;
+        }
+        if amount.exact_in {
+            Some((amount.value, calculated))
+        } else {
+            Some((calculated, amount.value))
+        }
+    }
+
+    /// Returns the next initialized tick within the same 256 tick word as
+    /// `tick` (or the word boundary if there is none) and whether it is
+    /// initialized. Mirrors `TickBitmap.nextInitializedTickWithinOneWord`.
+    fn next_initialized_tick(&self, tick: i32, lte: bool) -> (i32, bool) {
+        let spacing = self.tick_spacing;
+        let compressed = tick.div_euclid(spacing);
+        let (lower, upper) = if lte {
+            (compressed - compressed.rem_euclid(256), compressed)
+        } else {
+            let compressed = compressed + 1;
+            (compressed, compressed + (255 - compressed.rem_euclid(256)))
+        };
+        let mut ticks = self.state.liquidity_net.range((
+            Bound::Included(concentrated::Tick(lower * spacing)),
+            Bound::Included(concentrated::Tick(upper * spacing)),
+        ));
+        let next = if lte { ticks.next_back() } else { ticks.next() };
+        match next {
+            Some((tick, _)) => (tick.0, true),
+            None if lte => (lower * spacing, false),
+            None => (upper * spacing, false),
+        }
+    }
+}
+
+impl BaselineSolvable for Pool {
+    fn get_amount_out(&self, out_token: H160, (in_amount, in_token): (U256, H160)) -> Option<U256> {
+        let zero_for_one = self.zero_for_one(in_token, out_token)?;
+        let (_, out_amount) = self.swap(zero_for_one, Amount::exact_in(in_amount))?;
+        Some(out_amount)
+    }
+
+    fn get_amount_in(&self, in_token: H160, (out_amount, out_token): (U256, H160)) -> Option<U256> {
+        let zero_for_one = self.zero_for_one(in_token, out_token)?;
+        let (in_amount, _) = self.swap(zero_for_one, Amount::exact_out(out_amount))?;
+        Some(in_amount)
+    }
+
+    fn gas_cost(&self) -> usize {
+        self.gas.0.try_into().unwrap_or(usize::MAX)
+    }
+}
+
+impl Pool {
+    /// Returns whether swapping `in_token` for `out_token` swaps token 0 for
+    /// token 1 or `None` if the tokens don't match the pool.
+    fn zero_for_one(&self, in_token: H160, out_token: H160) -> Option<bool> {
+        let (token0, token1) = self.state.tokens.get();
+        match (in_token, out_token) {
+            (i, o) if i == token0.0 && o == token1.0 => Some(true),
+            (i, o) if i == token1.0 && o == token0.0 => Some(false),
+            _ => None,
+        }
+    }
+}
+
+/// The specified amount of a swap.
+#[derive(Clone, Copy, Debug)]
+struct Amount {
+    value: U256,
+    exact_in: bool,
+}
+
+impl Amount {
+    fn exact_in(value: U256) -> Self {
+        Self {
+            value,
+            exact_in: true,
+        }
+    }
+
+    fn exact_out(value: U256) -> Self {
+        Self {
+            value,
+            exact_in: false,
+        }
+    }
+}
+
+const FEE_DENOMINATOR: u32 = 1_000_000;
+const MIN_TICK: i32 = -887272;
+const MAX_TICK: i32 = -MIN_TICK;
+const MIN_SQRT_RATIO: U256 = U256([4295128739, 0, 0, 0]);
+const MAX_SQRT_RATIO: U256 = U256([0x5d951d5263988d26, 0xefd1fc6a50648849, 0xfffd8963, 0]);
+
+fn q96() -> U256 {
+    U256::one() << 96
+}
+
+fn mul_div(a: U256, b: U256, denominator: U256) -> Option<U256> {
+    a.full_mul(b)
+        .checked_div(U512::from(denominator))?
+        .try_into()
+        .ok()
+}
+
+fn mul_div_rounding_up(a: U256, b: U256, denominator: U256) -> Option<U256> {
+    let result = mul_div(a, b, denominator)?;
+    if (a.full_mul(b) % U512::from(denominator)).is_zero() {
+        Some(result)
+    } else {
+        result.checked_add(U256::one())
+    }
+}
+
+fn div_rounding_up(a: U256, b: U256) -> Option<U256> {
+    let result = a.checked_div(b)?;
+    if (a % b).is_zero() {
+        Some(result)
+    } else {
+        Some(result + 1)
+    }
+}
+
+/// Port of `TickMath.getSqrtRatioAtTick`.
+fn sqrt_ratio_at_tick(tick: i32) -> Option<U256> {
+    const FACTORS: [u128; 19] = [
+        0xfff97272373d413259a46990580e213a,
+        0xfff2e50f5f656932ef12357cf3c7fdcc,
+        0xffe5caca7e10e4e61c3624eaa0941cd0,
+        0xffcb9843d60f6159c9db58835c926644,
+        0xff973b41fa98c081472e6896dfb254c0,
+        0xff2ea16466c96a3843ec78b326b52861,
+        0xfe5dee046a99a2a811c461f1969c3053,
+        0xfcbe86c7900a88aedcffc83b479aa3a4,
+        0xf987a7253ac413176f2b074cf7815e54,
+        0xf3392b0822b70005940c7a398e4b70f3,
+        0xe7159475a2c29b7443b29c7fa6e889d9,
+        0xd097f3bdfd2022b8845ad8f792aa5825,
+        0xa9f746462d870fdf8a65dc1f90e061e5,
+        0x70d869a156d2a1b890bb3df62baf32f7,
+        0x31be135f97d08fd981231505542fcfa6,
+        0x9aa508b5b7a84e1c677de54f3e99bc9,
+        0x5d6af8dedb81196699c329225ee604,
+        0x2216e584f5fa1ea926041bedfe98,
+        0x48a170391f7dc42444e8fa2,
+    ];
+
+    let abs_tick = tick.unsigned_abs();
+    if abs_tick > MAX_TICK.unsigned_abs() {
+        return None;
+    }
+
+    let mut ratio = if abs_tick & 0x1 != 0 {
+        U256::from(0xfffcb933bd6fad37aa2d162d1a594001_u128)
+    } else {
+        U256::one() << 128
+    };
+    for (i, factor) in FACTORS.into_iter().enumerate() {
+        if abs_tick & (1 << (i + 1)) != 0 {
+            // Can't overflow since both values are at most 128 bits.
+            ratio = (ratio * U256::from(factor)) >> 128;
+        }
+    }
+    if tick > 0 {
+        ratio = U256::MAX / ratio;
+    }
+
+    // Divide by 1 << 32 rounding up to go from Q128.128 to Q128.96.
+    let remainder = ratio.low_u32();
+    Some((ratio >> 32) + U256::from(u8::from(remainder != 0)))
+}
+
+/// Port of `SqrtPriceMath.getAmount0Delta`.
+fn amount0_delta(a: U256, b: U256, liquidity: u128, round_up: bool) -> Option<U256> {
+    let (a, b) = if a > b { (b, a) } else { (a, b) };
+    if a.is_zero() {
+        return None;
+    }
+    let numerator1 = U256::from(liquidity) << 96;
+    let numerator2 = b - a;
+    if round_up {
+        div_rounding_up(mul_div_rounding_up(numerator1, numerator2, b)?, a)
+    } else {
+        Some(mul_div(numerator1, numerator2, b)? / a)
+    }
+}
+
+/// Port of `SqrtPriceMath.getAmount1Delta`.
+fn amount1_delta(a: U256, b: U256, liquidity: u128, round_up: bool) -> Option<U256> {
+    let (a, b) = if a > b { (b, a) } else { (a, b) };
+    if round_up {
+        mul_div_rounding_up(U256::from(liquidity), b - a, q96())
+    } else {
+        mul_div(U256::from(liquidity), b - a, q96())
+    }
+}
+
+/// Port of `SqrtPriceMath.getNextSqrtPriceFromAmount0RoundingUp`.
+fn next_sqrt_price_from_amount0(
+    sqrt_price: U256,
+    liquidity: u128,
+    amount: U256,
+    add: bool,
+) -> Option<U256> {
+    if amount.is_zero() {
+        return Some(sqrt_price);
+    }
+    let numerator1 = U256::from(liquidity) << 96;
+    let product = amount.checked_mul(sqrt_price);
+    if add {
+        if let Some(denominator) = product.and_then(|product| numerator1.checked_add(product)) {
+            return mul_div_rounding_up(numerator1, sqrt_price, denominator);
+        }
+        div_rounding_up(numerator1, (numerator1 / sqrt_price).checked_add(amount)?)
+    } else {
+        let product = product.filter(|product| numerator1 > *product)?;
+        let price = mul_div_rounding_up(numerator1, sqrt_price, numerator1 - product)?;
+        (price.bits() <= 160).then_some(price)
+    }
+}
+
+/// Port of `SqrtPriceMath.getNextSqrtPriceFromAmount1RoundingDown`.
+fn next_sqrt_price_from_amount1(
+    sqrt_price: U256,
+    liquidity: u128,
+    amount: U256,
+    add: bool,
+) -> Option<U256> {
+    let liquidity = U256::from(liquidity);
+    if add {
+        let quotient = if amount.bits() <= 160 {
+            (amount << 96).checked_div(liquidity)?
+        } else {
+            mul_div(amount, q96(), liquidity)?
+        };
+        let price = sqrt_price.checked_add(quotient)?;
+        (price.bits() <= 160).then_some(price)
+    } else {
+        let quotient = if amount.bits() <= 160 {
+            div_rounding_up(amount << 96, liquidity)?
+        } else {
+            mul_div_rounding_up(amount, q96(), liquidity)?
+        };
+        (sqrt_price > quotient).then(|| sqrt_price - quotient)
+    }
+}
+
+/// Port of `SqrtPriceMath.getNextSqrtPriceFromInput`.
+fn next_sqrt_price_from_input(
+    sqrt_price: U256,
+    liquidity: u128,
+    amount_in: U256,
+    zero_for_one: bool,
+) -> Option<U256> {
+    if sqrt_price.is_zero() || liquidity == 0 {
+        return None;
+    }
+    if zero_for_one {
+        next_sqrt_price_from_amount0(sqrt_price, liquidity, amount_in, true)
+    } else {
+        next_sqrt_price_from_amount1(sqrt_price, liquidity, amount_in, true)
+    }
+}
+
+/// Port of `SqrtPriceMath.getNextSqrtPriceFromOutput`.
+fn next_sqrt_price_from_output(
+    sqrt_price: U256,
+    liquidity: u128,
+    amount_out: U256,
+    zero_for_one: bool,
+) -> Option<U256> {
+    if sqrt_price.is_zero() || liquidity == 0 {
+        return None;
+    }
+    if zero_for_one {
+        next_sqrt_price_from_amount1(sqrt_price, liquidity, amount_out, false)
+    } else {
+        next_sqrt_price_from_amount0(sqrt_price, liquidity, amount_out, false)
+    }
+}
+
+struct SwapStep {
+    sqrt_price_next: U256,
+    amount_in: U256,
+    amount_out: U256,
+    fee_amount: U256,
+}
+
+/// Port of `SwapMath.computeSwapStep`.
+fn compute_swap_step(
+    sqrt_price_current: U256,
+    sqrt_price_target: U256,
+    liquidity: u128,
+    remaining: Amount,
+    fee: u32,
+) -> Option<SwapStep> {
+    let zero_for_one = sqrt_price_current >= sqrt_price_target;
+    let fee_complement = U256::from(FEE_DENOMINATOR - fee);
+
+    let mut amount_in = U256::zero();
+    let mut amount_out = U256::zero();
+    let sqrt_price_next = if remaining.exact_in {
+        let remaining_less_fee = mul_div(remaining.value, fee_complement, FEE_DENOMINATOR.into())?;
+        amount_in = if zero_for_one {
+            amount0_delta(sqrt_price_target, sqrt_price_current, liquidity, true)?
+        } else {
+            amount1_delta(sqrt_price_current, sqrt_price_target, liquidity, true)?
+        };
+        if remaining_less_fee >= amount_in {
+            sqrt_price_target
+        } else {
+            next_sqrt_price_from_input(
+                sqrt_price_current,
+                liquidity,
+                remaining_less_fee,
+                zero_for_one,
+            )?
+        }
+    } else {
+        amount_out = if zero_for_one {
+            amount1_delta(sqrt_price_target, sqrt_price_current, liquidity, false)?
+        } else {
+            amount0_delta(sqrt_price_current, sqrt_price_target, liquidity, false)?
+        };
+        if remaining.value >= amount_out {
+            sqrt_price_target
+        } else {
+            next_sqrt_price_from_output(
+                sqrt_price_current,
+                liquidity,
+                remaining.value,
+                zero_for_one,
+            )?
+        }
+    };
+
+    // Amounts that were already computed for reaching the target price don't
+    // need to be recomputed.
+    let max = sqrt_price_target == sqrt_price_next;
+    let recompute_in = !max || !remaining.exact_in;
+    let recompute_out = !max || remaining.exact_in;
+    if zero_for_one {
+        if recompute_in {
+            amount_in = amount0_delta(sqrt_price_next, sqrt_price_current, liquidity, true)?;
+        }
+        if recompute_out {
+            amount_out = amount1_delta(sqrt_price_next, sqrt_price_current, liquidity, false)?;
+        }
+    } else {
+        if recompute_in {
+            amount_in = amount1_delta(sqrt_price_current, sqrt_price_next, liquidity, true)?;
+        }
+        if recompute_out {
+            amount_out = amount0_delta(sqrt_price_current, sqrt_price_next, liquidity, false)?;
+        }
+    }
+
+    // Cap the output amount to not exceed the remaining output amount.
+    if !remaining.exact_in && amount_out > remaining.value {
+        amount_out = remaining.value;
+    }
+
+    let fee_amount = if remaining.exact_in && sqrt_price_next != sqrt_price_target {
+        // We didn't reach the target, so take the remainder of the maximum
+        // input as fee.
+        remaining.value.checked_sub(amount_in)?
+    } else {
+        mul_div_rounding_up(amount_in, fee.into(), fee_complement)?
+    };
+
+    Some(SwapStep {
+        sqrt_price_next,
+        amount_in,
+        amount_out,
+        fee_amount,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, crate::domain::liquidity, shared::addr, std::collections::BTreeMap};
+
+    fn token0() -> H160 {
+        addr!("a0b86991c6218b36c1d19d4a2e9eb0ce3606eb48")
+    }
+
+    fn token1() -> H160 {
+        addr!("c02aaa39b223fe8d0a0e5c4f27ead9083c756cc2")
+    }
+
+    fn to_wei(base: u32) -> U256 {
+        U256::from(base) * U256::exp10(18)
+    }
+
+    /// A pool with 0.3% fees, a price slightly above 1 and two overlapping
+    /// positions in the ranges [-600, 600] and [-120, 120].
+    fn pool(fee: eth::Rational) -> concentrated::Pool {
+        let liquidity = |base: i128| base * 10_i128.pow(20);
+        concentrated::Pool {
+            tokens: liquidity::TokenPair::new(
+                eth::TokenAddress(token0()),
+                eth::TokenAddress(token1()),
+            )
+            .unwrap(),
+            sqrt_price: concentrated::SqrtPrice(q96() + 12_345_678_901_234_567_u64),
+            liquidity: concentrated::Amount(500_000_000_000_000_000_000),
+            tick: concentrated::Tick(0),
+            liquidity_net: BTreeMap::from([
+                (
+                    concentrated::Tick(-600),
+                    concentrated::LiquidityNet(liquidity(3)),
+                ),
+                (
+                    concentrated::Tick(-120),
+                    concentrated::LiquidityNet(liquidity(2)),
+                ),
+                (
+                    concentrated::Tick(120),
+                    concentrated::LiquidityNet(-liquidity(2)),
+                ),
+                (
+                    concentrated::Tick(600),
+                    concentrated::LiquidityNet(-liquidity(3)),
+                ),
+            ]),
+            fee: concentrated::Fee(fee),
+        }
+    }
+
+    fn boundary_pool() -> Pool {
+        to_boundary_pool(
+            &pool(eth::Rational::new_raw(3.into(), 1000.into())),
+            eth::Gas(110_000.into()),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn sqrt_ratio_at_tick_bounds() {
+        assert_eq!(sqrt_ratio_at_tick(MIN_TICK), Some(MIN_SQRT_RATIO));
+        assert_eq!(sqrt_ratio_at_tick(MAX_TICK), Some(MAX_SQRT_RATIO));
+        assert_eq!(sqrt_ratio_at_tick(0), Some(q96()));
+        assert_eq!(
+            sqrt_ratio_at_tick(1),
+            Some(U256::from_dec_str("79232123823359799118286999568").unwrap()),
+        );
+        assert_eq!(sqrt_ratio_at_tick(MIN_TICK - 1), None);
+        assert_eq!(sqrt_ratio_at_tick(MAX_TICK + 1), None);
+    }
+
+    #[test]
+    fn swap_within_tick_range() {
+        let pool = boundary_pool();
+        let amount = to_wei(1);
+
+        assert_eq!(
+            pool.get_amount_out(token1(), (amount, token0())),
+            Some(U256::from(995_015_938_219_500_718_u128)),
+        );
+        assert_eq!(
+            pool.get_amount_out(token0(), (amount, token1())),
+            Some(U256::from(995_015_938_218_881_146_u128)),
+        );
+        assert_eq!(
+            pool.get_amount_in(token0(), (amount, token1())),
+            Some(U256::from(1_005_019_065_211_353_540_u128)),
+        );
+        assert_eq!(
+            pool.get_amount_in(token1(), (amount, token0())),
+            Some(U256::from(1_005_019_065_211_980_593_u128)),
+        );
+    }
+
+    #[test]
+    fn swap_across_initialized_ticks() {
+        let pool = boundary_pool();
+        let amount = to_wei(10);
+
+        assert_eq!(
+            pool.get_amount_out(token1(), (amount, token0())),
+            Some(U256::from(9_713_904_809_614_879_018_u128)),
+        );
+        assert_eq!(
+            pool.get_amount_out(token0(), (amount, token1())),
+            Some(U256::from(9_713_904_809_606_156_342_u128)),
+        );
+        assert_eq!(
+            pool.get_amount_in(token0(), (amount, token1())),
+            Some(U256::from(10_304_270_315_048_315_722_u128)),
+        );
+        assert_eq!(
+            pool.get_amount_in(token1(), (amount, token0())),
+            Some(U256::from(10_304_270_315_057_908_911_u128)),
+        );
+    }
+
+    #[test]
+    fn insufficient_liquidity() {
+        let pool = boundary_pool();
+        let amount = to_wei(20);
+
+        assert_eq!(pool.get_amount_out(token1(), (amount, token0())), None);
+        assert_eq!(pool.get_amount_out(token0(), (amount, token1())), None);
+        assert_eq!(pool.get_amount_in(token0(), (amount, token1())), None);
+        assert_eq!(pool.get_amount_in(token1(), (amount, token0())), None);
+    }
+
+    #[test]
+    fn wrong_tokens() {
+        let pool = boundary_pool();
+        let other = addr!("def1ca1fb7fbcdc777520aa7f396b4e015f497ab");
+
+        assert_eq!(pool.get_amount_out(other, (to_wei(1), token0())), None);
+        assert_eq!(pool.get_amount_in(token0(), (to_wei(1), other)), None);
+    }
+
+    #[test]
+    fn unknown_fee_tier() {
+        let pool = pool(eth::Rational::new_raw(2.into(), 1000.into()));
+        assert!(to_boundary_pool(&pool, eth::Gas(110_000.into())).is_none());
+    }
+}
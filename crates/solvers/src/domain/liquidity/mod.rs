@@ -27,6 +27,13 @@ pub enum State {
     ConstantProduct(constant_product::Pool),
     WeightedProduct(weighted_product::Pool),
     Stable(stable::Pool),
+    /// A Balancer V2 composable stable pool. These pools use the same math as
+    /// regular stable pools.
+    ComposableStable(stable::Pool),
+    /// A Balancer V2 liquidity bootstrapping pool. These pools use the same
+    /// math as regular weighted product pools, but their weights change over
+    /// time.
+    LiquidityBootstrapping(weighted_product::Pool),
     Concentrated(concentrated::Pool),
     LimitOrder(limit_order::LimitOrder),
 }
@@ -1,9 +1,9 @@
 //! "Baseline" solver implementation.
 //!
 //! The baseline solver is a simple solver implementation that finds the best
-//! path of at most length `max_hops + 1` over a set of on-chain liquidity. It
-//! **does not** try to split large orders into multiple parts and route them
-//! over separate paths.
+//! path of at most length `max_hops + 1` over a set of on-chain liquidity.
+//! Optionally, large orders can be split into multiple parts that get routed
+//! over separate paths not sharing any liquidity.
 
 use {
     crate::{
@@ -14,6 +14,7 @@ use {
             liquidity,
             order::{self, Order},
             solution,
+            solver::DEADLINE_SLACK,
         },
         infra::metrics,
     },
@@ -21,17 +22,14 @@ use {
     std::{cmp, collections::HashSet, sync::Arc},
 };
 
-pub struct Solver(Arc<Inner>);
-
-/// The amount of time we aim the solver to finish before the final deadline is
-/// reached.
-const DEADLINE_SLACK: chrono::Duration = chrono::Duration::milliseconds(500);
+pub struct Baseline(Arc<Inner>);
 
 pub struct Config {
     pub weth: eth::WethAddress,
     pub base_tokens: Vec<eth::TokenAddress>,
     pub max_hops: usize,
     pub max_partial_attempts: usize,
+    pub max_split_paths: usize,
     pub solution_gas_offset: eth::SignedGas,
     pub native_token_price_estimation_amount: eth::U256,
 }
@@ -58,6 +56,12 @@ struct Inner {
     /// valid solution or exceed this count.
     max_partial_attempts: usize,
 
+    /// The maximum number of paths to split an order across. Splitting is
+    /// disabled for values smaller than 2. Splits are only used if they result
+    /// in a better execution than the best single path after accounting for
+    /// the additional gas.
+    max_split_paths: usize,
+
     /// Units of gas that get added to the gas estimate for executing a
     /// computed trade route to arrive at a gas estimate for a whole settlement.
     solution_gas_offset: eth::SignedGas,
@@ -67,7 +71,7 @@ struct Inner {
     native_token_price_estimation_amount: eth::U256,
 }
 
-impl Solver {
+impl Baseline {
     /// Creates a new baseline solver for the specified configuration.
     pub fn new(config: Config) -> Self {
         Self(Arc::new(Inner {
@@ -75,6 +79,7 @@ impl Solver {
             base_tokens: config.base_tokens.into_iter().collect(),
             max_hops: config.max_hops,
             max_partial_attempts: config.max_partial_attempts,
+            max_split_paths: config.max_split_paths,
             solution_gas_offset: config.solution_gas_offset,
             native_token_price_estimation_amount: config.native_token_price_estimation_amount,
         }))
@@ -128,6 +133,7 @@ impl Inner {
     ) {
         let boundary_solver =
             boundary::baseline::Solver::new(&self.weth, &self.base_tokens, &auction.liquidity);
+        let deadline = auction.deadline.clone().reduce(DEADLINE_SLACK);
 
         for (i, order) in auction.orders.into_iter().enumerate() {
             let sell_token = order.sell.token;
@@ -162,10 +168,16 @@ impl Inner {
             let solution = self.requests_for_order(&order).find_map(|request| {
                 tracing::trace!(order =% order.uid, ?request, "finding route");
 
-                let route = boundary_solver.route(request, self.max_hops)?;
-                let interactions = route
-                    .segments
+                let routes = self.routes(
+                    &boundary_solver,
+                    request,
+                    &auction.tokens,
+                    auction.gas_price,
+                    &deadline,
+                )?;
+                let interactions = routes
                     .iter()
+                    .flat_map(|route| &route.segments)
                     .map(|segment| {
                         solution::Interaction::Liquidity(solution::LiquidityInteraction {
                             liquidity: segment.liquidity.clone(),
@@ -183,12 +195,12 @@ impl Inner {
                 // can buy slightly more than intended. Fix this by
                 // capping the output amount to the order's buy amount
                 // for buy orders.
-                let mut output = route.output();
+                let mut output = total(&routes, Route::output);
                 if let order::Side::Buy = order.side {
                     output.amount = cmp::min(output.amount, order.buy.amount);
                 }
 
-                let gas = route.gas() + self.solution_gas_offset;
+                let gas = total_gas(&routes) + self.solution_gas_offset;
                 let fee = sell_token_price
                     .ether_value(eth::Ether(gas.0.checked_mul(auction.gas_price.0 .0)?))?
                     .into();
@@ -196,7 +208,7 @@ impl Inner {
                 Some(
                     solution::Single {
                         order: order.clone(),
-                        input: route.input(),
+                        input: total(&routes, Route::input),
                         output,
                         interactions,
                         gas,
@@ -215,6 +227,57 @@ impl Inner {
         }
     }
 
+    /// Finds the routes to execute the request over. This is the best single
+    /// route unless splitting the request across multiple routes results in a
+    /// better execution after accounting for the additional gas.
+    fn routes<'a>(
+        &self,
+        boundary_solver: &boundary::baseline::Solver<'a>,
+        request: Request,
+        tokens: &auction::Tokens,
+        gas_price: auction::GasPrice,
+        deadline: &auction::Deadline,
+    ) -> Option<Vec<Route<'a>>> {
+        let split = (self.max_split_paths > 1)
+            .then(|| {
+                boundary_solver.route_split(&request, self.max_hops, self.max_split_paths, deadline)
+            })
+            .flatten()
+            .filter(|routes| routes.len() > 1);
+        let side = request.side;
+        let (sell_token, buy_token) = (request.sell.token, request.buy.token);
+        let single = boundary_solver
+            .route(request, self.max_hops)
+            .map(|route| vec![route]);
+
+        let (single, split) = match (single, split) {
+            (Some(single), Some(split)) => (single, split),
+            (single, split) => return single.or(split),
+        };
+
+        // Compare the executions in the token the order is optimizing for,
+        // converting the additional gas of the split into that token.
+        let token = match side {
+            order::Side::Buy => sell_token,
+            order::Side::Sell => buy_token,
+        };
+        let value = |routes: &[Route]| match side {
+            order::Side::Buy => total(routes, Route::input).amount,
+            order::Side::Sell => total(routes, Route::output).amount,
+        };
+        let Some(extra_gas_cost) = tokens.reference_price(&token).and_then(|price| {
+            let extra_gas = total_gas(&split).0.saturating_sub(total_gas(&single).0);
+            price.ether_value(eth::Ether(extra_gas.checked_mul(gas_price.0 .0)?))
+        }) else {
+            return Some(single);
+        };
+        let better = match side {
+            order::Side::Buy => value(&split).saturating_add(extra_gas_cost) < value(&single),
+            order::Side::Sell => value(&split).saturating_sub(extra_gas_cost) > value(&single),
+        };
+        Some(if better { split } else { single })
+    }
+
     fn requests_for_order(&self, order: &Order) -> impl Iterator<Item = Request> {
         let order::Order {
             sell, buy, side, ..
@@ -320,20 +383,44 @@ impl<'a> Route<'a> {
         Some(Self { segments })
     }
 
-    fn input(&self) -> eth::Asset {
+    pub fn segments(&self) -> &[Segment<'a>] {
+        &self.segments
+    }
+
+    pub fn input(&self) -> eth::Asset {
         self.segments[0].input
     }
 
-    fn output(&self) -> eth::Asset {
+    pub fn output(&self) -> eth::Asset {
         self.segments
             .last()
             .expect("route has at least one segment by construction")
             .output
     }
 
-    fn gas(&self) -> eth::Gas {
+    pub fn gas(&self) -> eth::Gas {
         eth::Gas(self.segments.iter().fold(U256::zero(), |acc, segment| {
             acc.saturating_add(segment.gas.0)
         }))
     }
 }
+
+/// Sums up the assets of multiple routes trading the same tokens.
+fn total<'a>(routes: &[Route<'a>], asset: impl Fn(&Route<'a>) -> eth::Asset) -> eth::Asset {
+    let mut assets = routes.iter().map(asset);
+    let first = assets
+        .next()
+        .expect("at least one route is always found by construction");
+    assets.fold(first, |total, asset| eth::Asset {
+        token: total.token,
+        amount: total.amount.saturating_add(asset.amount),
+    })
+}
+
+fn total_gas(routes: &[Route]) -> eth::Gas {
+    eth::Gas(
+        routes
+            .iter()
+            .fold(U256::zero(), |acc, route| acc.saturating_add(route.gas().0)),
+    )
+}
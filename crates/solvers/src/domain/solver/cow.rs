@@ -0,0 +1,604 @@
+//! "CoW" solver implementation.
+//!
+//! The CoW solver looks for coincidences of wants between the orders of an
+//! auction and settles them against each other at uniform clearing prices:
+//!
+//! - **Pairs**: orders trading opposite directions of the same token pair get
+//!   matched. Only the remaining imbalance gets routed through onchain
+//!   liquidity, whose execution price then defines the clearing price.
+//! - **Rings**: cycles of two or three orders (`A -> B -> C -> A`) where every
+//!   order receives exactly what the next one sells, requiring no liquidity at
+//!   all.
+//!
+//! Matches that don't share any tokens or liquidity are combined into a single
+//! solution for the whole batch. Orders get executed completely and only
+//! market orders are considered since the solver does not compute fees.
+
+use {
+    crate::{
+        boundary,
+        domain::{
+            auction,
+            eth,
+            order::{self, Order},
+            solution,
+            solver::{baseline, DEADLINE_SLACK},
+        },
+        infra::metrics,
+        util::math,
+    },
+    ethereum_types::U256,
+    shared::price_estimation::gas,
+    std::{
+        collections::{HashMap, HashSet},
+        sync::Arc,
+    },
+};
+
+/// The maximum number of times the clearing price of a pair gets updated to
+/// converge towards the execution price of the routed imbalance.
+const MAX_PRICE_ITERATIONS: usize = 8;
+
+/// The maximum number of order rings to consider for matching.
+const MAX_RINGS: usize = 10_000;
+
+/// Relative price difference (in basis points) applied in favour of the routed
+/// imbalance, so that rounding doesn't make the final prices infeasible.
+const PRICE_TOLERANCE_BPS: u64 = 1;
+
+pub struct Cow(Arc<Inner>);
+
+pub struct Config {
+    pub weth: eth::WethAddress,
+    pub base_tokens: Vec<eth::TokenAddress>,
+    pub max_hops: usize,
+}
+
+struct Inner {
+    weth: eth::WethAddress,
+
+    /// Set of tokens to additionally consider as intermediary hops when
+    /// routing the imbalance of matched orders.
+    base_tokens: HashSet<eth::TokenAddress>,
+
+    /// Maximum number of hops that can be considered in a trading path when
+    /// routing the imbalance of matched orders.
+    max_hops: usize,
+}
+
+impl Cow {
+    /// Creates a new CoW solver for the specified configuration.
+    pub fn new(config: Config) -> Self {
+        Self(Arc::new(Inner {
+            weth: config.weth,
+            base_tokens: config.base_tokens.into_iter().collect(),
+            max_hops: config.max_hops,
+        }))
+    }
+
+    /// Solves the specified auction, returning at most a single solution
+    /// containing all matched orders.
+    pub async fn solve(&self, auction: auction::Auction) -> Vec<solution::Solution> {
+        metrics::solve(&auction);
+        let deadline = auction.deadline.clone();
+        // Matching is CPU-heavy, so move it to a separate thread in order to
+        // not lock up the [`tokio`] runtime.
+        let remaining = auction
+            .deadline
+            .clone()
+            .reduce(DEADLINE_SLACK)
+            .remaining()
+            .unwrap_or_default();
+
+        let inner = self.0.clone();
+        let span = tracing::Span::current();
+        let background_work = tokio::task::spawn_blocking(move || {
+            let _entered = span.enter();
+            inner.solve(auction)
+        });
+
+        let solutions = match tokio::time::timeout(remaining, background_work).await {
+            Ok(Ok(solution)) => solution.into_iter().collect(),
+            Ok(Err(err)) => {
+                tracing::warn!(?err, "CoW matching failed");
+                vec![]
+            }
+            Err(_) => {
+                tracing::debug!("reached timeout while matching orders");
+                vec![]
+            }
+        };
+        metrics::solved(&deadline, &solutions);
+        solutions
+    }
+}
+
+impl Inner {
+    fn solve(&self, auction: auction::Auction) -> Option<solution::Solution> {
+        let deadline = auction.deadline.clone().reduce(DEADLINE_SLACK);
+        let boundary_solver =
+            boundary::baseline::Solver::new(&self.weth, &self.base_tokens, &auction.liquidity);
+
+        let orders = auction
+            .orders
+            .iter()
+            .filter(|order| order.class == order::Class::Market)
+            .collect::<Vec<_>>();
+        let mut by_sell_token = HashMap::<_, Vec<_>>::new();
+        for order in &orders {
+            by_sell_token
+                .entry(order.sell.token)
+                .or_default()
+                .push(*order);
+        }
+
+        let mut pairs = Vec::new();
+        for (token, sellers) in &by_sell_token {
+            for (other, buyers) in &by_sell_token {
+                if token >= other {
+                    continue;
+                }
+                let sellers = sellers
+                    .iter()
+                    .filter(|order| order.buy.token == *other)
+                    .copied()
+                    .collect::<Vec<_>>();
+                let buyers = buyers
+                    .iter()
+                    .filter(|order| order.buy.token == *token)
+                    .copied()
+                    .collect::<Vec<_>>();
+                if sellers.is_empty() || buyers.is_empty() {
+                    continue;
+                }
+                deadline.remaining()?;
+                if let Some(group) = self.match_pair(
+                    &boundary_solver,
+                    (*token, *other),
+                    &sellers,
+                    &buyers,
+                    &auction,
+                    &deadline,
+                ) {
+                    pairs.push(group);
+                }
+            }
+        }
+        let mut rings = Vec::new();
+        for ring in self::rings(&orders, &by_sell_token) {
+            deadline.remaining()?;
+            rings.extend(match_ring(&ring));
+        }
+
+        // Matches priced by onchain liquidity take precedence over rings that
+        // are only priced by the traded amounts. Groups can't share any tokens
+        // as they would need to agree on a single clearing price for them.
+        let by_value = |a: &Group, b: &Group| {
+            b.value(&auction.tokens)
+                .cmp(&a.value(&auction.tokens))
+                .then_with(|| b.trades.len().cmp(&a.trades.len()))
+        };
+        pairs.sort_by(by_value);
+        rings.sort_by(by_value);
+
+        let mut used_tokens = HashSet::new();
+        let mut used_liquidity = HashSet::new();
+        let mut selected = Vec::new();
+        for group in pairs.into_iter().chain(rings) {
+            let tokens = group.prices.iter().map(|(token, _)| *token);
+            let liquidity = group
+                .routes
+                .iter()
+                .flat_map(|route| route.segments())
+                .map(|segment| segment.liquidity.id.clone());
+            if tokens.clone().any(|token| used_tokens.contains(&token))
+                || liquidity.clone().any(|id| used_liquidity.contains(&id))
+            {
+                continue;
+            }
+            used_tokens.extend(tokens);
+            used_liquidity.extend(liquidity);
+            selected.push(group);
+        }
+        if selected.is_empty() {
+            return None;
+        }
+
+        let trades = selected
+            .iter()
+            .flat_map(|group| &group.trades)
+            .map(|(order, executed)| {
+                solution::Fulfillment::new((*order).clone(), *executed, solution::Fee::Protocol)
+                    .map(solution::Trade::Fulfillment)
+            })
+            .collect::<Option<Vec<_>>>()?;
+        let interactions = selected
+            .iter()
+            .flat_map(|group| &group.routes)
+            .flat_map(|route| route.segments())
+            .map(|segment| {
+                solution::Interaction::Liquidity(solution::LiquidityInteraction {
+                    liquidity: segment.liquidity.clone(),
+                    input: segment.input,
+                    output: segment.output,
+                    internalize: false,
+                })
+            })
+            .collect();
+        let gas = selected.iter().flat_map(|group| &group.routes).fold(
+            U256::from(gas::SETTLEMENT)
+                + U256::from(gas::TRADE + 2 * gas::ERC20_TRANSFER) * trades.len(),
+            |acc, route| acc.saturating_add(route.gas().0),
+        );
+
+        Some(
+            solution::Solution {
+                id: Default::default(),
+                prices: solution::ClearingPrices::new(
+                    selected.iter().flat_map(|group| group.prices.clone()),
+                ),
+                trades,
+                pre_interactions: Default::default(),
+                interactions,
+                post_interactions: Default::default(),
+                gas: Some(eth::Gas(gas)),
+            }
+            .with_buffers_internalizations(&auction.tokens),
+        )
+    }
+
+    /// Matches orders selling `token` for `other` (`sellers`) against orders
+    /// selling `other` for `token` (`buyers`).
+    ///
+    /// Starting at the auction's reference prices, the clearing price is
+    /// repeatedly set to the execution price of routing the imbalance of all
+    /// orders whose limit price is satisfied, until it converges.
+    fn match_pair<'a>(
+        &self,
+        boundary_solver: &boundary::baseline::Solver<'a>,
+        (token, other): (eth::TokenAddress, eth::TokenAddress),
+        sellers: &[&'a Order],
+        buyers: &[&'a Order],
+        auction: &auction::Auction,
+        deadline: &auction::Deadline,
+    ) -> Option<Group<'a>> {
+        let mut prices = (
+            auction.tokens.reference_price(&token)?.0 .0,
+            auction.tokens.reference_price(&other)?.0 .0,
+        );
+        let mut best = None;
+        for _ in 0..MAX_PRICE_ITERATIONS {
+            deadline.remaining()?;
+            let Some(matched) = Match::new(prices, sellers, buyers) else {
+                break;
+            };
+            let (imbalance, shortfall) = match (matched.token_surplus, matched.other_surplus) {
+                // Both tokens are balanced or in surplus, so there is nothing
+                // to route and the price can't be improved any further.
+                (true, true) => {
+                    best = Some(matched.into_group((token, other), prices, None));
+                    break;
+                }
+                (true, false) => (
+                    eth::Asset {
+                        token,
+                        amount: matched.token,
+                    },
+                    eth::Asset {
+                        token: other,
+                        amount: matched.other,
+                    },
+                ),
+                (false, true) => (
+                    eth::Asset {
+                        token: other,
+                        amount: matched.other,
+                    },
+                    eth::Asset {
+                        token,
+                        amount: matched.token,
+                    },
+                ),
+                (false, false) => break,
+            };
+
+            let Some(route) = boundary_solver.route(
+                baseline::Request {
+                    sell: imbalance,
+                    buy: eth::Asset {
+                        token: shortfall.token,
+                        amount: U256::one(),
+                    },
+                    side: order::Side::Sell,
+                },
+                self.max_hops,
+            ) else {
+                break;
+            };
+            let output = route.output().amount;
+
+            // The routed imbalance has to at least cover the shortfall of the
+            // other token. Remember the last feasible match since it is the
+            // closest to the execution price of the routed imbalance.
+            let next = {
+                let buffered = imbalance
+                    .amount
+                    .checked_mul((10_000 + PRICE_TOLERANCE_BPS).into())?
+                    / 10_000;
+                if imbalance.token == token {
+                    (output, buffered)
+                } else {
+                    (buffered, output)
+                }
+            };
+            let converged = same_price(prices, next);
+            if output >= shortfall.amount {
+                best = Some(matched.into_group((token, other), prices, Some(route)));
+            }
+            if converged {
+                break;
+            }
+            prices = next;
+        }
+        best
+    }
+}
+
+/// A group of orders that got matched with each other.
+struct Group<'a> {
+    prices: Vec<(eth::TokenAddress, U256)>,
+    /// The matched orders and their executed amounts.
+    trades: Vec<(&'a Order, U256)>,
+    /// Routes for the imbalance of the matched orders.
+    routes: Vec<baseline::Route<'a>>,
+}
+
+impl Group<'_> {
+    /// The total value in the native token that gets sold by the orders of the
+    /// group. Tokens without a reference price don't count towards the value.
+    fn value(&self, tokens: &auction::Tokens) -> U256 {
+        let prices = self.prices.iter().copied().collect::<HashMap<_, _>>();
+        self.trades
+            .iter()
+            .filter_map(|(order, executed)| {
+                let sold = match order.side {
+                    order::Side::Sell => *executed,
+                    order::Side::Buy => math::mul_div_ceil(
+                        *executed,
+                        *prices.get(&order.buy.token)?,
+                        *prices.get(&order.sell.token)?,
+                    )?,
+                };
+                let price = tokens.reference_price(&order.sell.token)?;
+                math::mul_div(sold, price.0 .0, U256::exp10(18))
+            })
+            .fold(U256::zero(), U256::saturating_add)
+    }
+}
+
+/// Orders of a token pair whose limit prices are satisfied by a pair of
+/// clearing prices and the resulting balances of the settlement contract.
+struct Match<'a> {
+    trades: Vec<(&'a Order, U256)>,
+    /// The absolute balance of the first token.
+    token: U256,
+    /// Whether the settlement contract ends up with a surplus or is balanced
+    /// in the first token (`true`) or has a shortfall (`false`).
+    token_surplus: bool,
+    /// The absolute balance of the second token.
+    other: U256,
+    /// Same as `token_surplus` but for the second token.
+    other_surplus: bool,
+}
+
+impl<'a> Match<'a> {
+    /// Matches all orders that can be executed at the specified clearing
+    /// prices. Returns `None` if no orders on one of the sides are matched.
+    fn new(
+        (token_price, other_price): (U256, U256),
+        sellers: &[&'a Order],
+        buyers: &[&'a Order],
+    ) -> Option<Self> {
+        let mut trades = Vec::new();
+        // The amounts of the first and second token going in and out of the
+        // settlement contract.
+        let (mut token_in, mut token_out) = (U256::zero(), U256::zero());
+        let (mut other_in, mut other_out) = (U256::zero(), U256::zero());
+        for (orders, sell_price, buy_price, sell_in, buy_out) in [
+            (
+                sellers,
+                token_price,
+                other_price,
+                &mut token_in,
+                &mut other_out,
+            ),
+            (
+                buyers,
+                other_price,
+                token_price,
+                &mut other_in,
+                &mut token_out,
+            ),
+        ] {
+            let before = trades.len();
+            for order in orders {
+                let Some((sell, buy, executed)) = execute(order, sell_price, buy_price) else {
+                    continue;
+                };
+                *sell_in = sell_in.checked_add(sell)?;
+                *buy_out = buy_out.checked_add(buy)?;
+                trades.push((*order, executed));
+            }
+            if trades.len() == before {
+                return None;
+            }
+        }
+
+        let (token, token_surplus) = abs_diff(token_in, token_out);
+        let (other, other_surplus) = abs_diff(other_in, other_out);
+        Some(Self {
+            trades,
+            token,
+            token_surplus,
+            other,
+            other_surplus,
+        })
+    }
+
+    fn into_group(
+        self,
+        (token, other): (eth::TokenAddress, eth::TokenAddress),
+        (token_price, other_price): (U256, U256),
+        route: Option<baseline::Route<'a>>,
+    ) -> Group<'a> {
+        Group {
+            prices: vec![(token, token_price), (other, other_price)],
+            trades: self.trades,
+            routes: route.into_iter().collect(),
+        }
+    }
+}
+
+/// Computes the sell and buy amounts of fully executing an order at the
+/// specified clearing prices, as well as its executed amount. Returns `None` if
+/// the order's limit price is not satisfied.
+fn execute(order: &Order, sell_price: U256, buy_price: U256) -> Option<(U256, U256, U256)> {
+    let (sell, buy, executed) = match order.side {
+        order::Side::Sell => {
+            let buy = math::mul_div(order.sell.amount, sell_price, buy_price)?;
+            (order.sell.amount, buy, order.sell.amount)
+        }
+        order::Side::Buy => {
+            let sell = math::mul_div_ceil(order.buy.amount, buy_price, sell_price)?;
+            (sell, order.buy.amount, order.buy.amount)
+        }
+    };
+    let limit_satisfied =
+        order.sell.amount.full_mul(buy) >= order.buy.amount.full_mul(sell) && !buy.is_zero();
+    limit_satisfied.then_some((sell, buy, executed))
+}
+
+/// Returns whether two pairs of clearing prices describe the same exchange
+/// rate up to a relative difference of 10^-6.
+fn same_price(a: (U256, U256), b: (U256, U256)) -> bool {
+    let (x, y) = (a.0.full_mul(b.1), a.1.full_mul(b.0));
+    let diff = if x > y { x - y } else { y - x };
+    diff.saturating_mul(1_000_000.into()) <= x
+}
+
+/// Returns the absolute difference of two amounts and whether `a` is at least
+/// as large as `b`.
+fn abs_diff(a: U256, b: U256) -> (U256, bool) {
+    if a >= b {
+        (a - b, true)
+    } else {
+        (b - a, false)
+    }
+}
+
+/// Finds all cycles of two or three orders where each order buys the token
+/// that the next order in the cycle sells.
+fn rings<'a>(
+    orders: &[&'a Order],
+    by_sell_token: &HashMap<eth::TokenAddress, Vec<&'a Order>>,
+) -> Vec<Vec<&'a Order>> {
+    let next = |order: &Order| {
+        by_sell_token
+            .get(&order.buy.token)
+            .into_iter()
+            .flatten()
+            .copied()
+    };
+    let mut rings = Vec::new();
+    for first in orders {
+        if rings.len() >= MAX_RINGS {
+            tracing::debug!("reached maximum number of rings");
+            break;
+        }
+        for second in next(first) {
+            if second.buy.token == first.sell.token {
+                // Only record each ring once (and not for every rotation).
+                if first.uid.0 < second.uid.0 {
+                    rings.push(vec![*first, second]);
+                }
+                continue;
+            }
+            for third in next(second) {
+                if third.buy.token == first.sell.token
+                    && first.uid.0 < second.uid.0
+                    && first.uid.0 < third.uid.0
+                {
+                    rings.push(vec![*first, second, third]);
+                }
+            }
+        }
+    }
+    rings
+}
+
+/// Matches a ring of orders where every order buys the token that the next
+/// order sells.
+///
+/// The amount of each token exchanged in the ring is either fixed by the order
+/// selling it (sell orders) or by the order buying it (buy orders). Tokens that
+/// neither fix get exchanged at the midpoint between the amounts acceptable to
+/// both orders. Clearing prices are then chosen such that every order receives
+/// exactly what the next order sells, leaving no imbalance to route.
+fn match_ring<'a>(ring: &[&'a Order]) -> Option<Group<'a>> {
+    let len = ring.len();
+    // The amount of the token sold by the order at the same index.
+    let amounts = (0..len)
+        .map(|i| {
+            let order = ring[i];
+            let previous = ring[(i + len - 1) % len];
+            let sold = (order.side == order::Side::Sell).then_some(order.sell.amount);
+            let bought = (previous.side == order::Side::Buy).then_some(previous.buy.amount);
+            match (sold, bought) {
+                (Some(sold), Some(bought)) => (sold == bought).then_some(sold),
+                (Some(amount), None) | (None, Some(amount)) => Some(amount),
+                (None, None) => {
+                    // The order wants to sell as little as possible while the
+                    // previous order has to get at least its limit amount.
+                    let (min, max) = (previous.buy.amount, order.sell.amount);
+                    (min <= max).then(|| min + (max - min) / 2)
+                }
+            }
+        })
+        .collect::<Option<Vec<_>>>()?;
+    if amounts.iter().any(|amount| amount.is_zero()) {
+        return None;
+    }
+
+    let mut trades = Vec::new();
+    for i in 0..len {
+        let (order, sell, buy) = (ring[i], amounts[i], amounts[(i + 1) % len]);
+        if order.sell.amount.full_mul(buy) < order.buy.amount.full_mul(sell)
+            || sell > order.sell.amount
+            || buy < order.buy.amount
+        {
+            return None;
+        }
+        let executed = match order.side {
+            order::Side::Sell => sell,
+            order::Side::Buy => buy,
+        };
+        trades.push((order, executed));
+    }
+
+    // Pricing each token as the product of the amounts of all other tokens
+    // makes every order receive exactly the amount sold by the next order.
+    let prices = (0..len)
+        .map(|i| {
+            let price = (0..len)
+                .filter(|j| *j != i)
+                .try_fold(U256::one(), |price, j| price.checked_mul(amounts[j]))?;
+            Some((ring[i].sell.token, price))
+        })
+        .collect::<Option<Vec<_>>>()?;
+
+    Some(Group {
+        prices,
+        trades,
+        routes: Vec::new(),
+    })
+}
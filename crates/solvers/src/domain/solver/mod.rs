@@ -0,0 +1,28 @@
+//! Solver engine implementations.
+
+use crate::domain::{auction, solution};
+
+pub mod baseline;
+pub mod cow;
+
+pub use self::{baseline::Baseline, cow::Cow};
+
+/// The amount of time we aim the solver to finish before the final deadline is
+/// reached.
+const DEADLINE_SLACK: chrono::Duration = chrono::Duration::milliseconds(500);
+
+pub enum Solver {
+    Baseline(Baseline),
+    Cow(Cow),
+}
+
+impl Solver {
+    /// Solves the specified auction, returning a vector of all possible
+    /// solutions.
+    pub async fn solve(&self, auction: auction::Auction) -> Vec<solution::Solution> {
+        match self {
+            Solver::Baseline(solver) => solver.solve(auction).await,
+            Solver::Cow(solver) => solver.solve(auction).await,
+        }
+    }
+}
@@ -35,4 +35,10 @@ pub enum Command {
         #[clap(long, env)]
         config: PathBuf,
     },
+    /// match orders with each other and only route the remaining imbalance
+    /// via provided onchain liquidity
+    Cow {
+        #[clap(long, env)]
+        config: PathBuf,
+    },
 }
@@ -1,7 +1,6 @@
 use {
     crate::{
         domain::{eth, solver},
-        infra::contracts,
         util::serialize,
     },
     chain::Chain,
@@ -9,8 +8,7 @@ use {
     serde::Deserialize,
     serde_with::serde_as,
     shared::price_estimation::gas::SETTLEMENT_OVERHEAD,
-    std::{fmt::Debug, path::Path},
-    tokio::fs,
+    std::path::Path,
 };
 
 #[serde_as]
@@ -39,6 +37,11 @@ struct Config {
     /// when trying to solve it against baseline liquidity.
     max_partial_attempts: usize,
 
+    /// The maximum number of paths not sharing any liquidity to split an order
+    /// across. Splitting is disabled by default.
+    #[serde(default = "default_max_split_paths")]
+    max_split_paths: usize,
+
     /// Units of gas that get added to the gas estimate for executing a
     /// computed trade route to arrive at a gas estimate for a whole settlement.
     #[serde(default = "default_gas_offset")]
@@ -50,30 +53,16 @@ struct Config {
     native_token_price_estimation_amount: eth::U256,
 }
 
-/// Load the driver configuration from a TOML file.
+/// Load the baseline solver configuration from a TOML file.
 ///
 /// # Panics
 ///
 /// This method panics if the config is invalid or on I/O errors.
-pub async fn load(path: &Path) -> solver::Config {
-    let data = fs::read_to_string(path)
-        .await
-        .unwrap_or_else(|e| panic!("I/O error while reading {path:?}: {e:?}"));
-    // Not printing detailed error because it could potentially leak secrets.
-    let config = unwrap_or_log(toml::de::from_str::<Config>(&data), &path);
-    let weth = match (config.chain_id, config.weth) {
-        (Some(chain_id), None) => contracts::Contracts::for_chain(chain_id).weth,
-        (None, Some(weth)) => eth::WethAddress(weth),
-        (Some(_), Some(_)) => panic!(
-            "invalid configuration: cannot specify both `chain-id` and `weth` configuration \
-             options",
-        ),
-        (None, None) => panic!(
-            "invalid configuration: must specify either `chain-id` or `weth` configuration options",
-        ),
-    };
+pub async fn load(path: &Path) -> solver::baseline::Config {
+    let config = super::load::<Config>(path).await;
+    let weth = super::weth(config.chain_id, config.weth);
 
-    solver::Config {
+    solver::baseline::Config {
         weth,
         base_tokens: config
             .base_tokens
@@ -82,27 +71,15 @@ pub async fn load(path: &Path) -> solver::Config {
             .collect(),
         max_hops: config.max_hops,
         max_partial_attempts: config.max_partial_attempts,
+        max_split_paths: config.max_split_paths,
         solution_gas_offset: config.solution_gas_offset.into(),
         native_token_price_estimation_amount: config.native_token_price_estimation_amount,
     }
 }
 
-/// Unwraps result or logs a `TOML` parsing error.
-fn unwrap_or_log<T, E, P>(result: Result<T, E>, path: &P) -> T
-where
-    E: Debug,
-    P: Debug,
-{
-    result.unwrap_or_else(|err| {
-        if std::env::var("TOML_TRACE_ERROR").is_ok_and(|v| v == "1") {
-            panic!("failed to parse TOML config at {path:?}: {err:#?}")
-        } else {
-            panic!(
-                "failed to parse TOML config at: {path:?}. Set TOML_TRACE_ERROR=1 to print \
-                 parsing error but this may leak secrets."
-            )
-        }
-    })
+/// Routes orders over a single path.
+fn default_max_split_paths() -> usize {
+    1
 }
 
 /// Returns minimum gas used for settling a single order.
@@ -0,0 +1,48 @@
+use {
+    crate::domain::{eth, solver},
+    chain::Chain,
+    ethereum_types::H160,
+    serde::Deserialize,
+    std::path::Path,
+};
+
+#[derive(Deserialize)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+struct Config {
+    /// Optional chain ID. This is used to automatically determine the address
+    /// of the WETH contract.
+    chain_id: Option<Chain>,
+
+    /// Optional WETH contract address. This can be used to specify a manual
+    /// value **instead** of using the canonical WETH contract for the
+    /// configured chain.
+    weth: Option<H160>,
+
+    /// List of base tokens to use when routing the imbalance of matched
+    /// orders. Note that WETH is always considered as a base token.
+    base_tokens: Vec<eth::H160>,
+
+    /// The maximum number of hops to consider when routing the imbalance of
+    /// matched orders.
+    max_hops: usize,
+}
+
+/// Load the CoW solver configuration from a TOML file.
+///
+/// # Panics
+///
+/// This method panics if the config is invalid or on I/O errors.
+pub async fn load(path: &Path) -> solver::cow::Config {
+    let config = super::load::<Config>(path).await;
+    let weth = super::weth(config.chain_id, config.weth);
+
+    solver::cow::Config {
+        weth,
+        base_tokens: config
+            .base_tokens
+            .into_iter()
+            .map(eth::TokenAddress)
+            .collect(),
+        max_hops: config.max_hops,
+    }
+}
@@ -0,0 +1,58 @@
+use {
+    crate::{domain::eth, infra::contracts},
+    chain::Chain,
+    ethereum_types::H160,
+    serde::de::DeserializeOwned,
+    std::{fmt::Debug, path::Path},
+    tokio::fs,
+};
+
+pub mod baseline;
+pub mod cow;
+
+/// Reads and parses a TOML configuration file.
+///
+/// # Panics
+///
+/// This method panics if the config is invalid or on I/O errors.
+async fn load<T: DeserializeOwned>(path: &Path) -> T {
+    let data = fs::read_to_string(path)
+        .await
+        .unwrap_or_else(|e| panic!("I/O error while reading {path:?}: {e:?}"));
+    // Not printing detailed error because it could potentially leak secrets.
+    unwrap_or_log(toml::de::from_str::<T>(&data), &path)
+}
+
+/// Resolves the WETH contract address from the mutually exclusive `chain-id`
+/// and `weth` configuration options.
+fn weth(chain_id: Option<Chain>, weth: Option<H160>) -> eth::WethAddress {
+    match (chain_id, weth) {
+        (Some(chain_id), None) => contracts::Contracts::for_chain(chain_id).weth,
+        (None, Some(weth)) => eth::WethAddress(weth),
+        (Some(_), Some(_)) => panic!(
+            "invalid configuration: cannot specify both `chain-id` and `weth` configuration \
+             options",
+        ),
+        (None, None) => panic!(
+            "invalid configuration: must specify either `chain-id` or `weth` configuration options",
+        ),
+    }
+}
+
+/// Unwraps result or logs a `TOML` parsing error.
+fn unwrap_or_log<T, E, P>(result: Result<T, E>, path: &P) -> T
+where
+    E: Debug,
+    P: Debug,
+{
+    result.unwrap_or_else(|err| {
+        if std::env::var("TOML_TRACE_ERROR").is_ok_and(|v| v == "1") {
+            panic!("failed to parse TOML config at {path:?}: {err:#?}")
+        } else {
+            panic!(
+                "failed to parse TOML config at: {path:?}. Set TOML_TRACE_ERROR=1 to print \
+                 parsing error but this may leak secrets."
+            )
+        }
+    })
+}
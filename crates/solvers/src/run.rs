@@ -30,8 +30,12 @@ async fn run_with(args: cli::Args, bind: Option<oneshot::Sender<SocketAddr>>) {
 
     let solver = match args.command {
         cli::Command::Baseline { config } => {
-            let config = config::load(&config).await;
-            solver::Solver::new(config)
+            let config = config::baseline::load(&config).await;
+            solver::Solver::Baseline(solver::Baseline::new(config))
+        }
+        cli::Command::Cow { config } => {
+            let config = config::cow::load(&config).await;
+            solver::Solver::Cow(solver::Cow::new(config))
         }
     };
 
@@ -0,0 +1,219 @@
+//! Test cases to verify baseline computation of Uniswap V3 liquidity. The
+//! pool has two overlapping positions so that the swap has to cross
+//! initialized ticks.
+
+use {crate::tests, serde_json::json};
+
+fn engine_config() -> tests::Config {
+    tests::Config::String(
+        r#"
+            chain-id = "1"
+            base-tokens = []
+            max-hops = 0
+            max-partial-attempts = 1
+            native-token-price-estimation-amount = "100000000000000000"
+        "#
+        .to_owned(),
+    )
+}
+
+fn liquidity() -> serde_json::Value {
+    json!([
+        {
+            "kind": "concentratedLiquidity",
+            "tokens": [
+                "0xc02aaa39b223fe8d0a0e5c4f27ead9083c756cc2",
+                "0xdef1ca1fb7fbcdc777520aa7f396b4e015f497ab"
+            ],
+            "sqrtPrice": "79228162514276683272445184903",
+            "liquidity": "500000000000000000000",
+            "tick": 0,
+            "liquidityNet": {
+                "-600": "300000000000000000000",
+                "-120": "200000000000000000000",
+                "120": "-200000000000000000000",
+                "600": "-300000000000000000000"
+            },
+            "fee": "0.003",
+            "id": "0",
+            "address": "0x0000000000000000000000000000000000000001",
+            "router": "0xe592427a0aece92de3edee1f18e0157c05861564",
+            "gasEstimate": "110000"
+        }
+    ])
+}
+
+fn tokens() -> serde_json::Value {
+    json!({
+        "0xc02aaa39b223fe8d0a0e5c4f27ead9083c756cc2": {
+            "decimals": 18,
+            "symbol": "WETH",
+            "referencePrice": "1000000000000000000",
+            "availableBalance": "0",
+            "trusted": true
+        },
+        "0xdef1ca1fb7fbcdc777520aa7f396b4e015f497ab": {
+            "decimals": 18,
+            "symbol": "COW",
+            "referencePrice": "1000000000000000000",
+            "availableBalance": "0",
+            "trusted": true
+        }
+    })
+}
+
+#[tokio::test]
+async fn sell() {
+    let engine = tests::SolverEngine::new("baseline", engine_config()).await;
+
+    let solution = engine
+        .solve(json!({
+            "id": "1",
+            "tokens": tokens(),
+            "orders": [
+                {
+                    "uid": "0x2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a\
+                              2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a\
+                              2a2a2a2a",
+                    "sellToken": "0xc02aaa39b223fe8d0a0e5c4f27ead9083c756cc2",
+                    "buyToken": "0xdef1ca1fb7fbcdc777520aa7f396b4e015f497ab",
+                    "sellAmount": "10000000000000000000",
+                    "fullSellAmount": "10000000000000000000",
+                    "buyAmount": "9000000000000000000",
+                    "fullBuyAmount": "9000000000000000000",
+                    "feePolicies": [],
+                    "validTo": 0,
+                    "kind": "sell",
+                    "owner": "0x5b1e2c2762667331bc91648052f646d1b0d35984",
+                    "partiallyFillable": false,
+                    "preInteractions": [],
+                    "postInteractions": [],
+                    "sellTokenSource": "erc20",
+                    "buyTokenDestination": "erc20",
+                    "class": "market",
+                    "appData": "0x6000000000000000000000000000000000000000000000000000000000000007",
+                    "signingScheme": "presign",
+                    "signature": "0x",
+                }
+            ],
+            "liquidity": liquidity(),
+            "effectiveGasPrice": "15000000000",
+            "deadline": "2106-01-01T00:00:00.000Z",
+            "surplusCapturingJitOrderOwners": []
+        }))
+        .await;
+
+    assert_eq!(
+        solution,
+        json!({
+            "solutions": [{
+                "id": 0,
+                "prices": {
+                    "0xc02aaa39b223fe8d0a0e5c4f27ead9083c756cc2": "9713904809614879018",
+                    "0xdef1ca1fb7fbcdc777520aa7f396b4e015f497ab": "10000000000000000000"
+                },
+                "trades": [
+                    {
+                        "kind": "fulfillment",
+                        "order": "0x2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a\
+                                    2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a\
+                                    2a2a2a2a",
+                        "executedAmount": "10000000000000000000"
+                    }
+                ],
+                "preInteractions": [],
+                "interactions": [
+                    {
+                        "kind": "liquidity",
+                        "internalize": false,
+                        "id": "0",
+                        "inputToken": "0xc02aaa39b223fe8d0a0e5c4f27ead9083c756cc2",
+                        "outputToken": "0xdef1ca1fb7fbcdc777520aa7f396b4e015f497ab",
+                        "inputAmount": "10000000000000000000",
+                        "outputAmount": "9713904809614879018"
+                    }
+                ],
+                "postInteractions": [],
+                "gas": 216391,
+            }]
+        }),
+    );
+}
+
+#[tokio::test]
+async fn buy() {
+    let engine = tests::SolverEngine::new("baseline", engine_config()).await;
+
+    let solution = engine
+        .solve(json!({
+            "id": "1",
+            "tokens": tokens(),
+            "orders": [
+                {
+                    "uid": "0x2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a\
+                              2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a\
+                              2a2a2a2a",
+                    "sellToken": "0xdef1ca1fb7fbcdc777520aa7f396b4e015f497ab",
+                    "buyToken": "0xc02aaa39b223fe8d0a0e5c4f27ead9083c756cc2",
+                    "sellAmount": "11000000000000000000",
+                    "fullSellAmount": "11000000000000000000",
+                    "buyAmount": "10000000000000000000",
+                    "fullBuyAmount": "10000000000000000000",
+                    "feePolicies": [],
+                    "validTo": 0,
+                    "kind": "buy",
+                    "owner": "0x5b1e2c2762667331bc91648052f646d1b0d35984",
+                    "partiallyFillable": false,
+                    "preInteractions": [],
+                    "postInteractions": [],
+                    "sellTokenSource": "erc20",
+                    "buyTokenDestination": "erc20",
+                    "class": "market",
+                    "appData": "0x6000000000000000000000000000000000000000000000000000000000000007",
+                    "signingScheme": "presign",
+                    "signature": "0x",
+                }
+            ],
+            "liquidity": liquidity(),
+            "effectiveGasPrice": "15000000000",
+            "deadline": "2106-01-01T00:00:00.000Z",
+            "surplusCapturingJitOrderOwners": []
+        }))
+        .await;
+
+    assert_eq!(
+        solution,
+        json!({
+            "solutions": [{
+                "id": 0,
+                "prices": {
+                    "0xc02aaa39b223fe8d0a0e5c4f27ead9083c756cc2": "10304270315057908911",
+                    "0xdef1ca1fb7fbcdc777520aa7f396b4e015f497ab": "10000000000000000000"
+                },
+                "trades": [
+                    {
+                        "kind": "fulfillment",
+                        "order": "0x2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a\
+                                    2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a\
+                                    2a2a2a2a",
+                        "executedAmount": "10000000000000000000"
+                    }
+                ],
+                "preInteractions": [],
+                "interactions": [
+                    {
+                        "kind": "liquidity",
+                        "internalize": false,
+                        "id": "0",
+                        "inputToken": "0xdef1ca1fb7fbcdc777520aa7f396b4e015f497ab",
+                        "outputToken": "0xc02aaa39b223fe8d0a0e5c4f27ead9083c756cc2",
+                        "inputAmount": "10304270315057908911",
+                        "outputAmount": "10000000000000000000"
+                    }
+                ],
+                "postInteractions": [],
+                "gas": 216391,
+            }]
+        }),
+    );
+}
@@ -0,0 +1,221 @@
+//! Test cases for the CoW solver matching orders against each other.
+
+use {crate::tests, serde_json::json};
+
+fn order(uid: u8, sell: (&str, &str), buy: (&str, &str)) -> serde_json::Value {
+    json!({
+        "uid": format!("0x{}", hex::encode([uid; 56])),
+        "sellToken": sell.0,
+        "buyToken": buy.0,
+        "sellAmount": sell.1,
+        "fullSellAmount": sell.1,
+        "buyAmount": buy.1,
+        "fullBuyAmount": buy.1,
+        "feePolicies": [],
+        "validTo": 0,
+        "kind": "sell",
+        "owner": "0x5b1e2c2762667331bc91648052f646d1b0d35984",
+        "partiallyFillable": false,
+        "preInteractions": [],
+        "postInteractions": [],
+        "sellTokenSource": "erc20",
+        "buyTokenDestination": "erc20",
+        "class": "market",
+        "appData": "0x6000000000000000000000000000000000000000000000000000000000000007",
+        "signingScheme": "presign",
+        "signature": "0x",
+    })
+}
+
+#[tokio::test]
+async fn pair_with_routed_imbalance() {
+    let engine =
+        tests::SolverEngine::new("cow", tests::Config::File("config/example.cow.toml".into()))
+            .await;
+
+    let weth = "0xc02aaa39b223fe8d0a0e5c4f27ead9083c756cc2";
+    let cow = "0xdef1ca1fb7fbcdc777520aa7f396b4e015f497ab";
+    let solution = engine
+        .solve(json!({
+            "id": "1",
+            "tokens": {
+                weth: {
+                    "decimals": 18,
+                    "symbol": "WETH",
+                    "referencePrice": "1000000000000000000",
+                    "availableBalance": "0",
+                    "trusted": true
+                },
+                cow: {
+                    "decimals": 18,
+                    "symbol": "COW",
+                    "referencePrice": "1000000000000000",
+                    "availableBalance": "0",
+                    "trusted": true
+                }
+            },
+            "orders": [
+                order(
+                    0x11,
+                    (weth, "1000000000000000000"),
+                    (cow, "900000000000000000000"),
+                ),
+                order(
+                    0x22,
+                    (cow, "500000000000000000000"),
+                    (weth, "450000000000000000"),
+                ),
+            ],
+            "liquidity": [
+                {
+                    "kind": "constantProduct",
+                    "tokens": {
+                        weth: {
+                            "balance": "100000000000000000000"
+                        },
+                        cow: {
+                            "balance": "100000000000000000000000"
+                        }
+                    },
+                    "fee": "0.003",
+                    "id": "0",
+                    "address": "0x97b744df0b59d93A866304f97431D8EfAd29a08d",
+                    "router": "0x7a250d5630b4cf539739df2c5dacb4c659f2488d",
+                    "gasEstimate": "110000"
+                }
+            ],
+            "effectiveGasPrice": "15000000000",
+            "deadline": "2106-01-01T00:00:00.000Z",
+            "surplusCapturingJitOrderOwners": []
+        }))
+        .await;
+
+    // Only the surplus WETH of the first order gets routed through the pool,
+    // which then defines the clearing price of both orders.
+    assert_eq!(
+        solution,
+        json!({
+            "solutions": [{
+                "id": 0,
+                "prices": {
+                    weth: "492024400426631308339",
+                    cow: "495994680516624875"
+                },
+                "trades": [
+                    {
+                        "kind": "fulfillment",
+                        "order": format!("0x{}", hex::encode([0x11; 56])),
+                        "executedAmount": "1000000000000000000"
+                    },
+                    {
+                        "kind": "fulfillment",
+                        "order": format!("0x{}", hex::encode([0x22; 56])),
+                        "executedAmount": "500000000000000000000"
+                    }
+                ],
+                "preInteractions": [],
+                "interactions": [
+                    {
+                        "kind": "liquidity",
+                        "internalize": false,
+                        "id": "0",
+                        "inputToken": weth,
+                        "outputToken": cow,
+                        "inputAmount": "495965362605440949",
+                        "outputAmount": "492044417746609792671"
+                    }
+                ],
+                "postInteractions": [],
+                "gas": 265417,
+            }]
+        }),
+    );
+}
+
+#[tokio::test]
+async fn ring() {
+    let engine =
+        tests::SolverEngine::new("cow", tests::Config::File("config/example.cow.toml".into()))
+            .await;
+
+    let weth = "0xc02aaa39b223fe8d0a0e5c4f27ead9083c756cc2";
+    let cow = "0xdef1ca1fb7fbcdc777520aa7f396b4e015f497ab";
+    let dai = "0x6b175474e89094c44da98b954eedeac495271d0f";
+    let token = |symbol: &str| {
+        json!({
+            "decimals": 18,
+            "symbol": symbol,
+            "referencePrice": null,
+            "availableBalance": "0",
+            "trusted": true
+        })
+    };
+    let solution = engine
+        .solve(json!({
+            "id": "1",
+            "tokens": {
+                weth: token("WETH"),
+                cow: token("COW"),
+                dai: token("DAI"),
+            },
+            "orders": [
+                order(
+                    0x11,
+                    (weth, "1000000000000000000"),
+                    (cow, "900000000000000000000"),
+                ),
+                order(
+                    0x22,
+                    (cow, "1000000000000000000000"),
+                    (dai, "990000000000000000000"),
+                ),
+                order(
+                    0x33,
+                    (dai, "1000000000000000000000"),
+                    (weth, "900000000000000000"),
+                ),
+            ],
+            "liquidity": [],
+            "effectiveGasPrice": "15000000000",
+            "deadline": "2106-01-01T00:00:00.000Z",
+            "surplusCapturingJitOrderOwners": []
+        }))
+        .await;
+
+    // Every order receives exactly what the next order in the ring sells, so
+    // no liquidity is needed.
+    assert_eq!(
+        solution,
+        json!({
+            "solutions": [{
+                "id": 0,
+                "prices": {
+                    weth: "1000000000000000000000000000000000000000000",
+                    cow: "1000000000000000000000000000000000000000",
+                    dai: "1000000000000000000000000000000000000000"
+                },
+                "trades": [
+                    {
+                        "kind": "fulfillment",
+                        "order": format!("0x{}", hex::encode([0x11; 56])),
+                        "executedAmount": "1000000000000000000"
+                    },
+                    {
+                        "kind": "fulfillment",
+                        "order": format!("0x{}", hex::encode([0x22; 56])),
+                        "executedAmount": "1000000000000000000000"
+                    },
+                    {
+                        "kind": "fulfillment",
+                        "order": format!("0x{}", hex::encode([0x33; 56])),
+                        "executedAmount": "1000000000000000000000"
+                    }
+                ],
+                "preInteractions": [],
+                "interactions": [],
+                "postInteractions": [],
+                "gas": 304443,
+            }]
+        }),
+    );
+}
@@ -1,8 +1,11 @@
-//! Baseline solver test cases.
+//! Solver engine test cases.
 
 mod bal_liquidity;
 mod buy_order_rounding;
+mod concentrated_liquidity;
+mod cow;
 mod direct_swap;
 mod internalization;
 mod limit_order_quoting;
+mod order_splitting;
 mod partial_fill;
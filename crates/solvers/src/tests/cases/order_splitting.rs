@@ -0,0 +1,155 @@
+//! Test case to verify that the baseline solver splits large orders across
+//! multiple paths when configured to do so.
+
+use {crate::tests, serde_json::json};
+
+#[tokio::test]
+async fn sell() {
+    let engine = tests::SolverEngine::new(
+        "baseline",
+        tests::Config::String(
+            r#"
+                chain-id = "1"
+                base-tokens = []
+                max-hops = 0
+                max-partial-attempts = 1
+                max-split-paths = 2
+                native-token-price-estimation-amount = "100000000000000000"
+            "#
+            .to_owned(),
+        ),
+    )
+    .await;
+
+    let solution = engine
+        .solve(json!({
+            "id": "1",
+            "tokens": {
+                "0xc02aaa39b223fe8d0a0e5c4f27ead9083c756cc2": {
+                    "decimals": 18,
+                    "symbol": "WETH",
+                    "referencePrice": "1000000000000000000",
+                    "availableBalance": "0",
+                    "trusted": true
+                },
+                "0xdef1ca1fb7fbcdc777520aa7f396b4e015f497ab": {
+                    "decimals": 18,
+                    "symbol": "COW",
+                    "referencePrice": "1000000000000000",
+                    "availableBalance": "0",
+                    "trusted": true
+                }
+            },
+            "orders": [
+                {
+                    "uid": "0x2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a\
+                              2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a\
+                              2a2a2a2a",
+                    "sellToken": "0xc02aaa39b223fe8d0a0e5c4f27ead9083c756cc2",
+                    "buyToken": "0xdef1ca1fb7fbcdc777520aa7f396b4e015f497ab",
+                    "sellAmount": "10000000000000000000",
+                    "fullSellAmount": "10000000000000000000",
+                    "buyAmount": "5000000000000000000000",
+                    "fullBuyAmount": "5000000000000000000000",
+                    "feePolicies": [],
+                    "validTo": 0,
+                    "kind": "sell",
+                    "owner": "0x5b1e2c2762667331bc91648052f646d1b0d35984",
+                    "partiallyFillable": false,
+                    "preInteractions": [],
+                    "postInteractions": [],
+                    "sellTokenSource": "erc20",
+                    "buyTokenDestination": "erc20",
+                    "class": "market",
+                    "appData": "0x6000000000000000000000000000000000000000000000000000000000000007",
+                    "signingScheme": "presign",
+                    "signature": "0x",
+                }
+            ],
+            "liquidity": [
+                {
+                    "kind": "constantProduct",
+                    "tokens": {
+                        "0xc02aaa39b223fe8d0a0e5c4f27ead9083c756cc2": {
+                            "balance": "10000000000000000000"
+                        },
+                        "0xdef1ca1fb7fbcdc777520aa7f396b4e015f497ab": {
+                            "balance": "10000000000000000000000"
+                        }
+                    },
+                    "fee": "0.003",
+                    "id": "0",
+                    "address": "0x0000000000000000000000000000000000000001",
+                    "router": "0x7a250d5630b4cf539739df2c5dacb4c659f2488d",
+                    "gasEstimate": "110000"
+                },
+                {
+                    "kind": "constantProduct",
+                    "tokens": {
+                        "0xc02aaa39b223fe8d0a0e5c4f27ead9083c756cc2": {
+                            "balance": "5000000000000000000"
+                        },
+                        "0xdef1ca1fb7fbcdc777520aa7f396b4e015f497ab": {
+                            "balance": "5100000000000000000000"
+                        }
+                    },
+                    "fee": "0.003",
+                    "id": "1",
+                    "address": "0x0000000000000000000000000000000000000002",
+                    "router": "0xd9e1ce17f2641f24ae83637ab66a2cca9c378b9f",
+                    "gasEstimate": "110000"
+                }
+            ],
+            "effectiveGasPrice": "15000000000",
+            "deadline": "2106-01-01T00:00:00.000Z",
+            "surplusCapturingJitOrderOwners": []
+        }))
+        .await;
+
+    // A single pool would only yield 4992488733099649474211 COW which doesn't
+    // even satisfy the order's limit price.
+    assert_eq!(
+        solution,
+        json!({
+            "solutions": [{
+                "id": 0,
+                "prices": {
+                    "0xc02aaa39b223fe8d0a0e5c4f27ead9083c756cc2": "6028512464259943412429",
+                    "0xdef1ca1fb7fbcdc777520aa7f396b4e015f497ab": "10000000000000000000"
+                },
+                "trades": [
+                    {
+                        "kind": "fulfillment",
+                        "order": "0x2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a\
+                                    2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a\
+                                    2a2a2a2a",
+                        "executedAmount": "10000000000000000000"
+                    }
+                ],
+                "preInteractions": [],
+                "interactions": [
+                    {
+                        "kind": "liquidity",
+                        "internalize": false,
+                        "id": "0",
+                        "inputToken": "0xc02aaa39b223fe8d0a0e5c4f27ead9083c756cc2",
+                        "outputToken": "0xdef1ca1fb7fbcdc777520aa7f396b4e015f497ab",
+                        "inputAmount": "6500000000000000000",
+                        "outputAmount": "3932222930129547040441"
+                    },
+                    {
+                        "kind": "liquidity",
+                        "internalize": false,
+                        "id": "1",
+                        "inputToken": "0xc02aaa39b223fe8d0a0e5c4f27ead9083c756cc2",
+                        "outputToken": "0xdef1ca1fb7fbcdc777520aa7f396b4e015f497ab",
+                        "inputAmount": "3500000000000000000",
+                        "outputAmount": "2096289534130396371988"
+                    }
+                ],
+                "postInteractions": [],
+                "gas": 226391,
+            }]
+        }),
+    );
+}
@@ -1,4 +1,4 @@
-use ethereum_types::U256;
+use ethereum_types::{U256, U512};
 
 /// Perform a ceiled U256 integer division.
 ///
@@ -18,3 +18,31 @@ pub fn div_ceil(q: U256, d: U256) -> Option<U256> {
         )
     }
 }
+
+/// Computes `x * q / d` rounding down without overflowing on the intermediate
+/// multiplication.
+///
+/// Returns `None` when dividing by `0` or if the result overflows.
+pub fn mul_div(x: U256, q: U256, d: U256) -> Option<U256> {
+    if d.is_zero() {
+        return None;
+    }
+    (x.full_mul(q) / U512::from(d)).try_into().ok()
+}
+
+/// Computes `x * q / d` rounding up without overflowing on the intermediate
+/// multiplication.
+///
+/// Returns `None` when dividing by `0` or if the result overflows.
+pub fn mul_div_ceil(x: U256, q: U256, d: U256) -> Option<U256> {
+    if d.is_zero() {
+        return None;
+    }
+    let (r, rem) = x.full_mul(q).div_mod(U512::from(d));
+    let r = U256::try_from(r).ok()?;
+    if rem.is_zero() {
+        Some(r)
+    } else {
+        r.checked_add(U256::one())
+    }
+}
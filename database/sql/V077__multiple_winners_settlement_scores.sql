@@ -0,0 +1,4 @@
+-- Auctions can have multiple winners, each of which is scored individually.
+-- Instead of one row per auction we store one row per winning solver.
+ALTER TABLE settlement_scores DROP CONSTRAINT settlement_scores_pkey;
+ALTER TABLE settlement_scores ADD PRIMARY KEY (auction_id, winner);
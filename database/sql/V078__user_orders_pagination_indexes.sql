@@ -0,0 +1,4 @@
+-- Cursor based pagination of a user's orders walks them ordered by `(creation_timestamp, uid)`.
+-- Including the uid in the index allows postgres to seek directly to the start of each page.
+CREATE INDEX user_order_creation_timestamp_uid ON orders USING BTREE (owner, creation_timestamp DESC, uid DESC);
+CREATE INDEX jit_user_order_creation_timestamp_uid ON jit_orders USING BTREE (owner, creation_timestamp DESC, uid DESC);
@@ -0,0 +1,2 @@
+-- Looking up all auctions an order was part of requires searching the `order_uids` arrays.
+CREATE INDEX auction_orders_order_uids ON auction_orders USING GIN (order_uids);
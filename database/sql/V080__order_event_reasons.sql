@@ -0,0 +1,5 @@
+-- Orders that get filtered from an auction or deemed invalid store why that happened
+-- so users can find out why their order never reached the solvers.
+ALTER TABLE order_events
+    ADD COLUMN reason text,
+    ADD COLUMN details jsonb;
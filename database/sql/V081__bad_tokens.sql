@@ -0,0 +1,7 @@
+-- Tokens drivers detected to be unsupported (e.g. because they take fees on transfer) so the
+-- knowledge survives restarts and is shared between driver instances.
+CREATE TABLE bad_tokens (
+    token bytea PRIMARY KEY,
+    reason text NOT NULL,
+    detected_at timestamptz NOT NULL
+);
@@ -0,0 +1,18 @@
+-- Conditional orders (e.g. TWAP orders) created via the ComposableCoW contract. The autopilot
+-- periodically polls them for discrete orders which are ready to be placed.
+CREATE TABLE conditional_orders (
+    owner bytea NOT NULL,
+    -- `ComposableCoW.hash(params)`, identifies the conditional order of an owner.
+    params_hash bytea NOT NULL,
+    handler bytea NOT NULL,
+    salt bytea NOT NULL,
+    static_input bytea NOT NULL,
+    block_number bigint NOT NULL,
+    log_index bigint NOT NULL,
+    -- The order doesn't get polled before this block and timestamp.
+    next_poll_block bigint NOT NULL DEFAULT 0,
+    next_poll_timestamp bigint NOT NULL DEFAULT 0,
+    PRIMARY KEY (owner, params_hash)
+);
+
+CREATE INDEX conditional_orders_block_number ON conditional_orders (block_number);
@@ -0,0 +1,3 @@
+-- Partner fees from the app data are stored as fee policies like protocol fees. The recipient
+-- allows reporting the fee taken for each partner separately. NULL for protocol fees.
+ALTER TABLE fee_policies ADD COLUMN partner_fee_recipient bytea;
@@ -0,0 +1,10 @@
+-- Balance override strategies detected for quote verification so the detection simulations don't
+-- have to be repeated after restarts and are shared between services. Entries are only valid as
+-- long as the token's code hash matches.
+CREATE TABLE balance_override_strategies (
+    token bytea PRIMARY KEY,
+    code_hash bytea NOT NULL,
+    -- NULL if no strategy could be detected for the token.
+    strategy text,
+    detected_at timestamptz NOT NULL
+);
@@ -0,0 +1,5 @@
+-- Looking up the historical native prices of a single token requires searching the prices of all auctions.
+CREATE INDEX auction_prices_token_auction_id ON auction_prices (token, auction_id);
+
+-- Finding the auctions surrounding a block requires searching all auctions by block.
+CREATE INDEX competition_auctions_block_id ON competition_auctions (block, id);
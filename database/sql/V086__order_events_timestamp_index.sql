@@ -0,0 +1,2 @@
+-- The orderbook polls for all order events registered since a given timestamp on every block.
+CREATE INDEX order_events_by_timestamp ON order_events USING BTREE (timestamp);